@@ -11,25 +11,29 @@ pub mod theme;
 pub mod tui_guard;
 pub mod markdown;
 
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use colored::*;
 use tracing_subscriber::EnvFilter;
 
 use zenclaw_core::agent::{Agent, AgentConfig};
 use zenclaw_core::bus::EventBus;
+use zenclaw_core::channel::Channel;
 use zenclaw_core::config::ZenClawConfig;
-use zenclaw_core::provider::ProviderConfig;
+use zenclaw_core::provider::{EmbeddingProvider, LlmProvider, ProviderConfig};
+use zenclaw_core::ratelimit::RateLimiter;
 use zenclaw_hub::channels::{DiscordConfig, TelegramConfig};
 use zenclaw_hub::memory::SqliteMemory;
-use zenclaw_hub::providers::OpenAiProvider;
-use zenclaw_hub::skills::SkillManager;
+use zenclaw_hub::providers::{AnthropicProvider, FallbackProvider, OpenAiProvider};
+use zenclaw_hub::skills::{ParsedSkill, SkillManager, ToolScope};
 use zenclaw_hub::plugins::PluginManager;
+use zenclaw_hub::router::AgentRouter;
 use zenclaw_hub::tools::{
-    CodebaseSearchTool, CronTool, EditFileTool, EnvTool, HealthTool, HistoryTool, ListDirTool, ProcessTool,
-    ReadFileTool, ShellTool, SubAgentTool, SystemInfoTool, WebFetchTool, WebScrapeTool, WebSearchTool, WriteFileTool,
+    AgentDelegateTool, CalcTool, CodebaseSearchTool, CronTool, EditFileTool, EnvPolicy, EnvTool, GitTool, GrepTool, HealthTool, HistoryTool, ListDirTool, MemoryTool, ProcessTool,
+    ReadFileTool, SearchHistoryTool, ShellTool, SubAgentTool, SystemInfoTool, UndoEditTool, WebFetchTool, WebScrapeTool, WebSearchTool, WriteFileTool, new_session_env,
 };
 
 // ─── CLI Definition ────────────────────────────────────────
@@ -40,6 +44,14 @@ use zenclaw_hub::tools::{
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Skip the background update check (also: `updates.check = false` in config)
+    #[arg(long, global = true, env = "ZENCLAW_NO_UPDATE_CHECK")]
+    no_update_check: bool,
+
+    /// Write log files as JSON lines (for Loki, ELK, ...)
+    #[arg(long, global = true, env = "ZENCLAW_JSON_LOGS")]
+    json_logs: bool,
 }
 
 #[derive(Subcommand)]
@@ -68,6 +80,22 @@ enum Commands {
         /// Activate a skill (e.g. --skill coding)
         #[arg(short, long)]
         skill: Option<Vec<String>>,
+
+        /// Extra provider parameters as JSON (e.g. '{"reasoning_effort":"high"}')
+        #[arg(long, value_name = "JSON")]
+        model_params: Option<String>,
+
+        /// Ask for approval before each tool call
+        #[arg(long)]
+        approve_tools: bool,
+
+        /// Providers to fall back to on network errors, 5xx or rate limits (e.g. groq,openai)
+        #[arg(long, value_delimiter = ',')]
+        fallback: Vec<String>,
+
+        /// Route each message to a specialist agent (coder, researcher, writer)
+        #[arg(long)]
+        router: bool,
     },
 
     /// ❓ Send a single message and get a response
@@ -86,6 +114,33 @@ enum Commands {
         /// API key
         #[arg(short = 'k', long)]
         api_key: Option<String>,
+
+        /// Extra provider parameters as JSON (e.g. '{"reasoning_effort":"high"}')
+        #[arg(long, value_name = "JSON")]
+        model_params: Option<String>,
+    },
+
+    /// 🔀 Read stdin, ask about it, print only the answer (for shell scripts)
+    Pipe {
+        /// Instruction added after the piped input (e.g. "explain this")
+        #[arg(default_value = "")]
+        prompt: String,
+
+        /// Print {"response", "usage", "tool_calls"} as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Model to use
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// Provider
+        #[arg(short, long)]
+        provider: Option<String>,
+
+        /// API key
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
     },
 
     /// ⚙️  Manage configuration
@@ -97,6 +152,9 @@ enum Commands {
     /// 📊 Show system info and status
     Status,
 
+    /// 🩺 Check that the provider, Ollama and the database actually respond
+    Doctor,
+
     /// 🤖 Start Telegram bot
     Telegram {
         /// Telegram bot token (or use config)
@@ -126,6 +184,10 @@ enum Commands {
         #[arg(short, long, env = "DISCORD_BOT_TOKEN")]
         token: Option<String>,
 
+        /// Receive messages over the Gateway websocket (guild channels too)
+        #[arg(long)]
+        gateway: bool,
+
         /// Model to use
         #[arg(short, long)]
         model: Option<String>,
@@ -162,12 +224,130 @@ enum Commands {
         allowed_channels: Option<String>,
     },
 
+    /// 🟩 Start Matrix bot
+    Matrix {
+        /// Homeserver URL (e.g. https://matrix.example.org)
+        #[arg(long, env = "MATRIX_HOMESERVER")]
+        homeserver: Option<String>,
+
+        /// Access token of the bot account
+        #[arg(short, long, env = "MATRIX_ACCESS_TOKEN")]
+        token: Option<String>,
+
+        /// Model to use
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// Provider
+        #[arg(short, long)]
+        provider: Option<String>,
+
+        /// API key
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
+
+        /// Allowed room IDs (comma-separated)
+        #[arg(long)]
+        allowed_rooms: Option<String>,
+    },
+
     /// 📚 List and manage skills
     Skills {
         #[command(subcommand)]
         action: Option<SkillAction>,
     },
 
+    /// 🧰 List the agent's tools with their parameters
+    Tools,
+
+    /// 🧩 Check plugin manifests
+    Plugins {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+
+    /// 🗂️  List the models your provider offers
+    Models {
+        /// Provider (overrides config)
+        #[arg(short, long)]
+        provider: Option<String>,
+
+        /// API key (overrides config)
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
+
+        /// Print {"provider", "source", "models"} as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 📏 Compare providers/models on a JSONL file of prompts
+    Eval {
+        /// Prompts file, one {"prompt": "..."} object per line
+        file: PathBuf,
+
+        /// Target as provider[:model], repeatable (default: configured provider)
+        #[arg(short, long = "target")]
+        targets: Vec<String>,
+
+        /// Score answers with this provider[:model] as judge
+        #[arg(long)]
+        judge: Option<String>,
+
+        /// Requests in flight at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Report path (.md, or .json for raw results)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// 📝 Manage reusable prompt snippets (used in chat with /use)
+    Prompts {
+        #[command(subcommand)]
+        action: Option<PromptAction>,
+    },
+
+    /// 🎨 Show the TUI color theme, or change it
+    Theme {
+        #[command(subcommand)]
+        action: Option<ThemeAction>,
+    },
+
+    /// 💾 Back up or restore a conversation as JSON
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+
+    /// 🔎 Add documents to the knowledge base and search it
+    Rag {
+        #[command(subcommand)]
+        action: RagAction,
+    },
+
+    /// ⏰ Schedule prompts to run on a cron schedule (see `daemon`)
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleAction,
+    },
+
+    /// 🕰️  Run scheduled prompts in the foreground
+    Daemon {
+        /// Provider
+        #[arg(short, long)]
+        provider: Option<String>,
+
+        /// Model to use
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// API key
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
+    },
+
     /// 🌐 Start REST API server
     Serve {
         /// Host to bind to
@@ -214,8 +394,28 @@ enum Commands {
         allowed_numbers: Option<String>,
     },
 
+    /// 🐚 Print a shell completion script
+    ///
+    /// Install it where your shell looks for completions, e.g.:
+    ///
+    ///   bash:        zenclaw completions bash > /etc/bash_completion.d/zenclaw
+    ///                (or ~/.local/share/bash-completion/completions/zenclaw)
+    ///   zsh:         zenclaw completions zsh > "${fpath[1]}/_zenclaw"
+    ///   fish:        zenclaw completions fish > ~/.config/fish/completions/zenclaw.fish
+    ///   elvish:      zenclaw completions elvish >> ~/.config/elvish/rc.elv
+    ///   powershell:  zenclaw completions powershell >> $PROFILE
+    #[command(verbatim_doc_comment)]
+    Completions {
+        /// bash, zsh, fish, elvish or powershell
+        shell: clap_complete::Shell,
+    },
+
     /// 🔄 Check for updates
-    Update,
+    Update {
+        /// Download and install the new version in place
+        #[arg(long)]
+        apply: bool,
+    },
 
     /// 🐛 Monitor ZenClaw internal diagnostic logs
     Logs {
@@ -223,6 +423,40 @@ enum Commands {
         #[arg(short, long, default_value_t = 50)]
         lines: usize,
     },
+
+    /// 🔍 Search past conversations (every saved message, all sessions)
+    Search {
+        /// Words to look for (every one must appear)
+        query: String,
+        /// Only messages from this session (e.g. telegram:12345)
+        #[arg(long)]
+        session: Option<String>,
+        /// Max results
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+        /// Print matches as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 🧾 Show the tool-call audit log (every tool the agent ran)
+    Audit {
+        /// Only calls of this tool
+        #[arg(long)]
+        tool: Option<String>,
+        /// Only calls from this session (e.g. telegram:12345)
+        #[arg(long)]
+        session: Option<String>,
+        /// Number of recent calls to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize,
+        /// Keep printing new calls as they happen
+        #[arg(short, long)]
+        follow: bool,
+        /// Print raw JSON lines
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -246,6 +480,102 @@ enum SkillAction {
     List,
     /// Show a skill's content
     Show { name: String },
+    /// Install a skill from a URL or a local .md file
+    Import {
+        /// `https://...` URL or path to the skill markdown
+        source: String,
+        /// Name to save it as (default: derived from the file name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Overwrite an existing skill without asking
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+    /// Write a skill to a file for sharing
+    Export { name: String, path: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum ThemeAction {
+    /// Show the current palette
+    Show,
+    /// Set a color (e.g. `primary "#0087af"`) or pick a preset (`preset light`)
+    Set { key: String, color: String },
+}
+
+#[derive(Subcommand)]
+enum PromptAction {
+    /// List saved snippets
+    List,
+    /// Save a snippet (use {args} where the /use arguments go)
+    Add {
+        name: String,
+        template: String,
+        /// One-line description
+        #[arg(short, long, default_value = "")]
+        description: String,
+    },
+    /// Delete a snippet
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+enum SessionAction {
+    /// Write a session's history to a JSON file
+    Export { key: String, file: PathBuf },
+    /// Load a session from a JSON export (merged by timestamp)
+    Import {
+        key: String,
+        file: PathBuf,
+        /// Replace the existing history instead of merging
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RagAction {
+    /// Chunk, embed and store a text file (re-adding replaces it)
+    Add {
+        file: PathBuf,
+        /// Free-form note stored with every chunk
+        #[arg(long, default_value = "")]
+        metadata: String,
+    },
+    /// Find the chunks most similar to a query
+    Search {
+        query: String,
+        /// Number of results
+        #[arg(short, default_value_t = 5)]
+        k: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum PluginAction {
+    /// Validate the plugins in a directory (default: the plugins folder)
+    Validate { dir: Option<PathBuf> },
+}
+
+#[derive(Subcommand)]
+enum ScheduleAction {
+    /// Add a job, e.g. add "0 9 * * *" "summarize my unread emails"
+    Add {
+        /// Standard 5-field cron expression (minute hour day month weekday, UTC)
+        cron: String,
+        /// Prompt sent to the agent
+        prompt: String,
+        /// Where the answer goes: log, telegram:<chat_id>, slack:<channel_id> or whatsapp:<number>
+        #[arg(short, long, default_value = "log")]
+        deliver: String,
+        /// Run once on daemon start if a run was missed while it was down
+        #[arg(long)]
+        catch_up: bool,
+    },
+    /// List jobs with their next run
+    List,
+    /// Remove a job by id
+    Remove { id: i64 },
 }
 
 // ─── Helpers ───────────────────────────────────────────────
@@ -293,13 +623,64 @@ fn default_model(provider: &str) -> &str {
     }
 }
 
+/// Parse a `--model-params` JSON object.
+fn parse_model_params(raw: Option<&str>) -> anyhow::Result<Option<serde_json::Value>> {
+    let Some(raw) = raw else { return Ok(None) };
+    let value: serde_json::Value = serde_json::from_str(raw)
+        .map_err(|e| anyhow::anyhow!("Invalid --model-params JSON: {}", e))?;
+    if !value.is_object() {
+        anyhow::bail!("--model-params must be a JSON object, got: {}", raw);
+    }
+    Ok(Some(value))
+}
+
 fn create_provider(
     provider_name: &str,
     api_key: &str,
     model: &str,
     api_base: Option<&str>,
-) -> OpenAiProvider {
-    match provider_name {
+    model_params: Option<serde_json::Value>,
+) -> Box<dyn LlmProvider> {
+    // CLI params win over the saved config
+    let saved = setup::load_saved_config();
+    let model_params = model_params
+        .or_else(|| saved.as_ref().and_then(|c| c.provider.model_params.clone()));
+    let system_role = saved.as_ref().and_then(|c| c.provider.system_role);
+    let defaults = ProviderConfig::default();
+    let (max_retries, retry_base_ms) = saved
+        .as_ref()
+        .map(|c| (c.provider.max_retries, c.provider.retry_base_ms))
+        .unwrap_or((defaults.max_retries, defaults.retry_base_ms));
+    // Custom headers belong to the saved provider's endpoint; fallbacks and
+    // `--provider` overrides don't get them
+    let (headers, override_auth) = saved
+        .as_ref()
+        .filter(|c| c.provider.provider == provider_name)
+        .map(|c| (c.provider.headers.clone(), c.provider.override_auth))
+        .unwrap_or_default();
+    // The vision override describes the saved model only
+    let vision = saved
+        .as_ref()
+        .filter(|c| c.provider.provider == provider_name && c.provider.model == model)
+        .and_then(|c| c.provider.vision);
+
+    if provider_name == "anthropic" {
+        let provider = AnthropicProvider::new(ProviderConfig {
+            provider: provider_name.to_string(),
+            model: model.to_string(),
+            api_key: Some(api_key.to_string()),
+            api_base: api_base.map(str::to_string),
+            ..Default::default()
+        });
+        return Box::new(
+            provider
+                .with_model_params(model_params)
+                .with_retry(max_retries, retry_base_ms)
+                .with_vision(vision),
+        );
+    }
+
+    let provider = match provider_name {
         "ollama" => OpenAiProvider::ollama(model),
         "openrouter" => OpenAiProvider::openrouter(api_key, model),
         "gemini" => OpenAiProvider::gemini(api_key, model),
@@ -318,7 +699,39 @@ fn create_provider(
                 OpenAiProvider::openai(api_key, model)
             }
         }
+    };
+    Box::new(
+        provider
+            .with_model_params(model_params)
+            .with_system_role(system_role)
+            .with_retry(max_retries, retry_base_ms)
+            .with_headers(headers, override_auth)
+            .with_vision(vision),
+    )
+}
+
+/// Per-client limiter from the `rate_limit` config section (None = off).
+fn rate_limiter() -> Option<Arc<RateLimiter>> {
+    let settings = setup::load_saved_config().map(|c| c.rate_limit).unwrap_or_default();
+    RateLimiter::from_settings(&settings).map(Arc::new)
+}
+
+/// Chain `primary` with the `--fallback` providers, each on its default
+/// model. Providers without an API key are skipped with a warning.
+fn with_fallbacks(primary: Box<dyn LlmProvider>, names: &[String]) -> Box<dyn LlmProvider> {
+    let mut chain: Vec<Arc<dyn LlmProvider>> = Vec::new();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        let Some(api_key) = resolve_api_key(None, name) else {
+            eprintln!("  {} No API key for fallback provider '{}', skipping", "⚠️".yellow(), name);
+            continue;
+        };
+        chain.push(Arc::from(create_provider(name, &api_key, default_model(name), None, None)));
     }
+    if chain.is_empty() {
+        return primary;
+    }
+    chain.insert(0, Arc::from(primary));
+    Box::new(FallbackProvider::new(chain))
 }
 
 async fn setup_bot_env(
@@ -326,8 +739,10 @@ async fn setup_bot_env(
     model: Option<&str>,
     api_key: Option<&str>,
     api_base: Option<&str>,
-    skill_prompt: Option<&str>,
-) -> anyhow::Result<(Agent, OpenAiProvider, SqliteMemory, String, String)> {
+    skills: Option<&ActiveSkills>,
+    model_params: Option<serde_json::Value>,
+    access: ToolAccess,
+) -> anyhow::Result<(Agent, Box<dyn LlmProvider>, SqliteMemory, String, String)> {
     let (resolved_provider_name, resolved_model, resolved_api_key, resolved_api_base) =
         resolve_config(provider_name, model, api_key, api_base)?;
 
@@ -336,15 +751,23 @@ async fn setup_bot_env(
         &resolved_api_key,
         &resolved_model,
         resolved_api_base.as_deref(),
+        model_params,
     );
 
     let data = setup::data_dir();
     std::fs::create_dir_all(&data)?;
 
     let db_path = data.join("memory.db");
-    let memory = SqliteMemory::open(&db_path)?;
+    let memory_settings = setup::load_saved_config().map(|c| c.memory).unwrap_or_default();
+    let memory = SqliteMemory::open_with(
+        &db_path,
+        &zenclaw_hub::memory::SqliteOptions {
+            readers: memory_settings.pool_size,
+            busy_timeout: std::time::Duration::from_millis(memory_settings.busy_timeout_ms),
+        },
+    )?;
 
-    let agent = build_agent(&resolved_model, skill_prompt).await;
+    let agent = build_agent(&resolved_model, skills, access).await;
 
     Ok((
         agent,
@@ -383,7 +806,10 @@ fn resolve_config(
         })
         .or_else(|| resolve_api_key(None, &provider_name))
         .or_else(|| {
-            // Interactive fallback prompt if missing
+            // Interactive fallback prompt if missing (not when piped, e.g. `zenclaw pipe`)
+            if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+                return None;
+            }
             let token = crate::tui_menu::run_tui_input(
                 &format!("🔧 Missing {} API Key", provider_name),
                 "Enter API Key to continue:",
@@ -429,16 +855,21 @@ fn resolve_config(
     Ok((provider_name, model, api_key, api_base))
 }
 
-/// Build agent with all tools + plugins.
-async fn build_agent(model: &str, skill_prompt: Option<&str>) -> Agent {
+/// System prompt: skills preset, else remote prompt, else config, else default —
+/// followed by the project context file, if any.
+fn compose_system_prompt(skill_prompt: Option<&str>, remote_prompt: Option<String>) -> String {
     let mut system_prompt = match skill_prompt {
         Some(p) => format!(
             "You are ZenClaw, a helpful AI assistant. You have access to tools to help the user.\n\
+             Today is {{date}}.\n\
              Use tools when needed to accomplish tasks.\n\
              Always be helpful, concise, and accurate.\n\n\
              {}", p
         ),
-        None => zenclaw_core::agent::DEFAULT_SYSTEM_PROMPT.to_string(),
+        None => remote_prompt
+            .or_else(|| setup::load_saved_config().and_then(|c| c.agent.system_prompt))
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| zenclaw_core::agent::DEFAULT_SYSTEM_PROMPT.to_string()),
     };
 
     // Load project context file (.zenclaw.md or ZENCLAW.md) if present
@@ -450,61 +881,313 @@ async fn build_agent(model: &str, skill_prompt: Option<&str>) -> Agent {
             break;
         }
     }
+    system_prompt
+}
+
+/// Re-fetch the remote system prompt whenever the process gets SIGHUP
+/// (the config reload signal), e.g. `kill -HUP $(pidof zenclaw)`.
+fn spawn_prompt_reload(
+    remote: zenclaw_hub::remote_prompt::RemotePrompt,
+    handle: Arc<std::sync::RwLock<String>>,
+) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(mut hup) = signal(SignalKind::hangup()) else { return };
+        while hup.recv().await.is_some() {
+            tracing::info!("SIGHUP — reloading system prompt from {}", remote.url());
+            if let Some(prompt) = remote.fetch().await
+                && let Ok(mut current) = handle.write()
+            {
+                *current = compose_system_prompt(None, Some(prompt));
+            }
+        }
+    });
+    #[cfg(not(unix))]
+    let _ = (remote, handle);
+}
+
+/// Tools exposed to bots and the REST API unless `agent.public_tools` is set.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "read_file",
+    "list_dir",
+    "code_search",
+    "grep",
+    "git",
+    "web_fetch",
+    "web_scrape",
+    "web_search",
+    "system_info",
+    "health",
+    "calculator",
+];
+
+/// Which tools an agent may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolAccess {
+    /// Every tool — the local, interactive CLI.
+    Full,
+    /// `agent.public_tools`, or read-only tools — bots and `serve`, where
+    /// anyone who can message the agent can trigger tools.
+    Public,
+}
 
-    let mut agent = Agent::with_config(AgentConfig {
+/// Skills active in a session: their prompt section and combined tool scope.
+struct ActiveSkills {
+    prompt: String,
+    tools: ToolScope,
+}
+
+/// Build agent with all tools + plugins, restricted to `access` and to the
+/// tool scope of the active skills.
+async fn build_agent(model: &str, skills: Option<&ActiveSkills>, access: ToolAccess) -> Agent {
+    let skill_prompt = skills.map(|s| s.prompt.as_str()).filter(|p| !p.is_empty());
+    let remote = setup::load_saved_config()
+        .and_then(|c| c.agent.system_prompt_url)
+        .filter(|url| !url.trim().is_empty() && skill_prompt.is_none())
+        .map(|url| zenclaw_hub::remote_prompt::RemotePrompt::new(&url, &setup::data_dir()));
+    let remote_prompt = match &remote {
+        Some(r) => r.fetch().await,
+        None => None,
+    };
+
+    let settings = setup::load_saved_config().map(|c| c.agent).unwrap_or_default();
+    let agent_config = AgentConfig {
         model: Some(model.to_string()),
-        system_prompt,
+        system_prompt: compose_system_prompt(skill_prompt, remote_prompt),
+        max_continuations: settings.max_continuations,
+        history_limit: settings.history_limit,
+        history_token_budget: settings.history_token_budget,
+        summarize_after: settings.summarize_after,
+        summary_prompt: settings
+            .summary_prompt
+            .unwrap_or_else(|| zenclaw_core::agent::DEFAULT_SUMMARY_PROMPT.to_string()),
+        confirm_destructive: settings.confirm_destructive,
+        // Bots and `serve` keep each session's files in its own directory
+        workspace_root: match access {
+            ToolAccess::Full => settings.workspace.as_deref().map(PathBuf::from),
+            ToolAccess::Public => Some(
+                settings
+                    .workspace
+                    .as_deref()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| setup::data_dir().join("workspaces")),
+            ),
+        },
+        workspace_per_session: access == ToolAccess::Public,
+        vision_model: settings.vision_model,
         ..Default::default()
-    });
+    };
+    let mut agent = Agent::with_config(agent_config);
+    if let Some(remote) = remote {
+        spawn_prompt_reload(remote, agent.system_prompt_handle());
+    }
 
-    agent.tools.register(ShellTool::new());
-    agent.tools.register(ProcessTool::new());
-    agent.tools.register(SubAgentTool::new());
+    // Every tool call, from any channel, goes to the audit log
+    let bus = Arc::new(EventBus::default());
+    zenclaw_hub::audit::ToolAudit::new(&audit_dir())
+        .with_policy(EnvPolicy::new(settings.env_allow.clone(), settings.env_deny.clone()))
+        .start(&bus);
+
+    // Outbound webhook: every turn, from any channel, is POSTed to the URL
+    if let Some(wh) = setup::load_saved_config()
+        .and_then(|c| c.channels.webhook)
+        .filter(|wh| !wh.url.trim().is_empty())
+    {
+        zenclaw_hub::channels::WebhookChannel::new(zenclaw_hub::channels::WebhookConfig {
+            url: wh.url,
+            secret: wh.secret,
+        })
+        .start(&bus);
+    }
+    agent.set_event_bus(bus);
+
+    let session_env = new_session_env();
+    agent.tools.register(ShellTool::new().with_session_env(session_env.clone()));
+    agent.tools.register(ProcessTool::new().with_session_env(session_env.clone()));
+    agent.tools.register(SubAgentTool::new().with_max_depth(settings.subagent_max_depth));
     agent.tools.register(ReadFileTool::new());
     agent.tools.register(WriteFileTool::new());
     agent.tools.register(EditFileTool::new());
+    agent.tools.register(UndoEditTool::new());
     agent.tools.register(ListDirTool::new());
     agent.tools.register(CodebaseSearchTool::new());
-    agent.tools.register(WebFetchTool::new());
+    agent.tools.register(GrepTool::new());
+    agent.tools.register(GitTool::new());
+    let web_cache_ttl = std::time::Duration::from_secs(settings.web_cache_ttl_secs);
+    agent.tools.register(WebFetchTool::new().with_cache_ttl(web_cache_ttl));
     agent.tools.register(WebScrapeTool::new());
-    agent.tools.register(WebSearchTool::new());
+    agent.tools.register(WebSearchTool::new().with_cache_ttl(web_cache_ttl));
     agent.tools.register(SystemInfoTool::new());
+    agent.tools.register(CalcTool::new());
     agent.tools.register(CronTool::new());
     agent.tools.register(HealthTool::new());
     agent.tools.register(HistoryTool::new());
-    agent.tools.register(EnvTool::new());
+    // Their own connection to memory.db: the store is shared file-level (WAL)
+    match SqliteMemory::open(&setup::data_dir().join("memory.db")) {
+        Ok(memory) => {
+            let memory: Arc<dyn zenclaw_core::memory::MemoryStore> = Arc::new(memory);
+            agent.tools.register(MemoryTool::new(memory.clone()));
+            let search = SearchHistoryTool::new(memory);
+            agent.tools.register(match access {
+                ToolAccess::Full => search,
+                ToolAccess::Public => search.current_session_only(),
+            });
+        }
+        Err(e) => tracing::warn!("Memory tools disabled: {}", e),
+    }
+    agent.tools.register(
+        EnvTool::new()
+            .with_policy(EnvPolicy::new(settings.env_allow.clone(), settings.env_deny.clone()))
+            .with_session_env(session_env),
+    );
 
     // Load plugins
     let data = setup::data_dir();
     let plugin_mgr = PluginManager::new(&data.join("plugins"));
     let plugins = plugin_mgr.load_all().await;
     for plugin in plugins {
-        agent.tools.register(plugin);
+        // Plugins never shadow built-ins; a clashing plugin gets a `plugin_` prefix
+        agent.tools.register_namespaced("plugin", plugin);
+    }
+
+    if access == ToolAccess::Public {
+        match &settings.public_tools {
+            Some(tools) if tools.iter().any(|t| t == "*") => {}
+            Some(tools) => agent.tools.set_allowed(tools),
+            None => agent.tools.set_allowed(READ_ONLY_TOOLS),
+        }
+    }
+    if let Some(skills) = skills {
+        skills.tools.apply(&mut agent.tools);
     }
 
     agent
 }
 
+/// A specialist agent for `chat --router`.
+struct Specialist {
+    name: &'static str,
+    /// Shown to the classifier — say what the agent handles.
+    description: &'static str,
+    /// Fallback routing when classification is ambiguous.
+    keywords: &'static [&'static str],
+    prompt: &'static str,
+    tools: &'static [&'static str],
+}
+
+const SPECIALISTS: &[Specialist] = &[
+    Specialist {
+        name: "coder",
+        description: "Writing, reading, debugging and running code; files, shell commands and git",
+        keywords: &["code", "bug", "error", "compile", "function", "script", "git", "file", "test", "rust", "python"],
+        prompt: "You are the coding specialist. Read the relevant code before changing it, \
+                 keep edits minimal, and run commands to verify your work.",
+        tools: &[
+            "read_file", "write_file", "edit_file", "undo_edit", "list_dir", "code_search", "grep", "git",
+            "exec", "process", "calculator",
+        ],
+    },
+    Specialist {
+        name: "researcher",
+        description: "Finding facts and current information on the web, comparing sources, calculations",
+        keywords: &["search", "find", "latest", "news", "who", "what is", "compare", "price", "research"],
+        prompt: "You are the research specialist. Search and read sources before answering, \
+                 cite the URLs you used, and say when sources disagree.",
+        tools: &["web_search", "web_fetch", "web_scrape", "read_file", "calculator", "system_info"],
+    },
+    Specialist {
+        name: "writer",
+        description: "Drafting, editing, summarizing and translating prose: emails, docs, posts",
+        keywords: &["write", "draft", "email", "rewrite", "summarize", "translate", "essay", "tone"],
+        prompt: "You are the writing specialist. Match the requested tone and format, \
+                 keep it clear and concise, and offer one short revision note if useful.",
+        tools: &["read_file", "write_file", "edit_file", "undo_edit", "list_dir"],
+    },
+];
+
+/// Specialist used when nothing clearly matches.
+const DEFAULT_SPECIALIST: &str = "researcher";
+
+/// Build the router for `chat --router`: one agent per [`SPECIALISTS`] entry.
+/// With `delegate`, every specialist also gets `ask_agent` to hand part of a
+/// task to another specialist (those can't delegate any further).
+async fn build_router(
+    model: &str,
+    skills: Option<&ActiveSkills>,
+    delegate: Option<(Arc<dyn LlmProvider>, Arc<dyn zenclaw_core::memory::MemoryStore>)>,
+) -> AgentRouter {
+    let peers = match delegate {
+        Some((provider, memory)) => {
+            let peers = Box::pin(build_router(model, skills, None)).await;
+            Some((Arc::new(peers), provider, memory))
+        }
+        None => None,
+    };
+
+    let mut router = AgentRouter::new();
+    for spec in SPECIALISTS {
+        let prompt = match skills {
+            Some(skills) => format!("{}\n\n{}", spec.prompt, skills.prompt),
+            None => spec.prompt.to_string(),
+        };
+        // The skill scope is applied after the specialist's allow-list so it narrows it
+        let spec_skills = ActiveSkills {
+            prompt,
+            tools: ToolScope::default(),
+        };
+        let mut agent = build_agent(model, Some(&spec_skills), ToolAccess::Full).await;
+        agent.tools.set_allowed(spec.tools);
+        if let Some((peers, provider, memory)) = &peers {
+            agent.tools.register(AgentDelegateTool::new(peers.clone(), provider.clone(), memory.clone()));
+            let mut allowed = spec.tools.to_vec();
+            allowed.push("ask_agent");
+            agent.tools.set_allowed(&allowed);
+        }
+        if let Some(skills) = skills {
+            skills.tools.apply(&mut agent.tools);
+        }
+        let keywords = spec.keywords.iter().map(|k| k.to_string()).collect();
+        router.register(spec.name, spec.description, keywords, agent);
+    }
+    router.set_default(DEFAULT_SPECIALIST);
+    router
+}
+
 // ─── Main ──────────────────────────────────────────────────
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    // Needs no config, data dir or logging — just print the script
+    if let Some(Commands::Completions { shell }) = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "zenclaw", &mut io::stdout());
+        return Ok(());
+    }
+
     // Setup filesystem log trailing instead of dumping tracing to stdout
     let log_dir = setup::data_dir().join("logs");
     std::fs::create_dir_all(&log_dir).ok();
     let file_appender = tracing_appender::rolling::daily(&log_dir, "zenclaw.log");
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
 
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| EnvFilter::new("info,zenclaw_core=debug,zenclaw_hub=debug")),
-        )
-        .with_writer(non_blocking)
-        .with_ansi(false) // logs in file should probably not have color
-        .init();
-
-    let cli = Cli::parse();
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("info,zenclaw_core=debug,zenclaw_hub=debug"));
+    if cli.json_logs {
+        tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_writer(non_blocking)
+            .init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(non_blocking)
+            .with_ansi(false) // logs in file should probably not have color
+            .init();
+    }
 
     match cli.command {
         // ─── Setup Wizard ──────────────────────────────
@@ -519,6 +1202,10 @@ async fn main() -> anyhow::Result<()> {
             api_key,
             api_base,
             skill,
+            model_params,
+            approve_tools,
+            fallback,
+            router,
         }) => {
             run_chat(
                 provider.as_deref(),
@@ -526,6 +1213,10 @@ async fn main() -> anyhow::Result<()> {
                 api_key.as_deref(),
                 api_base.as_deref(),
                 skill.unwrap_or_default(),
+                parse_model_params(model_params.as_deref())?,
+                approve_tools,
+                &fallback,
+                router,
             )
             .await?;
         }
@@ -536,16 +1227,28 @@ async fn main() -> anyhow::Result<()> {
             model,
             provider,
             api_key,
+            model_params,
         }) => {
             run_ask(
                 provider.as_deref(),
                 model.as_deref(),
                 api_key.as_deref(),
                 &message,
+                parse_model_params(model_params.as_deref())?,
             )
             .await?;
         }
 
+        Some(Commands::Pipe {
+            prompt,
+            json,
+            model,
+            provider,
+            api_key,
+        }) => {
+            run_pipe(provider.as_deref(), model.as_deref(), api_key.as_deref(), &prompt, json).await?;
+        }
+
         // ─── Config Management ─────────────────────────
         Some(Commands::Config { action }) => match action {
             ConfigAction::Show => setup::run_config_show()?,
@@ -560,6 +1263,10 @@ async fn main() -> anyhow::Result<()> {
             run_status().await?;
         }
 
+        Some(Commands::Doctor) => {
+            run_doctor().await?;
+        }
+
         // ─── Telegram Bot ──────────────────────────────
         Some(Commands::Telegram {
             token,
@@ -581,12 +1288,14 @@ async fn main() -> anyhow::Result<()> {
         // ─── Discord Bot ──────────────────────────────
         Some(Commands::Discord {
             token,
+            gateway,
             model,
             provider,
             api_key,
         }) => {
             run_discord(
                 token.as_deref(),
+                gateway,
                 provider.as_deref(),
                 model.as_deref(),
                 api_key.as_deref(),
@@ -615,47 +1324,122 @@ async fn main() -> anyhow::Result<()> {
             .await?;
         }
 
-        // ─── Skills ───────────────────────────────────
-        Some(Commands::Skills { action }) => {
-            run_skills(action).await?;
-        }
-
-        Some(Commands::Serve {
-            host,
-            port,
-            provider,
+        // ─── Matrix Bot ───────────────────────────────
+        Some(Commands::Matrix {
+            homeserver,
+            token,
             model,
+            provider,
             api_key,
+            allowed_rooms,
         }) => {
-            run_serve(
-                &host,
-                port,
+            let rooms = allowed_rooms
+                .map(|s| s.split(',').map(|s| s.trim().to_string()).collect())
+                .unwrap_or_default();
+            run_matrix(
+                homeserver.as_deref(),
+                token.as_deref(),
                 provider.as_deref(),
                 model.as_deref(),
                 api_key.as_deref(),
+                rooms,
             )
             .await?;
         }
 
-        Some(Commands::Whatsapp {
-            bridge,
-            provider,
-            model,
-            api_key,
-            allowed_numbers,
-        }) => {
-            run_whatsapp(
-                &bridge,
-                provider.as_deref(),
-                model.as_deref(),
-                api_key.as_deref(),
-                allowed_numbers.as_deref(),
-            )
-            .await?;
+        // ─── Skills ───────────────────────────────────
+        Some(Commands::Skills { action }) => {
+            run_skills(action).await?;
         }
 
-        Some(Commands::Update) => {
-            run_update_check().await?;
+        Some(Commands::Tools) => {
+            run_tools().await;
+        }
+
+        Some(Commands::Plugins {
+            action: PluginAction::Validate { dir },
+        }) => {
+            run_plugins_validate(dir)?;
+        }
+
+        Some(Commands::Models { provider, api_key, json }) => {
+            run_models(provider.as_deref(), api_key.as_deref(), json).await?;
+        }
+
+        Some(Commands::Eval { file, targets, judge, concurrency, output }) => {
+            run_eval(&file, &targets, judge.as_deref(), concurrency, output).await?;
+        }
+
+        Some(Commands::Prompts { action }) => {
+            run_prompts(action)?;
+        }
+
+        Some(Commands::Theme { action }) => {
+            run_theme(action)?;
+        }
+
+        Some(Commands::Session { action }) => {
+            run_session(action).await?;
+        }
+
+        Some(Commands::Search { query, session, limit, json }) => {
+            run_search(&query, session.as_deref(), limit, json).await?;
+        }
+
+        Some(Commands::Rag { action }) => {
+            run_rag(action).await?;
+        }
+
+        Some(Commands::Schedule { action }) => {
+            run_schedule(action)?;
+        }
+
+        Some(Commands::Daemon {
+            provider,
+            model,
+            api_key,
+        }) => {
+            run_daemon(provider.as_deref(), model.as_deref(), api_key.as_deref()).await?;
+        }
+
+        Some(Commands::Serve {
+            host,
+            port,
+            provider,
+            model,
+            api_key,
+        }) => {
+            run_serve(
+                &host,
+                port,
+                provider.as_deref(),
+                model.as_deref(),
+                api_key.as_deref(),
+            )
+            .await?;
+        }
+
+        Some(Commands::Whatsapp {
+            bridge,
+            provider,
+            model,
+            api_key,
+            allowed_numbers,
+        }) => {
+            run_whatsapp(
+                &bridge,
+                provider.as_deref(),
+                model.as_deref(),
+                api_key.as_deref(),
+                allowed_numbers.as_deref(),
+            )
+            .await?;
+        }
+
+        Some(Commands::Completions { .. }) => unreachable!("handled before logging is set up"),
+
+        Some(Commands::Update { apply }) => {
+            run_update_check(apply).await?;
         }
 
         // ─── Logs Monitoring ───────────────────────────
@@ -663,8 +1447,16 @@ async fn main() -> anyhow::Result<()> {
             run_logs(lines).await?;
         }
 
+        // ─── Tool Audit Log ────────────────────────────
+        Some(Commands::Audit { tool, session, lines, follow, json }) => {
+            let filter = zenclaw_hub::audit::AuditFilter { tool, session };
+            run_audit(filter, lines, follow, json).await?;
+        }
+
         // ─── Default: show interactive menu loop ─────────────
         None => {
+            let update_badge = spawn_update_check(cli.no_update_check);
+
             loop {
                 // Clear the screen for a cleaner UI loop experience
                 print!("\x1B[2J\x1B[1;1H");
@@ -672,7 +1464,11 @@ async fn main() -> anyhow::Result<()> {
                 
                 let has_config = ZenClawConfig::default_path().exists();
 
-                let selected_action = tui_menu::run_main_menu(has_config)?;
+                let latest = update_badge
+                    .lock()
+                    .ok()
+                    .and_then(|u| u.as_ref().map(|info| info.latest.clone()));
+                let selected_action = tui_menu::run_main_menu(has_config, latest.as_deref())?;
 
                 if selected_action.is_none() {
                     break;
@@ -683,23 +1479,25 @@ async fn main() -> anyhow::Result<()> {
                 
                 let result = match choice.as_str() {
                     "setup" => setup::run_setup(),
-                    "chat" => run_chat(None, None, None, None, vec![]).await,
+                    "chat" => run_chat(None, None, None, None, vec![], None, false, &[], false).await,
                     "switch" => {
-                        let _ = setup::run_model_switcher();
+                        let _ = setup::run_model_switcher().await;
                         Ok(())
                     }
                     "telegram" => run_telegram(None, None, None, None, None).await,
-                    "discord" => run_discord(None, None, None, None).await,
+                    "discord" => run_discord(None, false, None, None, None).await,
                     "whatsapp" => run_whatsapp("http://localhost:3001", None, None, None, None).await,
                     "api" => run_serve("127.0.0.1", 3000, None, None, None).await,
                     "skills" => run_skills(None).await,
+                    "knowledge" => run_knowledge().await,
                     "settings" => {
                         loop {
                             let config_options = vec![
                                 tui_menu::MenuItem { label: "1. Show Configuration".into(), description: "Display current config values.".into(), action_key: "0".into() },
                                 tui_menu::MenuItem { label: "2. Show Config Path".into(), description: "Show the absolute path to your config file.".into(), action_key: "1".into() },
                                 tui_menu::MenuItem { label: "3. Run Setup Wizard".into(), description: "Re-run the first-time setup wizard to generate a new config.".into(), action_key: "2".into() },
-                                tui_menu::MenuItem { label: "4. Back".into(), description: "Return to main menu.".into(), action_key: "3".into() },
+                                tui_menu::MenuItem { label: "4. Memory Usage".into(), description: "Sessions, messages and size of the memory database.".into(), action_key: "4".into() },
+                                tui_menu::MenuItem { label: "5. Back".into(), description: "Return to main menu.".into(), action_key: "3".into() },
                             ];
                             let config_sel = tui_menu::run_tui_menu("⚙️ Settings", &config_options, 0)?;
                             
@@ -727,6 +1525,9 @@ async fn main() -> anyhow::Result<()> {
                                 Some("2") => {
                                     setup::run_setup().ok();
                                 },
+                                Some("4") => {
+                                    tui_menu::run_tui_text_viewer("🧠 Memory Usage", &memory_status()).ok();
+                                },
                                 Some("3") | None => {
                                     break Ok(());
                                 }
@@ -734,7 +1535,7 @@ async fn main() -> anyhow::Result<()> {
                             }
                         }
                     }
-                    "updates" => run_update_check().await,
+                    "updates" => run_update_check(false).await,
                     "logs" => run_logs(50).await,
                     "exit" => {
                         should_exit = true;
@@ -748,7 +1549,7 @@ async fn main() -> anyhow::Result<()> {
                     let _ = tui_menu::run_tui_error("Execution Error", &e.to_string());
                 } else if !should_exit {
                     // Only pause if this was NOT a TUI command that already took over the screen
-                    let tui_commands = ["setup", "switch", "telegram", "discord", "whatsapp", "api", "skills", "settings", "logs", "chat"];
+                    let tui_commands = ["setup", "switch", "telegram", "discord", "whatsapp", "api", "skills", "knowledge", "settings", "logs", "chat"];
                     if !tui_commands.contains(&choice.as_str()) {
                         println!("\n{}", "Press Enter to return to main menu...".dimmed());
                         let mut input = String::new();
@@ -768,34 +1569,83 @@ async fn main() -> anyhow::Result<()> {
 
 // ─── Command Handlers ──────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 async fn run_chat(
     provider_name: Option<&str>,
     model: Option<&str>,
     api_key: Option<&str>,
     api_base: Option<&str>,
     active_skills: Vec<String>,
+    model_params: Option<serde_json::Value>,
+    approve_tools: bool,
+    fallback: &[String],
+    router: bool,
 ) -> anyhow::Result<()> {
-    let skill_prompt = if active_skills.is_empty() {
+    let skills = if active_skills.is_empty() {
         None
     } else {
         let data = setup::data_dir();
         let mut skill_mgr = SkillManager::new(&data.join("skills"));
         let _ = skill_mgr.load_all().await;
-        let prompt = skill_mgr.build_prompt(&active_skills);
-        if prompt.is_empty() { None } else { Some(prompt) }
+
+        // Ask for the skills' declared inputs; a skipped required one stops here
+        let mut inputs = std::collections::HashMap::new();
+        for input in skill_mgr.inputs(&active_skills) {
+            let value = crate::tui_menu::run_tui_input(&format!("🧩 Skill input: {}", input.name), &input.prompt, "", false)?
+                .unwrap_or_default();
+            let missing = value.trim().is_empty() && input.required;
+            inputs.insert(input.name.clone(), value);
+            if missing {
+                break;
+            }
+        }
+
+        let prompt = skill_mgr
+            .build_prompt(&active_skills, &inputs)
+            .map_err(|e| anyhow::anyhow!("Can't activate skills: {}", e))?;
+        Some(ActiveSkills {
+            prompt,
+            tools: skill_mgr.tool_scope(&active_skills),
+        })
     };
 
-    let (agent, provider, memory, provider_name, model) = setup_bot_env(
+    let (mut agent, provider, memory, provider_name, model) = setup_bot_env(
         provider_name,
         model,
         api_key,
         api_base,
-        skill_prompt.as_deref()
+        skills.as_ref(),
+        model_params,
+        ToolAccess::Full,
     ).await?;
+    let provider = with_fallbacks(provider, fallback);
+
+    if router {
+        let provider: Arc<dyn LlmProvider> = Arc::from(provider);
+        let memory = Arc::new(memory);
+        let delegate = (provider.clone(), memory.clone() as Arc<dyn zenclaw_core::memory::MemoryStore>);
+        let router = build_router(&model, skills.as_ref(), Some(delegate)).await;
+        return run_router_chat(&router, provider.as_ref(), &memory, &provider_name, &model).await;
+    }
 
-    ui::print_session_info(&provider_name, &model, agent.tools.len(), &active_skills);
+    let scoped = skills.as_ref().is_some_and(|s| !s.tools.is_empty());
+    let tool_names: Vec<&str> = agent.tools.names().into_iter().filter(|t| agent.tools.is_allowed(t)).collect();
+    ui::print_session_info(&provider_name, &model, &tool_names, scoped, &active_skills);
 
     let session_key = "cli:default";
+
+    // --approve-tools asks for every tool, confirm_destructive only for
+    // the ones that write files or run commands
+    if approve_tools {
+        agent.config.confirm_destructive = false;
+    }
+    let approvals = if approve_tools || agent.config.confirm_destructive {
+        let (approver, rx) = tui_app::approval_channel();
+        agent.set_approver(approver);
+        Some(rx)
+    } else {
+        None
+    };
     
     // Set up Alternate Screen & Raw Mode for TUI
     crossterm::terminal::enable_raw_mode()?;
@@ -819,6 +1669,7 @@ async fn run_chat(
         std::sync::Arc::new(memory),
         session_key.to_string(),
         bus,
+        approvals,
     ).await;
 
     // Restore terminal exactly as before
@@ -837,27 +1688,150 @@ async fn run_chat(
     Ok(())
 }
 
+/// Line-based chat where every message goes through the router.
+async fn run_router_chat(
+    router: &AgentRouter,
+    provider: &dyn LlmProvider,
+    memory: &SqliteMemory,
+    provider_name: &str,
+    model: &str,
+) -> anyhow::Result<()> {
+    ui::print_model_status(provider_name, model);
+    let agents: Vec<String> = router.list().iter().map(|(name, _)| name.to_string()).collect();
+    println!(
+        "  {} {} — type /quit to exit\n",
+        "Router:".dimmed(),
+        agents.join(", ").cyan()
+    );
+
+    // All specialists share one session so context carries across hand-offs
+    let session_key = "cli:router";
+    let skin = ui::make_mad_skin();
+    let stdin = io::stdin();
+    loop {
+        print!("{} ", "You ›".bright_green().bold());
+        io::stdout().flush().ok();
+
+        let mut input = String::new();
+        if stdin.read_line(&mut input)? == 0 {
+            break;
+        }
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if matches!(input, "/quit" | "/exit" | "exit") {
+            break;
+        }
+
+        match router.process(provider, memory, input, session_key).await {
+            Ok((agent, response)) => {
+                ui::print_ai_prefix();
+                println!("{}", ui::badge(&agent));
+                skin.print_text(&response);
+                ui::print_turn_divider();
+            }
+            Err(e) => eprintln!("{}: {}", "Error".red(), e),
+        }
+    }
+
+    Ok(())
+}
+
 async fn run_ask(
     provider_name: Option<&str>,
     model: Option<&str>,
     api_key: Option<&str>,
     message: &str,
+    model_params: Option<serde_json::Value>,
 ) -> anyhow::Result<()> {
     let (provider_name, model, api_key, _api_base) =
         resolve_config(provider_name, model, api_key, None)?;
 
-    let provider = create_provider(&provider_name, &api_key, &model, None);
+    let provider = create_provider(&provider_name, &api_key, &model, None, model_params);
     let memory = zenclaw_core::memory::InMemoryStore::new();
-    let agent = build_agent(&model, None).await;
-
-    match agent.process(&provider, &memory, message, "oneshot", None).await {
-        Ok(response) => println!("{}", response),
+    let agent = build_agent(&model, None, ToolAccess::Full).await;
+
+    match agent.process_detailed(&provider, &memory, message, "oneshot", None).await {
+        Ok((response, turn)) => {
+            println!("{}", response);
+            // Usage goes to stderr so the answer stays pipeable
+            eprintln!(
+                "{}",
+                format!(
+                    "tokens: {}{} in / {} out",
+                    if turn.estimated { "≈" } else { "" },
+                    turn.usage.prompt_tokens,
+                    turn.usage.completion_tokens
+                )
+                .dimmed()
+            );
+        }
         Err(e) => eprintln!("{}: {}", "Error".red(), e),
     }
 
     Ok(())
 }
 
+/// `zenclaw pipe`: stdin + prompt in, the bare answer (or JSON) out.
+/// Everything else goes to stderr or the log file, so stdout can be redirected.
+async fn run_pipe(
+    provider_name: Option<&str>,
+    model: Option<&str>,
+    api_key: Option<&str>,
+    prompt: &str,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut input = String::new();
+    if !io::stdin().is_terminal() {
+        io::stdin().read_to_string(&mut input)?;
+    }
+    let message = match (input.trim().is_empty(), prompt.trim().is_empty()) {
+        (true, true) => anyhow::bail!("Nothing to do: pipe some input in and/or pass a prompt"),
+        (true, false) => prompt.to_string(),
+        (false, true) => input.trim_end().to_string(),
+        (false, false) => format!("{}\n\n{}", input.trim_end(), prompt),
+    };
+
+    let (provider_name, model, api_key, api_base) = resolve_config(provider_name, model, api_key, None)?;
+    let provider = create_provider(&provider_name, &api_key, &model, api_base.as_deref(), None);
+    let memory = zenclaw_core::memory::InMemoryStore::new();
+    let agent = build_agent(&model, None, ToolAccess::Full).await;
+
+    let bus = EventBus::new(1024);
+    let mut events = bus.subscribe_system();
+    let (response, turn) = agent
+        .process_detailed(&provider, &memory, &message, "pipe", Some(&bus))
+        .await?;
+
+    if !json {
+        println!("{}", response);
+        return Ok(());
+    }
+
+    let mut tool_calls = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if event.event_type == "tool_use" {
+            tool_calls.push(serde_json::json!({
+                "tool": event.data["tool"],
+                "args": event.data["args"],
+            }));
+        }
+    }
+    let out = serde_json::json!({
+        "response": response,
+        "usage": {
+            "prompt_tokens": turn.usage.prompt_tokens,
+            "completion_tokens": turn.usage.completion_tokens,
+            "total_tokens": turn.usage.total_tokens,
+            "estimated": turn.estimated,
+        },
+        "tool_calls": tool_calls,
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
 async fn run_status() -> anyhow::Result<()> {
     let has_config = ZenClawConfig::default_path().exists();
     let config = setup::load_saved_config();
@@ -897,10 +1871,91 @@ async fn run_status() -> anyhow::Result<()> {
         data.join("skills").display()
     ));
 
+    out.push_str("\n  Memory:\n");
+    out.push_str(&memory_status());
+
     crate::tui_menu::run_tui_text_viewer("📊 System Status", &out).ok();
     Ok(())
 }
 
+/// Run the readiness checks and print a pass/warn/fail line for each.
+/// Fails (non-zero exit) when any check fails.
+async fn run_doctor() -> anyhow::Result<()> {
+    use zenclaw_hub::doctor::{self, CheckStatus, Report};
+
+    let saved = setup::load_saved_config();
+    let provider_name = saved
+        .as_ref()
+        .map(|c| c.provider.provider.clone())
+        .unwrap_or_else(|| "openai".to_string());
+    let model = saved
+        .as_ref()
+        .map(|c| c.provider.model.clone())
+        .unwrap_or_else(|| default_model(&provider_name).to_string());
+    let api_base = saved.as_ref().and_then(|c| c.provider.api_base.clone());
+    let api_key = saved
+        .as_ref()
+        .and_then(|c| c.provider.api_key.clone())
+        .or_else(|| resolve_api_key(None, &provider_name));
+
+    println!("{}", "🩺 ZenClaw doctor".bold());
+    println!("   Provider: {} ({})\n", provider_name, model);
+
+    let mut report = Report::default();
+    match api_key {
+        Some(key) => {
+            let provider = create_provider(&provider_name, &key, &model, api_base.as_deref(), None);
+            report.push(doctor::check_provider(provider.as_ref(), Some(&model)).await);
+        }
+        None => report.push(doctor::Check::new(
+            "provider",
+            CheckStatus::Fail,
+            format!("no API key for {} (run `zenclaw setup`)", provider_name),
+        )),
+    }
+
+    let ollama_url = api_base
+        .as_deref()
+        .filter(|_| provider_name == "ollama")
+        .unwrap_or(doctor::DEFAULT_OLLAMA_URL);
+    report.push(doctor::check_ollama(ollama_url, provider_name == "ollama").await);
+    report.push(doctor::check_database(&setup::data_dir().join("memory.db")));
+    report.push(doctor::check_api_keys(&provider_name));
+
+    println!("{}", report.render());
+
+    let failed = report.checks.iter().filter(|c| c.status == CheckStatus::Fail).count();
+    if failed > 0 {
+        anyhow::bail!("{} check(s) failed", failed);
+    }
+    println!("\n{}", "All good.".green());
+    Ok(())
+}
+
+/// Memory database summary for the status screens (doesn't create the DB).
+fn memory_status() -> String {
+    let db_path = setup::data_dir().join("memory.db");
+    if !db_path.exists() {
+        return "    No memory database yet.\n".to_string();
+    }
+
+    let stats = match SqliteMemory::open(&db_path).and_then(|m| m.stats()) {
+        Ok(stats) => stats,
+        Err(e) => return format!("    ❌ Failed to read {}: {}\n", db_path.display(), e),
+    };
+
+    let mb = stats.db_bytes as f64 / (1024.0 * 1024.0);
+    let mut out = String::new();
+    out.push_str(&format!("    Database: {} ({:.2} MB)\n", db_path.display(), mb));
+    out.push_str(&format!("    Sessions: {}\n", stats.sessions));
+    out.push_str(&format!("    Messages: {}\n", stats.messages));
+    out.push_str(&format!("    Facts:    {}\n", stats.facts));
+    if let (Some(oldest), Some(newest)) = (&stats.oldest, &stats.newest) {
+        out.push_str(&format!("    Range:    {} → {} (UTC)\n", oldest, newest));
+    }
+    out
+}
+
 
 async fn run_telegram(
     cli_bot_token: Option<&str>,
@@ -915,7 +1970,9 @@ async fn run_telegram(
         cli_model,
         cli_api_key,
         None,
-        None
+        None,
+        None,
+        ToolAccess::Public,
     ).await?;
 
     let agent = Arc::new(agent);
@@ -962,6 +2019,16 @@ async fn run_telegram(
             bot_token: token.clone(),
             allowed_users: allowed.clone(),
             poll_timeout: 30,
+            reply_format: saved
+                .as_ref()
+                .map(|c| c.channels.reply_format_for("telegram"))
+                .unwrap_or_default(),
+            greeting: saved.as_ref().and_then(|c| c.channels.greeting_for("telegram")),
+            reconnect: saved
+                .as_ref()
+                .map(|c| c.channels.reconnect_for("telegram"))
+                .unwrap_or_default(),
+            rate_limit: rate_limiter(),
         };
 
         let mut telegram = zenclaw_hub::channels::TelegramChannel::new(config);
@@ -995,6 +2062,7 @@ async fn run_telegram(
 
 async fn run_discord(
     cli_bot_token: Option<&str>,
+    cli_gateway: bool,
     cli_provider: Option<&str>,
     cli_model: Option<&str>,
     cli_api_key: Option<&str>,
@@ -1004,7 +2072,9 @@ async fn run_discord(
         cli_model,
         cli_api_key,
         None,
-        None
+        None,
+        None,
+        ToolAccess::Public,
     ).await?;
 
     let agent = Arc::new(agent);
@@ -1021,6 +2091,11 @@ async fn run_discord(
                 .map(|d| d.bot_token.clone())
                 .filter(|d| !d.is_empty())
         });
+    let use_gateway = cli_gateway
+        || saved
+            .as_ref()
+            .and_then(|c| c.channels.discord.as_ref())
+            .is_some_and(|d| d.use_gateway);
 
     loop {
         let token = match current_token {
@@ -1043,6 +2118,17 @@ async fn run_discord(
         let config = DiscordConfig {
             bot_token: token.clone(),
             allowed_users: vec![],
+            reply_format: saved
+                .as_ref()
+                .map(|c| c.channels.reply_format_for("discord"))
+                .unwrap_or_default(),
+            greeting: saved.as_ref().and_then(|c| c.channels.greeting_for("discord")),
+            reconnect: saved
+                .as_ref()
+                .map(|c| c.channels.reconnect_for("discord"))
+                .unwrap_or_default(),
+            rate_limit: rate_limiter(),
+            use_gateway,
         };
 
         let mut discord = zenclaw_hub::channels::DiscordChannel::new(config);
@@ -1052,8 +2138,8 @@ async fn run_discord(
                 let _ = setup::run_config_set("discord_token", &token);
                 let details = [
                     ("Channel", "Discord"),
-                    ("Connection", "Gateway/Secure"),
-                    ("Allowed Guilds", "All"),
+                    ("Connection", if use_gateway { "Gateway" } else { "DM polling" }),
+                    ("Guild Channels", if use_gateway { "When mentioned" } else { "Off" }),
                 ];
                 let _ = crate::tui_menu::run_bot_dashboard("Discord", &resolved_provider, &resolved_model, &details, None);
                 discord.stop().await;
@@ -1084,7 +2170,9 @@ async fn run_slack(
         cli_model,
         cli_api_key,
         None,
-        None
+        None,
+        None,
+        ToolAccess::Public,
     ).await?;
 
     let agent = Arc::new(agent);
@@ -1123,6 +2211,16 @@ async fn run_slack(
         let config = zenclaw_hub::channels::SlackConfig {
             bot_token: token.clone(),
             allowed_channels: allowed_channels.clone(),
+            reply_format: saved
+                .as_ref()
+                .map(|c| c.channels.reply_format_for("slack"))
+                .unwrap_or_default(),
+            greeting: saved.as_ref().and_then(|c| c.channels.greeting_for("slack")),
+            reconnect: saved
+                .as_ref()
+                .map(|c| c.channels.reconnect_for("slack"))
+                .unwrap_or_default(),
+            rate_limit: rate_limiter(),
         };
 
         let mut slack = zenclaw_hub::channels::SlackChannel::new(config);
@@ -1151,6 +2249,116 @@ async fn run_slack(
     }
 }
 
+async fn run_matrix(
+    cli_homeserver: Option<&str>,
+    cli_access_token: Option<&str>,
+    cli_provider: Option<&str>,
+    cli_model: Option<&str>,
+    cli_api_key: Option<&str>,
+    allowed_rooms: Vec<String>,
+) -> anyhow::Result<()> {
+    let (agent, provider, memory, resolved_provider, resolved_model) = setup_bot_env(
+        cli_provider,
+        cli_model,
+        cli_api_key,
+        None,
+        None,
+        None,
+        ToolAccess::Public,
+    ).await?;
+
+    let agent = Arc::new(agent);
+    let provider = Arc::new(provider);
+    let memory = Arc::new(memory);
+
+    let saved = setup::load_saved_config();
+    let saved_matrix = saved.as_ref().and_then(|c| c.channels.matrix.as_ref());
+    let homeserver = match cli_homeserver
+        .map(|s| s.to_string())
+        .or_else(|| saved_matrix.map(|m| m.homeserver_url.clone()).filter(|s| !s.is_empty()))
+    {
+        Some(url) => url,
+        None => {
+            let url = crate::tui_menu::run_tui_input(
+                "Matrix Homeserver Required",
+                "Enter your homeserver URL:",
+                "https://matrix.org",
+                false
+            ).ok().flatten().unwrap_or_default();
+
+            if url.is_empty() {
+                return Ok(());
+            }
+            url
+        }
+    };
+    let allowed_rooms = if allowed_rooms.is_empty() {
+        saved_matrix.map(|m| m.allowed_rooms.clone()).unwrap_or_default()
+    } else {
+        allowed_rooms
+    };
+    let mut current_token = cli_access_token
+        .map(|s| s.to_string())
+        .or_else(|| saved_matrix.map(|m| m.access_token.clone()).filter(|s| !s.is_empty()));
+
+    loop {
+        let token = match current_token {
+            Some(ref t) => t.clone(),
+            None => {
+                let t = crate::tui_menu::run_tui_input(
+                    "Matrix Access Token Required",
+                    "Enter the bot account's access token:",
+                    "",
+                    true
+                ).ok().flatten().unwrap_or_default();
+
+                if t.is_empty() {
+                    return Ok(());
+                }
+                t
+            }
+        };
+
+        let config = zenclaw_hub::channels::MatrixConfig {
+            homeserver_url: homeserver.clone(),
+            access_token: token.clone(),
+            allowed_rooms: allowed_rooms.clone(),
+            reply_format: saved
+                .as_ref()
+                .map(|c| c.channels.reply_format_for("matrix"))
+                .unwrap_or_default(),
+            greeting: saved.as_ref().and_then(|c| c.channels.greeting_for("matrix")),
+            rate_limit: rate_limiter(),
+        };
+
+        let mut matrix = zenclaw_hub::channels::MatrixChannel::new(config);
+
+        match matrix.start(agent.clone(), provider.clone(), memory.clone()).await {
+            Ok(_) => {
+                let _ = setup::run_config_set("matrix_homeserver", &homeserver);
+                let _ = setup::run_config_set("matrix_token", &token);
+                let details = [
+                    ("Channel", "Matrix"),
+                    ("Homeserver", homeserver.as_str()),
+                    ("Allowed Rooms", if allowed_rooms.is_empty() { "All" } else { "Restricted" }),
+                ];
+                let _ = crate::tui_menu::run_bot_dashboard("Matrix", &resolved_provider, &resolved_model, &details, None);
+                matrix.stop().await;
+                break Ok(());
+            }
+            Err(e) => {
+                let error_msg = e.to_string();
+                if error_msg.contains("M_UNKNOWN_TOKEN") || error_msg.contains("M_MISSING_TOKEN") {
+                    let _ = crate::tui_menu::run_tui_error("Matrix Connection Failed", &format!("{}\n\nPlease check your access token.", error_msg));
+                    current_token = None;
+                } else {
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+}
+
 async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
     let data = setup::data_dir();
     let mut skill_mgr = SkillManager::new(&data.join("skills"));
@@ -1159,9 +2367,24 @@ async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
     match action {
         Some(SkillAction::Show { name }) => {
             if let Some(skill) = skill_mgr.get(&name) {
+                let mut tools = String::new();
+                if !skill.tools_allow.is_empty() {
+                    tools.push_str(&format!("Tools allowed: {}\n", skill.tools_allow.join(", ")));
+                }
+                if !skill.tools_deny.is_empty() {
+                    tools.push_str(&format!("Tools denied: {}\n", skill.tools_deny.join(", ")));
+                }
+                if !skill.inputs.is_empty() {
+                    let inputs: Vec<String> = skill
+                        .inputs
+                        .iter()
+                        .map(|i| if i.required { format!("{} (required)", i.name) } else { i.name.clone() })
+                        .collect();
+                    tools.push_str(&format!("Inputs: {}\n", inputs.join(", ")));
+                }
                 let content = format!(
-                    "Skill: {}\nDescription: {}\nFile: {}\n\n{}",
-                    skill.title, skill.description, skill.path.display(), skill.content
+                    "Skill: {}\nDescription: {}\n{}File: {}\n\n{}",
+                    skill.title, skill.description, tools, skill.path.display(), skill.content
                 );
                 crate::tui_menu::run_tui_text_viewer(&skill.title, &content).ok();
             } else {
@@ -1169,6 +2392,38 @@ async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
                 crate::tui_menu::run_tui_error("Skill Not Found", &format!("Skill '{}' not found.\n\nAvailable: {}", name, available)).ok();
             }
         }
+        Some(SkillAction::Import { source, name, yes }) => {
+            let text = zenclaw_hub::skills::fetch_skill_source(&source).await?;
+            let skill = zenclaw_hub::skills::parse_skill_markdown(&text)
+                .map_err(|e| anyhow::anyhow!("{} is not a valid skill: {}", source, e))?;
+
+            let name = name.unwrap_or_else(|| zenclaw_hub::skills::skill_name_from_source(&source));
+            if !zenclaw_hub::skills::is_valid_skill_name(&name) {
+                anyhow::bail!("'{}' isn't a valid skill name — use lowercase letters, digits, '-' and '_' (pass --name)", name);
+            }
+
+            if skill_mgr.get(&name).is_some() && !yes {
+                if !io::stdin().is_terminal() {
+                    anyhow::bail!("skill '{}' already exists — pass --yes to overwrite it", name);
+                }
+                print!("  Skill '{}' already exists. Overwrite? [y/N] ", name);
+                io::stdout().flush()?;
+                let mut answer = String::new();
+                io::stdin().read_line(&mut answer)?;
+                if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+                    println!("  Import cancelled.");
+                    return Ok(());
+                }
+            }
+
+            skill_mgr.save_skill(&name, &skill).await?;
+            println!("  {} {} — {}", "✅ Imported skill".green(), name.bold(), skill.description.dimmed());
+            println!("  Use it with: zenclaw chat --skill {}", name);
+        }
+        Some(SkillAction::Export { name, path }) => {
+            skill_mgr.export_skill(&name, &path).await?;
+            println!("  {} {} → {}", "✅ Exported skill".green(), name.bold(), path.display());
+        }
         _ => {
             loop {
                 let mut items = vec![];
@@ -1201,7 +2456,8 @@ async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
                         if let Some(name) = name_input {
                             if !name.trim().is_empty() {
                                 if let Ok(Some((t, d, c))) = crate::tui_menu::run_tui_skill_editor(&name, &name, "", "") {
-                                    skill_mgr.save_skill(&name, &t, &d, &c).await?;
+                                    let skill = ParsedSkill { title: t, description: d, content: c, ..Default::default() };
+                                    skill_mgr.save_skill(&name, &skill).await?;
                                 }
                             }
                         }
@@ -1210,10 +2466,10 @@ async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
 
                     // Clone skill data to release immutable borrow on skill_mgr
                     let skill_data = skill_mgr.get(&action_key).map(|s| {
-                        (s.name.clone(), s.title.clone(), s.description.clone(), s.content.clone(), s.path.display().to_string())
+                        (s.name.clone(), s.title.clone(), s.description.clone(), s.content.clone(), s.path.display().to_string(), ParsedSkill::from(s))
                     });
 
-                    if let Some((s_name, s_title, s_desc, s_content, s_path)) = skill_data {
+                    if let Some((s_name, s_title, s_desc, s_content, s_path, s_file)) = skill_data {
                         loop {
                             let skill_options = vec![
                                 crate::tui_menu::MenuItem { label: "📄 View Content".into(), description: "Read the skill markdown content.".into(), action_key: "view".into() },
@@ -1230,7 +2486,9 @@ async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
                                 },
                                 Some("edit") => {
                                     if let Ok(Some((t, d, c))) = crate::tui_menu::run_tui_skill_editor(&s_name, &s_title, &s_desc, &s_content) {
-                                        skill_mgr.save_skill(&s_name, &t, &d, &c).await?;
+                                        // Keep the front-matter the editor doesn't show (tools, inputs)
+                                        let skill = ParsedSkill { title: t, description: d, content: c, ..s_file.clone() };
+                                        skill_mgr.save_skill(&s_name, &skill).await?;
                                         break;
                                     }
                                 },
@@ -1255,33 +2513,810 @@ async fn run_skills(action: Option<SkillAction>) -> anyhow::Result<()> {
     Ok(())
 }
 
-// ─── Serve (REST API) ──────────────────────────────────────
+// ─── Tools ─────────────────────────────────────────────────
+
+async fn run_tools() {
+    let model = setup::load_saved_config()
+        .map(|c| c.provider.model)
+        .unwrap_or_else(|| default_model("openai").to_string());
+    let agent = build_agent(&model, None, ToolAccess::Full).await;
+
+    println!("\n  {} {} tools available\n", "🧰".bold(), agent.tools.len());
+    for name in agent.tools.names() {
+        let Some(tool) = agent.tools.get(name) else { continue };
+        let params = serde_json::to_string_pretty(&tool.parameters()).unwrap_or_default();
+        println!("  {}", name.cyan().bold());
+        println!("  {}", tool.description());
+        for line in params.lines() {
+            println!("    {}", line.dimmed());
+        }
+        println!();
+    }
+}
 
-async fn run_serve(
-    cli_host: &str,
-    cli_port: u16,
-    cli_provider: Option<&str>,
-    cli_model: Option<&str>,
-    cli_api_key: Option<&str>,
-) -> anyhow::Result<()> {
-    let (agent, provider, memory, resolved_provider, resolved_model) = setup_bot_env(
-        cli_provider,
-        cli_model,
-        cli_api_key,
-        None,
-        None
-    ).await?;
+fn run_plugins_validate(dir: Option<PathBuf>) -> anyhow::Result<()> {
+    let dir = dir.unwrap_or_else(|| setup::data_dir().join("plugins"));
+    let results = PluginManager::validate(&dir);
+    if results.is_empty() {
+        anyhow::bail!("No plugins (plugin.toml or plugin.json) found in {}", dir.display());
+    }
 
-    let data = setup::data_dir();
-    let rag_path = data.join("rag.db");
-    let rag = zenclaw_hub::memory::RagStore::open(&rag_path).ok().map(Arc::new);
+    println!();
+    let mut invalid = 0;
+    for (path, result) in &results {
+        match result {
+            Ok(manifest) => println!(
+                "  {} {} {} ({})",
+                "✅".green(),
+                manifest.name.cyan().bold(),
+                manifest.version.as_deref().unwrap_or(""),
+                path.display()
+            ),
+            Err(reason) => {
+                invalid += 1;
+                println!("  {} {}\n     {}", "❌".red(), path.display(), reason.red());
+            }
+        }
+    }
+    println!();
+
+    if invalid > 0 {
+        anyhow::bail!("{} of {} plugin(s) invalid", invalid, results.len());
+    }
+    Ok(())
+}
+
+/// List the provider's models, falling back to the built-in list when the
+/// provider has no models endpoint or can't be reached.
+async fn run_models(provider_name: Option<&str>, api_key: Option<&str>, json: bool) -> anyhow::Result<()> {
+    let (provider_name, model, api_key, api_base) = resolve_config(provider_name, None, api_key, None)?;
+    let provider = create_provider(&provider_name, &api_key, &model, api_base.as_deref(), None);
+
+    let (models, source) = match provider.list_models().await {
+        Ok(models) if !models.is_empty() => (models, "api"),
+        result => {
+            if let Err(e) = result {
+                eprintln!("  {} Couldn't list {} models ({}), showing the built-in list", "⚠️".yellow(), provider_name, e);
+            }
+            let known = setup::known_models(&provider_name).iter().map(|m| m.to_string()).collect();
+            (known, "builtin")
+        }
+    };
+
+    let vision: Vec<&String> = models.iter().filter(|id| provider.supports_vision(id)).collect();
+    if json {
+        let out = serde_json::json!({ "provider": provider_name, "source": source, "models": models, "vision": vision });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    if models.is_empty() {
+        anyhow::bail!("No model list available for '{}'", provider_name);
+    }
+    println!("\n  {} {} models ({})\n", "🗂️".bold(), provider_name.cyan().bold(), if source == "api" { "live" } else { "built-in list" });
+    for id in &models {
+        let sees = if vision.contains(&id) { format!(" {}", "👁 vision".dimmed()) } else { String::new() };
+        if *id == model {
+            println!("  {} {}{}", "●".green(), id.green().bold(), sees);
+        } else {
+            println!("    {}{}", id, sees);
+        }
+    }
+    println!("\n  {}\n", "Switch with: zenclaw config set model <id>".dimmed());
+    Ok(())
+}
+
+// ─── Eval ──────────────────────────────────────────────────
+
+/// Build an eval target from `provider[:model]`, using the saved config or
+/// environment for the API key.
+fn eval_target(spec: &str) -> anyhow::Result<zenclaw_hub::eval::EvalTarget> {
+    let (provider_name, model) = match spec.split_once(':') {
+        Some((p, m)) => (p, m.to_string()),
+        None => (spec, default_model(spec).to_string()),
+    };
+
+    let saved = setup::load_saved_config().filter(|c| c.provider.provider == provider_name);
+    let api_key = saved
+        .as_ref()
+        .and_then(|c| c.provider.api_key.clone())
+        .or_else(|| resolve_api_key(None, provider_name))
+        .ok_or_else(|| anyhow::anyhow!("No API key for '{}' — set it in the config or environment", provider_name))?;
+    let api_base = saved.as_ref().and_then(|c| c.provider.api_base.clone());
+
+    let provider = create_provider(provider_name, &api_key, &model, api_base.as_deref(), None);
+    Ok(zenclaw_hub::eval::EvalTarget {
+        label: format!("{}:{}", provider_name, model),
+        model,
+        provider: Arc::new(provider),
+    })
+}
+
+async fn run_eval(
+    file: &std::path::Path,
+    target_specs: &[String],
+    judge: Option<&str>,
+    concurrency: usize,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    use zenclaw_hub::eval::{self, EvalOptions};
+
+    let prompts = eval::load_prompts(file)?;
+    if prompts.is_empty() {
+        anyhow::bail!("No prompts in {}", file.display());
+    }
+
+    let targets = if target_specs.is_empty() {
+        let (provider_name, model, _, _) = resolve_config(None, None, None, None)?;
+        vec![eval_target(&format!("{}:{}", provider_name, model))?]
+    } else {
+        target_specs.iter().map(|s| eval_target(s)).collect::<anyhow::Result<Vec<_>>>()?
+    };
+    let options = EvalOptions {
+        concurrency,
+        judge: judge.map(eval_target).transpose()?,
+        ..Default::default()
+    };
+
+    println!(
+        "\n  {} {} prompt(s) × {} target(s){}\n",
+        "📏".bold(),
+        prompts.len(),
+        targets.len(),
+        options.judge.as_ref().map(|j| format!(", judged by {}", j.label)).unwrap_or_default()
+    );
+
+    let bar = indicatif::ProgressBar::new((prompts.len() * targets.len()) as u64);
+    bar.set_style(
+        indicatif::ProgressStyle::with_template("  {bar:40.cyan/blue} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar()),
+    );
+    let progress = bar.clone();
+    let results = eval::run(&prompts, &targets, &options, move |r| {
+        progress.set_message(format!("{} #{}", r.target, r.prompt_id));
+        progress.inc(1);
+    })
+    .await;
+    bar.finish_and_clear();
+
+    for s in eval::summarize(&results) {
+        println!("  {}", s.target.cyan().bold());
+        println!(
+            "    latency {:.2}s avg · tokens {}/{} · cost {} · score {} · errors {}",
+            s.avg_latency_ms as f64 / 1000.0,
+            s.prompt_tokens,
+            s.completion_tokens,
+            s.cost_usd.map(|c| format!("${:.4}", c)).unwrap_or_else(|| "n/a".into()),
+            s.avg_score.map(|v| format!("{:.1}/10", v)).unwrap_or_else(|| "n/a".into()),
+            if s.errors > 0 { s.errors.to_string().red() } else { s.errors.to_string().green() },
+        );
+    }
+
+    let path = output.unwrap_or_else(|| {
+        PathBuf::from(format!("zenclaw-eval-{}.md", chrono::Local::now().format("%Y%m%d-%H%M%S")))
+    });
+    let report = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        serde_json::to_string_pretty(&results)?
+    } else {
+        eval::report_markdown(&prompts, &results)
+    };
+    std::fs::write(&path, report)?;
+    println!("\n  {} {}\n", "📄 Report:".green(), path.display());
+
+    Ok(())
+}
+
+// ─── Session export / import ───────────────────────────────
+
+async fn run_session(action: SessionAction) -> anyhow::Result<()> {
+    use zenclaw_core::memory::MemoryStore;
+
+    let data = setup::data_dir();
+    std::fs::create_dir_all(&data)?;
+    let memory = SqliteMemory::open(&data.join("memory.db"))?;
+
+    match action {
+        SessionAction::Export { key, file } => {
+            let json = memory.export_session(&key).await?;
+            let count = serde_json::from_str::<Vec<serde_json::Value>>(&json).map(|v| v.len()).unwrap_or(0);
+            if count == 0 {
+                anyhow::bail!("Session '{}' has no history", key);
+            }
+            std::fs::write(&file, json)?;
+            println!("  {} {} messages of {} → {}", "✅ Exported".green(), count, key.cyan(), file.display());
+        }
+        SessionAction::Import { key, file, replace } => {
+            let json = std::fs::read_to_string(&file)?;
+            let added = memory.import_session(&key, &json, replace).await?;
+            if replace {
+                println!("  {} {} messages into {} (history replaced)", "✅ Imported".green(), added, key.cyan());
+            } else {
+                println!("  {} {} new messages into {}", "✅ Merged".green(), added, key.cyan());
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_search(query: &str, session: Option<&str>, limit: usize, json: bool) -> anyhow::Result<()> {
+    use zenclaw_core::memory::MemoryStore;
+
+    let data = setup::data_dir();
+    std::fs::create_dir_all(&data)?;
+    let memory = SqliteMemory::open(&data.join("memory.db"))?;
+    let found = memory.search_history(query, session, limit).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&found)?);
+        return Ok(());
+    }
+    if found.is_empty() {
+        println!("  No messages found for '{}'", query);
+        return Ok(());
+    }
+    for m in &found {
+        println!("  {}", zenclaw_hub::tools::search_history::format_match(m));
+    }
+    Ok(())
+}
+
+// ─── Scheduled prompts ─────────────────────────────────────
+
+fn open_schedule_store() -> anyhow::Result<zenclaw_hub::scheduler::ScheduleStore> {
+    let data = setup::data_dir();
+    std::fs::create_dir_all(&data)?;
+    Ok(zenclaw_hub::scheduler::ScheduleStore::open(&data.join("schedule.db"))?)
+}
+
+fn run_schedule(action: ScheduleAction) -> anyhow::Result<()> {
+    use zenclaw_hub::scheduler::Delivery;
+
+    let store = open_schedule_store()?;
+    match action {
+        ScheduleAction::Add {
+            cron,
+            prompt,
+            deliver,
+            catch_up,
+        } => {
+            let delivery: Delivery = deliver.parse()?;
+            let job = store.add(&cron, &prompt, &delivery, catch_up)?;
+            println!(
+                "  {} job #{} → {} (next run {})",
+                "✅ Scheduled".green(),
+                job.id,
+                job.delivery,
+                job.next_run.format("%Y-%m-%d %H:%M UTC")
+            );
+            println!("  Run {} to execute it.", "zenclaw daemon".cyan());
+        }
+        ScheduleAction::List => {
+            let jobs = store.list()?;
+            if jobs.is_empty() {
+                println!("\n  No scheduled jobs. Add one with: zenclaw schedule add \"0 9 * * *\" \"<prompt>\"\n");
+                return Ok(());
+            }
+            println!();
+            for job in jobs {
+                println!(
+                    "  {} {}  {}  → {}{}",
+                    format!("#{}", job.id).bold(),
+                    job.cron.cyan(),
+                    job.next_run.format("%Y-%m-%d %H:%M UTC"),
+                    job.delivery,
+                    if job.catch_up { " (catch-up)" } else { "" }
+                );
+                println!("     {}", job.prompt.dimmed());
+            }
+            println!();
+        }
+        ScheduleAction::Remove { id } => {
+            if store.remove(id)? {
+                println!("  {} job #{}", "🗑️  Removed".green(), id);
+            } else {
+                anyhow::bail!("No scheduled job #{}", id);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_daemon(
+    cli_provider: Option<&str>,
+    cli_model: Option<&str>,
+    cli_api_key: Option<&str>,
+) -> anyhow::Result<()> {
+    use zenclaw_hub::scheduler::Notifier;
+
+    let store = open_schedule_store()?;
+    let (agent, provider, memory, provider_name, model) = setup_bot_env(
+        cli_provider,
+        cli_model,
+        cli_api_key,
+        None,
+        None,
+        None,
+        ToolAccess::Public,
+    )
+    .await?;
+
+    let channels = setup::load_saved_config().map(|c| c.channels).unwrap_or_default();
+    let notifier = Notifier {
+        telegram_token: std::env::var("TELEGRAM_BOT_TOKEN")
+            .ok()
+            .or_else(|| channels.telegram.map(|t| t.bot_token)),
+        slack_token: std::env::var("SLACK_BOT_TOKEN")
+            .ok()
+            .or_else(|| channels.slack.map(|s| s.bot_token)),
+        // Same default as `zenclaw whatsapp --bridge`
+        whatsapp_bridge: Some(
+            std::env::var("WHATSAPP_BRIDGE_URL").unwrap_or_else(|_| "http://localhost:3001".to_string()),
+        ),
+    };
+
+    println!(
+        "  {} {} job(s), {} / {}. Press Ctrl+C to stop.",
+        "⏰ Scheduler running:".green().bold(),
+        store.list()?.len(),
+        provider_name,
+        model
+    );
+    tokio::select! {
+        result = zenclaw_hub::scheduler::run_daemon(&store, &agent, provider.as_ref(), &memory, &notifier) => result?,
+        _ = tokio::signal::ctrl_c() => println!("\n  {}", "Scheduler stopped.".dimmed()),
+    }
+    Ok(())
+}
+
+// ─── RAG (semantic search) ─────────────────────────────────
+
+/// Default embedding model of providers with an embeddings endpoint.
+fn default_embedding_model(provider: &str) -> Option<&'static str> {
+    match provider {
+        "openai" => Some("text-embedding-3-small"),
+        "ollama" => Some("nomic-embed-text"),
+        "gemini" => Some("text-embedding-004"),
+        _ => None,
+    }
+}
+
+/// Embedding provider for RAG: `embedding_provider` / `embedding_model`
+/// from the config, else the chat provider with its default embedding model.
+fn create_embedder(
+    settings: &zenclaw_core::config::MemorySettings,
+    chat_provider: &str,
+    chat_api_key: &str,
+    chat_api_base: Option<&str>,
+) -> Option<Arc<dyn EmbeddingProvider>> {
+    use zenclaw_hub::providers::{OllamaEmbeddings, OpenAiEmbeddings};
+
+    let provider = settings.embedding_provider.as_deref().unwrap_or(chat_provider);
+    let model = settings
+        .embedding_model
+        .clone()
+        .or_else(|| default_embedding_model(provider).map(str::to_string))?;
+
+    // Only reuse the chat credentials for the same provider
+    let same = provider == chat_provider;
+    let api_key = if same {
+        chat_api_key.to_string()
+    } else {
+        resolve_api_key(None, provider).unwrap_or_default()
+    };
+    let api_base = if same { chat_api_base.map(str::to_string) } else { None };
+
+    let saved = setup::load_saved_config();
+    let defaults = ProviderConfig::default();
+    let (max_retries, retry_base_ms) = saved
+        .as_ref()
+        .map(|c| (c.provider.max_retries, c.provider.retry_base_ms))
+        .unwrap_or((defaults.max_retries, defaults.retry_base_ms));
+
+    let embedder: Arc<dyn EmbeddingProvider> = match provider {
+        "ollama" => Arc::new(OllamaEmbeddings::new(&model, api_base.as_deref())),
+        _ => {
+            let api_base = api_base.or_else(|| match provider {
+                "gemini" => Some("https://generativelanguage.googleapis.com/v1beta/openai".to_string()),
+                "openrouter" => Some("https://openrouter.ai/api/v1".to_string()),
+                _ => None,
+            });
+            Arc::new(
+                OpenAiEmbeddings::new(ProviderConfig {
+                    provider: provider.to_string(),
+                    model,
+                    api_key: Some(api_key),
+                    api_base,
+                    ..Default::default()
+                })
+                .with_retry(max_retries, retry_base_ms),
+            )
+        }
+    };
+    Some(embedder)
+}
+
+async fn run_rag(action: RagAction) -> anyhow::Result<()> {
+    use zenclaw_hub::memory::{ChunkOptions, RagStore};
+
+    let settings = setup::load_saved_config().map(|c| c.memory).unwrap_or_default();
+    let (provider_name, _, api_key, api_base) = resolve_config(None, None, None, None)?;
+
+    let data = setup::data_dir();
+    std::fs::create_dir_all(&data)?;
+    let mut rag = RagStore::open(&data.join("rag.db"))?.with_chunking(ChunkOptions {
+        chunk_size: settings.rag_chunk_size,
+        overlap: settings.rag_chunk_overlap,
+    });
+
+    match create_embedder(&settings, &provider_name, &api_key, api_base.as_deref()) {
+        Some(embedder) => rag = rag.with_embedder(embedder),
+        None => println!(
+            "  {} {} has no default embedding model — using keyword search. Set one with: zenclaw config set embedding_model <model>",
+            "⚠️".yellow(),
+            settings.embedding_provider.as_deref().unwrap_or(&provider_name)
+        ),
+    }
+
+    match action {
+        RagAction::Add { file, metadata } => {
+            let chunks = rag.ingest_file(&file, &metadata).await?;
+            println!("  {} {} ({} chunks)", "✅ Indexed".green(), file.display(), chunks);
+        }
+        RagAction::Search { query, k } => {
+            let results = rag.semantic_search(&query, k).await?;
+            if results.is_empty() {
+                println!("\n  No matches for '{}'\n", query);
+                return Ok(());
+            }
+            println!();
+            for (i, hit) in results.iter().enumerate() {
+                println!(
+                    "  {} {} {}",
+                    format!("{}.", i + 1).bold(),
+                    hit.document.source.cyan(),
+                    format!("({}) score {:.3}", hit.document.metadata, hit.score).dimmed()
+                );
+                let preview: String = hit.document.content.chars().take(200).collect();
+                println!("     {}\n", preview.replace('\n', " "));
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─── Prompt snippets ───────────────────────────────────────
+
+fn run_prompts(action: Option<PromptAction>) -> anyhow::Result<()> {
+    let library = zenclaw_hub::prompts::PromptLibrary::new(&setup::data_dir().join("prompts"));
+
+    match action {
+        Some(PromptAction::Add { name, template, description }) => {
+            let snippet = library.save(&name, &description, &template)?;
+            println!("  {} {} → {}", "✅ Saved".green(), name.cyan(), snippet.path.display());
+            println!("  {}", format!("Use it in chat: /use {} <args>", name).dimmed());
+        }
+        Some(PromptAction::Remove { name }) => {
+            if library.remove(&name)? {
+                println!("  {} {}", "🗑️  Removed".green(), name.cyan());
+            } else {
+                println!("  {} No snippet named '{}'", "Error:".red(), name);
+            }
+        }
+        Some(PromptAction::List) | None => {
+            let snippets = library.list()?;
+            if snippets.is_empty() {
+                println!("\n  No prompt snippets yet. Add one with:");
+                println!("  {}\n", "zenclaw prompts add code-review \"Review this code for bugs: {args}\"".cyan());
+                return Ok(());
+            }
+            println!("\n  {} {} snippet(s)\n", "📝".bold(), snippets.len());
+            for s in snippets {
+                println!("  {}  {}", s.name.cyan().bold(), s.description.dimmed());
+                let preview: String = s.template.lines().next().unwrap_or_default().chars().take(70).collect();
+                println!("    {}", preview);
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+fn run_theme(action: Option<ThemeAction>) -> anyhow::Result<()> {
+    use crate::theme::{KEYS, PRESETS, Theme};
+
+    match action {
+        Some(ThemeAction::Set { key, color }) => {
+            let path = theme::set_theme_key(&key, &color)?;
+            println!("  {} {} = {} → {}", "✅ Saved".green(), key.cyan(), color, path.display());
+        }
+        Some(ThemeAction::Show) | None => {
+            // Read the file again rather than THEME, so errors are reported
+            let table = theme::read_theme_file()?;
+            let palette = Theme::from_table(&table)?;
+            let preset = table.get("preset").and_then(|v| v.as_str()).unwrap_or("dark");
+            let source = if table.is_empty() {
+                "built-in".to_string()
+            } else {
+                theme::theme_path().display().to_string()
+            };
+
+            println!("\n  {} {} ({})\n", "🎨 Theme:".bold(), preset.cyan(), source.dimmed());
+            for key in KEYS {
+                let color = palette.color(key).unwrap_or_default();
+                let marker = if table.contains_key(*key) { "*" } else { " " };
+                println!("  {} {:<14}{} {}", theme::swatch(color), key, marker, color);
+            }
+            println!("\n  {}", "* = set in theme.toml".dimmed());
+            println!("  Presets: {}", PRESETS.join(", "));
+            println!("  {}\n", "zenclaw theme set <key> <color>  |  zenclaw theme set preset <name>".cyan());
+        }
+    }
+    Ok(())
+}
+
+// ─── Tool Audit Log ────────────────────────────────────────
+
+fn audit_dir() -> PathBuf {
+    setup::data_dir().join("audit")
+}
+
+fn print_audit_entry(entry: &zenclaw_hub::audit::AuditEntry, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string(entry).unwrap_or_default());
+        return;
+    }
+
+    let time = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| entry.timestamp.clone());
+    let status = if entry.success { "✓".green() } else { "✗".red() };
+    let args = match &entry.args {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let args: String = if args.chars().count() > 80 {
+        format!("{}…", args.chars().take(79).collect::<String>())
+    } else {
+        args
+    };
+    println!(
+        "{} {} {:<16} {:<22} {:>6}ms {:>8}B  {}",
+        time.dimmed(),
+        status,
+        entry.tool.cyan(),
+        entry.session,
+        entry.duration_ms,
+        entry.result_len,
+        args.dimmed()
+    );
+    if let Some(error) = &entry.error {
+        println!("    {} {}", "↳".red(), error.red());
+    }
+}
+
+async fn run_audit(
+    filter: zenclaw_hub::audit::AuditFilter,
+    lines: usize,
+    follow: bool,
+    json: bool,
+) -> anyhow::Result<()> {
+    use zenclaw_hub::audit;
+
+    let dir = audit_dir();
+    let entries = audit::read(&dir, &filter, lines);
+    if entries.is_empty() && !follow && !json {
+        println!("  No tool calls recorded in {}", dir.display());
+        return Ok(());
+    }
+    for entry in &entries {
+        print_audit_entry(entry, json);
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    // Poll today's file for appended lines; a new file starts each day (UTC)
+    let mut path = audit::current_file(&dir);
+    let mut offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let today = audit::current_file(&dir);
+        if today != path {
+            path = today;
+            offset = 0;
+        }
+        let Ok(content) = std::fs::read(&path) else { continue };
+        let Some(new) = content.get(offset as usize..) else { continue };
+        // Only complete lines; a partial one is picked up next time
+        let Some(end) = new.iter().rposition(|&b| b == b'\n') else { continue };
+        for line in String::from_utf8_lossy(&new[..end]).lines() {
+            if let Ok(entry) = serde_json::from_str::<audit::AuditEntry>(line)
+                && filter.matches(&entry)
+            {
+                print_audit_entry(&entry, json);
+            }
+        }
+        offset += end as u64 + 1;
+    }
+}
+
+// ─── Knowledge (RAG) ───────────────────────────────────────
+
+async fn run_knowledge() -> anyhow::Result<()> {
+    use zenclaw_hub::memory::RagStore;
+
+    let rag = RagStore::open(&setup::data_dir().join("rag.db"))?;
+
+    loop {
+        let sources = rag.list_sources()?;
+
+        let mut items = vec![crate::tui_menu::MenuItem {
+            label: "➕ Add File or URL".to_string(),
+            description: "Ingest a local file, a directory, or a web page into the knowledge base.\n\nContent is split into chunks and becomes searchable by the agent.".to_string(),
+            action_key: "add_new".to_string(),
+        }];
+        for src in &sources {
+            items.push(crate::tui_menu::MenuItem {
+                label: format!("{} {}", "•".cyan(), src.source),
+                description: format!(
+                    "ID: {}\nSource: {}\nChunks: {}\nAdded: {}",
+                    src.id, src.source, src.chunks, src.created_at
+                ),
+                action_key: src.source.clone(),
+            });
+        }
+        items.push(crate::tui_menu::MenuItem {
+            label: "❌ Back".to_string(),
+            description: "Return to previous menu.".to_string(),
+            action_key: "back".to_string(),
+        });
+
+        let title = format!("🧠 Manage Knowledge ({} documents)", sources.len());
+        let Some(action_key) = crate::tui_menu::run_tui_menu(&title, &items, 0)? else {
+            break;
+        };
+        if action_key == "back" {
+            break;
+        }
+
+        if action_key == "add_new" {
+            let input = crate::tui_menu::run_tui_input("Add Knowledge", "File path, directory, or URL:", "", false)?;
+            if let Some(target) = input.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+                let message = ingest_knowledge(&rag, &target).await;
+                crate::tui_menu::run_tui_text_viewer("Add Knowledge", &message).ok();
+            }
+            continue;
+        }
+
+        let source = action_key;
+        loop {
+            let options = vec![
+                crate::tui_menu::MenuItem { label: "📄 View Chunks".into(), description: "Read the indexed chunks of this document.".into(), action_key: "view".into() },
+                crate::tui_menu::MenuItem { label: "🗑️  Delete Document".into(), description: "Remove this document and all its chunks from the knowledge base.".into(), action_key: "delete".into() },
+                crate::tui_menu::MenuItem { label: "⬅️  Back".into(), description: "Return to knowledge list.".into(), action_key: "back".into() },
+            ];
+            match crate::tui_menu::run_tui_menu(&format!("Manage: {}", source), &options, 0)?.as_deref() {
+                Some("view") => {
+                    let chunks = rag.chunks(&source)?;
+                    let content = chunks
+                        .iter()
+                        .map(|c| {
+                            let label = if c.metadata.is_empty() { "chunk:1/1" } else { c.metadata.as_str() };
+                            format!("── #{} ({}) ──\n{}\n", c.id, label, c.content)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    crate::tui_menu::run_tui_text_viewer(&source, &content).ok();
+                }
+                Some("delete") => {
+                    let confirm = crate::tui_menu::run_tui_input("Confirm Delete", &format!("Delete '{}'? Type 'yes' to confirm:", source), "", false)?;
+                    if confirm.as_deref() == Some("yes") {
+                        rag.delete_by_source(&source)?;
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Index a file, directory or URL into the RAG store and describe the result.
+async fn ingest_knowledge(rag: &zenclaw_hub::memory::RagStore, target: &str) -> String {
+    use zenclaw_core::tool::Tool;
+    use zenclaw_hub::tools::indexer::{index_directory, index_single_file};
+
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let scraped = zenclaw_hub::tools::WebScrapeTool::new()
+            .execute(serde_json::json!({ "url": target, "max_chars": 50_000 }))
+            .await
+            .unwrap_or_else(|e| format!("Error: {}", e));
+        if scraped.starts_with("Error") {
+            return format!("❌ Failed to fetch {}\n\n{}", target, scraped);
+        }
+        // Drop the "--- EXTRACTED ... ---" banner
+        let text = scraped.split_once("\n\n").map(|(_, body)| body).unwrap_or(&scraped);
+        return match rag.index_chunked(target, text, 200, 30) {
+            Ok(ids) => format!("✅ Indexed {} ({} chunks)", target, ids.len()),
+            Err(e) => format!("❌ Failed to index {}: {}", target, e),
+        };
+    }
+
+    let path = std::path::Path::new(target);
+    if path.is_dir() {
+        let (mut files, mut chunks, mut errors) = (0, 0, Vec::new());
+        index_directory(rag, path, &mut files, &mut chunks, &mut errors);
+        let mut msg = format!("✅ Indexed {} files ({} chunks) from {}", files, chunks, target);
+        if !errors.is_empty() {
+            msg.push_str(&format!("\n\n⚠️ {} errors:\n  • {}", errors.len(), errors.join("\n  • ")));
+        }
+        msg
+    } else if path.is_file() {
+        match index_single_file(rag, path) {
+            Ok(chunks) => format!("✅ Indexed {} ({} chunks)", target, chunks),
+            Err(e) => format!("❌ Failed to index {}: {}", target, e),
+        }
+    } else {
+        format!("❌ Not found: {}", target)
+    }
+}
+
+// ─── Serve (REST API) ──────────────────────────────────────
+
+async fn run_serve(
+    cli_host: &str,
+    cli_port: u16,
+    cli_provider: Option<&str>,
+    cli_model: Option<&str>,
+    cli_api_key: Option<&str>,
+) -> anyhow::Result<()> {
+    let (mut agent, provider, memory, resolved_provider, resolved_model) = setup_bot_env(
+        cli_provider,
+        cli_model,
+        cli_api_key,
+        None,
+        None,
+        None,
+        ToolAccess::Public,
+    ).await?;
+
+    // Prometheus metrics come from the agent's own events (shared with the webhook, if any)
+    let events = match agent.event_bus() {
+        Some(bus) => bus.clone(),
+        None => {
+            let bus = Arc::new(EventBus::default());
+            agent.set_event_bus(bus.clone());
+            bus
+        }
+    };
+    let prometheus = Arc::new(zenclaw_hub::metrics::PrometheusMetrics::new());
+    prometheus.spawn_collector(&events);
+
+    let api_key = std::env::var("ZENCLAW_API_KEY")
+        .ok()
+        .or_else(|| setup::load_saved_config().and_then(|c| c.server.api_key))
+        .filter(|k| !k.trim().is_empty());
+    let limiter = rate_limiter();
+
+    let data = setup::data_dir();
+    let rag_path = data.join("rag.db");
+    let rag = zenclaw_hub::memory::RagStore::open(&rag_path).ok().map(Arc::new);
 
     let host = cli_host.to_string();
     let mut port = cli_port;
 
+    // Destructive tool calls wait for POST /v1/actions/{id}
+    let actions = Arc::new(zenclaw_hub::api::PendingActions::default());
+    if agent.config.confirm_destructive {
+        agent.set_approver(actions.clone());
+    }
+
     let agent = Arc::new(agent);
     let provider = Arc::new(provider);
     let memory = Arc::new(memory);
+    let metrics = zenclaw_hub::metrics::new_metrics();
 
     loop {
         let state = zenclaw_hub::api::ApiState {
@@ -1289,6 +3324,11 @@ async fn run_serve(
             provider: provider.clone(),
             memory: memory.clone(),
             rag: rag.clone(),
+            metrics: metrics.clone(),
+            prometheus: prometheus.clone(),
+            api_key: api_key.clone(),
+            rate_limiter: limiter.clone(),
+            actions: actions.clone(),
         };
 
         // Fail-fast test to see if we can bind to the port
@@ -1297,13 +3337,27 @@ async fn run_serve(
             Ok(listener) => {
                 drop(listener); // Close it so AXUM can take it
 
-                // Run server in background
+                // Run server in background; failures and heartbeats go to the dashboard
+                let (log_tx, log_rx) = tokio::sync::mpsc::channel(100);
                 let bg_host = host.clone();
                 let bg_port = port;
                 let bg_state = state; 
-                tokio::spawn(async move {
-                    let _ = zenclaw_hub::api::start_server_from_state(bg_state, &bg_host, bg_port).await;
+                let err_tx = log_tx.clone();
+                if api_key.is_none() {
+                    let _ = log_tx
+                        .send("⚠️  No API key set — anyone who can reach this port can use the agent. Set ZENCLAW_API_KEY or `zenclaw config set server_api_key <key>`.".to_string())
+                        .await;
+                }
+                let server = tokio::spawn(async move {
+                    if let Err(e) = zenclaw_hub::api::start_server_from_state(bg_state, &bg_host, bg_port).await {
+                        let _ = err_tx.send(format!("❌ Server stopped: {}", e)).await;
+                    }
                 });
+                let heartbeat = zenclaw_hub::metrics::spawn_heartbeat(
+                    metrics.clone(),
+                    std::time::Duration::from_secs(5),
+                    log_tx,
+                );
 
                 // Interactively monitor via TUI
                 let endpoint = format!("http://{}:{}", host, port);
@@ -1313,7 +3367,9 @@ async fn run_serve(
                     ("Status", "Listening"),
                     ("Endpoint", endpoint.as_str()),
                 ];
-                let _ = crate::tui_menu::run_bot_dashboard("REST API", &resolved_provider, &resolved_model, &details, None);
+                let _ = crate::tui_menu::run_bot_dashboard("REST API", &resolved_provider, &resolved_model, &details, Some(log_rx));
+                heartbeat.abort();
+                server.abort();
                 break Ok(());
             }
             Err(e) => {
@@ -1345,7 +3401,9 @@ async fn run_whatsapp(
         cli_model,
         cli_api_key,
         None,
-        None
+        None,
+        None,
+        ToolAccess::Public,
     ).await?;
 
     let agent = Arc::new(agent);
@@ -1358,10 +3416,21 @@ async fn run_whatsapp(
         cli_bridge_url.to_string()
     };
 
+    let saved = setup::load_saved_config();
+    let reply_format = saved
+        .as_ref()
+        .map(|c| c.channels.reply_format_for("whatsapp"))
+        .unwrap_or_default();
+    let greeting = saved.as_ref().and_then(|c| c.channels.greeting_for("whatsapp"));
+    let rate_limit = rate_limiter();
+
     let (log_tx, log_rx) = tokio::sync::mpsc::channel(100);
 
     loop {
-        let mut wa = zenclaw_hub::channels::WhatsAppChannel::new(&current_bridge_url);
+        let mut wa = zenclaw_hub::channels::WhatsAppChannel::new(&current_bridge_url)
+            .with_reply_format(reply_format.clone())
+            .with_greeting(greeting.clone())
+            .with_rate_limit(rate_limit.clone());
 
         if let Some(numbers) = allowed_numbers {
             let nums: Vec<String> = numbers.split(',').map(|s| s.trim().to_string()).collect();
@@ -1399,8 +3468,41 @@ async fn run_whatsapp(
 
 // ─── Update Check ──────────────────────────────────────────
 
-async fn run_update_check() -> anyhow::Result<()> {
-    match zenclaw_hub::updater::check_for_updates().await {
+/// Cached update state shared between the background check and the menu.
+type UpdateBadge = Arc<std::sync::Mutex<Option<zenclaw_hub::updater::UpdateInfo>>>;
+
+/// Start a non-blocking update check (cached, at most once per interval).
+fn spawn_update_check(disabled: bool) -> UpdateBadge {
+    let badge: UpdateBadge = Arc::new(std::sync::Mutex::new(None));
+
+    let settings = setup::load_saved_config().unwrap_or_default().updates;
+    if disabled || !settings.check {
+        return badge;
+    }
+
+    let cache_path = setup::data_dir().join("update_check.json");
+    let slot = badge.clone();
+    tokio::spawn(async move {
+        match zenclaw_hub::updater::check_for_updates_cached(&cache_path, settings.interval_hours).await {
+            Ok(update) => {
+                if let Ok(mut s) = slot.lock() {
+                    *s = update;
+                }
+            }
+            Err(e) => tracing::debug!("Background update check failed: {}", e),
+        }
+    });
+
+    badge
+}
+
+async fn run_update_check(apply: bool) -> anyhow::Result<()> {
+    let result = zenclaw_hub::updater::check_for_updates().await;
+    if let Ok(ref update) = result {
+        zenclaw_hub::updater::write_cache(&setup::data_dir().join("update_check.json"), update);
+    }
+
+    match result {
         Ok(Some(info)) => {
             let mut out = String::new();
             out.push_str("  🆕 New version available!\n\n");
@@ -1435,8 +3537,27 @@ async fn run_update_check() -> anyhow::Result<()> {
                 ),
             };
 
+            if apply {
+                return run_update_apply(&info).await;
+            }
+
             out.push_str(&format!("\n  To update, run:\n  {}\n", install_cmd));
+            out.push_str("\n  Or let ZenClaw install it: zenclaw update --apply\n");
             crate::tui_menu::run_tui_text_viewer("🔄 Update Available", &out).ok();
+
+            if info.platform_asset().is_some() {
+                let options = vec![
+                    crate::tui_menu::MenuItem {
+                        label: format!("⬇️  Install v{} now", info.latest),
+                        description: "Download the release (resumable), verify its checksum, and replace this binary.\n\nThe current binary is kept as a .old backup.".into(),
+                        action_key: "install".into(),
+                    },
+                    crate::tui_menu::MenuItem { label: "❌ Not now".into(), description: "Return to main menu.".into(), action_key: "back".into() },
+                ];
+                if crate::tui_menu::run_tui_menu("🔄 Update Available", &options, 0)?.as_deref() == Some("install") {
+                    run_update_apply(&info).await?;
+                }
+            }
         }
         Ok(None) => {
             let msg = format!("✅ You're on the latest version! (v{})", env!("CARGO_PKG_VERSION"));
@@ -1450,6 +3571,69 @@ async fn run_update_check() -> anyhow::Result<()> {
 }
 
 
+/// Install a release with a download progress bar.
+async fn run_update_apply(info: &zenclaw_hub::updater::UpdateInfo) -> anyhow::Result<()> {
+    let bar = indicatif::ProgressBar::new(0);
+    bar.set_style(
+        indicatif::ProgressStyle::with_template(
+            "  {spinner:.cyan} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+        )
+        .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar())
+        .progress_chars("█▓░"),
+    );
+    println!("\n  ⬇️  Downloading ZenClaw v{}...", info.latest);
+
+    let progress = |done: u64, total: u64| {
+        bar.set_length(total);
+        bar.set_position(done);
+    };
+    let result = zenclaw_hub::updater::apply_update(info, &setup::data_dir().join("updates"), &progress).await;
+    bar.finish_and_clear();
+
+    match result {
+        Ok(exe) => {
+            println!("  {} Installed v{} at {}", "✅".green(), info.latest, exe.display());
+            println!("  {}", "Restart ZenClaw to use the new version.".dimmed());
+            Ok(())
+        }
+        Err(e) => {
+            println!("  {} {}", "❌".red(), e);
+            println!("  {}", "Run the update again to resume the download.".dimmed());
+            Err(e.into())
+        }
+    }
+}
+
+/// Render a `--json-logs` line like the plain formatter does, so the log
+/// viewer reads the same either way. Non-JSON lines pass through unchanged.
+fn humanize_log_line(line: &str) -> String {
+    let Ok(serde_json::Value::Object(entry)) = serde_json::from_str::<serde_json::Value>(line) else {
+        return line.to_string();
+    };
+    let field = |key: &str| entry.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+
+    let mut extra = String::new();
+    let mut message = "";
+    if let Some(fields) = entry.get("fields").and_then(|f| f.as_object()) {
+        for (key, value) in fields {
+            match (key.as_str(), value.as_str()) {
+                ("message", Some(msg)) => message = msg,
+                (_, Some(v)) => extra.push_str(&format!(" {}={}", key, v)),
+                _ => extra.push_str(&format!(" {}={}", key, value)),
+            }
+        }
+    }
+
+    format!(
+        "{} {:>5} {}: {}{}",
+        field("timestamp"),
+        field("level"),
+        field("target"),
+        message,
+        extra
+    )
+}
+
 async fn run_logs(initial_lines: usize) -> anyhow::Result<()> {
     use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader};
     use tokio::fs::File;
@@ -1492,7 +3676,7 @@ async fn run_logs(initial_lines: usize) -> anyhow::Result<()> {
         let lines: Vec<&str> = content.lines().collect();
         let start = lines.len().saturating_sub(initial_lines);
         for line in lines.into_iter().skip(start) {
-            initial_logs.push(line.to_string());
+            initial_logs.push(humanize_log_line(line));
         }
     }
 
@@ -1509,7 +3693,7 @@ async fn run_logs(initial_lines: usize) -> anyhow::Result<()> {
                     match reader.read_line(&mut buf).await {
                         Ok(0) => tokio::time::sleep(Duration::from_millis(200)).await,
                         Ok(_) => {
-                            let line = buf.trim_end().to_string();
+                            let line = humanize_log_line(buf.trim_end());
                             if !line.is_empty() && tx.send(line).is_err() {
                                 break;
                             }
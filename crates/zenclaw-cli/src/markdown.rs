@@ -1,29 +1,43 @@
 //! Markdown → ratatui renderer.
 //!
 //! Converts markdown text into styled `Vec<Line>` for the chat widget.
-//! Supports: code fences, bold, italic, inline code, headers, lists, and HR.
+//! Supports: code fences (syntax-highlighted with syntect when the language
+//! is known), pipe tables, bold, italic, inline code, headers, lists, and HR.
+
+use std::sync::OnceLock;
 
 use ratatui::{
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
 };
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
 
 use crate::theme::THEME;
 
 /// Render markdown text into a sequence of styled `Line`s.
 ///
-/// `width` is used for horizontal rules; pass the available chat width.
+/// `width` is used for horizontal rules and tables; pass the available chat width.
 pub fn render_markdown(text: &str, width: usize) -> Vec<Line<'static>> {
     let mut lines: Vec<Line<'static>> = Vec::new();
     let mut in_code_block = false;
     let mut code_lang = String::new();
+    let mut code: Vec<&str> = Vec::new();
+
+    let raw_lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+    while i < raw_lines.len() {
+        let raw = raw_lines[i];
+        i += 1;
 
-    for raw in text.lines() {
         // ── Code fence toggle ──────────────────────────
         if raw.trim_start().starts_with("```") {
             if in_code_block {
                 // End code block
                 in_code_block = false;
+                lines.extend(highlight_code(&code, &code_lang));
+                code.clear();
                 lines.push(Line::from(Span::styled(
                     format!(" └─ end {} ", if code_lang.is_empty() { "code" } else { &code_lang }),
                     Style::default().fg(THEME.muted),
@@ -44,9 +58,21 @@ pub fn render_markdown(text: &str, width: usize) -> Vec<Line<'static>> {
         }
 
         if in_code_block {
-            // Inside code block → apply code styling
-            let styled = style_code_line(raw, &code_lang);
-            lines.push(styled);
+            // Highlighted as a whole when the fence closes
+            code.push(raw);
+            continue;
+        }
+
+        // ── Pipe table (header row + `|---|` separator) ─
+        if raw.trim_start().starts_with('|') && raw_lines.get(i).is_some_and(|next| is_table_separator(next)) {
+            let mut rows = vec![raw];
+            let separator = raw_lines[i];
+            i += 1;
+            while let Some(row) = raw_lines.get(i).filter(|r| r.trim_start().starts_with('|')) {
+                rows.push(row);
+                i += 1;
+            }
+            lines.extend(render_table(&rows, separator, width));
             continue;
         }
 
@@ -125,8 +151,9 @@ pub fn render_markdown(text: &str, width: usize) -> Vec<Line<'static>> {
         }
     }
 
-    // Close unclosed code block
+    // Close unclosed code block (e.g. while the reply is still streaming)
     if in_code_block {
+        lines.extend(highlight_code(&code, &code_lang));
         lines.push(Line::from(Span::styled(
             " └─ end code ".to_string(),
             Style::default().fg(THEME.muted),
@@ -221,6 +248,182 @@ fn parse_inline_markdown(text: &str) -> Vec<Span<'static>> {
     spans
 }
 
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// The active theme's syntect theme (see `Theme::syntax`).
+fn code_theme() -> &'static Theme {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    let themes = &THEME_SET.get_or_init(ThemeSet::load_defaults).themes;
+    themes.get(THEME.syntax).unwrap_or(&themes["base16-ocean.dark"])
+}
+
+/// Highlight a fenced code block with syntect. Unknown languages (and lines
+/// syntect can't parse) fall back to [`style_code_line`].
+fn highlight_code(code: &[&str], lang: &str) -> Vec<Line<'static>> {
+    let syntaxes = syntax_set();
+    let syntax = match lang.split_whitespace().next() {
+        Some(token) => syntaxes
+            .find_syntax_by_token(token)
+            .or_else(|| syntaxes.find_syntax_by_extension(token)),
+        None => None,
+    };
+    let Some(syntax) = syntax else {
+        return code.iter().map(|line| style_code_line(line, lang)).collect();
+    };
+
+    let mut highlighter = HighlightLines::new(syntax, code_theme());
+    code.iter()
+        .map(|line| {
+            let with_newline = format!("{}\n", line);
+            match highlighter.highlight_line(&with_newline, syntaxes) {
+                Ok(ranges) => {
+                    let mut spans = vec![Span::styled("  ", THEME.code())];
+                    for (style, piece) in ranges {
+                        let piece = piece.trim_end_matches('\n');
+                        if piece.is_empty() {
+                            continue;
+                        }
+                        let fg = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+                        spans.push(Span::styled(piece.to_string(), Style::default().fg(fg).bg(THEME.code_bg)));
+                    }
+                    Line::from(spans)
+                }
+                Err(_) => style_code_line(line, lang),
+            }
+        })
+        .collect()
+}
+
+/// `|---|:--:|` — the line under a table's header row.
+fn is_table_separator(line: &str) -> bool {
+    let cells = table_cells(line);
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let dashes = cell.trim_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+/// Cells of a `| a | b |` row, trimmed (`\|` stays in the cell).
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|cell| cell.trim().replace("**", "").replace('`', "")).collect()
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Fit `text` into `width` columns, cutting it with `…` if it's too long.
+fn fit_cell(text: &str, width: usize, align: Align) -> String {
+    let len = text.chars().count();
+    let text: String = if len > width {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    } else {
+        text.to_string()
+    };
+    let pad = width.saturating_sub(text.chars().count());
+    match align {
+        Align::Left => format!("{}{}", text, " ".repeat(pad)),
+        Align::Right => format!("{}{}", " ".repeat(pad), text),
+        Align::Center => format!("{}{}{}", " ".repeat(pad / 2), text, " ".repeat(pad - pad / 2)),
+    }
+}
+
+/// Render a pipe table with box borders, shrinking the widest columns (and
+/// truncating their cells) until it fits in `width`. Rows with missing or
+/// extra cells are padded or cut to the header's column count.
+fn render_table(rows: &[&str], separator: &str, width: usize) -> Vec<Line<'static>> {
+    let header = table_cells(rows[0]);
+    let columns = header.len();
+    let aligns: Vec<Align> = table_cells(separator)
+        .iter()
+        .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+            (true, true) => Align::Center,
+            (false, true) => Align::Right,
+            _ => Align::Left,
+        })
+        .chain(std::iter::repeat(Align::Left))
+        .take(columns)
+        .collect();
+    let body: Vec<Vec<String>> = rows[1..]
+        .iter()
+        .map(|row| {
+            let mut cells = table_cells(row);
+            cells.resize(columns, String::new());
+            cells
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            std::iter::once(&header[c])
+                .chain(body.iter().map(|row| &row[c]))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        })
+        .collect();
+    // Borders take 3 columns per cell plus 1
+    let budget = width.saturating_sub(3 * columns + 1);
+    while widths.iter().sum::<usize>() > budget {
+        let widest = (0..columns).max_by_key(|&c| widths[c]).unwrap_or(0);
+        if widths[widest] <= 3 {
+            break;
+        }
+        widths[widest] -= 1;
+    }
+
+    let border = Style::default().fg(THEME.muted);
+    let rule = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        Line::from(Span::styled(format!("{}{}{}", left, segments.join(mid), right), border))
+    };
+    let row_line = |cells: &[String], style: Style| {
+        let mut spans = vec![Span::styled("│", border)];
+        for (c, cell) in cells.iter().enumerate() {
+            spans.push(Span::styled(format!(" {} ", fit_cell(cell, widths[c], aligns[c])), style));
+            spans.push(Span::styled("│", border));
+        }
+        Line::from(spans)
+    };
+
+    let mut lines = vec![
+        rule("┌", "┬", "┐"),
+        row_line(&header, Style::default().fg(THEME.accent).add_modifier(Modifier::BOLD)),
+        rule("├", "┼", "┤"),
+    ];
+    for row in &body {
+        lines.push(row_line(row, Style::default()));
+    }
+    lines.push(rule("└", "┴", "┘"));
+    lines
+}
+
 /// Basic syntax coloring for code lines.
 fn style_code_line(line: &str, lang: &str) -> Line<'static> {
     let base_style = THEME.code();
@@ -267,11 +267,18 @@ pub fn run_setup() -> anyhow::Result<()> {
 
         // If the user didn't enter a new key, but selected the same provider they already had,
         // we preserve their old API key. Otherwise, we overwrite it (or set to None).
-        let final_api_key = if api_key.is_none() && config.provider.provider == provider.name {
+        let same_provider = config.provider.provider == provider.name;
+        let final_api_key = if api_key.is_none() && same_provider {
             config.provider.api_key.clone()
         } else {
             api_key.clone()
         };
+        // Custom headers are tied to the provider they were set for
+        let (headers, override_auth) = if same_provider {
+            (std::mem::take(&mut config.provider.headers), config.provider.override_auth)
+        } else {
+            Default::default()
+        };
 
         // Update only the provider section
         config.provider = ProviderConfig {
@@ -279,6 +286,8 @@ pub fn run_setup() -> anyhow::Result<()> {
             model: model.to_string(),
             api_key: final_api_key,
             api_base: final_api_base,
+            headers,
+            override_auth,
             ..Default::default()
         };
 
@@ -298,6 +307,22 @@ pub fn run_setup() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Save a provider/model switch made in chat. A new provider drops the saved
+/// API key and base URL, which belonged to the old one — its key is then
+/// found in the environment as usual.
+pub fn save_model_switch(provider: &str, model: &str) -> anyhow::Result<()> {
+    let config_path = ZenClawConfig::default_path();
+    let mut config = ZenClawConfig::load(&config_path).unwrap_or_default();
+    if config.provider.provider != provider {
+        config.provider.provider = provider.to_string();
+        config.provider.api_key = None;
+        config.provider.api_base = None;
+    }
+    config.provider.model = model.to_string();
+    config.save(&config_path)?;
+    Ok(())
+}
+
 /// Interactive config management.
 pub fn run_config_set(key: &str, value: &str) -> anyhow::Result<()> {
     let config_path = ZenClawConfig::default_path();
@@ -308,39 +333,237 @@ pub fn run_config_set(key: &str, value: &str) -> anyhow::Result<()> {
         "model" => config.provider.model = value.to_string(),
         "api_key" => config.provider.api_key = Some(value.to_string()),
         "api_base" => config.provider.api_base = Some(value.to_string()),
+        "model_params" => match serde_json::from_str::<serde_json::Value>(value) {
+            Ok(v) if v.is_object() => config.provider.model_params = Some(v),
+            _ => anyhow::bail!("model_params must be a JSON object, e.g. '{{\"reasoning_effort\":\"high\"}}'"),
+        },
+        "system_role" => {
+            config.provider.system_role = if value == "auto" {
+                None
+            } else {
+                Some(value.parse().map_err(|e: String| anyhow::anyhow!(e))?)
+            };
+        }
+        "max_retries" => {
+            if let Ok(v) = value.parse() {
+                config.provider.max_retries = v;
+            }
+        }
+        "retry_base_ms" => {
+            if let Ok(v) = value.parse() {
+                config.provider.retry_base_ms = v;
+            }
+        }
+        _ if key.starts_with("header:") => {
+            let name = &key["header:".len()..];
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                anyhow::bail!("Invalid header name '{}', e.g. `config set header:HTTP-Referer https://myapp`", name);
+            }
+            // Same header, whatever the case it was saved with
+            config.provider.headers.retain(|k, _| !k.eq_ignore_ascii_case(name));
+            if !value.is_empty() && value != "none" {
+                config.provider.headers.insert(name.to_string(), value.to_string());
+            }
+        }
+        "vision" => {
+            config.provider.vision = match value {
+                "auto" | "" => None,
+                "true" | "on" | "1" | "yes" => Some(true),
+                "false" | "off" | "0" | "no" => Some(false),
+                _ => anyhow::bail!("vision must be true, false or auto"),
+            };
+        }
+        "override_auth_header" => config.provider.override_auth = matches!(value, "true" | "on" | "1" | "yes"),
         "max_iterations" => {
             if let Ok(v) = value.parse() {
                 config.agent.max_iterations = v;
             }
         }
+        "max_continuations" => {
+            if let Ok(v) = value.parse() {
+                config.agent.max_continuations = v;
+            }
+        }
+        "history_limit" => {
+            if let Ok(v) = value.parse() {
+                config.agent.history_limit = v;
+            }
+        }
+        "history_token_budget" => {
+            config.agent.history_token_budget = value.parse().ok().filter(|&v| v > 0);
+        }
+        "summarize_after" => {
+            config.agent.summarize_after = value.parse().ok().filter(|&v| v > 0);
+        }
+        "summary_prompt" => {
+            config.agent.summary_prompt = (!value.is_empty() && value != "none").then(|| value.to_string());
+        }
         "system_prompt" => config.agent.system_prompt = Some(value.to_string()),
+        "web_cache_ttl_secs" => {
+            if let Ok(v) = value.parse() {
+                config.agent.web_cache_ttl_secs = v;
+            }
+        }
+        "subagent_max_depth" => {
+            if let Ok(v) = value.parse() {
+                config.agent.subagent_max_depth = v;
+            }
+        }
+        "workspace" => config.agent.workspace = (!value.is_empty() && value != "none").then(|| value.to_string()),
+        "confirm_destructive" => config.agent.confirm_destructive = matches!(value, "true" | "on" | "1" | "yes"),
+        "vision_model" => config.agent.vision_model = (!value.is_empty() && value != "none").then(|| value.to_string()),
+        "env_allow" | "env_deny" => {
+            let patterns = value
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty() && p != "none")
+                .collect();
+            if key == "env_allow" {
+                config.agent.env_allow = patterns;
+            } else {
+                config.agent.env_deny = patterns;
+            }
+        }
+        "public_tools" => {
+            config.agent.public_tools = (!value.is_empty() && value != "none").then(|| {
+                value
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            });
+        }
+        "system_prompt_url" => {
+            config.agent.system_prompt_url = (!value.is_empty() && value != "none").then(|| value.to_string());
+        }
+        "update_check" => config.updates.check = matches!(value, "true" | "on" | "1" | "yes"),
+        "update_interval_hours" => {
+            if let Ok(v) = value.parse() {
+                config.updates.interval_hours = v;
+            }
+        }
+        "server_api_key" => {
+            config.server.api_key = (!value.is_empty() && value != "none").then(|| value.to_string());
+        }
+        "rate_limit_per_minute" => {
+            if let Ok(v) = value.parse() {
+                config.rate_limit.requests_per_minute = v;
+            }
+        }
+        "rate_limit_burst" => {
+            if let Ok(v) = value.parse() {
+                config.rate_limit.burst = v;
+            }
+        }
+        "rate_limit_trusted_proxies" => {
+            config.rate_limit.trusted_proxies = value.split(',').filter_map(|ip| ip.trim().parse().ok()).collect();
+        }
+        "chat_enter_sends" => config.chat.enter_sends = matches!(value, "true" | "on" | "1" | "yes"),
+        "memory_pool_size" => {
+            if let Ok(v) = value.parse() {
+                config.memory.pool_size = v;
+            }
+        }
+        "memory_busy_timeout_ms" => {
+            if let Ok(v) = value.parse() {
+                config.memory.busy_timeout_ms = v;
+            }
+        }
+        "embedding_provider" => {
+            config.memory.embedding_provider = (!value.is_empty() && value != "none").then(|| value.to_string());
+        }
+        "embedding_model" => {
+            config.memory.embedding_model = (!value.is_empty() && value != "none").then(|| value.to_string());
+        }
+        "rag_chunk_size" => {
+            if let Ok(v) = value.parse() {
+                config.memory.rag_chunk_size = v;
+            }
+        }
+        "rag_chunk_overlap" => {
+            if let Ok(v) = value.parse() {
+                config.memory.rag_chunk_overlap = v;
+            }
+        }
         "telegram_token" => {
             let tg = config.channels.telegram.get_or_insert(
                 zenclaw_core::config::TelegramConfig {
                     bot_token: String::new(),
                     allowed_users: vec![],
+                    reply_format: None,
+                    greeting: None,
+                    reconnect: None,
                 },
             );
             tg.bot_token = value.to_string();
         }
-        "discord_token" => {
+        "discord_token" | "discord_gateway" => {
             let dc = config.channels.discord.get_or_insert(
                 zenclaw_core::config::DiscordConfig {
                     bot_token: String::new(),
                     allowed_users: vec![],
+                    use_gateway: false,
+                    reply_format: None,
+                    greeting: None,
+                    reconnect: None,
                 },
             );
-            dc.bot_token = value.to_string();
+            if key == "discord_gateway" {
+                dc.use_gateway = matches!(value, "true" | "on" | "1" | "yes");
+            } else {
+                dc.bot_token = value.to_string();
+            }
         }
         "slack_token" => {
             let sl = config.channels.slack.get_or_insert(
                 zenclaw_core::config::SlackConfig {
                     bot_token: String::new(),
                     allowed_channels: vec![],
+                    reply_format: None,
+                    greeting: None,
+                    reconnect: None,
                 },
             );
             sl.bot_token = value.to_string();
         }
+        "matrix_homeserver" | "matrix_token" => {
+            let mx = config.channels.matrix.get_or_insert(
+                zenclaw_core::config::MatrixConfig {
+                    homeserver_url: String::new(),
+                    access_token: String::new(),
+                    allowed_rooms: vec![],
+                    reply_format: None,
+                    greeting: None,
+                },
+            );
+            if key == "matrix_homeserver" {
+                mx.homeserver_url = value.to_string();
+            } else {
+                mx.access_token = value.to_string();
+            }
+        }
+        "webhook_url" => {
+            if value.is_empty() || value == "none" {
+                config.channels.webhook = None;
+            } else {
+                let wh = config.channels.webhook.get_or_insert(
+                    zenclaw_core::config::WebhookConfig {
+                        url: String::new(),
+                        secret: None,
+                    },
+                );
+                wh.url = value.to_string();
+            }
+        }
+        "webhook_secret" => match config.channels.webhook.as_mut() {
+            Some(wh) => wh.secret = Some(value.to_string()).filter(|s| !s.is_empty()),
+            None => anyhow::bail!("Set webhook_url first"),
+        },
+        "reply_prefix" => config.channels.reply_format.prefix = value.replace("\\n", "\n"),
+        "reply_suffix" => config.channels.reply_format.suffix = value.replace("\\n", "\n"),
+        "greeting" => {
+            config.channels.greeting = Some(value.replace("\\n", "\n")).filter(|g| !g.trim().is_empty());
+        }
         _ => {
             println!("{} Unknown key: {}", "Error:".red(), key);
             println!("\nAvailable keys:");
@@ -349,11 +572,53 @@ pub fn run_config_set(key: &str, value: &str) -> anyhow::Result<()> {
                 "model",
                 "api_key",
                 "api_base",
+                "model_params",
+                "system_role",
+                "max_retries",
+                "retry_base_ms",
+                "header:<name>",
+                "override_auth_header",
+                "vision",
                 "max_iterations",
+                "max_continuations",
+                "history_limit",
+                "history_token_budget",
+                "summarize_after",
+                "summary_prompt",
                 "system_prompt",
+                "system_prompt_url",
+                "public_tools",
+                "web_cache_ttl_secs",
+                "subagent_max_depth",
+                "confirm_destructive",
+                "workspace",
+                "vision_model",
+                "env_allow",
+                "env_deny",
+                "update_check",
+                "update_interval_hours",
+                "server_api_key",
+                "rate_limit_per_minute",
+                "rate_limit_burst",
+                "rate_limit_trusted_proxies",
+                "chat_enter_sends",
+                "memory_pool_size",
+                "memory_busy_timeout_ms",
+                "embedding_provider",
+                "embedding_model",
+                "rag_chunk_size",
+                "rag_chunk_overlap",
                 "telegram_token",
                 "discord_token",
+                "discord_gateway",
                 "slack_token",
+                "matrix_homeserver",
+                "matrix_token",
+                "webhook_url",
+                "webhook_secret",
+                "reply_prefix",
+                "reply_suffix",
+                "greeting",
             ] {
                 println!("  • {}", k.cyan());
             }
@@ -366,7 +631,7 @@ pub fn run_config_set(key: &str, value: &str) -> anyhow::Result<()> {
         "  {} {} = {}",
         "✅ Set".green(),
         key.cyan(),
-        if key.contains("key") || key.contains("token") {
+        if key.contains("key") || key.contains("token") || key.contains("secret") {
             "••••••••(hidden)".to_string()
         } else {
             value.to_string()
@@ -425,6 +690,51 @@ pub fn run_config_show() -> anyhow::Result<()> {
             base.dimmed()
         );
     }
+    if let Some(ref params) = config.provider.model_params {
+        println!(
+            "  {} {} = {}",
+            "│".dimmed(),
+            "model_params".cyan(),
+            params.to_string().dimmed()
+        );
+    }
+    if let Some(role) = config.provider.system_role {
+        println!(
+            "  {} {} = {}",
+            "│".dimmed(),
+            "system_role".cyan(),
+            format!("{:?}", role).to_lowercase().yellow()
+        );
+    }
+    println!(
+        "  {} {} = {} (base {}ms)",
+        "│".dimmed(),
+        "max_retries".cyan(),
+        config.provider.max_retries.to_string().yellow(),
+        config.provider.retry_base_ms
+    );
+    // Header values may be credentials, so only their names are shown
+    let mut headers: Vec<_> = config.provider.headers.keys().collect();
+    headers.sort();
+    for name in headers {
+        println!(
+            "  {} {} = {}",
+            "│".dimmed(),
+            format!("header:{}", name).cyan(),
+            "••••••••(set)".green()
+        );
+    }
+    if let Some(vision) = config.provider.vision {
+        println!("  {} {} = {}", "│".dimmed(), "vision".cyan(), vision.to_string().yellow());
+    }
+    if config.provider.override_auth {
+        println!(
+            "  {} {} = {}",
+            "│".dimmed(),
+            "override_auth_header".cyan(),
+            "true".yellow()
+        );
+    }
     println!("  {}", "│".dimmed());
     println!("  {}", "├─ Agent ────────────────────────".dimmed());
     println!(
@@ -433,6 +743,77 @@ pub fn run_config_show() -> anyhow::Result<()> {
         "max_iterations".cyan(),
         config.agent.max_iterations.to_string().yellow()
     );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "max_continuations".cyan(),
+        config.agent.max_continuations.to_string().yellow()
+    );
+    println!(
+        "  {} {} = {} (token budget: {})",
+        "│".dimmed(),
+        "history_limit".cyan(),
+        config.agent.history_limit.to_string().yellow(),
+        config
+            .agent
+            .history_token_budget
+            .map(|b| b.to_string())
+            .unwrap_or_else(|| "context window".into())
+    );
+    println!(
+        "  {} {} = {}{}",
+        "│".dimmed(),
+        "summarize_after".cyan(),
+        config
+            .agent
+            .summarize_after
+            .map(|n| format!("{} turns", n))
+            .unwrap_or_else(|| "off".into())
+            .yellow(),
+        if config.agent.summary_prompt.is_some() { " (custom prompt)" } else { "" }
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "public_tools".cyan(),
+        config
+            .agent
+            .public_tools
+            .as_ref()
+            .map(|t| t.join(","))
+            .unwrap_or_else(|| "read-only".into())
+            .yellow()
+    );
+    for (name, patterns) in [("env_allow", &config.agent.env_allow), ("env_deny", &config.agent.env_deny)] {
+        println!(
+            "  {} {} = {}",
+            "│".dimmed(),
+            name.cyan(),
+            if patterns.is_empty() { "default".to_string() } else { patterns.join(",") }.yellow()
+        );
+    }
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "web_cache_ttl_secs".cyan(),
+        match config.agent.web_cache_ttl_secs {
+            0 => "off".to_string(),
+            secs => secs.to_string(),
+        }
+        .yellow()
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "subagent_max_depth".cyan(),
+        config.agent.subagent_max_depth.to_string().yellow()
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "confirm_destructive".cyan(),
+        config.agent.confirm_destructive.to_string().yellow()
+    );
     if let Some(ref prompt) = config.agent.system_prompt {
         println!(
             "  {} {} = {}...",
@@ -441,8 +822,82 @@ pub fn run_config_show() -> anyhow::Result<()> {
             &prompt[..prompt.len().min(40)]
         );
     }
+    if let Some(ref url) = config.agent.system_prompt_url {
+        println!("  {} {} = {}", "│".dimmed(), "system_prompt_url".cyan(), url.yellow());
+    }
+    if let Some(ref workspace) = config.agent.workspace {
+        println!("  {} {} = {}", "│".dimmed(), "workspace".cyan(), workspace.yellow());
+    }
+    if let Some(ref vision_model) = config.agent.vision_model {
+        println!("  {} {} = {}", "│".dimmed(), "vision_model".cyan(), vision_model.yellow());
+    }
+    println!("  {}", "│".dimmed());
+    println!("  {}", "├─ Memory ───────────────────────".dimmed());
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "memory_pool_size".cyan(),
+        config.memory.pool_size.to_string().yellow()
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "memory_busy_timeout_ms".cyan(),
+        config.memory.busy_timeout_ms.to_string().yellow()
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "embedding_provider".cyan(),
+        config.memory.embedding_provider.as_deref().unwrap_or("(chat provider)").yellow()
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "embedding_model".cyan(),
+        config.memory.embedding_model.as_deref().unwrap_or("(provider default)").yellow()
+    );
+    println!(
+        "  {} {} = {} (overlap {})",
+        "│".dimmed(),
+        "rag_chunk_size".cyan(),
+        config.memory.rag_chunk_size.to_string().yellow(),
+        config.memory.rag_chunk_overlap
+    );
+    println!("  {}", "│".dimmed());
+    println!("  {}", "├─ Server ──────────────────────".dimmed());
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "server_api_key".cyan(),
+        if config.server.api_key.is_some() {
+            "••••••••(set)".green()
+        } else {
+            "(not set — API is open)".yellow()
+        }
+    );
+    println!(
+        "  {} {} = {}",
+        "│".dimmed(),
+        "rate_limit_per_minute".cyan(),
+        match config.rate_limit.requests_per_minute {
+            0 => "off".to_string(),
+            n => format!("{} (burst {})", n, config.rate_limit.burst),
+        }
+        .yellow()
+    );
     println!("  {}", "│".dimmed());
     println!("  {}", "├─ Channels ────────────────────".dimmed());
+    let reply = &config.channels.reply_format;
+    if !reply.prefix.is_empty() {
+        println!("  {} {} = {:?}", "│".dimmed(), "reply_prefix".cyan(), reply.prefix);
+    }
+    if !reply.suffix.is_empty() {
+        println!("  {} {} = {:?}", "│".dimmed(), "reply_suffix".cyan(), reply.suffix);
+    }
+    if let Some(ref greeting) = config.channels.greeting {
+        println!("  {} {} = {:?}", "│".dimmed(), "greeting".cyan(), greeting);
+    }
     if let Some(ref tg) = config.channels.telegram {
         println!(
             "  {} {} = {}",
@@ -473,6 +928,9 @@ pub fn run_config_show() -> anyhow::Result<()> {
                 "••••••••(set)".green()
             }
         );
+        if dc.use_gateway {
+            println!("  {} {} = {}", "│".dimmed(), "discord_gateway".cyan(), "true".yellow());
+        }
     } else {
         println!(
             "  {} {} = {}",
@@ -500,12 +958,47 @@ pub fn run_config_show() -> anyhow::Result<()> {
             "(not configured)".dimmed()
         );
     }
+    if let Some(ref mx) = config.channels.matrix {
+        println!(
+            "  {} {} = {} {}",
+            "│".dimmed(),
+            "matrix".cyan(),
+            mx.homeserver_url.yellow(),
+            if mx.access_token.is_empty() {
+                "(token not set)".red()
+            } else {
+                "••••••••(set)".green()
+            }
+        );
+    } else {
+        println!(
+            "  {} {} = {}",
+            "│".dimmed(),
+            "matrix".cyan(),
+            "(not configured)".dimmed()
+        );
+    }
+    if let Some(ref wh) = config.channels.webhook {
+        println!("  {} {} = {}", "│".dimmed(), "webhook_url".cyan(), wh.url.yellow());
+        if wh.secret.is_some() {
+            println!("  {} {} = {}", "│".dimmed(), "webhook_secret".cyan(), "••••••••(set)".green());
+        }
+    }
     println!("  {}", "└───────────────────────────────".dimmed());
     println!();
 
     Ok(())
 }
 
+/// Built-in model list of a provider (used when it can't list its own models).
+pub fn known_models(provider: &str) -> &'static [&'static str] {
+    PROVIDERS
+        .iter()
+        .find(|p| p.name == provider)
+        .map(|p| p.models)
+        .unwrap_or(&[])
+}
+
 /// Load provider from saved config.
 pub fn load_saved_config() -> Option<ZenClawConfig> {
     let path = ZenClawConfig::default_path();
@@ -523,9 +1016,79 @@ pub fn data_dir() -> PathBuf {
         .join("zenclaw")
 }
 
+/// How long a fetched model list is reused.
+const MODEL_CACHE_TTL_SECS: i64 = 24 * 60 * 60;
+/// Longest wait for a provider's model list.
+const MODEL_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Models offered by the switcher: the built-in list followed by any others
+/// the provider reports. The live list is cached for a day; without a key or
+/// network only the built-in list is shown.
+async fn switcher_models(provider: &ProviderInfo, api_key: Option<&str>) -> Vec<String> {
+    let mut models: Vec<String> = provider.models.iter().map(|m| m.to_string()).collect();
+
+    let live = match cached_models(provider.name) {
+        Some(live) => Some(live),
+        None => match api_key {
+            Some(key) => fetch_models(provider, key).await,
+            None => None,
+        },
+    };
+    for id in live.unwrap_or_default() {
+        if !models.contains(&id) {
+            models.push(id);
+        }
+    }
+    models
+}
+
+async fn fetch_models(provider: &ProviderInfo, api_key: &str) -> Option<Vec<String>> {
+    use zenclaw_core::provider::LlmProvider;
+
+    let client = zenclaw_hub::providers::OpenAiProvider::new(ProviderConfig {
+        provider: provider.name.to_string(),
+        model: provider.default_model.to_string(),
+        api_key: Some(api_key.to_string()),
+        api_base: provider.api_base.map(str::to_string),
+        ..Default::default()
+    });
+    let models = tokio::time::timeout(MODEL_FETCH_TIMEOUT, client.list_models())
+        .await
+        .ok()?
+        .ok()
+        .filter(|m| !m.is_empty())?;
+
+    let path = data_dir().join("models_cache.json");
+    let mut cache: serde_json::Value = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .filter(|v: &serde_json::Value| v.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    cache[provider.name] = serde_json::json!({
+        "fetched_at": chrono::Utc::now().timestamp(),
+        "models": models,
+    });
+    let _ = std::fs::create_dir_all(data_dir());
+    let _ = std::fs::write(&path, cache.to_string());
+
+    Some(models)
+}
+
+/// Model list fetched for `provider` within the last day.
+fn cached_models(provider: &str) -> Option<Vec<String>> {
+    let raw = std::fs::read_to_string(data_dir().join("models_cache.json")).ok()?;
+    let cache: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let entry = cache.get(provider)?;
+    let age = chrono::Utc::now().timestamp() - entry["fetched_at"].as_i64()?;
+    if age > MODEL_CACHE_TTL_SECS {
+        return None;
+    }
+    serde_json::from_value(entry["models"].clone()).ok()
+}
+
 /// Run an interactive model switcher and return the selected provider configurations if completing smoothly.
 #[allow(clippy::type_complexity)]
-pub fn run_model_switcher() -> anyhow::Result<Option<(String, String, Option<String>, Option<String>)>> {
+pub async fn run_model_switcher() -> anyhow::Result<Option<(String, String, Option<String>, Option<String>)>> {
     let mut config = load_saved_config().unwrap_or_default();
 
     loop {
@@ -565,8 +1128,18 @@ pub fn run_model_switcher() -> anyhow::Result<Option<(String, String, Option<Str
                 
             (Some(base), m)
         } else {
+            let known_key = if provider.needs_key {
+                (config.provider.provider == provider.name)
+                    .then(|| config.provider.api_key.clone())
+                    .flatten()
+                    .or_else(|| std::env::var(provider.env_var).ok().filter(|k| !k.is_empty()))
+            } else {
+                Some("local".to_string())
+            };
+            let models = switcher_models(provider, known_key.as_deref()).await;
+
             let mut model_items = vec![];
-            for (i, m) in provider.models.iter().enumerate() {
+            for (i, m) in models.iter().enumerate() {
                 model_items.push(crate::tui_menu::MenuItem {
                     label: m.to_string(),
                     description: format!("Model: {}", m),
@@ -590,7 +1163,7 @@ pub fn run_model_switcher() -> anyhow::Result<Option<(String, String, Option<Str
             }
             
             let model_idx = m_action.parse::<usize>().unwrap_or(0);
-            (provider.api_base.map(|s| s.to_string()), provider.models[model_idx].to_string())
+            (provider.api_base.map(|s| s.to_string()), models[model_idx].clone())
         };
 
         // Check API Key
@@ -1,10 +1,26 @@
 //! Centralized theme — single source of truth for all TUI colors and styles.
 //!
 //! Every TUI component imports from here. Changing a color is always a one-file edit.
+//!
+//! Users can pick a built-in preset and override single colors in
+//! `theme.toml` in the data dir (`zenclaw theme set <key> <color>`):
+//!
+//! ```toml
+//! preset = "light"      # dark (default), light or high-contrast
+//! primary = "#0087af"   # hex, or a name like "cyan" / "light-red"
+//! log_error = "red"
+//! ```
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
 
 use ratatui::style::{Color, Modifier, Style};
 
+/// Built-in presets, selectable with `preset = "<name>"`.
+pub const PRESETS: &[&str] = &["dark", "light", "high-contrast"];
+
 /// The application-wide color palette.
+#[derive(Clone)]
 pub struct Theme {
     // ─── Core brand ──────────────────────────────────
     pub primary: Color,
@@ -50,55 +66,291 @@ pub struct Theme {
     // ─── Borders ─────────────────────────────────────
     pub border: Color,
     pub border_focus: Color,
+
+    /// syntect theme for highlighted code blocks.
+    pub syntax: &'static str,
+}
+
+/// The palette used everywhere, loaded from `theme.toml` on first use.
+pub static THEME: LazyLock<Theme> = LazyLock::new(Theme::load);
+
+/// Generates the key → field lookups for every color `theme.toml` can set.
+macro_rules! color_keys {
+    ($($field:ident),* $(,)?) => {
+        /// Color keys accepted in `theme.toml`, in display order.
+        pub const KEYS: &[&str] = &[$(stringify!($field)),*];
+
+        impl Theme {
+            /// The color for `key` (None for unknown keys).
+            pub fn color(&self, key: &str) -> Option<Color> {
+                match key {
+                    $(stringify!($field) => Some(self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+                match key {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+        }
+    };
 }
 
-/// The default dark theme used everywhere.
-pub static THEME: Theme = Theme {
-    // Core brand
-    primary:       Color::Cyan,
-    accent:        Color::Yellow,
-
-    // Semantic
-    error:         Color::Red,
-    warning:       Color::Yellow,
-    success:       Color::Green,
-    info:          Color::Blue,
-    muted:         Color::DarkGray,
-
-    // Chat roles
-    user_msg:      Color::Yellow,
-    ai_msg:        Color::Cyan,
-    system_msg:    Color::Red,
-
-    // Selection
-    bg_selected:   Color::Rgb(40, 40, 60),
-    bg_marked:     Color::Rgb(30, 20, 50),
-    bg_hover:      Color::Rgb(35, 35, 50),
-
-    // Tool use
-    tool_pending:  Color::DarkGray,
-    tool_done:     Color::Green,
-    tool_error:    Color::Red,
-
-    // Code / markdown
-    code_bg:       Color::Rgb(30, 30, 40),
-    code_fg:       Color::Rgb(200, 200, 180),
-    code_keyword:  Color::Rgb(198, 120, 221),
-    code_string:   Color::Rgb(152, 195, 121),
-    code_comment:  Color::Rgb(92, 99, 112),
-    code_number:   Color::Rgb(209, 154, 102),
-
-    // Log levels
-    log_error:     Color::Red,
-    log_warn:      Color::Yellow,
-    log_info:      Color::Green,
-    log_debug:     Color::Blue,
-    log_trace:     Color::Magenta,
-
-    // Borders
-    border:        Color::DarkGray,
-    border_focus:  Color::Cyan,
-};
+color_keys!(
+    primary, accent,
+    error, warning, success, info, muted,
+    user_msg, ai_msg, system_msg,
+    bg_selected, bg_marked, bg_hover,
+    tool_pending, tool_done, tool_error,
+    code_bg, code_fg, code_keyword, code_string, code_comment, code_number,
+    log_error, log_warn, log_info, log_debug, log_trace,
+    border, border_focus,
+);
+
+impl Theme {
+    /// A built-in preset by name.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// The default dark theme.
+    pub fn dark() -> Self {
+        Self {
+            // Core brand
+            primary:       Color::Cyan,
+            accent:        Color::Yellow,
+
+            // Semantic
+            error:         Color::Red,
+            warning:       Color::Yellow,
+            success:       Color::Green,
+            info:          Color::Blue,
+            muted:         Color::DarkGray,
+
+            // Chat roles
+            user_msg:      Color::Yellow,
+            ai_msg:        Color::Cyan,
+            system_msg:    Color::Red,
+
+            // Selection
+            bg_selected:   Color::Rgb(40, 40, 60),
+            bg_marked:     Color::Rgb(30, 20, 50),
+            bg_hover:      Color::Rgb(35, 35, 50),
+
+            // Tool use
+            tool_pending:  Color::DarkGray,
+            tool_done:     Color::Green,
+            tool_error:    Color::Red,
+
+            // Code / markdown
+            code_bg:       Color::Rgb(30, 30, 40),
+            code_fg:       Color::Rgb(200, 200, 180),
+            code_keyword:  Color::Rgb(198, 120, 221),
+            code_string:   Color::Rgb(152, 195, 121),
+            code_comment:  Color::Rgb(92, 99, 112),
+            code_number:   Color::Rgb(209, 154, 102),
+
+            // Log levels
+            log_error:     Color::Red,
+            log_warn:      Color::Yellow,
+            log_info:      Color::Green,
+            log_debug:     Color::Blue,
+            log_trace:     Color::Magenta,
+
+            // Borders
+            border:        Color::DarkGray,
+            border_focus:  Color::Cyan,
+
+            syntax:        "base16-ocean.dark",
+        }
+    }
+
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            primary:       Color::Blue,
+            accent:        Color::Magenta,
+
+            error:         Color::Red,
+            warning:       Color::Rgb(175, 95, 0),
+            success:       Color::Green,
+            info:          Color::Blue,
+            muted:         Color::Rgb(110, 110, 110),
+
+            user_msg:      Color::Rgb(175, 95, 0),
+            ai_msg:        Color::Blue,
+            system_msg:    Color::Red,
+
+            bg_selected:   Color::Rgb(215, 225, 245),
+            bg_marked:     Color::Rgb(230, 215, 245),
+            bg_hover:      Color::Rgb(225, 230, 240),
+
+            tool_pending:  Color::Rgb(110, 110, 110),
+            tool_done:     Color::Green,
+            tool_error:    Color::Red,
+
+            code_bg:       Color::Rgb(240, 240, 235),
+            code_fg:       Color::Rgb(50, 50, 60),
+            code_keyword:  Color::Rgb(150, 40, 170),
+            code_string:   Color::Rgb(40, 120, 40),
+            code_comment:  Color::Rgb(130, 130, 130),
+            code_number:   Color::Rgb(175, 95, 0),
+
+            log_error:     Color::Red,
+            log_warn:      Color::Rgb(175, 95, 0),
+            log_info:      Color::Green,
+            log_debug:     Color::Blue,
+            log_trace:     Color::Magenta,
+
+            border:        Color::Rgb(160, 160, 160),
+            border_focus:  Color::Blue,
+
+            syntax:        "InspiredGitHub",
+        }
+    }
+
+    /// Bright colors on black, for low-vision use or washed-out screens.
+    pub fn high_contrast() -> Self {
+        Self {
+            primary:       Color::LightCyan,
+            accent:        Color::LightYellow,
+
+            error:         Color::LightRed,
+            warning:       Color::LightYellow,
+            success:       Color::LightGreen,
+            info:          Color::LightBlue,
+            muted:         Color::Gray,
+
+            user_msg:      Color::LightYellow,
+            ai_msg:        Color::LightCyan,
+            system_msg:    Color::LightRed,
+
+            bg_selected:   Color::Blue,
+            bg_marked:     Color::Magenta,
+            bg_hover:      Color::Rgb(0, 0, 95),
+
+            tool_pending:  Color::Gray,
+            tool_done:     Color::LightGreen,
+            tool_error:    Color::LightRed,
+
+            code_bg:       Color::Black,
+            code_fg:       Color::White,
+            code_keyword:  Color::LightMagenta,
+            code_string:   Color::LightGreen,
+            code_comment:  Color::Gray,
+            code_number:   Color::LightYellow,
+
+            log_error:     Color::LightRed,
+            log_warn:      Color::LightYellow,
+            log_info:      Color::LightGreen,
+            log_debug:     Color::LightBlue,
+            log_trace:     Color::LightMagenta,
+
+            border:        Color::White,
+            border_focus:  Color::LightCyan,
+
+            syntax:        "base16-eighties.dark",
+        }
+    }
+
+    /// Build a theme from `theme.toml`: the `preset` (default dark), then
+    /// each color key on top.
+    pub fn from_table(table: &toml::Table) -> anyhow::Result<Self> {
+        let preset = match table.get("preset") {
+            Some(value) => value.as_str().ok_or_else(|| anyhow::anyhow!("preset must be a string"))?,
+            None => "dark",
+        };
+        let mut theme = Self::preset(preset)
+            .ok_or_else(|| anyhow::anyhow!("unknown preset '{}' (available: {})", preset, PRESETS.join(", ")))?;
+
+        for (key, value) in table.iter().filter(|(key, _)| key.as_str() != "preset") {
+            let slot = theme
+                .color_mut(key)
+                .ok_or_else(|| anyhow::anyhow!("unknown color key '{}'", key))?;
+            let value = value
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("{}: expected a color string", key))?;
+            *slot = parse_color(value)?;
+        }
+        Ok(theme)
+    }
+
+    /// `theme.toml`, or the dark preset when it's missing or invalid.
+    fn load() -> Self {
+        let path = theme_path();
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Self::dark();
+        };
+        let theme = text
+            .parse::<toml::Table>()
+            .map_err(anyhow::Error::from)
+            .and_then(|table| Self::from_table(&table));
+        match theme {
+            Ok(theme) => theme,
+            Err(e) => {
+                tracing::warn!("{}: {:#} — using the default theme", path.display(), e);
+                Self::dark()
+            }
+        }
+    }
+}
+
+/// Location of the user's theme file.
+pub fn theme_path() -> PathBuf {
+    crate::setup::data_dir().join("theme.toml")
+}
+
+/// The user's `theme.toml` (empty when there is none).
+pub fn read_theme_file() -> anyhow::Result<toml::Table> {
+    let path = theme_path();
+    match std::fs::read_to_string(&path) {
+        Ok(text) => text
+            .parse()
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e)),
+        Err(_) => Ok(toml::Table::new()),
+    }
+}
+
+/// Set a color key, or `preset`, in `theme.toml`. Choosing a preset drops
+/// the color overrides made on top of the previous one.
+pub fn set_theme_key(key: &str, value: &str) -> anyhow::Result<PathBuf> {
+    let mut table = read_theme_file()?;
+    if key == "preset" {
+        table.clear();
+    }
+    table.insert(key.to_string(), toml::Value::String(value.to_string()));
+    // Refuse to save a file that wouldn't load
+    Theme::from_table(&table)?;
+
+    let path = theme_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, toml::to_string(&table)?)?;
+    Ok(path)
+}
+
+/// A color from hex (`#1e1e28`), a name (`cyan`, `light-red`) or a 256-color index.
+pub fn parse_color(value: &str) -> anyhow::Result<Color> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("'{}' isn't a color (use hex like #1e1e28 or a name like cyan)", value))
+}
+
+/// A block of `color` for printing to the terminal.
+pub fn swatch(color: Color) -> String {
+    use ratatui::crossterm::style::Stylize;
+    "██".with(color.into()).to_string()
+}
 
 // ─── Convenience helpers ────────────────────────────────────────────────────
 
@@ -8,13 +8,16 @@ use ratatui::{
     Terminal,
 };
 use std::cell::Cell;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tui_textarea::{Input, TextArea};
 
-use zenclaw_core::agent::Agent;
+use zenclaw_core::agent::{Agent, SessionSummary};
+use zenclaw_core::approval::{ApprovalDecision, ToolApprover};
 use zenclaw_core::memory::MemoryStore;
-use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::message::LlmResponse;
+use zenclaw_core::provider::{ChatRequest, LlmProvider};
 use zenclaw_core::bus::EventBus;
 
 use crate::theme::THEME;
@@ -24,10 +27,110 @@ pub enum AppEvent {
     Terminal(Event),
     Tick,
     AgentStatus(String),
+    AgentDelta(String),                 // streamed answer text
     AgentResponse(String, Vec<String>), // response_text, tool_calls
     AgentError(String),
+    Summary(std::result::Result<Option<SessionSummary>, String>), // /summary result
     ToolStart(String, String),           // tool_name, context
     ToolComplete(String, u64, Duration), // tool_name, result_bytes, elapsed
+    ApprovalRequest(ApprovalRequest),
+    TurnDone(TurnStats),
+}
+
+/// Summary of a finished agent turn (from the `agent_done` bus event).
+#[derive(Debug, Clone, Default)]
+pub struct TurnStats {
+    pub elapsed: Duration,
+    pub iterations: u64,
+    pub tool_calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Usage was estimated (the provider reported none).
+    pub estimated: bool,
+}
+
+impl TurnStats {
+    fn from_event(data: &serde_json::Value) -> Self {
+        Self {
+            elapsed: Duration::from_millis(data["elapsed_ms"].as_u64().unwrap_or(0)),
+            iterations: data["iterations"].as_u64().unwrap_or(0),
+            tool_calls: data["tool_calls"].as_u64().unwrap_or(0),
+            prompt_tokens: data["prompt_tokens"].as_u64().unwrap_or(0),
+            completion_tokens: data["completion_tokens"].as_u64().unwrap_or(0),
+            estimated: data["estimated"].as_bool().unwrap_or(false),
+        }
+    }
+
+    /// Compact one-line summary, e.g. "⏱ 3.2s · 2 steps · 1 tool".
+    fn summary(&self) -> String {
+        format!(
+            "⏱ {:.1}s · {} step{} · {} tool{}",
+            self.elapsed.as_secs_f64(),
+            self.iterations,
+            if self.iterations == 1 { "" } else { "s" },
+            self.tool_calls,
+            if self.tool_calls == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// Most preview (diff) lines shown in the approval popup.
+const MAX_PREVIEW_LINES: usize = 20;
+
+/// A pending "may the agent run this tool?" question.
+#[derive(Clone)]
+pub struct ApprovalRequest {
+    pub tool: String,
+    pub args: String,
+    /// What the call will change (e.g. an edit's diff), when the tool says.
+    pub preview: Option<String>,
+    reply: Arc<Mutex<Option<oneshot::Sender<ApprovalDecision>>>>,
+}
+
+impl ApprovalRequest {
+    fn answer(&self, decision: ApprovalDecision) {
+        if let Some(tx) = self.reply.lock().ok().and_then(|mut r| r.take()) {
+            let _ = tx.send(decision);
+        }
+    }
+}
+
+/// Tool approver that asks the user inside the chat TUI.
+pub struct TuiApprover {
+    tx: mpsc::Sender<ApprovalRequest>,
+}
+
+/// Create a TUI approver and the receiver `run_tui` reads prompts from.
+pub fn approval_channel() -> (Arc<TuiApprover>, mpsc::Receiver<ApprovalRequest>) {
+    let (tx, rx) = mpsc::channel(8);
+    (Arc::new(TuiApprover { tx }), rx)
+}
+
+impl TuiApprover {
+    async fn ask(&self, tool: &str, args: &str, preview: Option<&str>) -> ApprovalDecision {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let request = ApprovalRequest {
+            tool: tool.to_string(),
+            args: args.to_string(),
+            preview: preview.map(str::to_string),
+            reply: Arc::new(Mutex::new(Some(reply_tx))),
+        };
+        if self.tx.send(request).await.is_err() {
+            return ApprovalDecision::Deny;
+        }
+        reply_rx.await.unwrap_or(ApprovalDecision::Deny)
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolApprover for TuiApprover {
+    async fn approve(&self, _session_key: &str, tool: &str, args: &str) -> ApprovalDecision {
+        self.ask(tool, args, None).await
+    }
+
+    async fn approve_with_preview(&self, _session_key: &str, tool: &str, args: &str, preview: &str) -> ApprovalDecision {
+        self.ask(tool, args, Some(preview)).await
+    }
 }
 
 /// A tool call entry displayed inline in the chat.
@@ -60,12 +163,24 @@ pub struct App<'a> {
     pub pending_tools: Vec<ToolEntry>,
     pub token_in: u64,
     pub token_out: u64,
+    /// Some of the token counts are estimates.
+    pub token_estimated: bool,
     pub copy_feedback: Option<Instant>,
     pub provider_name: String,
     pub model_name: String,
     pub suggestion_active: bool,
     pub selected_suggestion: usize,
     pub input_height: Cell<u16>,
+    pub pending_approval: Option<ApprovalRequest>,
+    pub last_turn: Option<TurnStats>,
+    /// The last message is an AI reply still receiving streamed text.
+    pub streaming_reply: bool,
+    /// Prompt of the last turn sent, for `/retry` and `/edit`.
+    pub last_prompt: Option<String>,
+    /// The agent saved the last turn to memory (it finished without error).
+    pub last_turn_saved: bool,
+    /// Enter sends and Shift+Enter adds a line (`chat.enter_sends`).
+    pub enter_sends: bool,
 }
 
 const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
@@ -90,12 +205,19 @@ impl<'a> App<'a> {
             pending_tools: Vec::new(),
             token_in: 0,
             token_out: 0,
+            token_estimated: false,
             copy_feedback: None,
             provider_name: String::new(),
             model_name: String::new(),
             suggestion_active: false,
             selected_suggestion: 0,
             input_height: Cell::new(4),
+            pending_approval: None,
+            last_turn: None,
+            streaming_reply: false,
+            last_prompt: None,
+            last_turn_saved: false,
+            enter_sends: false,
         };
         app.configure_textarea();
         app
@@ -118,19 +240,170 @@ impl<'a> App<'a> {
 const SLASH_COMMANDS: &[(&str, &str)] = &[
     ("/help", "Show help menu"),
     ("/clear", "Clear chat history"),
-    ("/model", "Show model information"),
+    ("/model", "Show or switch the model (/model name)"),
+    ("/provider", "Switch provider (/provider name [model])"),
+    ("/tools", "List available tools"),
+    ("/lang", "Set reply language (e.g. /lang id)"),
+    ("/remember-session", "Save this chat to the knowledge base"),
+    ("/summary", "Summarize this chat as bullet points"),
+    ("/use", "Send a saved prompt snippet (/use name args)"),
+    ("/prompts", "List saved prompt snippets"),
     ("/tokens", "Show token usage"),
     ("/export", "Export chat to Markdown"),
     ("/copy", "Copy last AI response"),
+    ("/retry", "Re-send your last message"),
+    ("/edit", "Edit your last message"),
 ];
 
 // ─── Slash command handler ──────────────────────────────────────────────────
 
-/// Process a slash command. Returns `true` if the input was a command (handled).
-fn handle_slash_command(input: &str, app: &mut App, provider_name: &str, model_name: &str) -> bool {
+fn prompt_library() -> zenclaw_hub::prompts::PromptLibrary {
+    zenclaw_hub::prompts::PromptLibrary::new(&crate::setup::data_dir().join("prompts"))
+}
+
+/// `/use <snippet> [args]`: the expanded snippet, or None if `text` isn't `/use`.
+fn use_snippet(text: &str) -> Option<anyhow::Result<String>> {
+    let rest = text.strip_prefix("/use")?;
+    if !(rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    let rest = rest.trim();
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if name.is_empty() {
+        return Some(Err(anyhow::anyhow!("Usage: /use <snippet> [args]. Type /prompts to list snippets.")));
+    }
+
+    Some(match prompt_library().get(name) {
+        Ok(Some(snippet)) => Ok(snippet.expand(args.trim())),
+        Ok(None) => Err(anyhow::anyhow!("No prompt snippet named '{}'. Type /prompts to list them.", name)),
+        Err(e) => Err(e.into()),
+    })
+}
+
+/// `/remember-session`: copy the session transcript into the RAG store.
+async fn remember_session(memory: &dyn MemoryStore, session_key: &str) -> anyhow::Result<usize> {
+    let rag = zenclaw_hub::memory::RagStore::open(&crate::setup::data_dir().join("rag.db"))?;
+    Ok(rag.ingest_session(memory, session_key).await?)
+}
+
+/// Outcome of [`handle_slash_command`].
+enum SlashOutcome {
+    /// Not a command — send the input as a message.
+    NotCommand,
+    /// Handled here; nothing to send.
+    Handled,
+    /// Send this message to the agent (e.g. `/retry`).
+    Send(String),
+    /// Use this provider from now on (`/model`, `/provider`).
+    SwitchProvider(Arc<dyn LlmProvider>),
+}
+
+/// A provider picked with `/model` or `/provider`. The agent keeps sending
+/// the model it was built with, so requests use the provider's own model.
+struct SwitchedProvider(Box<dyn LlmProvider>);
+
+#[async_trait::async_trait]
+impl LlmProvider for SwitchedProvider {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.0.default_model()
+    }
+
+    async fn chat(&self, request: ChatRequest) -> zenclaw_core::error::Result<LlmResponse> {
+        self.0.chat(ChatRequest { model: None, ..request }).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> zenclaw_core::error::Result<LlmResponse> {
+        self.0.chat_stream(ChatRequest { model: None, ..request }, on_text).await
+    }
+
+    async fn list_models(&self) -> zenclaw_core::error::Result<Vec<String>> {
+        self.0.list_models().await
+    }
+
+    fn count_tokens(&self, messages: &[zenclaw_core::message::ChatMessage]) -> usize {
+        self.0.count_tokens(messages)
+    }
+
+    fn context_window(&self, _model: &str) -> usize {
+        self.0.context_window(self.0.default_model())
+    }
+
+    fn supports_vision(&self, _model: &str) -> bool {
+        self.0.supports_vision(self.0.default_model())
+    }
+}
+
+/// Build the provider for a `/model` or `/provider` switch and save the
+/// choice to the config. Fails, changing nothing, if no API key resolves.
+fn switch_provider(provider_name: &str, model: &str) -> anyhow::Result<Arc<dyn LlmProvider>> {
+    let saved = crate::setup::load_saved_config().filter(|c| c.provider.provider == provider_name);
+    let api_key = saved
+        .as_ref()
+        .and_then(|c| c.provider.api_key.clone())
+        .or_else(|| crate::resolve_api_key(None, provider_name))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No API key for '{}'. Set {}_API_KEY in the environment, then try again.",
+                provider_name,
+                provider_name.to_uppercase()
+            )
+        })?;
+    let api_base = saved.and_then(|c| c.provider.api_base);
+
+    let inner = crate::create_provider(provider_name, &api_key, model, api_base.as_deref(), None);
+    crate::setup::save_model_switch(provider_name, model)?;
+    Ok(Arc::new(SwitchedProvider(inner)))
+}
+
+/// Undo the last turn for `/retry` and `/edit`: drop it from the view, and
+/// from memory if the agent saved it, and return its prompt. Without a turn
+/// from this session, the last one saved in memory is used.
+async fn take_last_turn(app: &mut App<'_>, memory: &dyn MemoryStore, session_key: &str) -> anyhow::Result<Option<String>> {
+    let (prompt, saved) = match app.last_prompt.take() {
+        Some(prompt) => (prompt, app.last_turn_saved),
+        None => {
+            let history = memory.get_history(session_key, 50).await?;
+            let last_user = history
+                .into_iter()
+                .rev()
+                .find(|m| m.role == zenclaw_core::message::Role::User)
+                .and_then(|m| m.content);
+            match last_user {
+                Some(prompt) => (prompt, true),
+                None => return Ok(None),
+            }
+        }
+    };
+
+    if saved {
+        memory.delete_last_turn(session_key).await?;
+    }
+    app.last_turn_saved = false;
+    if let Some(index) = app.messages.iter().rposition(|m| m.role == "You" && m.content == prompt) {
+        app.messages.truncate(index);
+    }
+    Ok(Some(prompt))
+}
+
+/// Process a slash command.
+async fn handle_slash_command(
+    input: &str,
+    app: &mut App<'_>,
+    agent: &Agent,
+    memory: &dyn MemoryStore,
+    session_key: &str,
+) -> SlashOutcome {
     let trimmed = input.trim();
     if !trimmed.starts_with('/') {
-        return false;
+        return SlashOutcome::NotCommand;
     }
 
     let parts: Vec<&str> = trimmed.splitn(2, ' ').collect();
@@ -143,10 +416,19 @@ fn handle_slash_command(input: &str, app: &mut App, provider_name: &str, model_n
                 "Available Commands:\n\n",
                 "  /help           — Show this help\n",
                 "  /clear          — Clear chat history\n",
-                "  /model          — Show current model\n",
+                "  /model [name]   — Show or switch the model\n",
+                "  /provider name [model] — Switch provider\n",
+                "  /tools          — List available tools\n",
+                "  /lang [code]    — Reply language (auto = follow input)\n",
+                "  /remember-session — Save this chat to the knowledge base\n",
+                "  /summary        — Summarize this chat as bullet points\n",
+                "  /use name [args] — Send a saved prompt snippet\n",
+                "  /prompts        — List saved prompt snippets\n",
                 "  /tokens         — Show token usage\n",
                 "  /export [file]  — Export chat to file\n",
                 "  /copy           — Copy last AI response\n",
+                "  /retry          — Re-send your last message\n",
+                "  /edit           — Edit your last message\n",
             );
             app.messages.push(ChatMessage {
                 role: "System".into(),
@@ -160,11 +442,47 @@ fn handle_slash_command(input: &str, app: &mut App, provider_name: &str, model_n
             app.messages.clear();
             app.token_in = 0;
             app.token_out = 0;
+            app.token_estimated = false;
+            app.last_turn = None;
         }
-        "/model" => {
+        "/model" | "/provider" if !arg.is_empty() => {
+            let mut words = arg.split_whitespace();
+            let first = words.next().unwrap_or_default().to_string();
+            let (provider_name, model) = if cmd == "/model" {
+                (app.provider_name.clone(), first)
+            } else {
+                let model = words.next().map(str::to_string);
+                let model = model.unwrap_or_else(|| crate::default_model(&first).to_string());
+                (first, model)
+            };
+
+            let (role, content) = match switch_provider(&provider_name, &model) {
+                Ok(provider) => {
+                    app.provider_name = provider_name;
+                    app.model_name = model;
+                    app.messages.push(ChatMessage {
+                        role: "System".into(),
+                        content: format!("🔁 Switched to {} / {} (saved to config).", app.provider_name, app.model_name),
+                        displayed_length: 999,
+                        is_fully_loaded: true,
+                        tool_entries: Vec::new(),
+                    });
+                    return SlashOutcome::SwitchProvider(provider);
+                }
+                Err(e) => ("System (Error)", format!("❌ Can't switch to {} / {}: {}", provider_name, model, e)),
+            };
+            app.messages.push(ChatMessage {
+                role: role.into(),
+                content,
+                displayed_length: 999,
+                is_fully_loaded: true,
+                tool_entries: Vec::new(),
+            });
+        }
+        "/model" | "/provider" => {
             let info = format!(
-                "Current Model:\n  Provider: {}\n  Model:    {}",
-                provider_name, model_name
+                "Current Model:\n  Provider: {}\n  Model:    {}\n\nSwitch with /model <name> or /provider <name> [model].",
+                app.provider_name, app.model_name
             );
             app.messages.push(ChatMessage {
                 role: "System".into(),
@@ -174,11 +492,46 @@ fn handle_slash_command(input: &str, app: &mut App, provider_name: &str, model_n
                 tool_entries: Vec::new(),
             });
         }
+        "/tools" => {
+            let info = format!("Available Tools ({}):\n\n{}", agent.tools.len(), agent.tools.describe());
+            app.messages.push(ChatMessage {
+                role: "System".into(),
+                content: info,
+                displayed_length: 999,
+                is_fully_loaded: true,
+                tool_entries: Vec::new(),
+            });
+        }
+        "/prompts" => {
+            let info = match prompt_library().list() {
+                Ok(snippets) if snippets.is_empty() => {
+                    "No prompt snippets yet. Add one with:\n  zenclaw prompts add code-review \"Review this code: {args}\"".to_string()
+                }
+                Ok(snippets) => {
+                    let lines: Vec<String> = snippets
+                        .iter()
+                        .map(|s| format!("  /use {:<16} {}", s.name, s.description))
+                        .collect();
+                    format!("Prompt Snippets ({}):\n\n{}", snippets.len(), lines.join("\n"))
+                }
+                Err(e) => format!("❌ Failed to read prompt snippets: {}", e),
+            };
+            app.messages.push(ChatMessage {
+                role: "System".into(),
+                content: info,
+                displayed_length: 999,
+                is_fully_loaded: true,
+                tool_entries: Vec::new(),
+            });
+        }
         "/tokens" => {
-            let info = format!(
+            let mut info = format!(
                 "Token Usage:\n  Input:  {} tokens\n  Output: {} tokens\n  Total:  {} tokens",
                 app.token_in, app.token_out, app.token_in + app.token_out
             );
+            if app.token_estimated {
+                info.push_str("\n  (approximate — the provider didn't report usage for every call)");
+            }
             app.messages.push(ChatMessage {
                 role: "System".into(),
                 content: info,
@@ -224,10 +577,40 @@ fn handle_slash_command(input: &str, app: &mut App, provider_name: &str, model_n
                 app.copy_feedback = Some(Instant::now());
             }
         }
+        "/retry" | "/edit" => {
+            let (role, content) = match take_last_turn(app, memory, session_key).await {
+                Ok(Some(prompt)) if cmd == "/retry" => return SlashOutcome::Send(prompt),
+                Ok(Some(prompt)) => {
+                    app.textarea = TextArea::default();
+                    app.configure_textarea();
+                    for (i, line) in prompt.split('\n').enumerate() {
+                        if i > 0 {
+                            app.textarea.insert_newline();
+                        }
+                        app.textarea.insert_str(line);
+                    }
+                    return SlashOutcome::Handled;
+                }
+                Ok(None) => ("System", "Nothing to retry or edit yet.".to_string()),
+                Err(e) => ("System (Error)", format!("❌ Couldn't undo the last turn: {}", e)),
+            };
+            app.messages.push(ChatMessage {
+                role: role.into(),
+                content,
+                displayed_length: 999,
+                is_fully_loaded: true,
+                tool_entries: Vec::new(),
+            });
+        }
         _ => {
+            let commands: Vec<&str> = SLASH_COMMANDS.iter().map(|(c, _)| *c).collect();
+            let content = match zenclaw_core::command::suggest(cmd, &commands) {
+                Some(hint) => format!("Unknown command: {}. Did you mean {}?", cmd, hint),
+                None => format!("Unknown command: {}. Type /help for available commands.", cmd),
+            };
             app.messages.push(ChatMessage {
                 role: "System".into(),
-                content: format!("Unknown command: {}. Type /help for available commands.", cmd),
+                content,
                 displayed_length: 999,
                 is_fully_loaded: true,
                 tool_entries: Vec::new(),
@@ -235,7 +618,7 @@ fn handle_slash_command(input: &str, app: &mut App, provider_name: &str, model_n
         }
     }
 
-    true
+    SlashOutcome::Handled
 }
 
 /// Copy text to system clipboard via platform tools.
@@ -265,17 +648,16 @@ fn copy_to_clipboard(text: &str) {
 pub async fn run_tui(
     mut terminal: Terminal<CrosstermBackend<std::io::Stdout>>,
     agent: std::sync::Arc<Agent>,
-    provider: std::sync::Arc<dyn LlmProvider>,
+    mut provider: std::sync::Arc<dyn LlmProvider>,
     memory: std::sync::Arc<dyn MemoryStore>,
     session_key: String,
     bus: std::sync::Arc<EventBus>,
+    approvals: Option<mpsc::Receiver<ApprovalRequest>>,
 ) -> anyhow::Result<()> {
     let mut app = App::new();
     app.provider_name = provider.name().to_string();
     app.model_name = provider.default_model().to_string();
-
-    let provider_name = app.provider_name.clone();
-    let model_name = app.model_name.clone();
+    app.enter_sends = crate::setup::load_saved_config().is_some_and(|c| c.chat.enter_sends);
 
     // Event channel
     let (tx, mut rx) = mpsc::channel(100);
@@ -320,6 +702,9 @@ pub async fn run_tui(
                     let len = ev.data["result_len"].as_u64().unwrap_or(0);
                     let _ = tx_bus.send(AppEvent::ToolComplete(tool, len, Duration::from_millis(0))).await;
                 }
+                "agent_done" => {
+                    let _ = tx_bus.send(AppEvent::TurnDone(TurnStats::from_event(&ev.data))).await;
+                }
                 _ => {
                     if let Some(msg) = ev.format_status() {
                         let _ = tx_bus.send(AppEvent::AgentStatus(msg)).await;
@@ -329,6 +714,18 @@ pub async fn run_tui(
         }
     });
 
+    // Tool approval prompts → UI
+    if let Some(mut approvals) = approvals {
+        let tx_approval = tx.clone();
+        tokio::spawn(async move {
+            while let Some(req) = approvals.recv().await {
+                if tx_approval.send(AppEvent::ApprovalRequest(req)).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
     // ── Main UI Loop ────────────────────────────────────────
     while !app.should_quit {
         terminal.draw(|f| draw_ui(f, &app))?;
@@ -368,25 +765,83 @@ pub async fn run_tui(
                         entry.done = Some((bytes, elapsed));
                     }
                 }
+                AppEvent::ApprovalRequest(req) => {
+                    app.pending_approval = Some(req);
+                }
+                AppEvent::TurnDone(stats) => {
+                    app.token_in += stats.prompt_tokens;
+                    app.token_out += stats.completion_tokens;
+                    app.token_estimated |= stats.estimated;
+                    app.last_turn = Some(stats);
+                }
+                AppEvent::AgentDelta(text) => {
+                    match app.messages.last_mut() {
+                        Some(msg) if app.streaming_reply => {
+                            msg.content.push_str(&text);
+                            msg.displayed_length = msg.content.chars().count();
+                        }
+                        _ => {
+                            app.streaming_reply = true;
+                            app.messages.push(ChatMessage {
+                                role: "AI".into(),
+                                displayed_length: text.chars().count(),
+                                content: text,
+                                is_fully_loaded: true,
+                                tool_entries: Vec::new(),
+                            });
+                        }
+                    }
+                }
                 AppEvent::AgentResponse(msg, _blocks) => {
                     app.is_processing = false;
+                    app.last_turn_saved = true;
                     app.status_text.clear();
 
-                    // Estimate tokens (rough: 1 token ≈ 4 chars)
-                    app.token_out += (msg.len() as u64) / 4;
-
                     // Move pending tools into the response message
                     let tools = std::mem::take(&mut app.pending_tools);
+                    if std::mem::take(&mut app.streaming_reply)
+                        && let Some(last) = app.messages.last_mut()
+                    {
+                        // Already on screen — streamed as it was generated
+                        last.tool_entries = tools;
+                    } else {
+                        app.messages.push(ChatMessage {
+                            role: "AI".into(),
+                            content: msg,
+                            displayed_length: 0,
+                            is_fully_loaded: false,
+                            tool_entries: tools,
+                        });
+                    }
+                }
+                AppEvent::Summary(result) => {
+                    app.is_processing = false;
+                    app.status_text.clear();
+                    let (role, content) = match result {
+                        Ok(Some(summary)) => {
+                            let mut content = format!("🧾 Summary of this chat:\n\n{}", summary.text);
+                            if summary.truncated {
+                                content.push_str(&format!(
+                                    "\n\n(Only the last {} messages were summarized; older ones were left out.)",
+                                    summary.messages
+                                ));
+                            }
+                            ("System", content)
+                        }
+                        Ok(None) => ("System", "Nothing to summarize yet — this chat is empty.".to_string()),
+                        Err(e) => ("System (Error)", format!("❌ Failed to summarize: {}", e)),
+                    };
                     app.messages.push(ChatMessage {
-                        role: "AI".into(),
-                        content: msg,
-                        displayed_length: 0,
-                        is_fully_loaded: false,
-                        tool_entries: tools,
+                        role: role.into(),
+                        content,
+                        displayed_length: 999,
+                        is_fully_loaded: true,
+                        tool_entries: Vec::new(),
                     });
                 }
                 AppEvent::AgentError(msg) => {
                     app.is_processing = false;
+                    app.streaming_reply = false;
                     app.status_text.clear();
                     let tools = std::mem::take(&mut app.pending_tools);
                     app.messages.push(ChatMessage {
@@ -398,24 +853,27 @@ pub async fn run_tui(
                     });
                 }
                 AppEvent::Terminal(Event::Paste(text)) => {
-                    if !app.is_processing {
-                        let normalized = text.replace("\r\n", "\n");
-                        for (i, line) in normalized.split('\n').enumerate() {
-                            if i > 0 {
-                                app.textarea.insert_newline();
-                            }
-                            app.textarea.insert_str(line);
+                    let normalized = text.replace("\r\n", "\n");
+                    for (i, line) in normalized.split('\n').enumerate() {
+                        if i > 0 {
+                            app.textarea.insert_newline();
                         }
+                        app.textarea.insert_str(line);
                     }
                 }
                 AppEvent::Terminal(Event::Key(key)) => {
                     // Ctrl+C → cancel or quit
                     if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
                         if app.is_processing {
+                            // Stops running tools (killing shell commands) before dropping the task
+                            agent.cancel(&session_key);
                             if let Some(handle) = app.current_task_handle.take() {
                                 handle.abort();
                             }
+                            agent.take_injected(&session_key);
+                            app.pending_approval = None;
                             app.is_processing = false;
+                            app.streaming_reply = false;
                             app.status_text.clear();
                             app.pending_tools.clear();
                             app.messages.push(ChatMessage {
@@ -431,6 +889,35 @@ pub async fn run_tui(
                         continue;
                     }
 
+                    // Tool approval prompt: [y]es / [a]lways / [n]o / [d]eny always
+                    if let Some(req) = app.pending_approval.take() {
+                        let decision = match key.code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') => Some(ApprovalDecision::Allow),
+                            KeyCode::Char('a') | KeyCode::Char('A') => Some(ApprovalDecision::AlwaysAllow),
+                            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(ApprovalDecision::Deny),
+                            KeyCode::Char('d') | KeyCode::Char('D') => Some(ApprovalDecision::AlwaysDeny),
+                            _ => None,
+                        };
+                        match decision {
+                            Some(d) => {
+                                req.answer(d);
+                                if matches!(d, ApprovalDecision::AlwaysAllow | ApprovalDecision::AlwaysDeny) {
+                                    let verb = if d.is_allowed() { "allowed" } else { "denied" };
+                                    let note = format!("🔐 '{}' will be {} for the rest of this session.", req.tool, verb);
+                                    app.messages.push(ChatMessage {
+                                        role: "System".into(),
+                                        displayed_length: note.len(),
+                                        content: note,
+                                        is_fully_loaded: true,
+                                        tool_entries: Vec::new(),
+                                    });
+                                }
+                            }
+                            None => app.pending_approval = Some(req),
+                        }
+                        continue;
+                    }
+
                     // Ctrl+Y → copy last AI response
                     if key.code == KeyCode::Char('y') && key.modifiers.contains(KeyModifiers::CONTROL) {
                         if let Some(last_ai) = app.messages.iter().rev().find(|m| m.role == "AI") {
@@ -526,22 +1013,115 @@ pub async fn run_tui(
                     let is_enter = matches!(key.code, KeyCode::Enter | KeyCode::Char('\n') | KeyCode::Char('\r'));
                     let has_ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
                     let has_alt = key.modifiers.contains(KeyModifiers::ALT);
+                    let has_shift = key.modifiers.contains(KeyModifiers::SHIFT);
 
                     let is_ctrl_s = key.code == KeyCode::Char('s') && has_ctrl;
+                    // `chat.enter_sends`: Enter sends, Shift+Enter (or Alt+Enter where
+                    // the terminal can't report Shift) adds a line
+                    let sends = if app.enter_sends {
+                        is_ctrl_s || !(has_shift || has_alt)
+                    } else {
+                        has_ctrl || has_alt || is_ctrl_s
+                    };
 
                     if is_enter || is_ctrl_s {
-                        if has_ctrl || has_alt || is_ctrl_s {
-                            // Ctrl+Enter, Alt+Enter, or Ctrl+S → Send
-                            if !app.is_processing {
+                        if sends {
+                            // Ctrl+Enter, Alt+Enter, or Ctrl+S → Send (Enter with `chat.enter_sends`)
+                            // (while the agent is working → inject a note into the running turn)
+                            if app.is_processing {
+                                let text = app.textarea.lines().join("\n").trim().to_string();
+                                if !text.is_empty() {
+                                    app.textarea = TextArea::default();
+                                    app.configure_textarea();
+                                    app.scroll_offset = 0;
+
+                                    agent.inject(&session_key, &text);
+                                    app.messages.push(ChatMessage {
+                                        role: "You".into(),
+                                        content: text.clone(),
+                                        displayed_length: text.chars().count(),
+                                        is_fully_loaded: true,
+                                        tool_entries: Vec::new(),
+                                    });
+                                    app.status_text = "💬 Note queued for the next step...".into();
+                                }
+                            } else {
                                 let text = app.textarea.lines().join("\n").trim().to_string();
+                                let commands: Vec<&str> = SLASH_COMMANDS.iter().map(|(c, _)| *c).collect();
+                                let text = zenclaw_core::command::expand(&text, &commands);
                                 if !text.is_empty() {
                                     // Submit
                                     app.textarea = TextArea::default();
                                     app.configure_textarea();
                                     app.scroll_offset = 0;
 
-                                    if !handle_slash_command(&text, &mut app, &provider_name, &model_name) {
-                                        app.token_in += (text.len() as u64) / 4;
+                                    // `/use` sends the expanded snippet as a regular message
+                                    let text = match use_snippet(&text) {
+                                        Some(Ok(prompt)) => prompt,
+                                        Some(Err(e)) => {
+                                            app.messages.push(ChatMessage {
+                                                role: "System (Error)".into(),
+                                                content: format!("❌ {}", e),
+                                                displayed_length: 999,
+                                                is_fully_loaded: true,
+                                                tool_entries: Vec::new(),
+                                            });
+                                            continue;
+                                        }
+                                        None => text,
+                                    };
+
+                                    if text == "/remember-session" {
+                                        let (role, content) = match remember_session(memory.as_ref(), &session_key).await {
+                                            Ok(0) => ("System", "Nothing to remember yet — this chat is empty.".to_string()),
+                                            Ok(chunks) => ("System", format!("🧠 Saved this chat to the knowledge base ({} chunks). Future sessions can recall it.", chunks)),
+                                            Err(e) => ("System (Error)", format!("❌ Failed to save chat: {}", e)),
+                                        };
+                                        app.messages.push(ChatMessage {
+                                            role: role.into(),
+                                            content,
+                                            displayed_length: 999,
+                                            is_fully_loaded: true,
+                                            tool_entries: Vec::new(),
+                                        });
+                                    } else if text == "/summary" {
+                                        // Runs in the background like a turn, so Ctrl+C can cancel it
+                                        app.is_processing = true;
+                                        app.status_text = "🧾 Summarizing this chat...".into();
+                                        let agent_c = agent.clone();
+                                        let provider_c = provider.clone();
+                                        let memory_c = memory.clone();
+                                        let tx_c = tx.clone();
+                                        let sk = session_key.clone();
+                                        app.current_task_handle = Some(tokio::spawn(async move {
+                                            let result = agent_c
+                                                .session_summary(&*provider_c, &*memory_c, &sk)
+                                                .await
+                                                .map_err(|e| e.to_string());
+                                            let _ = tx_c.send(AppEvent::Summary(result)).await;
+                                        }));
+                                    } else if let Some(reply) = zenclaw_core::locale::command(memory.as_ref(), &session_key, &text).await {
+                                        app.messages.push(ChatMessage {
+                                            role: "System".into(),
+                                            content: reply,
+                                            displayed_length: 999,
+                                            is_fully_loaded: true,
+                                            tool_entries: Vec::new(),
+                                        });
+                                    } else {
+                                        let text = match handle_slash_command(
+                                            &text, &mut app, &agent, memory.as_ref(), &session_key,
+                                        ).await {
+                                            SlashOutcome::NotCommand => text,
+                                            SlashOutcome::Send(prompt) => prompt,
+                                            SlashOutcome::Handled => continue,
+                                            SlashOutcome::SwitchProvider(new_provider) => {
+                                                provider = new_provider;
+                                                continue;
+                                            }
+                                        };
+                                        app.last_prompt = Some(text.clone());
+                                        app.last_turn_saved = false;
                                         app.messages.push(ChatMessage {
                                             role: "You".into(),
                                             content: text.clone(),
@@ -561,7 +1141,26 @@ pub async fn run_tui(
                                         let bus_c = bus.clone();
 
                                         let handle = tokio::spawn(async move {
-                                            match agent_c.process(&*provider_c, &*memory_c, &text, &sk, Some(&bus_c)).await {
+                                            // Deltas go through their own unbounded queue so none are
+                                            // dropped; it is drained before the final response is sent
+                                            let (delta_tx, mut delta_rx) = mpsc::unbounded_channel::<String>();
+                                            let tx_delta = tx_c.clone();
+                                            let forwarder = tokio::spawn(async move {
+                                                while let Some(text) = delta_rx.recv().await {
+                                                    let _ = tx_delta.send(AppEvent::AgentDelta(text)).await;
+                                                }
+                                            });
+
+                                            let mut on_delta = |text: &str| {
+                                                let _ = delta_tx.send(text.to_string());
+                                            };
+                                            let result = agent_c
+                                                .process_streaming(&*provider_c, &*memory_c, &text, &sk, Some(&bus_c), &mut on_delta)
+                                                .await;
+                                            drop(delta_tx);
+                                            let _ = forwarder.await;
+
+                                            match result {
                                                 Ok(resp) => { let _ = tx_c.send(AppEvent::AgentResponse(resp, vec![])).await; },
                                                 Err(e) => { let _ = tx_c.send(AppEvent::AgentError(e.to_string())).await; },
                                             }
@@ -572,23 +1171,19 @@ pub async fn run_tui(
                             }
                         } else {
                             // Enter → New line + auto-indent
-                            if !app.is_processing {
-                                let lines = app.textarea.lines();
-                                let (row, _) = app.textarea.cursor();
-                                let current_line = lines.get(row).map(|s| s.as_str()).unwrap_or("");
-                                let indent = current_line.chars().take_while(|c| c.is_whitespace()).collect::<String>();
-                                
-                                app.textarea.insert_newline();
-                                app.textarea.insert_str(indent);
-                            }
+                            let lines = app.textarea.lines();
+                            let (row, _) = app.textarea.cursor();
+                            let current_line = lines.get(row).map(|s| s.as_str()).unwrap_or("");
+                            let indent = current_line.chars().take_while(|c| c.is_whitespace()).collect::<String>();
+
+                            app.textarea.insert_newline();
+                            app.textarea.insert_str(indent);
                         }
                         continue;
                     }
 
-                    // Regular typing
-                    if !app.is_processing {
-                        app.textarea.input(Input::from(key));
-                    }
+                    // Regular typing (also while processing, to write a mid-turn note)
+                    app.textarea.input(Input::from(key));
                 }
                 AppEvent::Terminal(Event::Mouse(mouse)) => {
                     let size = terminal.size().unwrap_or_default();
@@ -777,20 +1372,34 @@ fn draw_ui(f: &mut ratatui::Frame, app: &App) {
     }
 
     // ── Input area ─────────────────────────────────────
-    let help_text = if app.is_processing {
-        "[Ctrl+C] Cancel │ [Ctrl+↑↓] Scroll "
+    let help_text = match (app.is_processing, app.enter_sends) {
+        (true, false) => "[Ctrl+Enter/S] Add Note │ [Ctrl+C] Cancel │ [Ctrl+↑↓] Scroll ",
+        (true, true) => "[Enter] Add Note │ [Ctrl+C] Cancel │ [Ctrl+↑↓] Scroll ",
+        (false, false) => "[Ctrl+Enter/S] Send │ [Enter] New Line │ [Ctrl+C] Quit │ [Ctrl+Y] Copy ",
+        (false, true) => "[Enter] Send │ [Shift+Enter] New Line │ [Ctrl+C] Quit │ [Ctrl+Y] Copy ",
+    };
+
+    // Token estimate for long drafts (warn when it eats a big chunk of the context)
+    let draft_tokens = zenclaw_core::tokens::estimate_bpe(&app.textarea.lines().join("\n"));
+    let token_hint = if draft_tokens >= 500 {
+        let window = zenclaw_core::tokens::context_window(&app.model_name);
+        let style = if draft_tokens * 4 > window { Style::default().fg(THEME.warning) } else { THEME.hint() };
+        Some(Span::styled(format!(" ≈{} tokens ", draft_tokens), style))
     } else {
-        "[Ctrl+Enter/S] Send │ [Enter] New Line │ [Ctrl+C] Quit │ [Ctrl+Y] Copy "
+        None
     };
 
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(if app.is_processing { THEME.border_style() } else { THEME.border_focus_style() })
+        .title(Span::styled(" Message ", Style::default().fg(Color::White).bold()))
+        .title_bottom(Span::styled(help_text, THEME.hint()));
+    if let Some(hint) = token_hint {
+        block = block.title(Line::from(hint).right_aligned());
+    }
+
     let mut textarea = app.textarea.clone();
-    textarea.set_block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(if app.is_processing { THEME.border_style() } else { THEME.border_focus_style() })
-            .title(Span::styled(" Message ", Style::default().fg(Color::White).bold()))
-            .title_bottom(Span::styled(help_text, THEME.hint()))
-    );
+    textarea.set_block(block);
 
     f.render_widget(&textarea, chunks[1]);
 
@@ -859,6 +1468,62 @@ fn draw_ui(f: &mut ratatui::Frame, app: &App) {
         }
     }
 
+    // ── Tool Approval Popup ──
+    if let Some(req) = &app.pending_approval {
+        let area = f.area();
+        // Diff previews get the lines they need, up to a point
+        let preview_lines: Vec<&str> = req.preview.iter().flat_map(|p| p.lines()).take(MAX_PREVIEW_LINES).collect();
+        let width: u16 = if preview_lines.is_empty() { 70 } else { 100 };
+        let width = width.min(area.width.saturating_sub(4));
+        let height = (9 + preview_lines.len() as u16).min(area.height);
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+        let args_preview: String = req.args.chars().take(200).collect();
+        let mut body = vec![
+            Line::from(vec![
+                Span::raw("The agent wants to run "),
+                Span::styled(req.tool.clone(), Style::default().fg(THEME.accent).bold()),
+            ]),
+            Line::from(""),
+        ];
+        if preview_lines.is_empty() {
+            body.push(Line::from(Span::styled(args_preview, THEME.hint())));
+        }
+        for line in &preview_lines {
+            let style = if line.starts_with("@@") {
+                Style::default().fg(THEME.accent)
+            } else if line.starts_with('+') {
+                THEME.ok()
+            } else if line.starts_with('-') {
+                THEME.err()
+            } else {
+                THEME.hint()
+            };
+            body.push(Line::from(Span::styled(line.to_string(), style)));
+        }
+        body.extend([
+            Line::from(""),
+            Line::from(Span::styled(
+                "[y] Allow once  [a] Always allow  [n] Deny  [d] Always deny",
+                Style::default().fg(THEME.primary).bold(),
+            )),
+        ]);
+        f.render_widget(ratatui::widgets::Clear, popup_area);
+        f.render_widget(
+            Paragraph::new(body)
+                .wrap(Wrap { trim: preview_lines.is_empty() })
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(THEME.border_focus_style())
+                    .title(Span::styled(" 🔐 Tool Approval ", THEME.title()))),
+            popup_area,
+        );
+    }
+
     // ── Status bar ─────────────────────────────────────
     let copy_indicator = if app.copy_feedback.map_or(false, |t| t.elapsed().as_secs() < 2) {
         Span::styled(" 📋 Copied! ", THEME.ok())
@@ -866,15 +1531,24 @@ fn draw_ui(f: &mut ratatui::Frame, app: &App) {
         Span::raw("")
     };
 
+    let approx = if app.token_estimated { "≈" } else { "" };
     let token_info = format!(
-        " {} │ {} │ Tokens: ↑{} ↓{} ",
+        " {} │ {} │ Tokens: ↑{}{} ↓{}{} ",
         app.provider_name,
         app.model_name,
+        approx,
         app.token_in,
+        approx,
         app.token_out,
     );
 
+    let turn_info = match &app.last_turn {
+        Some(turn) if !app.is_processing => format!(" {} │", turn.summary()),
+        _ => String::new(),
+    };
+
     let status_bar = Paragraph::new(Line::from(vec![
+        Span::styled(turn_info, THEME.hint()),
         Span::styled(token_info, THEME.hint()),
         copy_indicator,
     ])).alignment(ratatui::layout::Alignment::Right);
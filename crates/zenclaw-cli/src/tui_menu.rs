@@ -227,7 +227,7 @@ pub fn run_tui_menu(title: &str, items: &[MenuItem], default_idx: usize) -> io::
     Ok(selected_action)
 }
 
-pub fn run_main_menu(has_config: bool) -> io::Result<Option<String>> {
+pub fn run_main_menu(has_config: bool, update_available: Option<&str>) -> io::Result<Option<String>> {
     let mut items = vec![];
     
     if !has_config {
@@ -274,16 +274,27 @@ pub fn run_main_menu(has_config: bool) -> io::Result<Option<String>> {
             description: "View and manage available skills.\n\nSkills give ZenClaw special instructions and capabilities for specific domains (like coding, system diagnostics, writing, etc).".to_string(),
             action_key: "skills".to_string(),
         },
+        MenuItem {
+            label: "🧠 Manage Knowledge".to_string(),
+            description: "Browse and manage the RAG knowledge base.\n\nAdd files, folders or web pages, inspect their indexed chunks, and delete documents the agent should no longer search.".to_string(),
+            action_key: "knowledge".to_string(),
+        },
         MenuItem {
             label: "⚙️  Settings".to_string(),
             description: "View or modify configuration parameters.\n\nCheck the path to your settings file or re-configure default behavior.".to_string(),
             action_key: "settings".to_string(),
         },
         MenuItem {
-            label: "🔄 Check for Updates".to_string(),
-            description: "Check if a newer version of ZenClaw is available and pull updates to your current installation.".to_string(),
-            action_key: "updates".to_string(),
+            label: match update_available {
+                Some(v) => format!("🔄 Check for Updates  🆕 v{}", v),
+                None => "🔄 Check for Updates".to_string(),
             },
+            description: match update_available {
+                Some(v) => format!("ZenClaw v{} is available!\n\nOpen to see the changelog and install instructions.", v),
+                None => "Check if a newer version of ZenClaw is available and pull updates to your current installation.".to_string(),
+            },
+            action_key: "updates".to_string(),
+        },
         MenuItem {
             label: "🐛 View Live Logs".to_string(),
             description: "Tail the ZenClaw internal diagnostic logs.\n\nGood for debugging connection issues, plugin errors, tools usages, and latency tracking.".to_string(),
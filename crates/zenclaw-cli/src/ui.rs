@@ -118,48 +118,61 @@ pub fn print_setup_complete(config_path: &str, provider: &str, model: &str, has_
 
 // ─── Session Info ───────────────────────────────────────────
 
-/// Compact info card at the start of `zenclaw chat`.
+/// Compact info card at the start of `zenclaw chat`. When skills scope the
+/// tools (`scoped`), the resolved tool set is listed too.
 pub fn print_session_info(
     provider: &str,
     model: &str,
-    tools_count: usize,
+    tools: &[&str],
+    scoped: bool,
     skills: &[String],
 ) {
     print_banner();
 
-    let tools_badge = badge(&format!("{} tools", tools_count));
+    let tools_badge = badge(&format!("{} tools", tools.len()));
     let memory_badge = badge("SQLite");
 
-    print_card(
-        "Session",
-        &[
-            &format!(
-                "{} {} {}  {}  {} {} {}",
-                "Provider".dimmed(),
-                "›".dimmed(),
-                provider.green().bold(),
-                "│".dimmed(),
-                "Model".dimmed(),
-                "›".dimmed(),
-                model.cyan().bold()
-            ),
-            &format!(
-                "{}  {}  {}",
-                tools_badge,
-                memory_badge,
-                if skills.is_empty() {
-                    String::new()
-                } else {
-                    format!("{} {}", "Skills:".dimmed(), skills.join(", ").yellow())
-                }
-            ),
-            &format!(
-                "{}",
-                "↑↓ history  │  /help for commands".dimmed()
-            ),
-        ],
-        CARD_WIDTH,
-    );
+    let mut lines = vec![
+        format!(
+            "{} {} {}  {}  {} {} {}",
+            "Provider".dimmed(),
+            "›".dimmed(),
+            provider.green().bold(),
+            "│".dimmed(),
+            "Model".dimmed(),
+            "›".dimmed(),
+            model.cyan().bold()
+        ),
+        format!(
+            "{}  {}  {}",
+            tools_badge,
+            memory_badge,
+            if skills.is_empty() {
+                String::new()
+            } else {
+                format!("{} {}", "Skills:".dimmed(), skills.join(", ").yellow())
+            }
+        ),
+    ];
+    if scoped {
+        // Wrap the tool names to the card width
+        let mut row = String::new();
+        for tool in tools {
+            if !row.is_empty() && row.len() + tool.len() + 9 > CARD_WIDTH - 4 {
+                lines.push(format!("{} {}", "Tools:".dimmed(), row.trim_end_matches(", ").cyan()));
+                row.clear();
+            }
+            row.push_str(tool);
+            row.push_str(", ");
+        }
+        if !row.is_empty() {
+            lines.push(format!("{} {}", "Tools:".dimmed(), row.trim_end_matches(", ").cyan()));
+        }
+    }
+    lines.push(format!("{}", "↑↓ history  │  /help for commands".dimmed()));
+
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    print_card("Session", &lines, CARD_WIDTH);
     println!();
 }
 
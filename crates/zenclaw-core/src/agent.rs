@@ -8,11 +8,18 @@
 //! 5. Loops until the agent gives a final answer
 //! 6. Returns the response
 
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use crate::approval::{self, ToolApprover};
 use crate::error::{Result, ZenClawError};
-use crate::memory::MemoryStore;
-use crate::message::{ChatMessage, LlmResponse};
-use crate::provider::{ChatRequest, LlmProvider};
-use crate::tool::ToolRegistry;
+use crate::memory::{MemoryStore, KV_GLOBAL};
+use crate::message::{ChatMessage, LlmResponse, Role, TokenUsage};
+use crate::prompt::PromptVars;
+use crate::provider::{ChatRequest, LlmProvider, ToolDefinition};
+use crate::tool::{CancellationToken, ToolRegistry};
 use crate::bus::{EventBus, SystemEvent};
 
 /// Configuration for the agent loop.
@@ -20,7 +27,8 @@ use crate::bus::{EventBus, SystemEvent};
 pub struct AgentConfig {
     /// Maximum reasoning iterations before giving up.
     pub max_iterations: usize,
-    /// System prompt template.
+    /// System prompt template (see [`crate::prompt`] for `{variables}`).
+    /// Initial value only — replace it at runtime via [`Agent::system_prompt_handle`].
     pub system_prompt: String,
     /// Model override (None = use provider default).
     pub model: Option<String>,
@@ -28,8 +36,42 @@ pub struct AgentConfig {
     pub max_tokens: u32,
     /// Temperature for generation.
     pub temperature: f32,
+    /// Follow-up requests allowed when an answer is cut off by `max_tokens`
+    /// (finish reason `length`). 0 = never continue.
+    pub max_continuations: usize,
+    /// Most history messages loaded from memory per turn.
+    pub history_limit: usize,
+    /// Token budget for the whole prompt (system + history + new message).
+    /// Older history is dropped to fit; None = only the model's context
+    /// window limits it.
+    pub history_token_budget: Option<usize>,
+    /// Summarize older history once this many user turns are in the loaded
+    /// window (see [`Agent::summarize_session`]). None = never.
+    pub summarize_after: Option<usize>,
+    /// Recent messages kept verbatim when summarizing.
+    pub summarize_keep: usize,
+    /// Instructions for the summarization call.
+    pub summary_prompt: String,
+    /// Ask before every [destructive](crate::tool::Tool::is_destructive)
+    /// tool call. Only those calls go to the approver, and they always do;
+    /// without an approver they are refused.
+    pub confirm_destructive: bool,
+    /// Confine the file tools to this directory (see
+    /// [`crate::tool::current_workspace`]). None = any path.
+    pub workspace_root: Option<PathBuf>,
+    /// Give every session its own subdirectory of `workspace_root`.
+    pub workspace_per_session: bool,
+    /// Model (same provider) that answers turns with images when `model`
+    /// can't see them. None = such turns fail with a clear error.
+    pub vision_model: Option<String>,
 }
 
+/// Sent when an answer stopped at `max_tokens`.
+const CONTINUE_PROMPT: &str = "Your previous message was cut off by the output limit. Continue exactly where it stopped — no repetition, no preamble.";
+
+/// Appended when the answer is still cut off after the last continuation.
+const TRUNCATED_NOTE: &str = "\n\n_[Answer truncated: it hit the output limit. Ask me to continue for the rest.]_";
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
@@ -38,31 +80,209 @@ impl Default for AgentConfig {
             model: None,
             max_tokens: 4096,
             temperature: 0.7,
+            max_continuations: 3,
+            history_limit: 50,
+            history_token_budget: None,
+            summarize_after: None,
+            summarize_keep: 6,
+            summary_prompt: DEFAULT_SUMMARY_PROMPT.to_string(),
+            confirm_destructive: false,
+            workspace_root: None,
+            workspace_per_session: false,
+            vision_model: None,
         }
     }
 }
 
+/// Per-turn counters reported in the `agent_done` event.
+#[derive(Debug, Default)]
+struct TurnStats {
+    iterations: usize,
+    tool_calls: usize,
+    /// Names of the tools called, in order.
+    tools: Vec<String>,
+    /// Provider calls that failed after the provider's own retries.
+    provider_errors: usize,
+    usage: TokenUsage,
+    /// True if any usage figure was estimated (provider reported none).
+    estimated: bool,
+}
+
+/// Token usage of a whole turn (every LLM call, tool steps included).
+#[derive(Debug, Clone, Default)]
+pub struct TurnUsage {
+    pub usage: TokenUsage,
+    /// True if the provider reported no usage and some figures are estimates.
+    pub estimated: bool,
+}
+
+/// Most recent messages read by [`Agent::session_summary`].
+pub const SESSION_SUMMARY_MAX_MESSAGES: usize = 200;
+
+/// An on-demand summary of a session (see [`Agent::session_summary`]).
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    /// Bullet points.
+    pub text: String,
+    /// Messages the summary covers.
+    pub messages: usize,
+    /// Older messages were left out (only the last
+    /// [`SESSION_SUMMARY_MAX_MESSAGES`] are read).
+    pub truncated: bool,
+}
+
 /// The core agent — ties together provider, tools, and memory.
 pub struct Agent {
     pub config: AgentConfig,
     pub tools: ToolRegistry,
+    /// Asks the user before each tool call (None = run tools freely).
+    pub approver: Option<Arc<dyn ToolApprover>>,
+    /// "Always allow/deny" answers given to the approver this run.
+    approvals: approval::Remembered,
+    /// User messages injected mid-turn, per session (see [`Agent::inject`]).
+    injected: Mutex<HashMap<String, Vec<String>>>,
+    /// Cancellation tokens of in-flight turns, per session (see [`Agent::cancel`]).
+    turns: Mutex<HashMap<String, CancellationToken>>,
+    /// Current system prompt template (starts as `config.system_prompt`).
+    system_prompt: Arc<RwLock<String>>,
+    /// Long-lived bus that also receives every `agent_done`, `tool_use` and
+    /// `tool_result` event (see [`Agent::set_event_bus`]).
+    events: Option<Arc<EventBus>>,
 }
 
 impl Agent {
     /// Create a new agent with default config.
     pub fn new() -> Self {
-        Self {
-            config: AgentConfig::default(),
-            tools: ToolRegistry::new(),
-        }
+        Self::with_config(AgentConfig::default())
     }
 
     /// Create a new agent with custom config.
     pub fn with_config(config: AgentConfig) -> Self {
         Self {
+            system_prompt: Arc::new(RwLock::new(config.system_prompt.clone())),
             config,
             tools: ToolRegistry::new(),
+            approver: None,
+            approvals: approval::Remembered::default(),
+            injected: Mutex::new(HashMap::new()),
+            turns: Mutex::new(HashMap::new()),
+            events: None,
+        }
+    }
+
+    /// Shared handle to the system prompt template. Writing to it changes
+    /// the prompt from the next turn on (e.g. after a remote prompt reload).
+    pub fn system_prompt_handle(&self) -> Arc<RwLock<String>> {
+        self.system_prompt.clone()
+    }
+
+    /// Require approval for tool calls.
+    pub fn set_approver(&mut self, approver: Arc<dyn ToolApprover>) {
+        self.approver = Some(approver);
+    }
+
+    /// Publish each turn's `agent_done` event, and every `tool_use` /
+    /// `tool_result`, to `bus` as well, whichever channel ran the turn. Lets
+    /// one subscriber (e.g. an outbound webhook or the tool audit log) see
+    /// all activity of the agent.
+    pub fn set_event_bus(&mut self, bus: Arc<EventBus>) {
+        self.events = Some(bus);
+    }
+
+    /// Publish to the turn's bus and the long-lived one.
+    fn publish_shared(&self, bus: Option<&EventBus>, event: SystemEvent) {
+        if let Some(events) = &self.events {
+            events.publish_system(event.clone());
+        }
+        if let Some(b) = bus {
+            b.publish_system(event);
+        }
+    }
+
+    /// Directory the file tools of `session_key` are confined to, if any.
+    pub fn session_workspace(&self, session_key: &str) -> Option<PathBuf> {
+        let root = self.config.workspace_root.as_ref()?;
+        if !self.config.workspace_per_session {
+            return Some(root.clone());
+        }
+        // Only safe characters, so a session key can't point outside the root
+        let dir: String = session_key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        Some(root.join(dir))
+    }
+
+    /// The bus set with [`set_event_bus`](Self::set_event_bus), if any.
+    pub fn event_bus(&self) -> Option<&Arc<EventBus>> {
+        self.events.as_ref()
+    }
+
+    /// Add a user message to the in-flight turn of `session_key`.
+    ///
+    /// It is picked up before the next provider call, so the agent can
+    /// adjust course without the turn being cancelled.
+    pub fn inject(&self, session_key: &str, message: &str) {
+        if let Ok(mut queue) = self.injected.lock() {
+            queue
+                .entry(session_key.to_string())
+                .or_default()
+                .push(message.to_string());
+        }
+    }
+
+    /// Remove and return queued mid-turn messages (e.g. when a turn is cancelled).
+    pub fn take_injected(&self, session_key: &str) -> Vec<String> {
+        self.injected
+            .lock()
+            .ok()
+            .and_then(|mut queue| queue.remove(session_key))
+            .unwrap_or_default()
+    }
+
+    /// Cancel the in-flight turn of `session_key`.
+    ///
+    /// The pending provider call is dropped and running tools are told to
+    /// stop (shell commands are killed); the turn then fails with
+    /// [`ZenClawError::Cancelled`]. Returns false if no turn was running.
+    pub fn cancel(&self, session_key: &str) -> bool {
+        let token = self
+            .turns
+            .lock()
+            .ok()
+            .and_then(|turns| turns.get(session_key).cloned());
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Append queued mid-turn messages to `messages` and `turn` (what gets
+    /// saved with the turn); returns how many were added.
+    fn apply_injected(
+        &self,
+        messages: &mut Vec<ChatMessage>,
+        turn: &mut Vec<ChatMessage>,
+        session_key: &str,
+        bus: Option<&EventBus>,
+    ) -> usize {
+        let injected = self.take_injected(session_key);
+        for text in &injected {
+            tracing::info!("Injected user message into turn: {} chars", text.len());
+            messages.push(ChatMessage::user(text));
+            turn.push(ChatMessage::user(text));
+            if let Some(b) = bus {
+                b.publish_system(SystemEvent {
+                    run_id: session_key.to_string(),
+                    event_type: "user_inject".into(),
+                    data: serde_json::json!({ "message": text }),
+                });
+            }
         }
+        injected.len()
     }
 
     /// Run the ReAct loop for a single user message.
@@ -83,7 +303,43 @@ impl Agent {
         self.process_with_media(provider, memory, user_message, Vec::new(), session_key, bus).await
     }
 
+    /// Like [`Agent::process`], but also returns the token usage of the turn.
+    pub async fn process_detailed(
+        &self,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        user_message: &str,
+        session_key: &str,
+        bus: Option<&EventBus>,
+    ) -> Result<(String, TurnUsage)> {
+        self.process_inner(provider, memory, user_message, Vec::new(), session_key, bus, None)
+            .await
+    }
+
+    /// Like [`Agent::process`], but passes answer text to `on_delta` as the
+    /// provider generates it (see [`LlmProvider::chat_stream`]).
+    ///
+    /// Text the model writes before calling a tool is flushed as it streams;
+    /// once the tools have run, streaming resumes after a blank line. The
+    /// returned string is the final answer only.
+    pub async fn process_streaming(
+        &self,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        user_message: &str,
+        session_key: &str,
+        bus: Option<&EventBus>,
+        on_delta: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<String> {
+        self.process_inner(provider, memory, user_message, Vec::new(), session_key, bus, Some(on_delta))
+            .await
+            .map(|(answer, _)| answer)
+    }
+
     /// Run the ReAct loop for a single user message containing a media payload.
+    ///
+    /// Always ends by publishing an `agent_done` event (on success or error)
+    /// with iteration/tool-call counts, elapsed time and token usage.
     pub async fn process_with_media(
         &self,
         provider: &dyn LlmProvider,
@@ -93,14 +349,132 @@ impl Agent {
         session_key: &str,
         bus: Option<&EventBus>,
     ) -> Result<String> {
-        // 1. Load conversation history
-        let history = memory.get_history(session_key, 50).await?;
+        self.process_inner(provider, memory, user_message, media, session_key, bus, None)
+            .await
+            .map(|(answer, _)| answer)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn process_inner(
+        &self,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        user_message: &str,
+        media: Vec<String>,
+        session_key: &str,
+        bus: Option<&EventBus>,
+        on_delta: Option<&mut (dyn for<'a> FnMut(&'a str) + Send)>,
+    ) -> Result<(String, TurnUsage)> {
+        let started = Instant::now();
+        let mut stats = TurnStats::default();
+
+        let cancel = CancellationToken::new();
+        if let Ok(mut turns) = self.turns.lock() {
+            turns.insert(session_key.to_string(), cancel.clone());
+        }
+
+        let result = self
+            .run_turn(provider, memory, user_message, media, session_key, bus, &mut stats, on_delta, &cancel)
+            .await;
+
+        if let Ok(mut turns) = self.turns.lock() {
+            turns.remove(session_key);
+        }
+        // Too late for this turn; don't let it leak into the next one
+        let late = self.take_injected(session_key);
+        if !late.is_empty() {
+            tracing::warn!("Dropped {} message(s) injected after the turn ended", late.len());
+        }
+
+        let done = SystemEvent {
+            run_id: session_key.to_string(),
+            event_type: "agent_done".into(),
+            data: serde_json::json!({
+                "success": result.is_ok(),
+                "error": result.as_ref().err().map(|e| e.to_string()),
+                "input": user_message,
+                "output": result.as_ref().ok(),
+                "iterations": stats.iterations,
+                "tool_calls": stats.tool_calls,
+                "tools": stats.tools,
+                "provider_errors": stats.provider_errors,
+                "elapsed_ms": started.elapsed().as_millis() as u64,
+                "prompt_tokens": stats.usage.prompt_tokens,
+                "completion_tokens": stats.usage.completion_tokens,
+                "total_tokens": stats.usage.total_tokens,
+                "estimated": stats.estimated,
+            }),
+        };
+        self.publish_shared(bus, done);
+
+        result.map(|answer| {
+            let usage = TurnUsage {
+                usage: stats.usage,
+                estimated: stats.estimated,
+            };
+            (answer, usage)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_turn(
+        &self,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        user_message: &str,
+        media: Vec<String>,
+        session_key: &str,
+        bus: Option<&EventBus>,
+        stats: &mut TurnStats,
+        mut on_delta: Option<&mut (dyn for<'a> FnMut(&'a str) + Send)>,
+        cancel: &CancellationToken,
+    ) -> Result<String> {
+        // 1. Load conversation history, compressing it first once it grew long
+        let mut history = memory.get_history(session_key, self.config.history_limit).await?;
+        if let Some(after) = self.config.summarize_after {
+            let turns = history.iter().filter(|m| m.role == Role::User).count();
+            if turns >= after.max(1) {
+                match self.summarize_session(provider, memory, session_key).await {
+                    Ok(true) => {
+                        if let Some(b) = bus {
+                            b.publish_system(SystemEvent {
+                                run_id: session_key.to_string(),
+                                event_type: "history_summarize".into(),
+                                data: serde_json::json!({ "turns": turns }),
+                            });
+                        }
+                        history = memory.get_history(session_key, self.config.history_limit).await?;
+                    }
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("History summarization failed: {}", e),
+                }
+            }
+        }
 
         // 2. Build initial messages
         let mut messages = Vec::new();
+        let model = self.turn_model(provider, !media.is_empty())?;
 
-        // System prompt & RAG Auto-Inject
-        let mut sys_prompt = self.config.system_prompt.clone();
+        // System prompt (rendered per turn so {date}/{time} stay current) & RAG Auto-Inject
+        let mut vars = PromptVars::from_env(&self.tools);
+        vars.set("model", model.as_deref().unwrap_or(provider.default_model()));
+        let template = self.system_prompt.read().map(|p| p.clone()).unwrap_or_else(|e| e.into_inner().clone());
+        let mut sys_prompt = vars.render(&template);
+        let lang = crate::locale::get(memory, session_key).await;
+        let workspace = self.session_workspace(session_key);
+        if let Some(dir) = &workspace
+            && let Err(e) = std::fs::create_dir_all(dir)
+        {
+            tracing::warn!("Can't create workspace {}: {}", dir.display(), e);
+        }
+        if let Some(code) = &lang {
+            sys_prompt.push_str("\n\n");
+            sys_prompt.push_str(&crate::locale::prompt_instruction(code));
+        }
+        if let Some(facts) = remembered_facts(memory, session_key).await {
+            sys_prompt.push_str("\n\n");
+            sys_prompt.push_str(&facts);
+        }
         if let Ok(Some(context)) = memory.search_knowledge(user_message, 3).await {
             if !context.is_empty() {
                 sys_prompt.push_str("\n\n");
@@ -117,44 +491,44 @@ impl Agent {
         }
         messages.push(ChatMessage::system(&sys_prompt));
 
-        // Token Summarization Strategy (Context Window Control)
-        // Keep calculating total string length to approximate tokens
-        let mut history_to_keep = Vec::new();
-        let mut history_len = 0;
-        let max_history_chars = 30_000; // Roughly ~7500 tokens before it gets too huge
+        messages.extend(history);
 
-        for msg in history.into_iter().rev() { // Start from newest
-            let content_len = msg.content.as_deref().unwrap_or("").len();
-            if history_len + content_len > max_history_chars {
+        // Current user message
+        messages.push(ChatMessage::user_with_media(user_message, media));
+
+        // Keep long conversations within the configured budget
+        if let Some(budget) = self.config.history_token_budget {
+            let dropped = trim_history(&mut messages, budget, crate::tokens::estimate_messages);
+            if dropped > 0 {
+                tracing::info!("History budget {}: dropped {} old message(s)", budget, dropped);
+                messages.insert(
+                    1,
+                    ChatMessage::system(
+                        "[System Note: Older conversation history has been truncated automatically to prevent memory overflow.]",
+                    ),
+                );
                 if let Some(b) = bus {
                     b.publish_system(crate::bus::SystemEvent {
                         run_id: session_key.to_string(),
                         event_type: "memory_truncate".into(),
-                        data: serde_json::json!({ "kept_chars": history_len }),
+                        data: serde_json::json!({ "budget": budget, "dropped_messages": dropped }),
                     });
                 }
-                history_to_keep.push(ChatMessage::system(
-                    "[System Note: Older conversation history has been truncated automatically to prevent memory overflow.]",
-                ));
-                break;
             }
-            history_len += content_len;
-            history_to_keep.push(msg);
         }
-        
-        history_to_keep.reverse();
-        messages.extend(history_to_keep);
-
-        // Current user message
-        messages.push(ChatMessage::user_with_media(user_message, media));
 
         // Get tool definitions
         let tool_defs = self.tools.definitions();
 
         // 3. ReAct loop
         let mut iterations = 0;
+        // User messages injected mid-turn (and answers they superseded)
+        let mut injected = Vec::new();
+        // Whether answer text has been streamed yet (to separate steps)
+        let mut streamed_any = false;
         let final_response = loop {
             iterations += 1;
+            stats.iterations = iterations.min(self.config.max_iterations);
             if iterations > self.config.max_iterations {
                 return Err(ZenClawError::MaxIterations(self.config.max_iterations));
             }
@@ -173,6 +547,12 @@ impl Agent {
                 });
             }
 
+            // Mid-turn clarifications from the user
+            self.apply_injected(&mut messages, &mut injected, session_key, bus);
+
+            // Pre-flight: trim oldest history if the prompt won't fit the context window
+            self.fit_context(provider, model.as_deref(), &mut messages, &tool_defs, session_key, bus);
+
             // Call LLM
             let request = ChatRequest {
                 messages: messages.clone(),
@@ -181,57 +561,67 @@ impl Agent {
                 } else {
                     tool_defs.clone()
                 },
-                model: self.config.model.clone(),
+                model: model.clone(),
                 max_tokens: self.config.max_tokens,
                 temperature: self.config.temperature,
             };
 
-            let mut retry_count = 0;
-            let max_retries = 5; // Increased from 3 to allow waiting out rate limits
-            let mut backoff_ms = 2000;
-
-            let response: LlmResponse = loop {
-                match provider.chat(request.clone()).await {
-                    Ok(resp) => break resp,
-                    Err(e) => {
-                        if retry_count >= max_retries {
-                            tracing::error!("LLM Provider failed after {} retries: {}", max_retries, e);
-                            return Err(e);
-                        }
-
-                        let err_str = e.to_string();
-                        // 429 handles "Too Many Requests" rate limit
-                        let is_rate_limit = err_str.contains("429") || err_str.to_lowercase().contains("rate limit");
-                        
-                        let wait_ms = if is_rate_limit {
-                            // Free tiers (e.g. 3 RPM) need ~20s cool-off between retries.
-                            20_000
-                        } else {
-                            backoff_ms
+            // Transient failures (429, 5xx, timeouts) are already retried by the provider
+            let call = async {
+                match reborrow(&mut on_delta) {
+                    Some(sink) => {
+                        let mut needs_break = streamed_any;
+                        let mut emitted = false;
+                        let mut forward = |text: &str| {
+                            if needs_break {
+                                sink("\n\n");
+                                needs_break = false;
+                            }
+                            emitted = true;
+                            sink(text);
                         };
-
-                        tracing::warn!("LLM Error: {}. Retrying in {}ms...", e, wait_ms);
-                        if let Some(b) = bus {
-                            b.publish_system(SystemEvent {
-                                run_id: session_key.to_string(),
-                                event_type: "llm_retry".into(),
-                                data: serde_json::json!({ 
-                                    "attempt": retry_count + 1, 
-                                    "error": e.to_string(),
-                                    "is_rate_limit": is_rate_limit,
-                                    "wait_ms": wait_ms,
-                                }),
-                            });
-                        }
-                        tokio::time::sleep(std::time::Duration::from_millis(wait_ms)).await;
-                        
-                        retry_count += 1;
-                        if !is_rate_limit {
-                            backoff_ms *= 2;
-                        }
+                        let attempt = provider.chat_stream(request.clone(), &mut forward).await;
+                        streamed_any |= emitted;
+                        attempt
                     }
+                    None => provider.chat(request.clone()).await,
                 }
             };
+            let attempt = tokio::select! {
+                attempt = call => attempt,
+                _ = cancel.cancelled() => return Err(ZenClawError::Cancelled),
+            };
+            let response: LlmResponse = match attempt {
+                Ok(resp) => resp,
+                Err(e) => {
+                    stats.provider_errors += 1;
+                    tracing::error!("LLM provider failed: {}", e);
+                    return Err(e);
+                }
+            };
+
+            if response.usage.total_tokens > 0 {
+                stats.usage += &response.usage;
+            } else {
+                // Provider didn't report usage — fall back to an estimate
+                let prompt = provider.count_tokens(&messages) as u32;
+                let completion = crate::tokens::estimate_chars(response.content.as_deref().unwrap_or_default()) as u32
+                    + response
+                        .tool_calls
+                        .iter()
+                        .map(|c| crate::tokens::estimate_chars(&c.function.arguments) as u32)
+                        .sum::<u32>();
+                stats.usage += &TokenUsage {
+                    prompt_tokens: prompt,
+                    completion_tokens: completion,
+                    total_tokens: prompt + completion,
+                };
+                stats.estimated = true;
+            }
+            stats.tool_calls += response.tool_calls.len();
+            stats
+                .tools
+                .extend(response.tool_calls.iter().map(|c| c.function.name.clone()));
 
             tracing::debug!(
                 "LLM response: finish_reason={}, tool_calls={}, tokens={}",
@@ -254,23 +644,90 @@ impl Agent {
                 let mut exec_futures = Vec::new();
 
                 for call in tool_calls {
-                    if let Some(b) = bus {
-                        b.publish_system(SystemEvent {
+                    self.publish_shared(
+                        bus,
+                        SystemEvent {
                             run_id: session_key.to_string(),
                             event_type: "tool_use".into(),
                             data: serde_json::json!({ "tool": call.function.name, "args": call.function.arguments }),
+                        },
+                    );
+
+                    let mut args: serde_json::Value =
+                        serde_json::from_str(&call.function.arguments).unwrap_or_default();
+
+                    // Approvals are asked one at a time, before anything runs.
+                    // Tools refused by policy fail on execution without a prompt.
+                    let allowed = self.tools.is_allowed(&call.function.name);
+                    let gated = allowed && self.config.confirm_destructive && self.tools.is_destructive(&call.function.name);
+                    let asks = self.approver.is_some() && (gated || (allowed && !self.config.confirm_destructive));
+                    let preview = match self.tools.get(&call.function.name) {
+                        Some(tool) if asks || gated => {
+                            crate::tool::with_workspace(workspace.clone(), tool.preview(&args)).await
+                        }
+                        _ => None,
+                    };
+                    if gated {
+                        self.publish_shared(
+                            bus,
+                            SystemEvent {
+                                run_id: session_key.to_string(),
+                                event_type: "confirm_required".into(),
+                                data: serde_json::json!({
+                                    "tool": call.function.name,
+                                    "args": call.function.arguments,
+                                    "preview": preview,
+                                }),
+                            },
+                        );
+                    }
+                    let denied = match &self.approver {
+                        Some(approver) if asks => !approval::check(
+                            approver.as_ref(),
+                            &self.approvals,
+                            session_key,
+                            &call.function.name,
+                            &call.function.arguments,
+                            preview.as_deref(),
+                        )
+                        .await,
+                        _ => gated,
+                    };
+
+                    if denied && let Some(b) = bus {
+                        b.publish_system(SystemEvent {
+                            run_id: session_key.to_string(),
+                            event_type: "tool_denied".into(),
+                            data: serde_json::json!({ "tool": call.function.name }),
                         });
                     }
 
-                    let args: serde_json::Value =
-                        serde_json::from_str(&call.function.arguments).unwrap_or_default();
+                    // Sticky session language wins over whatever the model guessed
+                    if let Some(code) = &lang
+                        && self
+                            .tools
+                            .get(&call.function.name)
+                            .is_some_and(|t| t.parameters()["properties"].get("lang").is_some())
+                        && let Some(obj) = args.as_object_mut()
+                    {
+                        obj.insert("lang".into(), serde_json::json!(code));
+                    }
 
                     let fut = async move {
+                        let started = Instant::now();
+                        if denied {
+                            let msg = format!(
+                                "Error: The user denied permission to run '{}'. Do not retry it; continue without it or ask the user.",
+                                call.function.name
+                            );
+                            return (call, msg, started.elapsed());
+                        }
+
                         // Anti-Freeze Execution (Hard Timeout)
                         let timeout_duration = std::time::Duration::from_secs(60);
                         let execution = tokio::time::timeout(
                             timeout_duration,
-                            self.tools.execute(&call.function.name, args),
+                            self.tools.execute_cancellable(&call.function.name, args, cancel),
                         );
 
                         let result = match execution.await {
@@ -291,31 +748,45 @@ impl Agent {
                                 timeout_err_msg
                             }
                         };
-                        (call, result)
+                        (call, result, started.elapsed())
                     };
-                    exec_futures.push(fut);
+                    exec_futures.push(crate::tool::with_session(
+                        session_key,
+                        crate::tool::with_workspace(workspace.clone(), fut),
+                    ));
                 }
 
                 let results = futures::future::join_all(exec_futures).await;
+                if cancel.is_cancelled() {
+                    return Err(ZenClawError::Cancelled);
+                }
 
                 // Add tool results to messages
-                for (call, result) in results {
+                for (call, result, elapsed) in results {
                     messages.push(ChatMessage::tool_result(
                         &call.id,
                         &call.function.name,
                         &result,
                     ));
 
-                    if let Some(b) = bus {
-                        b.publish_system(SystemEvent {
+                    // Tool failures, denials and timeouts all come back as "Error..." text
+                    let error = result
+                        .starts_with("Error")
+                        .then(|| result.lines().next().unwrap_or_default().chars().take(200).collect::<String>());
+                    self.publish_shared(
+                        bus,
+                        SystemEvent {
                             run_id: session_key.to_string(),
                             event_type: "tool_result".into(),
                             data: serde_json::json!({
                                 "tool": call.function.name,
-                                "result_len": result.len()
+                                "result_len": result.len(),
+                                "elapsed_ms": elapsed.as_millis() as u64,
+                                "success": error.is_none(),
+                                "error": error,
                             }),
-                        });
-                    }
+                        },
+                    );
                 }
 
                 // Continue loop — LLM will see tool results and decide next step
@@ -323,14 +794,41 @@ impl Agent {
             }
 
             // No tool calls — this is the final answer
-            let answer = response.content.unwrap_or_default();
+            let mut answer = response.content.unwrap_or_default();
+
+            // Cut off by max_tokens: ask for the rest instead of ending mid-sentence
+            if response.finish_reason == "length" {
+                answer = self
+                    .continue_answer(provider, model.as_deref(), &messages, answer, session_key, bus, stats, reborrow(&mut on_delta))
+                    .await;
+            }
+
+            // ...unless the user added something while we were thinking
+            let mut pending = Vec::new();
+            let mut late = vec![ChatMessage::assistant(&answer)];
+            if self.apply_injected(&mut pending, &mut late, session_key, bus) > 0 {
+                messages.push(ChatMessage::assistant(&answer));
+                messages.extend(pending);
+                injected.extend(late);
+                continue;
+            }
             break answer;
         };
 
-        // 4. Save to memory
-        memory
-            .save_turn(session_key, user_message, &final_response)
-            .await?;
+        // 4. Save to memory, with any messages the user added mid-turn
+        if injected.is_empty() {
+            memory
+                .save_turn(session_key, user_message, &final_response)
+                .await?;
+        } else {
+            memory.save_message(session_key, &ChatMessage::user(user_message)).await?;
+            for message in &injected {
+                memory.save_message(session_key, message).await?;
+            }
+            memory
+                .save_message(session_key, &ChatMessage::assistant(&final_response))
+                .await?;
+        }
 
         tracing::info!(
             "Agent completed in {} iteration(s), response: {} chars",
@@ -340,6 +838,234 @@ impl Agent {
 
         Ok(final_response)
     }
+
+    /// Model for a turn: the configured one, or `vision_model` when the turn
+    /// has images the configured one can't see.
+    fn turn_model(&self, provider: &dyn LlmProvider, has_media: bool) -> Result<Option<String>> {
+        let model = self.config.model.as_deref().unwrap_or(provider.default_model());
+        if !has_media || provider.supports_vision(model) {
+            return Ok(self.config.model.clone());
+        }
+        match &self.config.vision_model {
+            Some(vision_model) => {
+                tracing::info!("{} can't see images, {} answers this turn", model, vision_model);
+                Ok(Some(vision_model.clone()))
+            }
+            None => Err(ZenClawError::Config(format!(
+                "The model {} can't see images. Switch to a vision-capable model or set agent.vision_model",
+                model
+            ))),
+        }
+    }
+
+    /// Request continuations of an answer that hit `max_tokens`, up to
+    /// `config.max_continuations`. Adds a note if it's still cut off.
+    #[allow(clippy::too_many_arguments)]
+    async fn continue_answer(
+        &self,
+        provider: &dyn LlmProvider,
+        model: Option<&str>,
+        messages: &[ChatMessage],
+        mut answer: String,
+        session_key: &str,
+        bus: Option<&EventBus>,
+        stats: &mut TurnStats,
+        mut on_delta: Option<&mut (dyn for<'a> FnMut(&'a str) + Send)>,
+    ) -> String {
+        for n in 1..=self.config.max_continuations {
+            if let Some(b) = bus {
+                b.publish_system(SystemEvent {
+                    run_id: session_key.to_string(),
+                    event_type: "llm_continue".into(),
+                    data: serde_json::json!({ "continuation": n, "max": self.config.max_continuations }),
+                });
+            }
+
+            let mut request_messages = messages.to_vec();
+            request_messages.push(ChatMessage::assistant(&answer));
+            request_messages.push(ChatMessage::user(CONTINUE_PROMPT));
+            let request = ChatRequest {
+                messages: request_messages,
+                tools: vec![],
+                model: model.map(str::to_string),
+                max_tokens: self.config.max_tokens,
+                temperature: self.config.temperature,
+            };
+
+            let attempt = match reborrow(&mut on_delta) {
+                Some(sink) => provider.chat_stream(request, sink).await,
+                None => provider.chat(request).await,
+            };
+            let response = match attempt {
+                Ok(resp) => resp,
+                Err(e) => {
+                    tracing::warn!("Continuation {} failed: {}", n, e);
+                    break;
+                }
+            };
+            stats.usage += &response.usage;
+            answer.push_str(response.content.as_deref().unwrap_or_default());
+
+            if response.finish_reason != "length" {
+                tracing::info!("Answer completed after {} continuation(s)", n);
+                return answer;
+            }
+        }
+
+        tracing::warn!("Answer still truncated after {} continuation(s)", self.config.max_continuations);
+        answer.push_str(TRUNCATED_NOTE);
+        if let Some(sink) = on_delta {
+            sink(TRUNCATED_NOTE);
+        }
+        answer
+    }
+
+    /// Compress the older part of a session into a single [`Role::Summary`]
+    /// message stored back in memory; the last `summarize_keep` messages
+    /// stay as they are. An earlier summary is folded into the new one.
+    ///
+    /// Returns false when there is too little history to summarize.
+    pub async fn summarize_session(
+        &self,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        session_key: &str,
+    ) -> Result<bool> {
+        let history = memory.get_history(session_key, 10_000).await?;
+
+        // Keep from a user turn on, so tool calls stay next to their results
+        let mut split = history.len().saturating_sub(self.config.summarize_keep);
+        while split < history.len() && history[split].role != Role::User {
+            split += 1;
+        }
+        let (older, recent) = history.split_at(split);
+        if older.len() < 2 {
+            return Ok(false);
+        }
+
+        let request = ChatRequest {
+            messages: vec![
+                ChatMessage::system(&self.config.summary_prompt),
+                ChatMessage::user(&summary_transcript(older)),
+            ],
+            model: self.config.model.clone(),
+            max_tokens: 1024,
+            temperature: 0.2,
+            ..Default::default()
+        };
+        let response = provider.chat(request).await?;
+        let summary = response.content.as_deref().unwrap_or_default().trim();
+        if summary.is_empty() {
+            return Err(ZenClawError::Provider("Summarization returned no text".into()));
+        }
+
+        let mut compacted = vec![ChatMessage::summary(summary)];
+        compacted.extend_from_slice(recent);
+        memory.replace_history(session_key, &compacted).await?;
+
+        tracing::info!(
+            "Summarized {} message(s) of {}, kept {}",
+            older.len(),
+            session_key,
+            recent.len()
+        );
+        Ok(true)
+    }
+
+    /// Bullet summary of a session for the user (catching up, handoff
+    /// notes). Unlike [`summarize_session`](Self::summarize_session) the
+    /// stored history is left untouched.
+    ///
+    /// Returns None when the session has no history.
+    pub async fn session_summary(
+        &self,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        session_key: &str,
+    ) -> Result<Option<SessionSummary>> {
+        let mut history = memory
+            .get_history(session_key, SESSION_SUMMARY_MAX_MESSAGES + 1)
+            .await?;
+        let truncated = history.len() > SESSION_SUMMARY_MAX_MESSAGES;
+        if truncated {
+            history.remove(0);
+        }
+        if history.iter().all(|m| m.role == Role::System) {
+            return Ok(None);
+        }
+
+        let request = ChatRequest {
+            messages: vec![
+                ChatMessage::system(SESSION_SUMMARY_PROMPT),
+                ChatMessage::user(&summary_transcript(&history)),
+            ],
+            model: self.config.model.clone(),
+            max_tokens: 1024,
+            temperature: 0.2,
+            ..Default::default()
+        };
+        let response = provider.chat(request).await?;
+        let text = response.content.as_deref().unwrap_or_default().trim();
+        if text.is_empty() {
+            return Err(ZenClawError::Provider("Summarization returned no text".into()));
+        }
+
+        Ok(Some(SessionSummary {
+            text: text.to_string(),
+            messages: history.len(),
+            truncated,
+        }))
+    }
+
+    /// Drop the oldest history messages until the estimated prompt fits in
+    /// the model's context window (leaving room for the reply).
+    fn fit_context(
+        &self,
+        provider: &dyn LlmProvider,
+        model: Option<&str>,
+        messages: &mut Vec<ChatMessage>,
+        tool_defs: &[ToolDefinition],
+        session_key: &str,
+        bus: Option<&EventBus>,
+    ) {
+        let model = model.unwrap_or(provider.default_model());
+        let budget = provider
+            .context_window(model)
+            .saturating_sub(self.config.max_tokens as usize);
+        let tools_cost: usize = tool_defs
+            .iter()
+            .map(|t| serde_json::to_string(t).map(|s| s.len() / 4).unwrap_or(0))
+            .sum();
+
+        let before = provider.count_tokens(messages) + tools_cost;
+        let dropped = trim_history(messages, budget, |m| provider.count_tokens(m) + tools_cost);
+        let estimate = if dropped > 0 {
+            provider.count_tokens(messages) + tools_cost
+        } else {
+            before
+        };
+
+        if dropped > 0 || estimate > budget {
+            tracing::warn!(
+                "Context pre-flight: ~{} tokens (budget {}), dropped {} old message(s)",
+                estimate,
+                budget,
+                dropped
+            );
+            if let Some(b) = bus {
+                b.publish_system(SystemEvent {
+                    run_id: session_key.to_string(),
+                    event_type: "context_trim".into(),
+                    data: serde_json::json!({
+                        "estimated_tokens": before,
+                        "remaining_tokens": estimate,
+                        "budget": budget,
+                        "dropped_messages": dropped,
+                    }),
+                });
+            }
+        }
+    }
 }
 
 impl Default for Agent {
@@ -348,9 +1074,117 @@ impl Default for Agent {
     }
 }
 
+/// Reborrow an optional delta sink for one call, so it can be used again
+/// on the next loop iteration.
+fn reborrow<'s>(
+    sink: &'s mut Option<&mut (dyn for<'a> FnMut(&'a str) + Send)>,
+) -> Option<&'s mut (dyn for<'a> FnMut(&'a str) + Send + 's)> {
+    match sink {
+        Some(f) => {
+            let f: &'s mut (dyn for<'a> FnMut(&'a str) + Send + 's) = &mut **f;
+            Some(f)
+        }
+        None => None,
+    }
+}
+
+/// Cap on the facts injected into the system prompt.
+const MAX_FACTS_PROMPT_BYTES: usize = 2_000;
+
+/// Facts saved with the `memory` tool (global, then the session's own,
+/// which win on equal keys) as a system prompt section.
+async fn remembered_facts(memory: &dyn MemoryStore, session_key: &str) -> Option<String> {
+    let mut facts: Vec<(String, String)> = memory.kv_list(KV_GLOBAL).await.unwrap_or_default();
+    for (key, value) in memory.kv_list(session_key).await.unwrap_or_default() {
+        facts.retain(|(k, _)| *k != key);
+        facts.push((key, value));
+    }
+    if facts.is_empty() {
+        return None;
+    }
+    let mut section = String::from("Facts you saved with the memory tool:");
+    for (key, value) in facts {
+        let line = format!("\n- {}: {}", key, value);
+        if section.len() + line.len() > MAX_FACTS_PROMPT_BYTES {
+            section.push_str("\n- ... (more: use the memory tool's list action)");
+            break;
+        }
+        section.push_str(&line);
+    }
+    Some(section)
+}
+
+/// Plain-text transcript of `messages` for the summarization call.
+fn summary_transcript(messages: &[ChatMessage]) -> String {
+    messages
+        .iter()
+        .filter_map(|m| {
+            let text = m.content.as_deref().unwrap_or_default().trim();
+            let line = match m.role {
+                Role::Summary => format!("Earlier summary: {}", text),
+                Role::User => format!("User: {}", text),
+                Role::Assistant => {
+                    let tools: Vec<&str> = m
+                        .tool_calls
+                        .iter()
+                        .flatten()
+                        .map(|c| c.function.name.as_str())
+                        .collect();
+                    if tools.is_empty() {
+                        format!("Assistant: {}", text)
+                    } else {
+                        format!("Assistant: {} [called {}]", text, tools.join(", "))
+                    }
+                }
+                Role::Tool => {
+                    let preview: String = text.chars().take(500).collect();
+                    format!("Tool result: {}", preview)
+                }
+                Role::System => return None,
+            };
+            Some(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Drop the oldest messages until `estimate(messages)` fits in `budget`,
+/// always keeping the system prompt (index 0) and the latest user turn.
+/// Returns the number of messages dropped.
+fn trim_history(
+    messages: &mut Vec<ChatMessage>,
+    budget: usize,
+    estimate: impl Fn(&[ChatMessage]) -> usize,
+) -> usize {
+    let last_user = messages.iter().rposition(|m| m.role == Role::User).unwrap_or(0);
+    let mut removable = last_user.saturating_sub(1);
+    let mut dropped = 0;
+
+    while removable > 0 && estimate(messages) > budget {
+        messages.remove(1);
+        removable -= 1;
+        dropped += 1;
+        // Never leave tool results without their assistant tool-call message
+        while removable > 0 && messages.get(1).map(|m| m.role == Role::Tool).unwrap_or(false) {
+            messages.remove(1);
+            removable -= 1;
+            dropped += 1;
+        }
+    }
+    dropped
+}
+
+/// Default instructions for [`Agent::summarize_session`].
+pub const DEFAULT_SUMMARY_PROMPT: &str = "Summarize the conversation below so it can replace the original messages. Keep names, decisions, facts, open questions and anything the user asked to remember. Write compact notes in the conversation's language, no preamble.";
+
+/// Instructions for [`Agent::session_summary`].
+const SESSION_SUMMARY_PROMPT: &str = "Summarize the conversation below as short bullet points for someone catching up or taking it over: what was asked, what was decided or done, and what is still open. Write in the conversation's language, no preamble.";
+
 /// Default system prompt for ZenClaw.
 pub const DEFAULT_SYSTEM_PROMPT: &str = r#"You are ZenClaw, a capable and helpful AI assistant.
 
+Today is {date}. You are running on {os} in `{cwd}`.
+
 ## Core Principles
 - Be helpful, accurate, and concise
 - Use tools when needed to accomplish tasks
@@ -368,3 +1202,317 @@ When executing tasks:
 5. Report back clearly
 
 Always prioritize accuracy over speed. If you need to use multiple tools, do so methodically."#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_trimmed_to_budget() {
+        let mut messages = vec![ChatMessage::system("You are a helpful assistant.")];
+        for i in 0..200 {
+            let text = format!("Message number {} in a long conversation about Rust lifetimes and borrowing.", i);
+            messages.push(if i % 2 == 0 {
+                ChatMessage::user(&text)
+            } else {
+                ChatMessage::assistant(&text)
+            });
+        }
+        messages.push(ChatMessage::user("What did we decide?"));
+        assert!(crate::tokens::estimate_messages(&messages) > 2000);
+
+        let dropped = trim_history(&mut messages, 2000, crate::tokens::estimate_messages);
+        assert!(dropped > 0);
+        assert!(crate::tokens::estimate_messages(&messages) <= 2000);
+        assert_eq!(messages.len(), 202 - dropped);
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(messages.last().unwrap().content.as_deref(), Some("What did we decide?"));
+        // The newest history survives, the oldest goes
+        assert!(messages[messages.len() - 2].content.as_deref().unwrap().contains("number 199"));
+        assert!(!messages.iter().any(|m| m.content.as_deref().unwrap_or("").contains("number 0 ")));
+    }
+
+    struct SummaryProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for SummaryProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        fn default_model(&self) -> &str {
+            "fake"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+            let transcript = request.messages[1].content.clone().unwrap_or_default();
+            Ok(LlmResponse {
+                content: Some(format!("{} lines summarized", transcript.lines().filter(|l| !l.is_empty()).count())),
+                tool_calls: Vec::new(),
+                model: "fake".into(),
+                usage: TokenUsage::default(),
+                finish_reason: "stop".into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summarize_session() {
+        let memory = crate::memory::InMemoryStore::new();
+        for i in 0..10 {
+            memory.save_turn("cli:s", &format!("q{}", i), &format!("a{}", i)).await.unwrap();
+        }
+        let agent = Agent::new();
+
+        assert!(agent.summarize_session(&SummaryProvider, &memory, "cli:s").await.unwrap());
+        let history = memory.get_history("cli:s", 100).await.unwrap();
+        assert_eq!(history.len(), 1 + agent.config.summarize_keep);
+        assert_eq!(history[0].role, Role::Summary);
+        assert_eq!(history[0].content.as_deref(), Some("14 lines summarized"));
+        assert_eq!(history[1].content.as_deref(), Some("q7"));
+
+        // A second pass folds the old summary into the new one
+        memory.save_turn("cli:s", "q10", "a10").await.unwrap();
+        assert!(agent.summarize_session(&SummaryProvider, &memory, "cli:s").await.unwrap());
+        let history = memory.get_history("cli:s", 100).await.unwrap();
+        assert_eq!(history[0].content.as_deref(), Some("3 lines summarized"));
+        assert_eq!(history.iter().filter(|m| m.role == Role::Summary).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_keeps_history() {
+        let memory = crate::memory::InMemoryStore::new();
+        let agent = Agent::new();
+        assert!(agent.session_summary(&SummaryProvider, &memory, "cli:s").await.unwrap().is_none());
+
+        for i in 0..3 {
+            memory.save_turn("cli:s", &format!("q{}", i), &format!("a{}", i)).await.unwrap();
+        }
+        let summary = agent.session_summary(&SummaryProvider, &memory, "cli:s").await.unwrap().unwrap();
+        assert_eq!(summary.text, "6 lines summarized");
+        assert_eq!(summary.messages, 6);
+        assert!(!summary.truncated);
+        assert_eq!(memory.get_history("cli:s", 100).await.unwrap().len(), 6);
+
+        for i in 0..SESSION_SUMMARY_MAX_MESSAGES {
+            memory.save_turn("cli:s", &format!("q{}", i), &format!("a{}", i)).await.unwrap();
+        }
+        let summary = agent.session_summary(&SummaryProvider, &memory, "cli:s").await.unwrap().unwrap();
+        assert_eq!(summary.messages, SESSION_SUMMARY_MAX_MESSAGES);
+        assert!(summary.truncated);
+    }
+
+    /// Calls `exec` once, then answers with the tool's result.
+    struct ExecProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ExecProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        fn default_model(&self) -> &str {
+            "fake"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+            let result = request.messages.iter().find(|m| m.role == Role::Tool);
+            let tool_calls = match result {
+                Some(_) => Vec::new(),
+                None => vec![crate::message::ToolCall {
+                    id: "call_1".into(),
+                    r#type: "function".into(),
+                    function: crate::message::FunctionCall {
+                        name: "exec".into(),
+                        arguments: r#"{"command":"rm -rf build"}"#.into(),
+                    },
+                }],
+            };
+            Ok(LlmResponse {
+                content: result.and_then(|m| m.content.clone()),
+                tool_calls,
+                model: "fake".into(),
+                usage: TokenUsage::default(),
+                finish_reason: "stop".into(),
+            })
+        }
+    }
+
+    /// Destructive tool that records whether it ran.
+    struct Exec(Arc<std::sync::atomic::AtomicBool>);
+
+    #[async_trait::async_trait]
+    impl crate::tool::Tool for Exec {
+        fn name(&self) -> &str {
+            "exec"
+        }
+
+        fn description(&self) -> &str {
+            "Run a command"
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object", "properties": { "command": { "type": "string" } } })
+        }
+
+        fn is_destructive(&self) -> bool {
+            true
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<String> {
+            self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok("removed".into())
+        }
+    }
+
+    struct Answer(approval::ApprovalDecision);
+
+    #[async_trait::async_trait]
+    impl ToolApprover for Answer {
+        async fn approve(&self, _session_key: &str, _tool: &str, _args: &str) -> approval::ApprovalDecision {
+            self.0
+        }
+    }
+
+    /// Run one `exec` turn with `confirm_destructive` on; true if the tool ran.
+    async fn run_gated(approver: Option<approval::ApprovalDecision>) -> bool {
+        let executed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut agent = Agent::with_config(AgentConfig {
+            confirm_destructive: true,
+            ..Default::default()
+        });
+        agent.tools.register(Exec(executed.clone()));
+        if let Some(decision) = approver {
+            agent.set_approver(Arc::new(Answer(decision)));
+        }
+
+        let bus = EventBus::new(64);
+        let mut events = bus.subscribe_system();
+        let memory = crate::memory::InMemoryStore::new();
+        agent.process(&ExecProvider, &memory, "clean up", "cli:s", Some(&bus)).await.unwrap();
+
+        let mut kinds = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            kinds.push(event.event_type);
+        }
+        assert!(kinds.iter().any(|k| k == "confirm_required"));
+        executed.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_destructive_tool_waits_for_approval() {
+        assert!(!run_gated(None).await, "ran without anyone to confirm");
+        assert!(!run_gated(Some(approval::ApprovalDecision::Deny)).await, "ran after being denied");
+        assert!(run_gated(Some(approval::ApprovalDecision::Allow)).await, "didn't run after approval");
+    }
+
+    /// Answers with the model each request asked for.
+    struct EchoModelProvider;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for EchoModelProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        fn default_model(&self) -> &str {
+            "text-only"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+            Ok(LlmResponse {
+                content: request.model.or(Some("default".into())),
+                tool_calls: Vec::new(),
+                model: "fake".into(),
+                usage: TokenUsage::default(),
+                finish_reason: "stop".into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_turn_needs_a_vision_model() {
+        let memory = crate::memory::InMemoryStore::new();
+        let image = vec!["https://example.com/cat.png".to_string()];
+        let mut agent = Agent::new();
+
+        let err = agent
+            .process_with_media(&EchoModelProvider, &memory, "What is this?", image.clone(), "cli:v", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("can't see images"), "{}", err);
+
+        agent.config.vision_model = Some("gpt-4o-mini".into());
+        let answer = agent
+            .process_with_media(&EchoModelProvider, &memory, "What is this?", image, "cli:v", None)
+            .await
+            .unwrap();
+        assert_eq!(answer, "gpt-4o-mini");
+
+        // Only that turn: text goes to the configured model
+        let answer = agent.process(&EchoModelProvider, &memory, "Thanks", "cli:v", None).await.unwrap();
+        assert_eq!(answer, "default");
+    }
+
+    #[tokio::test]
+    async fn test_injected_messages_are_saved_with_the_turn() {
+        let memory = crate::memory::InMemoryStore::new();
+        let agent = Agent::new();
+        agent.inject("cli:i", "in metric units please");
+
+        agent.process(&EchoModelProvider, &memory, "How tall is Rinjani?", "cli:i", None).await.unwrap();
+        let history = memory.get_history("cli:i", 10).await.unwrap();
+        let saved: Vec<(Role, Option<&str>)> = history.iter().map(|m| (m.role.clone(), m.content.as_deref())).collect();
+        assert_eq!(
+            saved,
+            [
+                (Role::User, Some("How tall is Rinjani?")),
+                (Role::User, Some("in metric units please")),
+                (Role::Assistant, Some("default")),
+            ]
+        );
+        assert!(agent.take_injected("cli:i").is_empty());
+    }
+
+    /// Always fails, counting the calls it gets.
+    struct DownProvider(std::sync::atomic::AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl LlmProvider for DownProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        fn default_model(&self) -> &str {
+            "fake"
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> Result<LlmResponse> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(ZenClawError::Provider("API error 401: invalid api key".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_errors_are_not_retried_by_the_agent() {
+        let memory = crate::memory::InMemoryStore::new();
+        let provider = DownProvider(Default::default());
+
+        let err = Agent::new().process(&provider, &memory, "Hi", "cli:d", None).await.unwrap_err();
+        assert!(err.to_string().contains("401"), "{}", err);
+        assert_eq!(provider.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_session_workspace() {
+        let mut agent = Agent::new();
+        assert_eq!(agent.session_workspace("telegram:42"), None);
+
+        agent.config.workspace_root = Some(PathBuf::from("/srv/zenclaw"));
+        assert_eq!(agent.session_workspace("telegram:42"), Some(PathBuf::from("/srv/zenclaw")));
+
+        agent.config.workspace_per_session = true;
+        assert_eq!(agent.session_workspace("telegram:42"), Some(PathBuf::from("/srv/zenclaw/telegram_42")));
+        assert_eq!(agent.session_workspace("../.."), Some(PathBuf::from("/srv/zenclaw/_____")));
+    }
+}
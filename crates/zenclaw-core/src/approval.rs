@@ -0,0 +1,113 @@
+//! Tool approval — ask the user before the agent runs a tool.
+//!
+//! "Always allow" / "always deny" decisions are kept in memory per session
+//! for as long as the process runs, so later calls to the same tool skip the
+//! prompt. Nothing is persisted: a restart asks again.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+/// The user's answer to an approval prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    /// Run this call only.
+    Allow,
+    /// Skip this call only.
+    Deny,
+    /// Run this and every later call of the tool in this session.
+    AlwaysAllow,
+    /// Skip this and every later call of the tool in this session.
+    AlwaysDeny,
+}
+
+impl ApprovalDecision {
+    pub fn is_allowed(self) -> bool {
+        matches!(self, Self::Allow | Self::AlwaysAllow)
+    }
+}
+
+/// Approval prompt — implement this for each interactive front-end.
+#[async_trait]
+pub trait ToolApprover: Send + Sync {
+    /// Ask whether `tool` may run with the given (JSON string) arguments.
+    async fn approve(&self, session_key: &str, tool: &str, args: &str) -> ApprovalDecision;
+
+    /// Like [`approve`](Self::approve), with the tool's
+    /// [preview](crate::tool::Tool::preview) of what the call will change.
+    /// Front-ends that can't show it fall back to `approve`.
+    async fn approve_with_preview(&self, session_key: &str, tool: &str, args: &str, _preview: &str) -> ApprovalDecision {
+        self.approve(session_key, tool, args).await
+    }
+}
+
+/// "Always" answers by (session, tool): `true` allows, `false` denies.
+pub type Remembered = Mutex<HashMap<(String, String), bool>>;
+
+/// Resolve approval for a tool call, consulting remembered decisions first.
+///
+/// Returns `true` if the call may run.
+pub async fn check(
+    approver: &dyn ToolApprover,
+    remembered: &Remembered,
+    session_key: &str,
+    tool: &str,
+    args: &str,
+    preview: Option<&str>,
+) -> bool {
+    let key = (session_key.to_string(), tool.to_string());
+    if let Some(&allowed) = remembered.lock().ok().as_ref().and_then(|r| r.get(&key)) {
+        return allowed;
+    }
+
+    let decision = match preview {
+        Some(preview) => approver.approve_with_preview(session_key, tool, args, preview).await,
+        None => approver.approve(session_key, tool, args).await,
+    };
+    if matches!(decision, ApprovalDecision::AlwaysAllow | ApprovalDecision::AlwaysDeny)
+        && let Ok(mut remembered) = remembered.lock()
+    {
+        remembered.insert(key, decision.is_allowed());
+    }
+
+    decision.is_allowed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Gives `answer` and counts how often it was asked.
+    struct Approver {
+        answer: ApprovalDecision,
+        asked: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ToolApprover for Approver {
+        async fn approve(&self, _session_key: &str, _tool: &str, _args: &str) -> ApprovalDecision {
+            self.asked.fetch_add(1, Ordering::SeqCst);
+            self.answer
+        }
+    }
+
+    #[tokio::test]
+    async fn test_always_is_remembered_per_session() {
+        let approver = Approver {
+            answer: ApprovalDecision::AlwaysDeny,
+            asked: AtomicUsize::new(0),
+        };
+        let remembered = Remembered::default();
+
+        assert!(!check(&approver, &remembered, "cli:a", "exec", "{}", None).await);
+        assert!(!check(&approver, &remembered, "cli:a", "exec", "{}", None).await);
+        assert_eq!(approver.asked.load(Ordering::SeqCst), 1);
+
+        // Another session, or a new process, asks again
+        assert!(!check(&approver, &remembered, "cli:b", "exec", "{}", None).await);
+        assert!(!check(&approver, &Remembered::default(), "cli:a", "exec", "{}", None).await);
+        assert_eq!(approver.asked.load(Ordering::SeqCst), 3);
+    }
+}
@@ -55,6 +55,7 @@ impl SystemEvent {
                     "read_file"             => format!("📂 Reading file{}", context),
                     "write_file"            => format!("✏️  Writing file{}", context),
                     "edit_file"             => format!("🔧 Editing file{}", context),
+                    "undo_edit"             => format!("↩️  Undoing last change{}", context),
                     "list_dir"              => format!("📁 Listing directory{}", context),
                     "shell" | "exec"        => format!("⚡ Running command{}", context),
                     "process"               => format!("🔄 Managing process{}", context),
@@ -85,28 +86,52 @@ impl SystemEvent {
                 Some(msg)
             }
 
+            "agent_done" => {
+                if !self.data["success"].as_bool().unwrap_or(true) {
+                    return None;
+                }
+                let secs = self.data["elapsed_ms"].as_u64().unwrap_or(0) as f64 / 1000.0;
+                let iterations = self.data["iterations"].as_u64().unwrap_or(0);
+                let tools = self.data["tool_calls"].as_u64().unwrap_or(0);
+                let tokens = self.data["total_tokens"].as_u64().unwrap_or(0);
+                Some(format!("✅ Done in {:.1}s · {} step(s) · {} tool call(s) · {} tokens", secs, iterations, tools, tokens))
+            }
+
+            "llm_continue" => {
+                let n = self.data["continuation"].as_u64().unwrap_or(1);
+                let max = self.data["max"].as_u64().unwrap_or(1);
+                Some(format!("✍️  Answer was cut off — continuing ({}/{})...", n, max))
+            }
+
+            "confirm_required" => {
+                let tool = self.data["tool"].as_str().unwrap_or("tool");
+                Some(format!("⏸️  Waiting for confirmation to run '{}'...", tool))
+            }
+
+            "user_inject" => Some("💬 Got your note — taking it into account...".to_string()),
+
+            "context_trim" => {
+                let dropped = self.data["dropped_messages"].as_u64().unwrap_or(0);
+                let tokens = self.data["estimated_tokens"].as_u64().unwrap_or(0);
+                Some(format!("✂️  Context too large (~{} tokens) — dropped {} old message(s)...", tokens, dropped))
+            }
+
+            "history_summarize" => Some("🧾 Summarizing older conversation...".to_string()),
+
             "memory_truncate" => {
                 Some("🧹 Trimming old conversation to save memory...".to_string())
             }
 
-            "tool_timeout" => {
+            "tool_denied" => {
                 let tool = self.data["tool"].as_str().unwrap_or("tool");
-                Some(format!("⚠️  '{}' timed out — trying a different approach...", tool))
+                Some(format!("🚫 '{}' was not approved — skipping...", tool))
             }
 
-            "llm_retry" => {
-                let attempt = self.data["attempt"].as_u64().unwrap_or(1);
-                let is_rate_limit = self.data["is_rate_limit"].as_bool().unwrap_or(false);
-                let wait_ms = self.data["wait_ms"].as_u64().unwrap_or(2000);
-                
-                if is_rate_limit {
-                    Some(format!("⏳ Rate limit hit (API quota/limits). Waiting {}s before retry (attempt {})...", wait_ms / 1000, attempt))
-                } else {
-                    Some(format!("🔁 Connection hiccup, retrying in {}s... (attempt {})", wait_ms / 1000, attempt))
-                }
+            "tool_timeout" => {
+                let tool = self.data["tool"].as_str().unwrap_or("tool");
+                Some(format!("⚠️  '{}' timed out — trying a different approach...", tool))
             }
 
-
             _ => None,
         }
     }
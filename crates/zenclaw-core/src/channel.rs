@@ -1,44 +1,211 @@
 //! Channel trait — abstraction for chat platform adapters.
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
 
+use crate::agent::Agent;
+use crate::bus::EventBus;
 use crate::error::Result;
-use crate::message::{Channel, OutboundMessage};
+use crate::memory::MemoryStore;
+use crate::prompt::PromptVars;
+use crate::provider::LlmProvider;
+
+/// Text of the status message posted when an agent turn starts.
+pub const STATUS_PLACEHOLDER: &str = "🧠 Process Started...";
 
-/// Channel adapter trait — implement this for each chat platform.
+/// How often the typing indicator is renewed (Telegram's lasts 5s).
+const TYPING_REFRESH: Duration = Duration::from_secs(4);
+
+/// Chat platform adapter — implement this for each platform.
+///
+/// Adapters provide the message primitives and their bot loop; the live
+/// status message shown during a turn is shared ([`StatusMessage`]).
+/// Chats and messages are identified by the platform's ids as strings.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// struct MyChannel;
+/// #[derive(Clone)]
+/// struct MyChannel { /* HTTP client, token */ }
 ///
 /// #[async_trait]
-/// impl ChannelAdapter for MyChannel {
-///     fn channel_type(&self) -> Channel { Channel::Telegram }
-///     async fn start(&mut self) -> Result<()> { /* connect */ Ok(()) }
-///     async fn stop(&mut self) -> Result<()> { /* disconnect */ Ok(()) }
-///     async fn send(&self, msg: OutboundMessage) -> Result<()> { /* send */ Ok(()) }
+/// impl Channel for MyChannel {
+///     fn name(&self) -> &'static str { "mychat" }
+///     async fn send(&self, chat: &str, text: &str) -> Result<String> { /* post */ }
+///     async fn edit(&self, chat: &str, id: &str, text: &str) -> Result<()> { /* update */ }
+///     async fn delete(&self, chat: &str, id: &str) -> Result<()> { /* remove */ }
+///     async fn start<P, M>(&mut self, agent: Arc<Agent>, provider: Arc<P>, memory: Arc<M>) -> Result<()>
+///     where P: LlmProvider + 'static, M: MemoryStore + 'static { /* spawn the bot loop */ }
+///     async fn stop(&mut self) { /* signal the loop */ }
 /// }
 /// ```
 #[async_trait]
-pub trait ChannelAdapter: Send + Sync {
-    /// Channel type identifier.
-    fn channel_type(&self) -> Channel;
+pub trait Channel: Send + Sync {
+    /// Platform name ("telegram"), used in logs and reply formatting.
+    fn name(&self) -> &'static str;
 
-    /// Start listening for messages. Should run in background.
-    async fn start(&mut self) -> Result<()>;
+    /// Send `text` to `chat`, split to the platform's length limit.
+    /// Returns the id of the (last) message sent.
+    async fn send(&self, chat: &str, text: &str) -> Result<String>;
 
-    /// Stop the channel gracefully.
-    async fn stop(&mut self) -> Result<()>;
+    /// Replace the text of a message sent with [`send`](Self::send).
+    async fn edit(&self, chat: &str, message_id: &str, text: &str) -> Result<()>;
 
-    /// Send a message through this channel.
-    async fn send(&self, msg: OutboundMessage) -> Result<()>;
+    /// Delete a message sent with [`send`](Self::send).
+    async fn delete(&self, chat: &str, message_id: &str) -> Result<()>;
 
-    /// Check if a sender is allowed.
-    fn is_allowed(&self, _sender_id: &str) -> bool {
-        true // Default: allow everyone
+    /// Show "typing..." in `chat` for a few seconds (default: unsupported).
+    async fn typing(&self, _chat: &str) -> Result<()> {
+        Ok(())
     }
 
-    /// Check if the channel is running.
-    fn is_running(&self) -> bool;
+    /// Minimum pause between two status edits, to stay under rate limits.
+    fn status_interval(&self) -> Duration {
+        Duration::from_millis(500)
+    }
+
+    /// Start receiving messages and answering them — runs in background,
+    /// returns immediately.
+    async fn start<P, M>(&mut self, agent: Arc<Agent>, provider: Arc<P>, memory: Arc<M>) -> Result<()>
+    where
+        P: LlmProvider + 'static,
+        M: MemoryStore + 'static;
+
+    /// Stop the bot loop.
+    async fn stop(&mut self);
+}
+
+/// The live status message of an agent turn.
+///
+/// Posts [`STATUS_PLACEHOLDER`], edits it with each status update the agent
+/// publishes on the turn's bus (thinking, tool use, ...) and keeps the typing
+/// indicator on until the turn is [`finish`](Self::finish)ed.
+pub struct StatusMessage {
+    chat: String,
+    message_id: Option<String>,
+    task: JoinHandle<()>,
+}
+
+impl StatusMessage {
+    /// Post the placeholder in `chat` and follow `bus` in the background.
+    pub async fn start<C>(channel: &C, chat: &str, bus: &EventBus) -> Self
+    where
+        C: Channel + Clone + 'static,
+    {
+        let mut rx = bus.subscribe_system();
+        let message_id = channel.send(chat, STATUS_PLACEHOLDER).await.ok();
+
+        let (channel, chat_id, id) = (channel.clone(), chat.to_string(), message_id.clone());
+        let task = tokio::spawn(async move {
+            let mut typing = tokio::time::interval(TYPING_REFRESH);
+            let mut last_status = String::new();
+            loop {
+                tokio::select! {
+                    _ = typing.tick() => {
+                        let _ = channel.typing(&chat_id).await;
+                    }
+                    event = rx.recv() => {
+                        let event = match event {
+                            Ok(event) => event,
+                            Err(RecvError::Lagged(_)) => continue,
+                            Err(RecvError::Closed) => break,
+                        };
+                        if event.event_type == "agent_done" {
+                            break; // Turn finished; the status message is about to be deleted
+                        }
+                        if let (Some(id), Some(status)) = (&id, event.format_status())
+                            && status != last_status
+                        {
+                            let _ = channel.edit(&chat_id, id, &status).await;
+                            last_status = status;
+                            tokio::time::sleep(channel.status_interval()).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            chat: chat.to_string(),
+            message_id,
+            task,
+        }
+    }
+
+    /// Stop updating and delete the status message.
+    pub async fn finish<C: Channel>(self, channel: &C) {
+        self.task.abort();
+        if let Some(id) = &self.message_id {
+            let _ = channel.delete(&self.chat, id).await;
+        }
+    }
+}
+
+/// Kind of outbound reply, used to suppress [`ReplyFormat`] decoration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplyKind {
+    /// Normal agent response.
+    Response,
+    /// Error message sent when the agent fails.
+    Error,
+    /// Built-in bot command reply (e.g. `/help`, `/clear`).
+    Command,
+}
+
+/// Prefix/suffix added to every bot reply on a channel (branding, footers).
+///
+/// Both support the `{channel}`, `{date}` and `{time}` variables, e.g.
+/// `prefix = "🤖 ZenClaw:"` or `suffix = "\n— powered by ZenClaw"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplyFormat {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub prefix: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub suffix: String,
+    /// Reply kinds left undecorated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<ReplyKind>,
+}
+
+impl ReplyFormat {
+    /// True if no prefix or suffix is configured.
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_empty() && self.suffix.is_empty()
+    }
+
+    /// Decorate a reply for `channel`, unless its kind is suppressed.
+    pub fn apply(&self, kind: ReplyKind, channel: &str, text: &str) -> String {
+        if self.is_empty() || self.skip.contains(&kind) {
+            return text.to_string();
+        }
+
+        let now = chrono::Local::now();
+        let mut vars = PromptVars::new();
+        vars.set("channel", channel)
+            .set("date", now.format("%Y-%m-%d").to_string())
+            .set("time", now.format("%H:%M").to_string());
+
+        let mut out = String::with_capacity(text.len() + self.prefix.len() + self.suffix.len() + 2);
+        if !self.prefix.is_empty() {
+            out.push_str(&vars.render(&self.prefix));
+            // A prefix ending in a newline starts its own line; otherwise join with a space
+            if !self.prefix.ends_with('\n') {
+                out.push(' ');
+            }
+        }
+        out.push_str(text);
+        if !self.suffix.is_empty() {
+            if !self.suffix.starts_with('\n') {
+                out.push_str("\n\n");
+            }
+            out.push_str(&vars.render(&self.suffix));
+        }
+        out
+    }
 }
@@ -0,0 +1,60 @@
+//! Slash-command matching — prefix completion and "did you mean" hints.
+//!
+//! Shared by the TUI and the chat channels so a typo like `/hepl` or a
+//! shorthand like `/exp` behaves the same everywhere.
+
+/// Expand an unambiguous command prefix, keeping any arguments
+/// (`/exp notes.md` → `/export notes.md`). Exact matches, ambiguous
+/// prefixes and non-commands are returned unchanged.
+pub fn expand(input: &str, commands: &[&str]) -> String {
+    let trimmed = input.trim();
+    if !trimmed.starts_with('/') {
+        return input.to_string();
+    }
+
+    let (cmd, rest) = match trimmed.split_once(char::is_whitespace) {
+        Some((cmd, rest)) => (cmd, Some(rest)),
+        None => (trimmed, None),
+    };
+    if cmd.len() < 2 || commands.contains(&cmd) {
+        return input.to_string();
+    }
+
+    let mut matches = commands.iter().filter(|c| c.starts_with(cmd));
+    match (matches.next(), matches.next()) {
+        (Some(full), None) => match rest {
+            Some(rest) => format!("{} {}", full, rest),
+            None => full.to_string(),
+        },
+        _ => input.to_string(),
+    }
+}
+
+/// The known command closest to `cmd`, if it is a likely typo
+/// (edit distance of 1, or 2 for commands longer than 4 characters).
+pub fn suggest<'a>(cmd: &str, commands: &[&'a str]) -> Option<&'a str> {
+    let max_distance = if cmd.chars().count() > 4 { 2 } else { 1 };
+    commands
+        .iter()
+        .map(|c| (levenshtein(cmd, c), *c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein edit distance between two strings (by character).
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
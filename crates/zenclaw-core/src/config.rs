@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::channel::ReplyFormat;
 use crate::error::{Result, ZenClawError};
 use crate::provider::ProviderConfig;
 
@@ -20,6 +21,79 @@ pub struct ZenClawConfig {
     /// Channel configurations.
     #[serde(default)]
     pub channels: ChannelSettings,
+
+    /// Update check settings.
+    #[serde(default)]
+    pub updates: UpdateSettings,
+
+    /// Memory database settings.
+    #[serde(default)]
+    pub memory: MemorySettings,
+
+    /// REST API server settings.
+    #[serde(default)]
+    pub server: ServerSettings,
+
+    /// Per-client rate limits for the REST API and bot channels.
+    #[serde(default)]
+    pub rate_limit: RateLimitSettings,
+
+    /// TUI chat settings.
+    #[serde(default)]
+    pub chat: ChatSettings,
+}
+
+/// REST API server (`zenclaw serve`) settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerSettings {
+    /// Key clients must send as a bearer token (None = no auth).
+    /// `ZENCLAW_API_KEY` takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+/// Per-client rate limits (see [`crate::ratelimit::RateLimiter`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitSettings {
+    /// Sustained requests per minute for each client (0 = no limit).
+    #[serde(default = "default_requests_per_minute")]
+    pub requests_per_minute: u32,
+
+    /// Requests a client may send in a quick burst.
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+
+    /// Reverse proxies in front of the REST API. `X-Forwarded-For` is only
+    /// believed on requests coming from one of these addresses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_proxies: Vec<std::net::IpAddr>,
+}
+
+fn default_requests_per_minute() -> u32 {
+    20
+}
+
+fn default_burst() -> u32 {
+    5
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 20,
+            burst: 5,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// TUI chat settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatSettings {
+    /// Enter sends and Shift+Enter adds a line (default: Enter adds a line
+    /// and Ctrl+Enter / Ctrl+S sends).
+    #[serde(default)]
+    pub enter_sends: bool,
 }
 
 /// Agent-specific settings.
@@ -29,23 +103,199 @@ pub struct AgentSettings {
     #[serde(default = "default_max_iterations")]
     pub max_iterations: usize,
 
-    /// Custom system prompt (None = use default).
+    /// Follow-up requests when an answer is cut off by the output limit.
+    #[serde(default = "default_max_continuations")]
+    pub max_continuations: usize,
+
+    /// History messages loaded per turn.
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+
+    /// Prompt token budget; older history is dropped to fit (None = context window only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_token_budget: Option<usize>,
+
+    /// Summarize older history after this many user turns (None = never).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summarize_after: Option<usize>,
+
+    /// Custom instructions for history summaries (None = built-in prompt).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_prompt: Option<String>,
+
+    /// Custom system prompt (None = use default). Supports `{date}`, `{os}`,
+    /// `{cwd}`, `{user}`, `{tools}` and other [`crate::prompt`] variables.
     pub system_prompt: Option<String>,
 
-    /// Workspace directory.
+    /// Fetch the system prompt from this URL at startup (cached locally,
+    /// re-fetched on reload). Takes precedence over `system_prompt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_url: Option<String>,
+
+    /// Directory the file tools are confined to. The CLI is unrestricted
+    /// without it; bots and `zenclaw serve` give every session its own
+    /// subdirectory of it (default: `workspaces/` in the data dir).
     pub workspace: Option<String>,
+
+    /// Tools the bots and `zenclaw serve` may use (None = read-only tools,
+    /// `["*"]` = all). The interactive CLI is never restricted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_tools: Option<Vec<String>>,
+
+    /// Seconds `web_search` and `web_fetch` results are cached (0 = off).
+    #[serde(default = "default_web_cache_ttl_secs")]
+    pub web_cache_ttl_secs: u64,
+
+    /// How deep `sub_agent` may nest (a sub-agent spawning sub-agents is depth 2).
+    #[serde(default = "default_subagent_max_depth")]
+    pub subagent_max_depth: usize,
+
+    /// Ask before running tools that write files or run commands
+    /// (`write_file`, `edit_file`, `exec`). Where nobody can answer, they
+    /// are refused.
+    #[serde(default)]
+    pub confirm_destructive: bool,
+
+    /// Model (same provider) for messages with images when the configured
+    /// one can't see them (None = such messages are refused).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vision_model: Option<String>,
+
+    /// Variables the `env` tool may read even when denied (`*` wildcards).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_allow: Vec<String>,
+
+    /// Variables the `env` tool reads as `<redacted>`, on top of API keys,
+    /// tokens, secrets and passwords (`*` wildcards).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_deny: Vec<String>,
 }
 
 fn default_max_iterations() -> usize {
     20
 }
 
+fn default_max_continuations() -> usize {
+    3
+}
+
+fn default_history_limit() -> usize {
+    50
+}
+
+fn default_web_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_subagent_max_depth() -> usize {
+    2
+}
+
 impl Default for AgentSettings {
     fn default() -> Self {
         Self {
             max_iterations: 20,
+            max_continuations: 3,
+            history_limit: 50,
+            history_token_budget: None,
+            summarize_after: None,
+            summary_prompt: None,
             system_prompt: None,
+            system_prompt_url: None,
             workspace: None,
+            public_tools: None,
+            web_cache_ttl_secs: 300,
+            subagent_max_depth: 2,
+            confirm_destructive: false,
+            vision_model: None,
+            env_allow: Vec::new(),
+            env_deny: Vec::new(),
+        }
+    }
+}
+
+/// Update check settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSettings {
+    /// Check GitHub for new releases in the background on startup.
+    #[serde(default = "default_update_check")]
+    pub check: bool,
+
+    /// Minimum hours between two network checks (results are cached).
+    #[serde(default = "default_update_interval_hours")]
+    pub interval_hours: u64,
+}
+
+fn default_update_check() -> bool {
+    true
+}
+
+fn default_update_interval_hours() -> u64 {
+    24
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            check: true,
+            interval_hours: 24,
+        }
+    }
+}
+
+/// Memory database (SQLite) settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySettings {
+    /// Read connections kept open next to the writer.
+    #[serde(default = "default_pool_size")]
+    pub pool_size: usize,
+
+    /// Milliseconds to wait for a locked database before giving up.
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+
+    /// Provider computing embeddings for `zenclaw rag` (None = chat provider).
+    #[serde(default)]
+    pub embedding_provider: Option<String>,
+
+    /// Embedding model for `zenclaw rag` (None = provider default).
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+
+    /// Tokens per knowledge chunk.
+    #[serde(default = "default_rag_chunk_size")]
+    pub rag_chunk_size: usize,
+
+    /// Tokens shared between consecutive chunks.
+    #[serde(default = "default_rag_chunk_overlap")]
+    pub rag_chunk_overlap: usize,
+}
+
+fn default_pool_size() -> usize {
+    4
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_rag_chunk_size() -> usize {
+    512
+}
+
+fn default_rag_chunk_overlap() -> usize {
+    64
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            pool_size: 4,
+            busy_timeout_ms: 5000,
+            embedding_provider: None,
+            embedding_model: None,
+            rag_chunk_size: 512,
+            rag_chunk_overlap: 64,
         }
     }
 }
@@ -56,6 +306,83 @@ pub struct ChannelSettings {
     pub telegram: Option<TelegramConfig>,
     pub discord: Option<DiscordConfig>,
     pub slack: Option<SlackConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixConfig>,
+    /// Outbound webhook receiving every agent turn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+    /// Reply prefix/suffix for all channels (overridable per channel).
+    #[serde(default)]
+    pub reply_format: ReplyFormat,
+    /// First-contact onboarding message for all channels (overridable per
+    /// channel). Supports `{bot}`, `{model}` and `{channel}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+}
+
+impl ChannelSettings {
+    /// Effective reply format for a channel ("telegram", "discord", "slack", "matrix", "whatsapp").
+    pub fn reply_format_for(&self, channel: &str) -> ReplyFormat {
+        let custom = match channel {
+            "telegram" => self.telegram.as_ref().and_then(|c| c.reply_format.clone()),
+            "discord" => self.discord.as_ref().and_then(|c| c.reply_format.clone()),
+            "slack" => self.slack.as_ref().and_then(|c| c.reply_format.clone()),
+            "matrix" => self.matrix.as_ref().and_then(|c| c.reply_format.clone()),
+            _ => None,
+        };
+        custom.unwrap_or_else(|| self.reply_format.clone())
+    }
+
+    /// Effective greeting for a channel (None = don't greet).
+    pub fn greeting_for(&self, channel: &str) -> Option<String> {
+        let custom = match channel {
+            "telegram" => self.telegram.as_ref().and_then(|c| c.greeting.clone()),
+            "discord" => self.discord.as_ref().and_then(|c| c.greeting.clone()),
+            "slack" => self.slack.as_ref().and_then(|c| c.greeting.clone()),
+            "matrix" => self.matrix.as_ref().and_then(|c| c.greeting.clone()),
+            _ => None,
+        };
+        custom.or_else(|| self.greeting.clone()).filter(|g| !g.trim().is_empty())
+    }
+
+    /// Reconnection backoff for a channel's poller ("telegram", "discord", "slack").
+    pub fn reconnect_for(&self, channel: &str) -> ReconnectSettings {
+        let custom = match channel {
+            "telegram" => self.telegram.as_ref().and_then(|c| c.reconnect),
+            "discord" => self.discord.as_ref().and_then(|c| c.reconnect),
+            "slack" => self.slack.as_ref().and_then(|c| c.reconnect),
+            _ => None,
+        };
+        custom.unwrap_or_default()
+    }
+}
+
+/// How a channel's poller backs off while the platform is unreachable: the
+/// delay starts at `base_secs`, doubles on each consecutive failure up to
+/// `max_secs`, and resets once a request succeeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconnectSettings {
+    #[serde(default = "default_reconnect_base_secs")]
+    pub base_secs: u64,
+    #[serde(default = "default_reconnect_max_secs")]
+    pub max_secs: u64,
+}
+
+fn default_reconnect_base_secs() -> u64 {
+    2
+}
+
+fn default_reconnect_max_secs() -> u64 {
+    60
+}
+
+impl Default for ReconnectSettings {
+    fn default() -> Self {
+        Self {
+            base_secs: 2,
+            max_secs: 60,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +390,12 @@ pub struct TelegramConfig {
     pub bot_token: String,
     #[serde(default)]
     pub allowed_users: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_format: Option<ReplyFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<ReconnectSettings>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +403,15 @@ pub struct DiscordConfig {
     pub bot_token: String,
     #[serde(default)]
     pub allowed_users: Vec<String>,
+    /// Receive messages over the Gateway websocket instead of DM polling.
+    #[serde(default)]
+    pub use_gateway: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_format: Option<ReplyFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<ReconnectSettings>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,24 +419,48 @@ pub struct SlackConfig {
     pub bot_token: String,
     #[serde(default)]
     pub allowed_channels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_format: Option<ReplyFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect: Option<ReconnectSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixConfig {
+    pub homeserver_url: String,
+    pub access_token: String,
+    #[serde(default)]
+    pub allowed_rooms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_format: Option<ReplyFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// HMAC-SHA256 signing secret (sent as `X-ZenClaw-Signature`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
 }
 
 impl ZenClawConfig {
-    /// Load config from a TOML file.
+    /// Load config from a TOML, JSON or YAML file (chosen by extension).
     pub fn load(path: &Path) -> Result<Self> {
         if !path.exists() {
             return Ok(Self::default());
         }
         let content = std::fs::read_to_string(path)
             .map_err(|e| ZenClawError::Config(format!("Failed to read config: {}", e)))?;
-        toml::from_str(&content)
-            .map_err(|e| ZenClawError::Config(format!("Failed to parse config: {}", e)))
+        ConfigFormat::from_path(path).parse(&content)
     }
 
-    /// Save config to a TOML file.
+    /// Save config, keeping the format implied by the file extension.
     pub fn save(&self, path: &Path) -> Result<()> {
-        let content = toml::to_string_pretty(self)
-            .map_err(|e| ZenClawError::Config(format!("Failed to serialize config: {}", e)))?;
+        let content = ConfigFormat::from_path(path).serialize(self)?;
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -103,11 +469,66 @@ impl ZenClawConfig {
     }
 
     /// Get the default config file path.
+    ///
+    /// `ZENCLAW_CONFIG` overrides it; otherwise the first existing
+    /// `config.{toml,json,yaml,yml}` in the config dir wins (TOML if none).
     pub fn default_path() -> PathBuf {
-        dirs::config_dir()
+        if let Ok(path) = std::env::var("ZENCLAW_CONFIG")
+            && !path.is_empty()
+        {
+            return PathBuf::from(path);
+        }
+
+        let dir = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
-            .join("zenclaw")
-            .join("config.toml")
+            .join("zenclaw");
+        ["config.toml", "config.json", "config.yaml", "config.yml"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|p| p.exists())
+            .unwrap_or_else(|| dir.join("config.toml"))
+    }
+}
+
+/// On-disk config format, detected from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Detect the format from a path (unknown extensions default to TOML).
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("json") => Self::Json,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+
+    fn parse(self, content: &str) -> Result<ZenClawConfig> {
+        let parsed = match self {
+            Self::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Self::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| ZenClawError::Config(format!("Failed to parse config: {}", e)))
+    }
+
+    fn serialize(self, config: &ZenClawConfig) -> Result<String> {
+        let content = match self {
+            Self::Toml => toml::to_string_pretty(config).map_err(|e| e.to_string()),
+            Self::Json => serde_json::to_string_pretty(config).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::to_string(config).map_err(|e| e.to_string()),
+        };
+        content.map_err(|e| ZenClawError::Config(format!("Failed to serialize config: {}", e)))
     }
 }
 
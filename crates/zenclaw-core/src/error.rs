@@ -14,6 +14,12 @@ pub enum ZenClawError {
     #[error("Tool not found: {0}")]
     ToolNotFound(String),
 
+    #[error("Invalid arguments for tool '{tool}': {message}")]
+    InvalidArguments { tool: String, message: String },
+
+    #[error("Tool '{0}' is not allowed here — use another tool or answer without it")]
+    ToolDenied(String),
+
     #[error("Configuration error: {0}")]
     Config(String),
 
@@ -29,6 +35,9 @@ pub enum ZenClawError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("Cancelled")]
+    Cancelled,
+
     #[error("Max iterations reached ({0})")]
     MaxIterations(usize),
 
@@ -4,12 +4,18 @@
 //! This crate is the foundation — all other crates depend on it.
 
 pub mod agent;
+pub mod approval;
 pub mod bus;
 pub mod channel;
+pub mod command;
 pub mod config;
 pub mod error;
+pub mod locale;
 pub mod memory;
 pub mod message;
+pub mod prompt;
 pub mod provider;
+pub mod ratelimit;
 pub mod session;
+pub mod tokens;
 pub mod tool;
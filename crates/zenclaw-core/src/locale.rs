@@ -0,0 +1,117 @@
+//! Per-session language preference (`/lang id`).
+//!
+//! The preference is stored in the [`MemoryStore`] fact table, so it survives
+//! restarts. The agent adds it to the system prompt and passes it to every
+//! tool that takes a `lang` argument (e.g. `web_search`).
+
+use crate::error::Result;
+use crate::memory::MemoryStore;
+
+/// Language codes with a known display name.
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("de", "German"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("hi", "Hindi"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("jv", "Javanese"),
+    ("ko", "Korean"),
+    ("ms", "Malay"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("su", "Sundanese"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+fn fact_key(session_key: &str) -> String {
+    format!("lang:{}", session_key)
+}
+
+/// Display name for a language code (`"id"` → `"Indonesian"`).
+pub fn language_name(code: &str) -> Option<&'static str> {
+    let primary = code.split(['-', '_']).next().unwrap_or(code);
+    LANGUAGES
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(primary))
+        .map(|(_, name)| *name)
+}
+
+/// Normalize a user-supplied code (`"ID"` → `"id"`, `"pt_BR"` → `"pt-br"`).
+/// Returns None if it doesn't look like a language code.
+pub fn normalize(code: &str) -> Option<String> {
+    let code = code.trim().to_ascii_lowercase().replace('_', "-");
+    let mut parts = code.split('-');
+    let primary = parts.next()?;
+    let valid_primary = (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic());
+    let valid_rest = parts.all(|p| (2..=4).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()));
+    (valid_primary && valid_rest).then_some(code)
+}
+
+/// The session's preferred language code, if set.
+pub async fn get(memory: &dyn MemoryStore, session_key: &str) -> Option<String> {
+    memory
+        .get_fact(&fact_key(session_key))
+        .await
+        .ok()
+        .flatten()
+        .filter(|code| !code.is_empty())
+}
+
+/// Set (`Some`) or clear (`None`) the session's preferred language.
+pub async fn set(memory: &dyn MemoryStore, session_key: &str, code: Option<&str>) -> Result<()> {
+    memory.save_fact(&fact_key(session_key), code.unwrap_or_default()).await
+}
+
+/// System prompt instruction for a language code.
+pub fn prompt_instruction(code: &str) -> String {
+    let name = language_name(code).unwrap_or(code);
+    format!(
+        "## Language\n\nAlways respond in {} (language code: {}), regardless of the language the user writes in.",
+        name, code
+    )
+}
+
+/// Handle a `/lang` command. Returns the reply, or None if `text` is not one.
+///
+/// - `/lang` shows the current preference
+/// - `/lang <code>` sets it (e.g. `/lang id`)
+/// - `/lang auto` clears it
+pub async fn command(memory: &dyn MemoryStore, session_key: &str, text: &str) -> Option<String> {
+    let text = text.trim();
+    let arg = match text.strip_prefix("/lang") {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest.trim(),
+        _ => return None,
+    };
+
+    let reply = match arg {
+        "" => match get(memory, session_key).await {
+            Some(code) => format!(
+                "🌐 Language: {} ({}). Use /lang <code> to change it or /lang auto to reset.",
+                language_name(&code).unwrap_or(&code),
+                code
+            ),
+            None => "🌐 Language: auto (replies follow your messages). Use /lang <code>, e.g. /lang id.".to_string(),
+        },
+        "auto" | "off" | "reset" => match set(memory, session_key, None).await {
+            Ok(()) => "🌐 Language reset — replies follow your messages again.".to_string(),
+            Err(e) => format!("❌ Failed to reset language: {}", e),
+        },
+        _ => match normalize(arg) {
+            Some(code) => match set(memory, session_key, Some(&code)).await {
+                Ok(()) => format!("🌐 I'll reply in {} from now on.", language_name(&code).unwrap_or(&code)),
+                Err(e) => format!("❌ Failed to set language: {}", e),
+            },
+            None => format!("❌ '{}' is not a language code. Try /lang en, /lang id or /lang pt-br.", arg),
+        },
+    };
+    Some(reply)
+}
@@ -1,9 +1,14 @@
 //! Memory store trait — conversation history & knowledge persistence.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
-use crate::message::ChatMessage;
+use crate::message::{ChatMessage, Role};
+
+/// Key-value scope shared by every session (see [`MemoryStore::kv_get`]).
+pub const KV_GLOBAL: &str = "*";
 
 /// Memory store trait — implement for different storage backends.
 ///
@@ -27,6 +32,29 @@ pub trait MemoryStore: Send + Sync {
     /// Clear history for a session.
     async fn clear_history(&self, session_key: &str) -> Result<()>;
 
+    /// Replace the whole history of a session (e.g. with a summary plus
+    /// the recent turns).
+    async fn replace_history(&self, session_key: &str, messages: &[ChatMessage]) -> Result<()> {
+        self.clear_history(session_key).await?;
+        for message in messages {
+            self.save_message(session_key, message).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete the last user message and everything after it (the reply and
+    /// any tool messages). Returns `false` if the session has no user message.
+    async fn delete_last_turn(&self, session_key: &str) -> Result<bool>;
+
+    /// Export a session as a JSON array of [`SessionEntry`] (chat messages
+    /// with the time they were saved), oldest first.
+    async fn export_session(&self, session_key: &str) -> Result<String>;
+
+    /// Import an exported session. With `replace` the session's history is
+    /// swapped for the import; otherwise both are merged by timestamp (see
+    /// [`merge_session`]). Returns the number of messages added.
+    async fn import_session(&self, session_key: &str, json: &str, replace: bool) -> Result<usize>;
+
     /// Store a fact/preference for later retrieval.
     async fn save_fact(&self, key: &str, value: &str) -> Result<()>;
 
@@ -36,15 +64,94 @@ pub trait MemoryStore: Send + Sync {
     /// Search facts by keyword.
     async fn search_facts(&self, query: &str, limit: usize) -> Result<Vec<(String, String)>>;
 
+    /// Full-text search over saved user and assistant messages, best
+    /// matches first. Every word of `query` must appear (as a word or the
+    /// start of one); `session_key` limits the search to one session.
+    async fn search_history(&self, query: &str, session_key: Option<&str>, limit: usize) -> Result<Vec<HistoryMatch>>;
+
+    /// Get an exact fact the agent saved with the `memory` tool. `scope` is
+    /// a session key or [`KV_GLOBAL`].
+    async fn kv_get(&self, _scope: &str, _key: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Save a fact in `scope`, replacing any value under the same key.
+    async fn kv_set(&self, _scope: &str, _key: &str, _value: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Delete a fact from `scope`. Returns `false` if there was none.
+    async fn kv_delete(&self, _scope: &str, _key: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// All facts in `scope`, sorted by key.
+    async fn kv_list(&self, _scope: &str) -> Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+
     /// Retrieve relevant knowledge context (e.g. from a RAG store) based on a query.
     async fn search_knowledge(&self, _query: &str, _limit: usize) -> Result<Option<String>> {
         Ok(None)
     }
 }
 
+/// A message found by [`MemoryStore::search_history`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMatch {
+    pub session_key: String,
+    #[serde(flatten)]
+    pub entry: SessionEntry,
+}
+
+/// A history message and when it was saved — the unit of session export.
+///
+/// Serializes as a [`ChatMessage`] with an extra `timestamp` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEntry {
+    #[serde(flatten)]
+    pub message: ChatMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl SessionEntry {
+    /// Same message saved at the same second (exports may drop sub-seconds).
+    fn same_as(&self, other: &SessionEntry) -> bool {
+        self.timestamp.map(|t| t.timestamp()) == other.timestamp.map(|t| t.timestamp())
+            && self.message.role == other.message.role
+            && self.message.content == other.message.content
+            && self.message.tool_call_id == other.message.tool_call_id
+    }
+}
+
+/// Parse an exported session (a JSON array of [`SessionEntry`]).
+pub fn parse_session(json: &str) -> Result<Vec<SessionEntry>> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Merge `imported` into `existing`, ordered by timestamp.
+///
+/// Entries already present are skipped, so importing the same export twice
+/// changes nothing. Equal timestamps keep existing entries first; entries
+/// without a timestamp go last, in import order. Returns the merged history
+/// and the number of entries added.
+pub fn merge_session(existing: Vec<SessionEntry>, imported: Vec<SessionEntry>) -> (Vec<SessionEntry>, usize) {
+    let mut merged = existing;
+    let before = merged.len();
+    for entry in imported {
+        if !merged.iter().any(|e| e.same_as(&entry)) {
+            merged.push(entry);
+        }
+    }
+    let added = merged.len() - before;
+    merged.sort_by_key(|e| (e.timestamp.is_none(), e.timestamp));
+    (merged, added)
+}
+
 /// In-memory store for testing and lightweight usage.
 pub struct InMemoryStore {
-    history: std::sync::Mutex<std::collections::HashMap<String, Vec<ChatMessage>>>,
+    history: std::sync::Mutex<std::collections::HashMap<String, Vec<SessionEntry>>>,
     facts: std::sync::Mutex<std::collections::HashMap<String, String>>,
 }
 
@@ -67,9 +174,9 @@ impl Default for InMemoryStore {
 impl MemoryStore for InMemoryStore {
     async fn get_history(&self, session_key: &str, limit: usize) -> Result<Vec<ChatMessage>> {
         let store = self.history.lock().unwrap();
-        let messages = store.get(session_key).cloned().unwrap_or_default();
-        let start = messages.len().saturating_sub(limit);
-        Ok(messages[start..].to_vec())
+        let entries = store.get(session_key).map(Vec::as_slice).unwrap_or_default();
+        let start = entries.len().saturating_sub(limit);
+        Ok(entries[start..].iter().map(|e| e.message.clone()).collect())
     }
 
     async fn save_turn(
@@ -80,15 +187,22 @@ impl MemoryStore for InMemoryStore {
     ) -> Result<()> {
         let mut store = self.history.lock().unwrap();
         let history = store.entry(session_key.to_string()).or_default();
-        history.push(ChatMessage::user(user_message));
-        history.push(ChatMessage::assistant(assistant_response));
+        for message in [ChatMessage::user(user_message), ChatMessage::assistant(assistant_response)] {
+            history.push(SessionEntry {
+                message,
+                timestamp: Some(Utc::now()),
+            });
+        }
         Ok(())
     }
 
     async fn save_message(&self, session_key: &str, message: &ChatMessage) -> Result<()> {
         let mut store = self.history.lock().unwrap();
         let history = store.entry(session_key.to_string()).or_default();
-        history.push(message.clone());
+        history.push(SessionEntry {
+            message: message.clone(),
+            timestamp: Some(Utc::now()),
+        });
         Ok(())
     }
 
@@ -98,6 +212,65 @@ impl MemoryStore for InMemoryStore {
         Ok(())
     }
 
+    async fn delete_last_turn(&self, session_key: &str) -> Result<bool> {
+        let mut store = self.history.lock().unwrap();
+        let Some(history) = store.get_mut(session_key) else {
+            return Ok(false);
+        };
+        match history.iter().rposition(|e| e.message.role == Role::User) {
+            Some(index) => {
+                history.truncate(index);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn export_session(&self, session_key: &str) -> Result<String> {
+        let store = self.history.lock().unwrap();
+        let entries = store.get(session_key).cloned().unwrap_or_default();
+        Ok(serde_json::to_string_pretty(&entries)?)
+    }
+
+    async fn import_session(&self, session_key: &str, json: &str, replace: bool) -> Result<usize> {
+        let imported = parse_session(json)?;
+        let mut store = self.history.lock().unwrap();
+        if replace {
+            let added = imported.len();
+            store.insert(session_key.to_string(), imported);
+            return Ok(added);
+        }
+        let existing = store.remove(session_key).unwrap_or_default();
+        let (merged, added) = merge_session(existing, imported);
+        store.insert(session_key.to_string(), merged);
+        Ok(added)
+    }
+
+    async fn search_history(&self, query: &str, session_key: Option<&str>, limit: usize) -> Result<Vec<HistoryMatch>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        let store = self.history.lock().unwrap();
+        let mut found: Vec<HistoryMatch> = store
+            .iter()
+            .filter(|(key, _)| session_key.is_none_or(|s| s == key.as_str()))
+            .flat_map(|(key, entries)| entries.iter().map(move |e| (key, e)))
+            .filter(|(_, e)| matches!(e.message.role, Role::User | Role::Assistant))
+            .filter(|(_, e)| {
+                let text = e.message.content.as_deref().unwrap_or_default().to_lowercase();
+                words.iter().all(|w| text.contains(w.as_str()))
+            })
+            .map(|(key, e)| HistoryMatch {
+                session_key: key.clone(),
+                entry: e.clone(),
+            })
+            .collect();
+        found.sort_by_key(|m| std::cmp::Reverse(m.entry.timestamp));
+        found.truncate(limit);
+        Ok(found)
+    }
+
     async fn save_fact(&self, key: &str, value: &str) -> Result<()> {
         let mut store = self.facts.lock().unwrap();
         store.insert(key.to_string(), value.to_string());
@@ -85,6 +85,9 @@ pub enum Role {
     User,
     Assistant,
     Tool,
+    /// Compressed summary of older turns (see [`crate::agent::Agent::summarize_session`]).
+    /// Sent to providers as a system message.
+    Summary,
 }
 
 /// A single message in a conversation history.
@@ -114,6 +117,17 @@ impl ChatMessage {
         }
     }
 
+    pub fn summary(content: &str) -> Self {
+        Self {
+            role: Role::Summary,
+            content: Some(content.to_string()),
+            media: Vec::new(),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        }
+    }
+
     pub fn user(content: &str) -> Self {
         Self {
             role: Role::User,
@@ -216,3 +230,11 @@ pub struct TokenUsage {
     pub completion_tokens: u32,
     pub total_tokens: u32,
 }
+
+impl std::ops::AddAssign<&TokenUsage> for TokenUsage {
+    fn add_assign(&mut self, other: &TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
@@ -0,0 +1,81 @@
+//! System prompt templating — substitute runtime variables into prompts.
+//!
+//! Supported variables:
+//!
+//! | Variable  | Value                                        |
+//! |-----------|----------------------------------------------|
+//! | `{date}`  | Current local date, e.g. `2025-01-31 (Friday)` |
+//! | `{time}`  | Current local time, e.g. `14:05`             |
+//! | `{os}`    | Operating system and architecture            |
+//! | `{cwd}`   | Current working directory                    |
+//! | `{user}`  | Current OS user name                         |
+//! | `{tools}` | Comma-separated list of registered tools     |
+//! | `{model}` | Active model name (if known)                 |
+//!
+//! Unknown `{...}` placeholders are left untouched, so prompts may still
+//! contain literal braces (e.g. JSON examples).
+
+use std::collections::BTreeMap;
+
+use crate::tool::ToolRegistry;
+
+/// Variables available to a system prompt template.
+#[derive(Debug, Clone, Default)]
+pub struct PromptVars {
+    vars: BTreeMap<String, String>,
+}
+
+impl PromptVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the standard variable set from the current environment.
+    pub fn from_env(tools: &ToolRegistry) -> Self {
+        let now = chrono::Local::now();
+        let cwd = std::env::current_dir()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string());
+
+        let mut tool_names = tools.names();
+        tool_names.sort_unstable();
+
+        let mut vars = Self::new();
+        vars.set("date", now.format("%Y-%m-%d (%A)").to_string())
+            .set("time", now.format("%H:%M").to_string())
+            .set(
+                "os",
+                format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            )
+            .set("cwd", cwd)
+            .set("user", user)
+            .set("tools", tool_names.join(", "));
+        vars
+    }
+
+    /// Set (or override) a variable.
+    pub fn set(&mut self, key: &str, value: impl Into<String>) -> &mut Self {
+        self.vars.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Get a variable value.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(|s| s.as_str())
+    }
+
+    /// Substitute all known `{variable}` placeholders in a template.
+    pub fn render(&self, template: &str) -> String {
+        let mut out = template.to_string();
+        for (key, value) in &self.vars {
+            let placeholder = format!("{{{}}}", key);
+            if out.contains(&placeholder) {
+                out = out.replace(&placeholder, value);
+            }
+        }
+        out
+    }
+}
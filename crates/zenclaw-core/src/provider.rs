@@ -1,10 +1,13 @@
 //! LLM Provider trait — the abstraction over different AI model APIs.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
 use crate::message::{ChatMessage, LlmResponse};
+use crate::tokens;
 
 /// Tool definition in OpenAI function calling format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,10 +75,101 @@ pub trait LlmProvider: Send + Sync {
     /// Send a chat completion request.
     async fn chat(&self, request: ChatRequest) -> Result<LlmResponse>;
 
+    /// Send a chat completion request, passing text deltas to `on_text` as
+    /// the model generates them. The returned response holds the full text;
+    /// tool calls are only returned there, never passed to `on_text`.
+    ///
+    /// The default calls [`chat`](Self::chat) and delivers the whole text
+    /// as a single delta, for providers without streaming support.
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<LlmResponse> {
+        let response = self.chat(request).await?;
+        if let Some(text) = response.content.as_deref().filter(|t| !t.is_empty()) {
+            on_text(text);
+        }
+        Ok(response)
+    }
+
     /// List available models (optional).
     async fn list_models(&self) -> Result<Vec<String>> {
         Ok(vec![self.default_model().to_string()])
     }
+
+    /// Estimate how many prompt tokens `messages` will cost (heuristic by default).
+    fn count_tokens(&self, messages: &[ChatMessage]) -> usize {
+        tokens::estimate_messages(messages)
+    }
+
+    /// Context window of `model` in tokens.
+    fn context_window(&self, model: &str) -> usize {
+        tokens::context_window(model)
+    }
+
+    /// Whether `model` accepts images (guessed from the model family).
+    fn supports_vision(&self, model: &str) -> bool {
+        supports_vision(model)
+    }
+}
+
+/// Forward to the boxed provider, so a runtime-chosen `Box<dyn LlmProvider>`
+/// can be used wherever a concrete provider is expected.
+#[async_trait]
+impl<P: LlmProvider + ?Sized> LlmProvider for Box<P> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn default_model(&self) -> &str {
+        (**self).default_model()
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+        (**self).chat(request).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<LlmResponse> {
+        (**self).chat_stream(request, on_text).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        (**self).list_models().await
+    }
+
+    fn count_tokens(&self, messages: &[ChatMessage]) -> usize {
+        (**self).count_tokens(messages)
+    }
+
+    fn context_window(&self, model: &str) -> usize {
+        (**self).context_window(model)
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        (**self).supports_vision(model)
+    }
+}
+
+/// Best guess whether a model accepts image input, from its name.
+pub fn supports_vision(model: &str) -> bool {
+    let m = model.to_lowercase();
+    let m = m.rsplit('/').next().unwrap_or(&m);
+
+    const VISION_FAMILIES: &[&str] = &[
+        "gpt-4o", "gpt-4.1", "gpt-4-turbo", "gpt-5", "o1", "o3", "o4", "claude", "gemini", "gemma3", "gemma-3",
+        "llava", "bakllava", "llama-4", "llama4", "pixtral", "moondream", "minicpm-v", "grok-2-vision",
+    ];
+    if m.starts_with("o1-mini") || m.starts_with("o3-mini") || m.starts_with("claude-2") || m.starts_with("claude-instant") {
+        return false;
+    }
+    VISION_FAMILIES.iter().any(|f| m.starts_with(f))
+        || m.contains("vision")
+        || m.contains("-vl")
 }
 
 /// Provider configuration.
@@ -89,6 +183,98 @@ pub struct ProviderConfig {
     pub max_tokens: u32,
     #[serde(default = "default_temperature")]
     pub temperature: f32,
+    /// Extra provider-specific parameters merged into every request body
+    /// (e.g. `{"reasoning_effort": "high"}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_params: Option<serde_json::Value>,
+    /// Role used for the system prompt (None = pick from the model family).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_role: Option<SystemRole>,
+    /// Retries on rate limits (429), 5xx and connection errors.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// First retry delay in milliseconds; doubles on every attempt.
+    #[serde(default = "default_retry_base_ms")]
+    pub retry_base_ms: u64,
+    /// Extra HTTP headers sent with every request (e.g. OpenRouter's
+    /// `HTTP-Referer` / `X-Title`, or a gateway's own auth header).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Let an `Authorization` entry in `headers` replace the provider's own
+    /// `Bearer <api_key>`. Off by default, so it is ignored.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub override_auth: bool,
+    /// Whether `model` accepts images (None = guess from its name, see
+    /// [`supports_vision`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vision: Option<bool>,
+}
+
+impl ProviderConfig {
+    /// `headers` that may be sent: all of them with `override_auth`, else
+    /// everything but `Authorization`.
+    pub fn extra_headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .filter(|(name, _)| self.override_auth || !name.eq_ignore_ascii_case("authorization"))
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Whether `model` accepts images: the `vision` override for the
+    /// configured model, the name-based guess otherwise.
+    pub fn supports_vision(&self, model: &str) -> bool {
+        match self.vision {
+            Some(vision) if model == self.model => vision,
+            _ => supports_vision(model),
+        }
+    }
+
+    /// True if `headers` brings its own `Authorization` and may use it.
+    pub fn overrides_auth(&self) -> bool {
+        self.override_auth && self.headers.keys().any(|name| name.eq_ignore_ascii_case("authorization"))
+    }
+}
+
+/// How a provider should send the system prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemRole {
+    /// Standard `system` message.
+    System,
+    /// `developer` message (OpenAI o-series and newer reasoning models).
+    Developer,
+    /// No system role: instructions are prepended to the first user message.
+    User,
+}
+
+impl SystemRole {
+    /// Best role for a model family.
+    pub fn for_model(model: &str) -> Self {
+        let m = model.to_lowercase();
+        let m = m.rsplit('/').next().unwrap_or(&m);
+
+        if m.starts_with("o1-mini") || m.starts_with("o1-preview") || m.starts_with("gemma") {
+            // These reject both `system` and `developer` messages
+            Self::User
+        } else if m.starts_with("o1") || m.starts_with("o3") || m.starts_with("o4") || m.starts_with("gpt-5") {
+            Self::Developer
+        } else {
+            Self::System
+        }
+    }
+}
+
+impl std::str::FromStr for SystemRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "system" => Ok(Self::System),
+            "developer" => Ok(Self::Developer),
+            "user" => Ok(Self::User),
+            other => Err(format!("unknown system role '{}' (expected system, developer or user)", other)),
+        }
+    }
 }
 
 fn default_max_tokens() -> u32 {
@@ -99,6 +285,14 @@ fn default_temperature() -> f32 {
     0.7
 }
 
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_base_ms() -> u64 {
+    500
+}
+
 impl Default for ProviderConfig {
     fn default() -> Self {
         Self {
@@ -108,6 +302,31 @@ impl Default for ProviderConfig {
             api_base: None,
             max_tokens: 4096,
             temperature: 0.7,
+            model_params: None,
+            system_role: None,
+            max_retries: default_max_retries(),
+            retry_base_ms: default_retry_base_ms(),
+            headers: HashMap::new(),
+            override_auth: false,
+            vision: None,
         }
     }
 }
+
+/// Turns text into vectors for semantic search.
+///
+/// Kept apart from [`LlmProvider`] so a cheap embedding model can be paired
+/// with any chat model, including providers that have no embeddings API.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embedding model name, stored next to every vector.
+    fn model(&self) -> &str;
+
+    /// Most texts accepted by one [`embed`](Self::embed) call.
+    fn max_batch(&self) -> usize {
+        64
+    }
+
+    /// Embed each text, one vector per input, in order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
@@ -0,0 +1,160 @@
+//! Per-client rate limiting — a token bucket for each key.
+//!
+//! Shared by the REST API (keyed by API key or client address) and the bot
+//! channels (keyed by session), so one busy user can't exhaust the
+//! provider quota for everybody else.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::RateLimitSettings;
+
+/// Most buckets kept at once. Past it idle, fully refilled buckets are
+/// dropped, then the least recently used one.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token-bucket limiter: each key may send `burst` requests at once, then
+/// one more every `60 / requests_per_minute` seconds.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second.
+    rate: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    /// Proxies whose `X-Forwarded-For` the REST API believes.
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32, burst: u32) -> Self {
+        Self {
+            rate: f64::from(requests_per_minute) / 60.0,
+            burst: f64::from(burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+            trusted_proxies: Vec::new(),
+        }
+    }
+
+    /// Believe `X-Forwarded-For` on requests from `proxies`.
+    pub fn with_trusted_proxies(mut self, proxies: Vec<IpAddr>) -> Self {
+        self.trusted_proxies = proxies;
+        self
+    }
+
+    pub fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.contains(&ip)
+    }
+
+    /// Limiter for `settings`, or None when rate limiting is off.
+    pub fn from_settings(settings: &RateLimitSettings) -> Option<Self> {
+        (settings.requests_per_minute > 0).then(|| {
+            Self::new(settings.requests_per_minute, settings.burst)
+                .with_trusted_proxies(settings.trusted_proxies.clone())
+        })
+    }
+
+    /// Take one request for `key`. Err holds how long until the next one is allowed.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let Ok(mut buckets) = self.buckets.lock() else { return Ok(()) };
+
+        if buckets.len() >= MAX_TRACKED_KEYS && !buckets.contains_key(key) {
+            let (rate, burst) = (self.rate, self.burst);
+            buckets.retain(|_, b| b.tokens + now.duration_since(b.updated).as_secs_f64() * rate < burst);
+            if buckets.len() >= MAX_TRACKED_KEYS
+                && let Some(oldest) = buckets.iter().min_by_key(|(_, b)| b.updated).map(|(k, _)| k.clone())
+            {
+                buckets.remove(&oldest);
+            }
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+/// Reply sent on a chat channel when a user is rate limited.
+pub fn too_fast_message(retry_after: Duration) -> String {
+    format!(
+        "⏳ You're sending messages too fast — please wait {}s and try again.",
+        retry_after.as_secs().max(1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_after_burst_within_window() {
+        let limiter = RateLimiter::new(60, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_at("alice", now).is_ok());
+        }
+        let wait = limiter.check_at("alice", now).unwrap_err();
+        assert!(wait <= Duration::from_secs(1));
+
+        // Other keys have their own bucket
+        assert!(limiter.check_at("bob", now).is_ok());
+    }
+
+    #[test]
+    fn test_refills_over_time() {
+        let limiter = RateLimiter::new(60, 2);
+        let now = Instant::now();
+        assert!(limiter.check_at("alice", now).is_ok());
+        assert!(limiter.check_at("alice", now).is_ok());
+        assert!(limiter.check_at("alice", now + Duration::from_millis(500)).is_err());
+
+        // One token per second at 60/min
+        assert!(limiter.check_at("alice", now + Duration::from_millis(1100)).is_ok());
+        assert!(limiter.check_at("alice", now + Duration::from_millis(1200)).is_err());
+
+        // Never refills past the burst size
+        let later = now + Duration::from_secs(60);
+        assert!(limiter.check_at("alice", later).is_ok());
+        assert!(limiter.check_at("alice", later).is_ok());
+        assert!(limiter.check_at("alice", later).is_err());
+    }
+
+    #[test]
+    fn test_tracked_keys_are_capped() {
+        let limiter = RateLimiter::new(60, 2);
+        let now = Instant::now();
+        for i in 0..MAX_TRACKED_KEYS {
+            assert!(limiter.check_at(&format!("client{}", i), now).is_ok());
+        }
+
+        // None has refilled yet, so the least recently seen one makes room
+        let later = now + Duration::from_millis(10);
+        assert!(limiter.check_at("client0", later).is_ok());
+        assert!(limiter.check_at("newcomer", later).is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), MAX_TRACKED_KEYS);
+        assert!(buckets.contains_key("client0") && buckets.contains_key("newcomer"));
+    }
+}
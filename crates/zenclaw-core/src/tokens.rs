@@ -0,0 +1,157 @@
+//! Token estimation — pre-flight checks against a model's context window.
+//!
+//! No tokenizer tables are bundled (they would double the binary size), so
+//! counts are estimates. [`estimate_bpe`] mimics how tiktoken-style BPE
+//! tokenizers pre-split text and is within ~10% for English and code;
+//! [`estimate_chars`] is the cruder "4 characters per token" rule.
+
+use crate::message::ChatMessage;
+
+/// Tokens added per message for role/formatting (OpenAI chat format).
+const PER_MESSAGE_OVERHEAD: usize = 4;
+/// Tokens added once per request to prime the assistant reply.
+const REPLY_PRIMING: usize = 3;
+
+/// Rough estimate: ~4 characters per token.
+pub fn estimate_chars(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// BPE-style estimate: split like cl100k pre-tokenization, then assume
+/// long words break into ~4-character pieces.
+pub fn estimate_bpe(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word_len = 0;
+    let mut digit_len = 0;
+
+    let flush_word = |len: &mut usize, tokens: &mut usize| {
+        if *len > 0 {
+            *tokens += len.div_ceil(4);
+            *len = 0;
+        }
+    };
+    let flush_digits = |len: &mut usize, tokens: &mut usize| {
+        if *len > 0 {
+            // Numbers are split into groups of up to 3 digits
+            *tokens += len.div_ceil(3);
+            *len = 0;
+        }
+    };
+
+    for c in text.chars() {
+        if c.is_alphabetic() && (c as u32) < 0x2E80 {
+            flush_digits(&mut digit_len, &mut tokens);
+            word_len += 1;
+        } else if c.is_ascii_digit() {
+            flush_word(&mut word_len, &mut tokens);
+            digit_len += 1;
+        } else {
+            flush_word(&mut word_len, &mut tokens);
+            flush_digits(&mut digit_len, &mut tokens);
+            if !c.is_whitespace() {
+                // Punctuation, symbols and CJK ideographs: ~1 token each
+                tokens += 1;
+            } else if c == '\n' {
+                tokens += 1;
+            }
+        }
+    }
+    flush_word(&mut word_len, &mut tokens);
+    flush_digits(&mut digit_len, &mut tokens);
+
+    tokens
+}
+
+/// Estimate the tokens of a message list with a per-text estimator.
+pub fn estimate_messages_with(messages: &[ChatMessage], estimate: fn(&str) -> usize) -> usize {
+    let mut total = REPLY_PRIMING;
+    for msg in messages {
+        total += PER_MESSAGE_OVERHEAD;
+        if let Some(content) = &msg.content {
+            total += estimate(content);
+        }
+        if let Some(calls) = &msg.tool_calls {
+            for call in calls {
+                total += estimate(&call.function.name) + estimate(&call.function.arguments);
+            }
+        }
+        // Images cost a fixed-ish amount on most vision models
+        total += msg.media.len() * 765;
+    }
+    total
+}
+
+/// Estimate the tokens of a message list (character heuristic).
+pub fn estimate_messages(messages: &[ChatMessage]) -> usize {
+    estimate_messages_with(messages, estimate_chars)
+}
+
+/// Best-known context window (in tokens) for a model name.
+pub fn context_window(model: &str) -> usize {
+    let m = model.to_lowercase();
+    let m = m.rsplit('/').next().unwrap_or(&m); // "openai/gpt-4o" → "gpt-4o"
+
+    if m.starts_with("gpt-4.1") || m.starts_with("gemini") {
+        1_000_000
+    } else if m.starts_with("gpt-5") {
+        400_000
+    } else if m.starts_with("o1") || m.starts_with("o3") || m.starts_with("o4") || m.starts_with("claude") {
+        200_000
+    } else if m.starts_with("gpt-4o") || m.starts_with("gpt-4-turbo") || m.starts_with("llama-3") || m.starts_with("llama3.1") || m.starts_with("llama3.2") || m.starts_with("deepseek") {
+        128_000
+    } else if m.starts_with("mixtral") || m.contains("32k") || m.contains("32768") {
+        32_768
+    } else if m.starts_with("gpt-3.5") {
+        16_385
+    } else if m.starts_with("gemma") || m.starts_with("llama3") || m.starts_with("gpt-4") {
+        8_192
+    } else {
+        32_768
+    }
+}
+
+/// Approximate list price of `model` in USD per million tokens
+/// (input, output). None for unknown and local models.
+pub fn price_per_million(model: &str) -> Option<(f64, f64)> {
+    let m = model.to_lowercase();
+    let m = m.rsplit('/').next().unwrap_or(&m);
+
+    let price = if m.starts_with("gpt-4o-mini") {
+        (0.15, 0.60)
+    } else if m.starts_with("gpt-4o") {
+        (2.50, 10.00)
+    } else if m.starts_with("gpt-4.1-nano") {
+        (0.10, 0.40)
+    } else if m.starts_with("gpt-4.1-mini") {
+        (0.40, 1.60)
+    } else if m.starts_with("gpt-4.1") {
+        (2.00, 8.00)
+    } else if m.starts_with("gpt-5-mini") {
+        (0.25, 2.00)
+    } else if m.starts_with("gpt-5") {
+        (1.25, 10.00)
+    } else if m.starts_with("o3-mini") || m.starts_with("o4-mini") {
+        (1.10, 4.40)
+    } else if m.starts_with("gemini-2.0-flash") || m.starts_with("gemini-1.5-flash") {
+        (0.10, 0.40)
+    } else if m.starts_with("gemini-2.5-flash") {
+        (0.30, 2.50)
+    } else if m.starts_with("gemini-2.5-pro") || m.starts_with("gemini-1.5-pro") {
+        (1.25, 10.00)
+    } else if m.starts_with("llama-3.1-8b") {
+        (0.05, 0.08)
+    } else if m.starts_with("llama-3.3-70b") || m.starts_with("llama-3.1-70b") {
+        (0.59, 0.79)
+    } else if m.starts_with("deepseek") {
+        (0.27, 1.10)
+    } else {
+        return None;
+    };
+    Some(price)
+}
+
+/// Estimated cost in USD of a request to `model`, if its price is known.
+pub fn estimate_cost(model: &str, prompt_tokens: u32, completion_tokens: u32) -> Option<f64> {
+    let (input, output) = price_per_million(model)?;
+    Some((prompt_tokens as f64 * input + completion_tokens as f64 * output) / 1_000_000.0)
+}
@@ -2,11 +2,45 @@
 
 use async_trait::async_trait;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 use crate::error::{Result, ZenClawError};
 use crate::provider::ToolDefinition;
 
+pub use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static WORKSPACE: PathBuf;
+    static SESSION: String;
+}
+
+/// Directory the running tool call is confined to, when the agent has a
+/// [workspace root](crate::agent::AgentConfig::workspace_root). File tools
+/// must refuse paths outside it.
+pub fn current_workspace() -> Option<PathBuf> {
+    WORKSPACE.try_with(|dir| dir.clone()).ok()
+}
+
+/// Run `fut` with [`current_workspace`] set to `workspace`.
+pub async fn with_workspace<F: std::future::Future>(workspace: Option<PathBuf>, fut: F) -> F::Output {
+    match workspace {
+        Some(dir) => WORKSPACE.scope(dir, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Session key of the turn the running tool call belongs to, when the
+/// agent runs it (e.g. to remember what a session has already seen).
+pub fn current_session() -> Option<String> {
+    SESSION.try_with(|key| key.clone()).ok()
+}
+
+/// Run `fut` with [`current_session`] set to `session_key`.
+pub async fn with_session<F: std::future::Future>(session_key: &str, fut: F) -> F::Output {
+    SESSION.scope(session_key.to_string(), fut).await
+}
+
 /// Abstract tool trait — implement this to give the agent new abilities.
 ///
 /// # Example
@@ -48,6 +82,30 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments.
     async fn execute(&self, args: Value) -> Result<String>;
 
+    /// Execute the tool, giving up as soon as `cancel` fires.
+    ///
+    /// The default drops the [`execute`](Self::execute) future, which is
+    /// enough for tools that only await I/O (e.g. HTTP requests). Tools that
+    /// spawn child processes should override this and kill them.
+    async fn execute_cancellable(&self, args: Value, cancel: &CancellationToken) -> Result<String> {
+        tokio::select! {
+            result = self.execute(args) => result,
+            _ = cancel.cancelled() => Err(ZenClawError::Cancelled),
+        }
+    }
+
+    /// Whether the tool changes the system (writes files, runs commands).
+    /// With `confirm_destructive` on, the agent asks before running it.
+    fn is_destructive(&self) -> bool {
+        false
+    }
+
+    /// What the call would change (e.g. a diff), shown when asking for
+    /// approval. None = nothing to preview.
+    async fn preview(&self, _args: &Value) -> Option<String> {
+        None
+    }
+
     /// Convert to OpenAI tool definition format.
     fn to_definition(&self) -> ToolDefinition {
         ToolDefinition {
@@ -61,23 +119,114 @@ pub trait Tool: Send + Sync {
     }
 }
 
+/// Whether a registered tool may be used by the agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Policy {
+    /// Advertised to the LLM and executable (unless an allow-list excludes it).
+    #[default]
+    Allowed,
+    /// Hidden from the LLM and refused if called anyway. Wins over the allow-list.
+    Denied,
+}
+
 /// Registry for managing tools. Core component of the agent.
+///
+/// Tools can be restricted with a per-tool [`Policy`] and an allow-list
+/// ([`set_allowed`](Self::set_allowed)). Restricted tools are left out of
+/// [`definitions`](Self::definitions) and calling them fails with
+/// [`ZenClawError::ToolDenied`].
 pub struct ToolRegistry {
     tools: HashMap<String, Box<dyn Tool>>,
+    policies: HashMap<String, Policy>,
+    /// Only these tools may be used (None = all).
+    allowed: Option<HashSet<String>>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            policies: HashMap::new(),
+            allowed: None,
         }
     }
 
     /// Register a new tool.
-    pub fn register(&mut self, tool: impl Tool + 'static) {
+    ///
+    /// Names must be unique: if a tool with the same name is already
+    /// registered, the new one is rejected (with a warning) and `false` is
+    /// returned. Use [`register_namespaced`](Self::register_namespaced) for
+    /// third-party tools that may collide with built-ins.
+    pub fn register(&mut self, tool: impl Tool + 'static) -> bool {
+        self.insert(Box::new(tool))
+    }
+
+    /// Register a tool, prefixing its name with `{namespace}_` if it would
+    /// shadow an existing tool (e.g. a plugin named `shell` → `plugin_shell`).
+    ///
+    /// An underscore is used rather than `:` because function names sent to
+    /// OpenAI-compatible APIs must match `^[a-zA-Z0-9_-]+$`.
+    pub fn register_namespaced(&mut self, namespace: &str, tool: impl Tool + 'static) -> bool {
+        if !self.has(tool.name()) {
+            return self.insert(Box::new(tool));
+        }
+
+        let name = format!("{}_{}", namespace, tool.name());
+        tracing::warn!(
+            "Tool '{}' conflicts with an existing tool — registering it as '{}'",
+            tool.name(),
+            name
+        );
+        self.insert(Box::new(Renamed {
+            name,
+            inner: Box::new(tool),
+        }))
+    }
+
+    /// Register a tool with an explicit policy (e.g. [`Policy::Denied`] to
+    /// keep it around but unusable).
+    pub fn register_with_policy(&mut self, tool: impl Tool + 'static, policy: Policy) -> bool {
         let name = tool.name().to_string();
+        let added = self.register(tool);
+        if added {
+            self.set_policy(&name, policy);
+        }
+        added
+    }
+
+    /// Set the policy of a tool by name. May be called before the tool is registered.
+    pub fn set_policy(&mut self, name: &str, policy: Policy) {
+        self.policies.insert(name.to_string(), policy);
+    }
+
+    /// Restrict the agent to the given tools; every other tool is hidden and
+    /// refused. Denied tools stay denied even if listed here.
+    pub fn set_allowed<S: AsRef<str>>(&mut self, names: &[S]) {
+        self.allowed = Some(names.iter().map(|n| n.as_ref().to_string()).collect());
+    }
+
+    /// Lift the allow-list set by [`set_allowed`](Self::set_allowed).
+    pub fn clear_allowed(&mut self) {
+        self.allowed = None;
+    }
+
+    /// Whether the policy lets the agent use `name`.
+    pub fn is_allowed(&self, name: &str) -> bool {
+        if self.policies.get(name) == Some(&Policy::Denied) {
+            return false;
+        }
+        self.allowed.as_ref().is_none_or(|allowed| allowed.contains(name))
+    }
+
+    fn insert(&mut self, tool: Box<dyn Tool>) -> bool {
+        let name = tool.name().to_string();
+        if self.tools.contains_key(&name) {
+            tracing::warn!("Tool '{}' is already registered — ignoring duplicate", name);
+            return false;
+        }
         tracing::debug!("Registered tool: {}", name);
-        self.tools.insert(name, Box::new(tool));
+        self.tools.insert(name, tool);
+        true
     }
 
     /// Get a tool by name.
@@ -85,30 +234,67 @@ impl ToolRegistry {
         self.tools.get(name).map(|t| t.as_ref())
     }
 
+    /// True if `name` is registered and [`Tool::is_destructive`].
+    pub fn is_destructive(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|t| t.is_destructive())
+    }
+
     /// Check if a tool is registered.
     pub fn has(&self, name: &str) -> bool {
         self.tools.contains_key(name)
     }
 
-    /// Get all tool definitions for LLM function calling.
+    /// Get the definitions of all allowed tools for LLM function calling.
     pub fn definitions(&self) -> Vec<ToolDefinition> {
-        self.tools.values().map(|t| t.to_definition()).collect()
+        self.tools
+            .iter()
+            .filter(|(name, _)| self.is_allowed(name))
+            .map(|(_, t)| t.to_definition())
+            .collect()
     }
 
     /// Execute a tool by name.
     pub async fn execute(&self, name: &str, args: Value) -> Result<String> {
+        self.execute_cancellable(name, args, &CancellationToken::new()).await
+    }
+
+    /// Execute a tool by name, aborting it when `cancel` fires.
+    pub async fn execute_cancellable(
+        &self,
+        name: &str,
+        args: Value,
+        cancel: &CancellationToken,
+    ) -> Result<String> {
         let tool = self
             .tools
             .get(name)
             .ok_or_else(|| ZenClawError::ToolNotFound(name.to_string()))?;
+        if !self.is_allowed(name) {
+            tracing::warn!("Refused call to tool '{}' (not allowed by policy)", name);
+            return Err(ZenClawError::ToolDenied(name.to_string()));
+        }
+        if let Err(problems) = validate_args(&tool.parameters(), &args) {
+            tracing::warn!("Invalid arguments for tool {}: {}", name, problems.join("; "));
+            return Err(ZenClawError::InvalidArguments {
+                tool: name.to_string(),
+                message: format!(
+                    "{}. Fix the arguments and call the tool again.",
+                    problems.join("; ")
+                ),
+            });
+        }
 
         tracing::info!("Executing tool: {} with args: {}", name, args);
 
-        match tool.execute(args).await {
+        match tool.execute_cancellable(args, cancel).await {
             Ok(result) => {
                 tracing::debug!("Tool {} completed ({} bytes)", name, result.len());
                 Ok(result)
             }
+            Err(ZenClawError::Cancelled) => {
+                tracing::info!("Tool {} cancelled", name);
+                Err(ZenClawError::Cancelled)
+            }
             Err(e) => {
                 tracing::error!("Tool {} failed: {}", name, e);
                 Err(ZenClawError::ToolExecution {
@@ -119,9 +305,23 @@ impl ToolRegistry {
         }
     }
 
-    /// List all registered tool names.
+    /// List all registered tool names (sorted).
     pub fn names(&self) -> Vec<&str> {
-        self.tools.keys().map(|s| s.as_str()).collect()
+        let mut names: Vec<&str> = self.tools.keys().map(|s| s.as_str()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Human-readable listing of every tool: name, description and
+    /// pretty-printed parameter schema.
+    pub fn describe(&self) -> String {
+        let mut out = String::new();
+        for name in self.names() {
+            let tool = &self.tools[name];
+            let params = serde_json::to_string_pretty(&tool.parameters()).unwrap_or_default();
+            out.push_str(&format!("■ {}\n  {}\n\n{}\n\n", name, tool.description(), params));
+        }
+        out
     }
 
     /// Number of registered tools.
@@ -139,3 +339,304 @@ impl Default for ToolRegistry {
         Self::new()
     }
 }
+
+/// Check `args` against a tool's parameter schema: required fields must be
+/// present and fields with a `type` must match it. Returns one message per problem.
+///
+/// Only the basics are checked (`required`, and `type` of top-level
+/// properties); unknown fields and other schema keywords are ignored.
+pub fn validate_args(schema: &Value, args: &Value) -> std::result::Result<(), Vec<String>> {
+    let empty = serde_json::Map::new();
+    let fields = match args {
+        Value::Object(map) => map,
+        Value::Null => &empty,
+        other => return Err(vec![format!("arguments must be a JSON object, got {}", json_type(other))]),
+    };
+
+    let mut problems = Vec::new();
+    let properties = schema["properties"].as_object();
+
+    for name in schema["required"].as_array().into_iter().flatten().filter_map(|v| v.as_str()) {
+        if fields.get(name).is_none_or(Value::is_null) {
+            let hint = properties
+                .and_then(|p| p.get(name))
+                .and_then(|p| p["type"].as_str())
+                .map(|t| format!(" ({})", t))
+                .unwrap_or_default();
+            problems.push(format!("missing required field '{}'{}", name, hint));
+        }
+    }
+
+    for (name, value) in fields {
+        let Some(expected) = properties.and_then(|p| p.get(name)).map(|p| &p["type"]) else {
+            continue;
+        };
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ => continue,
+        };
+        if !value.is_null() && !allowed.iter().any(|t| type_matches(t, value)) {
+            problems.push(format!(
+                "field '{}' must be {}, got {}",
+                name,
+                allowed.join(" or "),
+                json_type(value)
+            ));
+        }
+    }
+
+    if problems.is_empty() { Ok(()) } else { Err(problems) }
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        // 5.0 is accepted as an integer
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true, // unknown type names aren't enforced
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A tool exposed under a different name (see [`ToolRegistry::register_namespaced`]).
+struct Renamed {
+    name: String,
+    inner: Box<dyn Tool>,
+}
+
+#[async_trait]
+impl Tool for Renamed {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters(&self) -> Value {
+        self.inner.parameters()
+    }
+
+    fn is_destructive(&self) -> bool {
+        self.inner.is_destructive()
+    }
+
+    async fn preview(&self, args: &Value) -> Option<String> {
+        self.inner.preview(args).await
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        self.inner.execute(args).await
+    }
+
+    async fn execute_cancellable(&self, args: Value, cancel: &CancellationToken) -> Result<String> {
+        self.inner.execute_cancellable(args, cancel).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo(&'static str);
+
+    #[async_trait]
+    impl Tool for Echo {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Echo the arguments"
+        }
+
+        fn parameters(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, args: Value) -> Result<String> {
+            Ok(args.to_string())
+        }
+    }
+
+    /// Declares a typed schema to validate against.
+    struct Search;
+
+    #[async_trait]
+    impl Tool for Search {
+        fn name(&self) -> &str {
+            "search"
+        }
+
+        fn description(&self) -> &str {
+            "Search for something"
+        }
+
+        fn parameters(&self) -> Value {
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "max_results": { "type": "integer" },
+                    "exact": { "type": "boolean" },
+                    "filters": { "type": "object" }
+                },
+                "required": ["query"]
+            })
+        }
+
+        async fn execute(&self, args: Value) -> Result<String> {
+            Ok(args["query"].as_str().unwrap_or_default().to_string())
+        }
+    }
+
+    /// Destructive, with a preview of what it removes.
+    struct Remove;
+
+    #[async_trait]
+    impl Tool for Remove {
+        fn name(&self) -> &str {
+            "remove"
+        }
+
+        fn description(&self) -> &str {
+            "Remove a file"
+        }
+
+        fn parameters(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        fn is_destructive(&self) -> bool {
+            true
+        }
+
+        async fn preview(&self, args: &Value) -> Option<String> {
+            Some(format!("- {}", args["path"].as_str().unwrap_or_default()))
+        }
+
+        async fn execute(&self, _args: Value) -> Result<String> {
+            Ok("removed".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_renamed_tool_keeps_gating_and_preview() {
+        let mut tools = ToolRegistry::new();
+        tools.register(Echo("remove"));
+        tools.register_namespaced("plugin", Remove);
+
+        assert!(!tools.is_destructive("remove"));
+        assert!(tools.is_destructive("plugin_remove"));
+        let preview = tools.get("plugin_remove").unwrap().preview(&serde_json::json!({ "path": "notes.md" })).await;
+        assert_eq!(preview.as_deref(), Some("- notes.md"));
+    }
+
+    fn registry() -> ToolRegistry {
+        let mut tools = ToolRegistry::new();
+        tools.register(Echo("read_file"));
+        tools.register(Echo("web_search"));
+        tools.register(Echo("exec"));
+        tools
+    }
+
+    fn advertised(tools: &ToolRegistry) -> Vec<String> {
+        let mut names: Vec<String> = tools.definitions().into_iter().map(|d| d.function.name).collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_allow_list_filters_tools() {
+        let mut tools = registry();
+        tools.set_allowed(&["read_file", "web_search"]);
+
+        assert_eq!(advertised(&tools), ["read_file", "web_search"]);
+        assert!(tools.execute("read_file", serde_json::json!({})).await.is_ok());
+        let err = tools.execute("exec", serde_json::json!({})).await.unwrap_err();
+        assert!(matches!(err, ZenClawError::ToolDenied(ref name) if name == "exec"));
+    }
+
+    #[tokio::test]
+    async fn test_deny_wins_over_allow_list() {
+        let mut tools = registry();
+        tools.register_with_policy(Echo("env"), Policy::Denied);
+        tools.set_allowed(&["read_file", "env"]);
+        tools.set_policy("read_file", Policy::Denied);
+
+        assert!(advertised(&tools).is_empty());
+        assert!(matches!(
+            tools.execute("env", serde_json::json!({})).await,
+            Err(ZenClawError::ToolDenied(_))
+        ));
+
+        // Without an allow-list only the denied tools are blocked
+        tools.clear_allowed();
+        assert_eq!(advertised(&tools), ["exec", "web_search"]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_field_is_reported() {
+        let mut tools = ToolRegistry::new();
+        tools.register(Search);
+
+        let err = tools
+            .execute("search", serde_json::json!({ "max_results": 3 }))
+            .await
+            .unwrap_err();
+        let ZenClawError::InvalidArguments { tool, message } = err else {
+            panic!("expected InvalidArguments, got {:?}", err);
+        };
+        assert_eq!(tool, "search");
+        assert!(message.contains("missing required field 'query' (string)"), "{}", message);
+        assert!(message.contains("call the tool again"));
+
+        // Unparseable arguments reach the registry as null
+        assert!(matches!(
+            tools.execute("search", Value::Null).await,
+            Err(ZenClawError::InvalidArguments { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_type_is_reported() {
+        let mut tools = ToolRegistry::new();
+        tools.register(Search);
+
+        let err = tools
+            .execute(
+                "search",
+                serde_json::json!({ "query": "rust", "max_results": "five", "exact": 1, "filters": [] }),
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("field 'max_results' must be integer, got string"), "{}", err);
+        assert!(err.contains("field 'exact' must be boolean, got integer"), "{}", err);
+        assert!(err.contains("field 'filters' must be object, got array"), "{}", err);
+
+        let ok = tools
+            .execute("search", serde_json::json!({ "query": "rust", "max_results": 5.0, "extra": true }))
+            .await
+            .unwrap();
+        assert_eq!(ok, "rust");
+    }
+}
@@ -1,35 +1,59 @@
 //! REST API server — expose ZenClaw as an HTTP service.
 //!
 //! Endpoints:
-//! - POST /v1/chat — Send a message and get a response
+//! - POST /v1/chat — Send a message and get a response; optional
+//!   `max_tokens`, `temperature` and `timeout_seconds` apply to that request only
+//! - POST /v1/chat/stream — Same, streamed as server-sent events
 //! - GET  /v1/status — System status
-//! - GET  /v1/health — Health check
+//! - GET  /v1/sessions/{key}/summary — Bullet summary of a session's history
+//! - GET  /v1/actions — Tool calls waiting for confirmation;
+//!   POST /v1/actions/{id} with `{"approve": bool}` answers one (see [`actions`])
+//! - GET  /v1/health, /health — Health check (never needs the API key);
+//!   `?deep=true` also pings the provider
 //! - POST /v1/rag/index — Index a document into RAG
 //! - POST /v1/rag/search — Search indexed documents
+//! - GET  /metrics — Prometheus metrics
+//! - POST /v1/chat/completions, GET /v1/models — OpenAI-compatible
+//!   (see [`openai`])
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
-    response::sse::{Event, Sse},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json},
+    response::sse::{Event, KeepAlive, Sse},
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc, Mutex};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::Stream;
 use core::convert::Infallible;
-use tracing::info;
+use tracing::{info, warn};
 
 use zenclaw_core::agent::Agent;
 use zenclaw_core::memory::MemoryStore;
 use zenclaw_core::provider::LlmProvider;
-use zenclaw_core::bus::EventBus;
+use zenclaw_core::ratelimit::RateLimiter;
+use zenclaw_core::bus::{EventBus, SystemEvent};
 
+use crate::doctor;
 use crate::memory::RagStore;
+use crate::metrics::{PrometheusMetrics, SharedMetrics};
+
+mod actions;
+mod openai;
+
+pub use actions::{PendingAction, PendingActions};
+
+use openai::Tuned;
+
+/// Longest `timeout_seconds` a chat request may ask for.
+pub const MAX_TIMEOUT_SECS: u64 = 300;
 
 /// Shared API state.
 #[derive(Clone)]
@@ -38,6 +62,16 @@ pub struct ApiState {
     pub provider: Arc<dyn LlmProvider>,
     pub memory: Arc<dyn MemoryStore>,
     pub rag: Option<Arc<RagStore>>,
+    pub metrics: SharedMetrics,
+    /// Fed from the agent's event bus (see [`PrometheusMetrics::spawn_collector`]).
+    pub prometheus: Arc<PrometheusMetrics>,
+    /// Key clients must send as `Authorization: Bearer <key>` (None = open).
+    pub api_key: Option<String>,
+    /// Per-client request limit (None = unlimited).
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Tool calls waiting for confirmation (the agent's approver when
+    /// `confirm_destructive` is on).
+    pub actions: Arc<PendingActions>,
 }
 
 type SharedState = Arc<Mutex<ApiState>>;
@@ -51,12 +85,98 @@ pub struct ChatRequest {
     pub media: Vec<String>,
     #[serde(default = "default_session")]
     pub session: String,
+    #[serde(flatten)]
+    pub overrides: ChatOverrides,
 }
 
 fn default_session() -> String {
     "api".to_string()
 }
 
+/// Per-request overrides of the agent's defaults; the config is untouched.
+#[derive(Debug, Default, Deserialize)]
+pub struct ChatOverrides {
+    /// Completion tokens per LLM call, up to the model's context window.
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature, 0.0 to 2.0.
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Give up on the whole turn after this long, up to [`MAX_TIMEOUT_SECS`].
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+}
+
+impl ChatOverrides {
+    /// Reject values out of range; `context_window` is the model's limit.
+    fn validate(&self, context_window: usize) -> Result<(), String> {
+        if let Some(max) = self.max_tokens
+            && (max == 0 || max as usize > context_window)
+        {
+            return Err(format!("max_tokens must be between 1 and {} for this model", context_window));
+        }
+        if let Some(t) = self.temperature
+            && !(0.0..=2.0).contains(&t)
+        {
+            return Err("temperature must be between 0.0 and 2.0".to_string());
+        }
+        if let Some(secs) = self.timeout_seconds
+            && !(1..=MAX_TIMEOUT_SECS).contains(&secs)
+        {
+            return Err(format!("timeout_seconds must be between 1 and {}", MAX_TIMEOUT_SECS));
+        }
+        Ok(())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_seconds.map(Duration::from_secs)
+    }
+
+    /// `provider` with these overrides applied to every call, once they are
+    /// checked against the agent's model.
+    fn apply(
+        &self,
+        agent: &Agent,
+        provider: Arc<dyn LlmProvider>,
+    ) -> Result<Arc<dyn LlmProvider>, (StatusCode, Json<ErrorResponse>)> {
+        let model = agent
+            .config
+            .model
+            .clone()
+            .unwrap_or_else(|| provider.default_model().to_string());
+        self.validate(provider.context_window(&model))
+            .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+        if self.max_tokens.is_none() && self.temperature.is_none() {
+            return Ok(provider);
+        }
+        Ok(Arc::new(Tuned {
+            inner: provider,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        }))
+    }
+}
+
+/// Run a turn, failing with `Timeout` once `limit` (if any) has passed.
+async fn with_timeout<T>(
+    limit: Option<Duration>,
+    turn: impl std::future::Future<Output = zenclaw_core::error::Result<T>>,
+) -> Result<zenclaw_core::error::Result<T>, Timeout> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, turn).await.map_err(|_| Timeout(limit)),
+        None => Ok(turn.await),
+    }
+}
+
+/// A turn ran past its `timeout_seconds`.
+struct Timeout(Duration);
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Request timed out after {}s", self.0.as_secs())
+    }
+}
+
 #[derive(Serialize)]
 pub struct ChatResponse {
     pub response: String,
@@ -109,6 +229,21 @@ pub struct RagSearchResponse {
     pub count: usize,
 }
 
+#[derive(Serialize)]
+pub struct SessionSummaryResponse {
+    pub session: String,
+    pub summary: String,
+    /// Messages the summary covers.
+    pub messages: usize,
+    /// Older messages were left out of the summary.
+    pub truncated: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ActionDecision {
+    pub approve: bool,
+}
+
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub error: String,
@@ -116,11 +251,53 @@ pub struct ErrorResponse {
 
 // ─── Handlers ──────────────────────────────────────────────
 
-async fn health() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "ok",
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-    }))
+#[derive(Deserialize)]
+pub struct HealthQuery {
+    /// Also ping the provider, Ollama and the memory store.
+    #[serde(default)]
+    pub deep: bool,
+}
+
+/// Liveness check; `?deep=true` runs the readiness checks of `zenclaw doctor`
+/// and answers 503 if any of them fails.
+async fn health(
+    State(state): State<SharedState>,
+    Query(query): Query<HealthQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !query.deep {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "ok",
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+            })),
+        );
+    }
+
+    let (agent, provider, memory) = {
+        let s = state.lock().await;
+        (s.agent.clone(), s.provider.clone(), s.memory.clone())
+    };
+    let mut report = doctor::Report::default();
+    report.push(doctor::check_provider(provider.as_ref(), agent.config.model.as_deref()).await);
+    report.push(doctor::check_memory(memory.as_ref()).await);
+    report.push(doctor::check_ollama(doctor::DEFAULT_OLLAMA_URL, provider.name() == "ollama").await);
+    report.push(doctor::check_api_keys(provider.name()));
+
+    let status = report.status();
+    let code = if status == doctor::CheckStatus::Fail {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (
+        code,
+        Json(serde_json::json!({
+            "status": status,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "checks": report.checks,
+        })),
+    )
 }
 
 async fn status(State(state): State<SharedState>) -> Json<StatusResponse> {
@@ -138,17 +315,56 @@ async fn chat(
     State(state): State<SharedState>,
     Json(req): Json<ChatRequest>,
 ) -> Result<Json<ChatResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let s = state.lock().await;
-
-    match s
-        .agent
-        .process_with_media(s.provider.as_ref(), s.memory.as_ref(), &req.message, req.media.clone(), &req.session, None)
-        .await
-    {
-        Ok(response) => Ok(Json(ChatResponse {
+    let (agent, provider, memory) = {
+        let s = state.lock().await;
+        (s.agent.clone(), s.provider.clone(), s.memory.clone())
+    };
+    let provider = req.overrides.apply(&agent, provider)?;
+
+    let turn = agent.process_with_media(provider.as_ref(), memory.as_ref(), &req.message, req.media.clone(), &req.session, None);
+    match with_timeout(req.overrides.timeout(), turn).await {
+        Ok(Ok(response)) => Ok(Json(ChatResponse {
             response,
             session: req.session,
         })),
+        Ok(Err(e)) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )),
+        Err(timeout) => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse {
+                error: timeout.to_string(),
+            }),
+        )),
+    }
+}
+
+/// Summarize a session's history on demand, without changing it.
+async fn session_summary(
+    State(state): State<SharedState>,
+    Path(session): Path<String>,
+) -> Result<Json<SessionSummaryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (agent, provider, memory) = {
+        let s = state.lock().await;
+        (s.agent.clone(), s.provider.clone(), s.memory.clone())
+    };
+
+    match agent.session_summary(provider.as_ref(), memory.as_ref(), &session).await {
+        Ok(Some(summary)) => Ok(Json(SessionSummaryResponse {
+            session,
+            summary: summary.text,
+            messages: summary.messages,
+            truncated: summary.truncated,
+        })),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No history for session '{}'", session),
+            }),
+        )),
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -158,62 +374,166 @@ async fn chat(
     }
 }
 
+async fn list_actions(State(state): State<SharedState>) -> Json<Vec<PendingAction>> {
+    Json(state.lock().await.actions.list())
+}
+
+/// Approve or deny a pending tool call.
+async fn decide_action(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    Json(req): Json<ActionDecision>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let actions = state.lock().await.actions.clone();
+    if actions.decide(&id, req.approve) {
+        Ok(Json(serde_json::json!({ "id": id, "approved": req.approve })))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No pending action '{}'", id),
+            }),
+        ))
+    }
+}
+
+/// Stream a chat turn as server-sent events.
+///
+/// Events, in order:
+/// - `token` — answer text as the model generates it (one event with the
+///   whole answer if the provider can't stream, or the message has media)
+/// - `tool` — `{"tool", "status": "start", "args"}` when a tool is called and
+///   `{"tool", "status": "done", "result_len"}` when it returns
+/// - `confirm_required` — a [`PendingAction`] the turn is waiting on; answer
+///   it with `POST /v1/actions/{id}`
+/// - `done` — `{"session", "usage": {...}, "elapsed_ms", "iterations"}`, or
+///   `error` with the message if the turn failed or timed out
+///
+/// Out-of-range overrides are rejected with a 400 before the stream starts.
 async fn chat_stream(
     State(state): State<SharedState>,
     Json(req): Json<ChatRequest>,
-) -> Sse<ReceiverStream<Result<Event, Infallible>>> {
-    let (tx, rx) = mpsc::channel(128);
-
-    let bus = EventBus::new(32);
-    let mut bus_rx = bus.subscribe_system();
-
-    // The state and trait objects need to be cloned/Arc'd to be spawned, 
-    // but we can't easily move s.agent and s.provider. 
-    // So let's clone the Agent and use a specialized spawn.
-    // However, they aren't clonable easily. 
-    // Wait, the agent.process requires &self, provider, memory.
-    // Instead of spawning agent, we can run agent in another tokio task but we need Arc<Mutex<...>>.
-    let shared_state = state.clone();
-    let message = req.message.clone();
-    let session = req.session.clone();
-    let media = req.media.clone();
-
-    // Background task listening to EventBus
-    let tx_clone = tx.clone();
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ErrorResponse>)> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    // Don't hold the state lock for the whole turn
+    let (agent, provider, memory, mut pending) = {
+        let s = state.lock().await;
+        (s.agent.clone(), s.provider.clone(), s.memory.clone(), s.actions.subscribe())
+    };
+    let provider = req.overrides.apply(&agent, provider)?;
+
     tokio::spawn(async move {
-        while let Ok(event) = bus_rx.recv().await {
-            // Raw JSON payloads
-            let data_str = serde_json::to_string(&event.data).unwrap_or_default();
-            let sse_event = Event::default().event(event.event_type.clone()).data(data_str);
-            if tx_clone.send(Ok(sse_event)).await.is_err() {
-                break;
+        let bus = EventBus::new(64);
+        let mut events = bus.subscribe_system();
+        let mut done: Option<serde_json::Value> = None;
+        let streamed = req.media.is_empty();
+
+        let token_tx = tx.clone();
+        let mut on_delta = move |text: &str| {
+            let _ = token_tx.send(Ok(Event::default().event("token").data(text)));
+        };
+        let turn = with_timeout(req.overrides.timeout(), async {
+            if streamed {
+                agent
+                    .process_streaming(provider.as_ref(), memory.as_ref(), &req.message, &req.session, Some(&bus), &mut on_delta)
+                    .await
+            } else {
+                agent
+                    .process_with_media(provider.as_ref(), memory.as_ref(), &req.message, req.media.clone(), &req.session, Some(&bus))
+                    .await
             }
-            
-            // Human readable status stream
-            if let Some(msg) = event.format_status() {
-                let status_event = Event::default().event("status_text").data(msg);
-                let _ = tx_clone.send(Ok(status_event)).await;
+        });
+        tokio::pin!(turn);
+
+        let result = loop {
+            tokio::select! {
+                result = &mut turn => break result,
+                Ok(event) = events.recv() => forward_event(&tx, event, &mut done),
+                Ok(action) = pending.recv() => {
+                    if action.session == req.session
+                        && let Ok(data) = serde_json::to_string(&action)
+                    {
+                        let _ = tx.send(Ok(Event::default().event("confirm_required").data(data)));
+                    }
+                }
             }
+        };
+        while let Ok(event) = events.try_recv() {
+            forward_event(&tx, event, &mut done);
         }
-    });
-
-    let tx_for_agent = tx.clone();
-    tokio::spawn(async move {
-        let s2 = shared_state.lock().await;
-        let p = s2.provider.as_ref();
-        let m = s2.memory.as_ref();
 
-        match s2.agent.process_with_media(p, m, &message, media, &session, Some(&bus)).await {
-            Ok(response) => {
-                let _ = tx_for_agent.send(Ok(Event::default().event("result").data(response))).await;
+        match result {
+            Ok(Ok(answer)) => {
+                if !streamed {
+                    let _ = tx.send(Ok(Event::default().event("token").data(answer)));
+                }
+                let data = done.unwrap_or_default();
+                let summary = serde_json::json!({
+                    "session": req.session,
+                    "usage": {
+                        "prompt_tokens": data["prompt_tokens"],
+                        "completion_tokens": data["completion_tokens"],
+                        "total_tokens": data["total_tokens"],
+                        "estimated": data["estimated"],
+                    },
+                    "elapsed_ms": data["elapsed_ms"],
+                    "iterations": data["iterations"],
+                });
+                let _ = tx.send(Ok(Event::default().event("done").data(summary.to_string())));
+            }
+            Ok(Err(e)) => {
+                let _ = tx.send(Ok(Event::default().event("error").data(e.to_string())));
             }
-            Err(e) => {
-                let _ = tx_for_agent.send(Ok(Event::default().event("error").data(e.to_string()))).await;
+            Err(timeout) => {
+                let _ = tx.send(Ok(Event::default().event("error").data(timeout.to_string())));
             }
         }
     });
 
-    Sse::new(ReceiverStream::new(rx))
+    Ok(Sse::new(UnboundedReceiverStream::new(rx)).keep_alive(KeepAlive::default()))
+}
+
+/// Turn an agent event into a `tool` SSE event; keeps `agent_done` data for the `done` event.
+fn forward_event(
+    tx: &mpsc::UnboundedSender<Result<Event, Infallible>>,
+    event: SystemEvent,
+    done: &mut Option<serde_json::Value>,
+) {
+    let data = match event.event_type.as_str() {
+        "tool_use" => serde_json::json!({
+            "tool": event.data["tool"],
+            "status": "start",
+            "args": event.data["args"],
+        }),
+        "tool_result" => serde_json::json!({
+            "tool": event.data["tool"],
+            "status": "done",
+            "result_len": event.data["result_len"],
+        }),
+        "agent_done" => {
+            *done = Some(event.data);
+            return;
+        }
+        _ => return,
+    };
+    let _ = tx.send(Ok(Event::default().event("tool").data(data.to_string())));
+}
+
+/// Prometheus scrape endpoint (text exposition format).
+///
+/// Metrics, all updated once per finished agent turn:
+/// - `zenclaw_requests_total{status="success|error"}` — agent turns
+/// - `zenclaw_tool_invocations_total{tool}` — tool calls, by tool name
+/// - `zenclaw_provider_errors_total` — LLM provider calls that failed after retries
+/// - `zenclaw_request_duration_seconds` — histogram of time per turn
+/// - `zenclaw_response_tokens` — histogram of completion tokens per turn
+async fn metrics(State(state): State<SharedState>) -> impl IntoResponse {
+    let prometheus = state.lock().await.prometheus.clone();
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        prometheus.render(),
+    )
 }
 
 async fn rag_index(
@@ -285,28 +605,115 @@ async fn rag_search(
 
 // ─── Server builder ────────────────────────────────────────
 
-/// Build the API router.
-pub fn build_router(state: SharedState) -> Router {
-    Router::new()
-        .route("/v1/health", get(health))
+/// Build the API router. Everything but the health check requires
+/// `api_key` when one is set (see [`crate::middleware::auth_middleware`])
+/// and counts against the caller's rate limit.
+pub fn build_router(
+    state: SharedState,
+    api_key: Option<&str>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> Router {
+    let protected = Router::new()
         .route("/v1/status", get(status))
         .route("/v1/chat", post(chat))
         .route("/v1/chat/stream", post(chat_stream))
+        .route("/v1/sessions/{key}/summary", get(session_summary))
+        .route("/v1/actions", get(list_actions))
+        .route("/v1/actions/{id}", post(decide_action))
+        .route("/v1/chat/completions", post(openai::chat_completions))
+        .route("/v1/models", get(openai::list_models))
         .route("/v1/rag/index", post(rag_index))
         .route("/v1/rag/search", post(rag_search))
+        .route("/metrics", get(metrics));
+    let protected = crate::middleware::rate_limit(protected, rate_limiter, api_key);
+
+    Router::new()
+        .route("/health", get(health))
+        .route("/v1/health", get(health))
+        .merge(crate::middleware::require_api_key(protected, api_key))
         .with_state(state)
 }
 
 /// Start the API server.
 pub async fn start_server_from_state(state: ApiState, host: &str, port: u16) -> anyhow::Result<()> {
+    let metrics = state.metrics.clone();
+    let api_key = state.api_key.clone().filter(|k| !k.is_empty());
+    if api_key.is_none() {
+        warn!(
+            "⚠️  No API key configured — anyone who can reach {}:{} can use the agent. \
+             Set ZENCLAW_API_KEY or `zenclaw config set server_api_key <key>`.",
+            host, port
+        );
+    }
+    let rate_limiter = state.rate_limiter.clone();
     let shared = Arc::new(Mutex::new(state));
-    let app = build_router(shared);
+    let app = build_router(shared, api_key.as_deref(), rate_limiter).layer(axum::middleware::from_fn_with_state(
+        metrics,
+        crate::middleware::metrics_middleware,
+    ));
 
     let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
     info!("🌐 API server listening on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // Peer addresses identify clients without an API key for rate limiting
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_in_range_are_accepted() {
+        let req: ChatRequest = serde_json::from_value(serde_json::json!({
+            "message": "Hi",
+            "max_tokens": 256,
+            "temperature": 0.2,
+            "timeout_seconds": 30,
+        }))
+        .unwrap();
+        assert!(req.overrides.validate(8192).is_ok());
+        assert_eq!(req.overrides.timeout(), Some(Duration::from_secs(30)));
+
+        let tuned = Tuned {
+            inner: Arc::new(crate::providers::OpenAiProvider::ollama("llama3.2")),
+            temperature: req.overrides.temperature,
+            max_tokens: req.overrides.max_tokens,
+        };
+        let request = tuned.tune(zenclaw_core::provider::ChatRequest::default());
+        assert_eq!((request.max_tokens, request.temperature), (256, 0.2));
+
+        let plain: ChatRequest = serde_json::from_value(serde_json::json!({ "message": "Hi" })).unwrap();
+        assert!(plain.overrides.validate(8192).is_ok());
+        assert_eq!(plain.overrides.timeout(), None);
+    }
+
+    #[test]
+    fn test_overrides_out_of_range_are_rejected() {
+        let check = |overrides: ChatOverrides| overrides.validate(8192);
+        assert!(check(ChatOverrides { max_tokens: Some(0), ..Default::default() }).is_err());
+        assert!(check(ChatOverrides { max_tokens: Some(8193), ..Default::default() }).is_err());
+        assert!(check(ChatOverrides { max_tokens: Some(8192), ..Default::default() }).is_ok());
+        assert!(check(ChatOverrides { temperature: Some(-0.1), ..Default::default() }).is_err());
+        assert!(check(ChatOverrides { temperature: Some(2.5), ..Default::default() }).is_err());
+        assert!(check(ChatOverrides { timeout_seconds: Some(0), ..Default::default() }).is_err());
+        assert!(check(ChatOverrides { timeout_seconds: Some(MAX_TIMEOUT_SECS + 1), ..Default::default() }).is_err());
+        assert!(check(ChatOverrides { timeout_seconds: Some(MAX_TIMEOUT_SECS), ..Default::default() }).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_turn_past_its_timeout_fails() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok("late")
+        };
+        let result = with_timeout(Some(Duration::from_millis(10)), slow).await;
+        assert_eq!(result.err().map(|t| t.0), Some(Duration::from_millis(10)));
+
+        let result = with_timeout(None, async { Ok("now") }).await;
+        assert_eq!(result.ok().and_then(|r| r.ok()), Some("now"));
+    }
+}
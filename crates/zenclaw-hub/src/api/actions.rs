@@ -0,0 +1,170 @@
+//! Pending actions — tool calls waiting for a REST client's confirmation.
+//!
+//! With `agent.confirm_destructive` on, [`PendingActions`] is the agent's
+//! approver under `zenclaw serve`: every destructive call gets an id, is
+//! listed by `GET /v1/actions` (and announced to `/v1/chat/stream` clients
+//! of the same session as a `confirm_required` event), and runs only once
+//! `POST /v1/actions/{id}` answers `{"approve": true}`. Calls nobody answers
+//! within [`CONFIRM_TIMEOUT`] are denied.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::{broadcast, oneshot};
+
+use zenclaw_core::approval::{ApprovalDecision, ToolApprover};
+
+/// How long a call waits for its confirmation before it is denied.
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A tool call waiting for confirmation.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingAction {
+    pub id: String,
+    pub session: String,
+    pub tool: String,
+    /// Arguments as sent by the model (a string if they weren't valid JSON).
+    pub args: serde_json::Value,
+    /// What the call will change (e.g. an edit's unified diff), when the
+    /// tool can tell.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+    /// When the call was paused (RFC 3339, UTC).
+    pub created: String,
+}
+
+struct Waiting {
+    action: PendingAction,
+    reply: oneshot::Sender<ApprovalDecision>,
+}
+
+/// Tool approver answered over the REST API.
+pub struct PendingActions {
+    waiting: Mutex<HashMap<String, Waiting>>,
+    announce: broadcast::Sender<PendingAction>,
+    timeout: Duration,
+}
+
+impl Default for PendingActions {
+    fn default() -> Self {
+        Self::new(CONFIRM_TIMEOUT)
+    }
+}
+
+impl PendingActions {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            waiting: Mutex::new(HashMap::new()),
+            announce: broadcast::channel(64).0,
+            timeout,
+        }
+    }
+
+    /// Actions waiting for an answer, oldest first.
+    pub fn list(&self) -> Vec<PendingAction> {
+        let mut actions: Vec<PendingAction> = self
+            .waiting
+            .lock()
+            .map(|w| w.values().map(|w| w.action.clone()).collect())
+            .unwrap_or_default();
+        actions.sort_by(|a, b| a.created.cmp(&b.created));
+        actions
+    }
+
+    /// Answer action `id`. Returns false if it isn't pending (unknown,
+    /// already answered or timed out).
+    pub fn decide(&self, id: &str, approve: bool) -> bool {
+        let Some(waiting) = self.waiting.lock().ok().and_then(|mut w| w.remove(id)) else {
+            return false;
+        };
+        let decision = if approve {
+            ApprovalDecision::Allow
+        } else {
+            ApprovalDecision::Deny
+        };
+        waiting.reply.send(decision).is_ok()
+    }
+
+    /// New pending actions, as they are created.
+    pub fn subscribe(&self) -> broadcast::Receiver<PendingAction> {
+        self.announce.subscribe()
+    }
+
+    /// Queue a call and wait for its answer (or the timeout).
+    async fn wait(&self, session_key: &str, tool: &str, args: &str, preview: Option<&str>) -> ApprovalDecision {
+        let action = PendingAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            session: session_key.to_string(),
+            tool: tool.to_string(),
+            args: serde_json::from_str(args).unwrap_or_else(|_| serde_json::json!(args)),
+            preview: preview.map(str::to_string),
+            created: chrono::Utc::now().to_rfc3339(),
+        };
+        let id = action.id.clone();
+        let (reply, answer) = oneshot::channel();
+        // Scoped so the guard isn't held across the await below
+        {
+            let Ok(mut waiting) = self.waiting.lock() else {
+                return ApprovalDecision::Deny;
+            };
+            waiting.insert(id.clone(), Waiting { action: action.clone(), reply });
+        }
+        let _ = self.announce.send(action);
+
+        match tokio::time::timeout(self.timeout, answer).await {
+            Ok(Ok(decision)) => decision,
+            _ => {
+                if let Ok(mut w) = self.waiting.lock() {
+                    w.remove(&id);
+                }
+                ApprovalDecision::Deny
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ToolApprover for PendingActions {
+    async fn approve(&self, session_key: &str, tool: &str, args: &str) -> ApprovalDecision {
+        self.wait(session_key, tool, args, None).await
+    }
+
+    async fn approve_with_preview(&self, session_key: &str, tool: &str, args: &str, preview: &str) -> ApprovalDecision {
+        self.wait(session_key, tool, args, Some(preview)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_decide_answers_the_waiting_call() {
+        let actions = Arc::new(PendingActions::default());
+        let mut announced = actions.subscribe();
+
+        let approver = actions.clone();
+        let call = tokio::spawn(async move { approver.approve("api", "exec", r#"{"command":"ls"}"#).await });
+
+        let action = announced.recv().await.unwrap();
+        assert_eq!((action.session.as_str(), action.tool.as_str()), ("api", "exec"));
+        assert_eq!(action.args, serde_json::json!({ "command": "ls" }));
+        assert_eq!(actions.list().len(), 1);
+
+        assert!(actions.decide(&action.id, true));
+        assert_eq!(call.await.unwrap(), ApprovalDecision::Allow);
+        assert!(actions.list().is_empty());
+        assert!(!actions.decide(&action.id, true));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_call_is_denied() {
+        let actions = PendingActions::new(Duration::from_millis(20));
+        assert_eq!(actions.approve("api", "write_file", "{}").await, ApprovalDecision::Deny);
+        assert!(actions.list().is_empty());
+    }
+}
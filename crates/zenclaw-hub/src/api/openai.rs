@@ -0,0 +1,394 @@
+//! OpenAI-compatible endpoints, so OpenAI SDK clients can use ZenClaw as a
+//! drop-in, tool-augmented backend.
+//!
+//! - POST /v1/chat/completions — `messages`, `model`, `temperature`,
+//!   `max_tokens` and `stream` (SSE `chat.completion.chunk`s ending with
+//!   `data: [DONE]`)
+//! - GET  /v1/models — the configured model
+//!
+//! Each request is a fresh conversation: the client's messages become the
+//! history (up to `agent.history_limit`) and the last user message is the
+//! turn. The agent's own tools run server-side; client-defined `tools` and
+//! `tool` messages are ignored. `model` is echoed back but the configured
+//! model answers.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    response::{IntoResponse, Json, Response},
+};
+use core::convert::Infallible;
+use serde::Deserialize;
+use tokio::sync::{broadcast::error::TryRecvError, mpsc};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use zenclaw_core::bus::EventBus;
+use zenclaw_core::error::Result;
+use zenclaw_core::memory::{InMemoryStore, MemoryStore};
+use zenclaw_core::message::{ChatMessage, LlmResponse};
+use zenclaw_core::provider::{ChatRequest, LlmProvider};
+
+use super::SharedState;
+
+/// Session key of the throwaway conversation built for each request.
+const SESSION: &str = "openai";
+
+#[derive(Deserialize)]
+pub struct CompletionRequest {
+    pub messages: Vec<CompletionMessage>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default, alias = "max_completion_tokens")]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub stream_options: Option<StreamOptions>,
+}
+
+#[derive(Deserialize)]
+pub struct StreamOptions {
+    #[serde(default)]
+    pub include_usage: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CompletionMessage {
+    pub role: String,
+    /// A string, or an array of `text` / `image_url` parts.
+    #[serde(default)]
+    pub content: serde_json::Value,
+}
+
+impl CompletionMessage {
+    /// Text of the message, and image URLs from `image_url` parts.
+    fn text_and_media(&self) -> (String, Vec<String>) {
+        match &self.content {
+            serde_json::Value::String(text) => (text.clone(), Vec::new()),
+            serde_json::Value::Array(parts) => {
+                let mut text = Vec::new();
+                let mut media = Vec::new();
+                for part in parts {
+                    match part["type"].as_str() {
+                        Some("text") => text.extend(part["text"].as_str().map(str::to_string)),
+                        Some("image_url") => media.extend(part["image_url"]["url"].as_str().map(str::to_string)),
+                        _ => {}
+                    }
+                }
+                (text.join("\n"), media)
+            }
+            _ => (String::new(), Vec::new()),
+        }
+    }
+}
+
+/// An error in OpenAI's `{"error": {...}}` shape.
+pub(super) struct ApiError {
+    status: StatusCode,
+    message: String,
+    kind: &'static str,
+}
+
+impl ApiError {
+    fn invalid(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+            kind: "invalid_request_error",
+        }
+    }
+
+    fn server(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: message.into(),
+            kind: "server_error",
+        }
+    }
+
+    fn body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": { "message": self.message, "type": self.kind, "param": null, "code": null }
+        })
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
+
+/// Split the client's messages into history and the turn's user message.
+fn split_messages(messages: &[CompletionMessage]) -> std::result::Result<(Vec<ChatMessage>, String, Vec<String>), ApiError> {
+    let (last, earlier) = messages
+        .split_last()
+        .ok_or_else(|| ApiError::invalid("`messages` must not be empty"))?;
+    if last.role != "user" {
+        return Err(ApiError::invalid("the last message must have role `user`"));
+    }
+
+    let history = earlier
+        .iter()
+        .filter_map(|m| {
+            let (text, media) = m.text_and_media();
+            match m.role.as_str() {
+                "system" | "developer" => Some(ChatMessage::system(&text)),
+                "user" => Some(ChatMessage::user_with_media(&text, media)),
+                "assistant" if !text.is_empty() => Some(ChatMessage::assistant(&text)),
+                _ => None,
+            }
+        })
+        .collect();
+    let (text, media) = last.text_and_media();
+    Ok((history, text, media))
+}
+
+/// Applies the request's `temperature` / `max_tokens` to every LLM call.
+pub(super) struct Tuned {
+    pub(super) inner: Arc<dyn LlmProvider>,
+    pub(super) temperature: Option<f32>,
+    pub(super) max_tokens: Option<u32>,
+}
+
+impl Tuned {
+    pub(super) fn tune(&self, mut request: ChatRequest) -> ChatRequest {
+        if let Some(t) = self.temperature {
+            request.temperature = t;
+        }
+        if let Some(max) = self.max_tokens {
+            request.max_tokens = max;
+        }
+        request
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Tuned {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+        self.inner.chat(self.tune(request)).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<LlmResponse> {
+        self.inner.chat_stream(self.tune(request), on_text).await
+    }
+
+    fn count_tokens(&self, messages: &[ChatMessage]) -> usize {
+        self.inner.count_tokens(messages)
+    }
+
+    fn context_window(&self, model: &str) -> usize {
+        self.inner.context_window(model)
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.inner.supports_vision(model)
+    }
+}
+
+fn completion_id() -> String {
+    format!("chatcmpl-{}", uuid::Uuid::new_v4().simple())
+}
+
+/// `usage` block from an `agent_done` event's data.
+fn usage_json(done: &serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "prompt_tokens": done["prompt_tokens"].as_u64().unwrap_or(0),
+        "completion_tokens": done["completion_tokens"].as_u64().unwrap_or(0),
+        "total_tokens": done["total_tokens"].as_u64().unwrap_or(0),
+    })
+}
+
+pub(super) async fn chat_completions(
+    State(state): State<SharedState>,
+    Json(req): Json<CompletionRequest>,
+) -> std::result::Result<Response, ApiError> {
+    let (history, message, media) = split_messages(&req.messages)?;
+
+    let (agent, provider) = {
+        let s = state.lock().await;
+        (s.agent.clone(), s.provider.clone())
+    };
+    let model = req
+        .model
+        .clone()
+        .or_else(|| agent.config.model.clone())
+        .unwrap_or_else(|| provider.default_model().to_string());
+    let provider = Tuned {
+        inner: provider,
+        temperature: req.temperature,
+        max_tokens: req.max_tokens,
+    };
+
+    let memory = InMemoryStore::new();
+    memory
+        .replace_history(SESSION, &history)
+        .await
+        .map_err(|e| ApiError::server(e.to_string()))?;
+
+    let id = completion_id();
+    let created = chrono::Utc::now().timestamp();
+
+    if !req.stream {
+        let bus = EventBus::new(256);
+        let mut events = bus.subscribe_system();
+        let answer = agent
+            .process_with_media(&provider, &memory, &message, media, SESSION, Some(&bus))
+            .await
+            .map_err(|e| ApiError::server(e.to_string()))?;
+        let done = last_done(&mut events);
+
+        return Ok(Json(serde_json::json!({
+            "id": id,
+            "object": "chat.completion",
+            "created": created,
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": answer },
+                "finish_reason": "stop",
+            }],
+            "usage": usage_json(&done),
+        }))
+        .into_response());
+    }
+
+    let include_usage = req.stream_options.is_some_and(|o| o.include_usage);
+    let (tx, rx) = mpsc::unbounded_channel::<std::result::Result<Event, Infallible>>();
+    let chunk = move |delta: serde_json::Value, finish: Option<&str>| {
+        serde_json::json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish }],
+        })
+    };
+
+    tokio::spawn(async move {
+        let send = |value: serde_json::Value| {
+            let _ = tx.send(Ok(Event::default().data(value.to_string())));
+        };
+        send(chunk(serde_json::json!({ "role": "assistant", "content": "" }), None));
+
+        let bus = EventBus::new(256);
+        let mut events = bus.subscribe_system();
+        let streamed = media.is_empty();
+        let result = if streamed {
+            let mut on_delta = |text: &str| send(chunk(serde_json::json!({ "content": text }), None));
+            agent
+                .process_streaming(&provider, &memory, &message, SESSION, Some(&bus), &mut on_delta)
+                .await
+        } else {
+            agent
+                .process_with_media(&provider, &memory, &message, media, SESSION, Some(&bus))
+                .await
+        };
+
+        match result {
+            Ok(answer) => {
+                if !streamed {
+                    send(chunk(serde_json::json!({ "content": answer }), None));
+                }
+                send(chunk(serde_json::json!({}), Some("stop")));
+                if include_usage {
+                    let mut last = chunk(serde_json::json!({}), None);
+                    last["choices"] = serde_json::json!([]);
+                    last["usage"] = usage_json(&last_done(&mut events));
+                    send(last);
+                }
+            }
+            Err(e) => send(ApiError::server(e.to_string()).body()),
+        }
+        let _ = tx.send(Ok(Event::default().data("[DONE]")));
+    });
+
+    Ok(Sse::new(UnboundedReceiverStream::new(rx))
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+/// Data of the last `agent_done` event received so far (or `null`).
+fn last_done(events: &mut tokio::sync::broadcast::Receiver<zenclaw_core::bus::SystemEvent>) -> serde_json::Value {
+    let mut done = serde_json::Value::Null;
+    loop {
+        match events.try_recv() {
+            Ok(event) if event.event_type == "agent_done" => done = event.data,
+            Ok(_) | Err(TryRecvError::Lagged(_)) => continue,
+            Err(_) => break,
+        }
+    }
+    done
+}
+
+pub(super) async fn list_models(State(state): State<SharedState>) -> Json<serde_json::Value> {
+    let s = state.lock().await;
+    let model = s
+        .agent
+        .config
+        .model
+        .clone()
+        .unwrap_or_else(|| s.provider.default_model().to_string());
+    Json(serde_json::json!({
+        "object": "list",
+        "data": [{ "id": model, "object": "model", "created": 0, "owned_by": "zenclaw" }],
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::message::Role;
+
+    fn msg(role: &str, content: serde_json::Value) -> CompletionMessage {
+        CompletionMessage {
+            role: role.into(),
+            content,
+        }
+    }
+
+    #[test]
+    fn test_split_messages() {
+        let messages = vec![
+            msg("system", "Be brief.".into()),
+            msg("user", "Hi".into()),
+            msg("assistant", "Hello!".into()),
+            msg("tool", "ignored".into()),
+            msg(
+                "user",
+                serde_json::json!([
+                    { "type": "text", "text": "What is this?" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/cat.png" } },
+                ]),
+            ),
+        ];
+        let (history, text, media) = split_messages(&messages).ok().unwrap();
+        assert_eq!(
+            history.iter().map(|m| m.role.clone()).collect::<Vec<_>>(),
+            vec![Role::System, Role::User, Role::Assistant]
+        );
+        assert_eq!(text, "What is this?");
+        assert_eq!(media, vec!["https://example.com/cat.png"]);
+
+        assert!(split_messages(&[]).is_err());
+        assert!(split_messages(&[msg("assistant", "Hi".into())]).is_err());
+    }
+}
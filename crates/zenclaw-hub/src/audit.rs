@@ -0,0 +1,348 @@
+//! Tool-call audit log — an append-only JSONL record of what the agent did.
+//!
+//! Subscribes to the agent's long-lived [`EventBus`] and pairs each
+//! `tool_use` event with its `tool_result`, so tool code stays untouched.
+//! Every call becomes one line in `tools.jsonl.<YYYY-MM-DD>` (UTC, a new
+//! file each day like the tracing logs) with the session, tool, arguments,
+//! result size, duration and outcome.
+//!
+//! Arguments are cleaned before they hit the disk: values of secret
+//! variables (per the [`EnvPolicy`]) and HTTP header values are redacted,
+//! file contents and request bodies are logged as their length only, and
+//! other long strings are cut short.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use zenclaw_core::bus::{EventBus, SystemEvent};
+
+use crate::tools::env::{EnvPolicy, REDACTED};
+
+/// File name prefix of the daily audit files.
+const FILE_PREFIX: &str = "tools.jsonl";
+/// Arguments carrying file contents or request bodies: only their length is logged.
+const PAYLOAD_FIELDS: &[&str] = &["content", "old_text", "new_text", "body"];
+/// Other string arguments are cut to this many characters.
+const MAX_ARG_CHARS: usize = 200;
+
+/// One tool call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the call started (RFC 3339, UTC).
+    pub timestamp: String,
+    pub session: String,
+    pub tool: String,
+    /// Arguments as sent by the model (a string if they weren't valid JSON),
+    /// with secrets and payloads redacted.
+    pub args: serde_json::Value,
+    pub result_len: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// First line of the error, when the call failed, was denied or timed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Writes an [`AuditEntry`] for every tool call published on a bus.
+pub struct ToolAudit {
+    dir: PathBuf,
+    /// Decides which `env` values are secret.
+    policy: EnvPolicy,
+    /// Calls started but not finished yet, per session, in order.
+    pending: HashMap<String, VecDeque<(String, serde_json::Value, String)>>,
+}
+
+impl ToolAudit {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            policy: EnvPolicy::default(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Redact the variables `policy` denies (the default policy otherwise).
+    pub fn with_policy(mut self, policy: EnvPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Start recording tool calls published on `bus` — runs in background,
+    /// returns immediately.
+    pub fn start(mut self, bus: &EventBus) -> tokio::task::JoinHandle<()> {
+        let mut rx = bus.subscribe_system();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        if let Some(entry) = self.observe(&event)
+                            && let Err(e) = append(&self.dir, &entry)
+                        {
+                            warn!("Failed to write the tool audit log: {}", e);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Tool audit log lagged, {} event(s) skipped", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Track `event`; returns the finished call when it is a `tool_result`.
+    fn observe(&mut self, event: &SystemEvent) -> Option<AuditEntry> {
+        let data = &event.data;
+        let tool = data["tool"].as_str().unwrap_or_default();
+        match event.event_type.as_str() {
+            "tool_use" => {
+                let raw = data["args"].as_str().unwrap_or_default();
+                let args = serde_json::from_str(raw).unwrap_or_else(|_| serde_json::json!(raw));
+                let args = redact(&self.policy, args);
+                self.pending
+                    .entry(event.run_id.clone())
+                    .or_default()
+                    .push_back((tool.to_string(), args, chrono::Utc::now().to_rfc3339()));
+                None
+            }
+            "tool_result" => {
+                let started = self.pending.get_mut(&event.run_id).and_then(|calls| {
+                    let index = calls.iter().position(|(name, _, _)| name == tool)?;
+                    calls.remove(index)
+                });
+                let (args, timestamp) = match started {
+                    Some((_, args, timestamp)) => (args, timestamp),
+                    None => (serde_json::Value::Null, chrono::Utc::now().to_rfc3339()),
+                };
+                Some(AuditEntry {
+                    timestamp,
+                    session: event.run_id.clone(),
+                    tool: tool.to_string(),
+                    args,
+                    result_len: data["result_len"].as_u64().unwrap_or(0),
+                    duration_ms: data["elapsed_ms"].as_u64().unwrap_or(0),
+                    success: data["success"].as_bool().unwrap_or(true),
+                    error: data["error"].as_str().map(str::to_string),
+                })
+            }
+            "agent_done" => {
+                // Calls of a cancelled turn never report a result
+                self.pending.remove(&event.run_id);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `args` with secret `env` values and header values redacted, payloads
+/// replaced by their length and long strings cut short.
+fn redact(policy: &EnvPolicy, args: Value) -> Value {
+    match args {
+        Value::Object(mut map) => {
+            // `env set`: the value of a denied variable
+            let secret = map
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| !policy.is_readable(name));
+            for (key, value) in map.iter_mut() {
+                *value = match (key.as_str(), value.take()) {
+                    ("value", Value::String(_)) if secret => json!(REDACTED),
+                    ("headers", Value::Object(headers)) => {
+                        Value::Object(headers.into_iter().map(|(name, _)| (name, json!(REDACTED))).collect())
+                    }
+                    (key, Value::String(s)) if PAYLOAD_FIELDS.contains(&key) => {
+                        json!(format!("<{} chars>", s.chars().count()))
+                    }
+                    (_, value) => redact(policy, value),
+                };
+            }
+            Value::Object(map)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(|v| redact(policy, v)).collect()),
+        Value::String(s) if s.chars().count() > MAX_ARG_CHARS => {
+            let kept: String = s.chars().take(MAX_ARG_CHARS).collect();
+            json!(format!("{}… ({} chars)", kept, s.chars().count()))
+        }
+        other => other,
+    }
+}
+
+/// Today's audit file in `dir`.
+pub fn current_file(dir: &Path) -> PathBuf {
+    dir.join(format!("{}.{}", FILE_PREFIX, chrono::Utc::now().format("%Y-%m-%d")))
+}
+
+/// All audit files in `dir`, oldest first.
+pub fn files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().starts_with(FILE_PREFIX))
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn append(dir: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(current_file(dir))?;
+    let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+    writeln!(file, "{}", line)
+}
+
+/// Filter for [`read`]: entries match when every set field matches.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub tool: Option<String>,
+    pub session: Option<String>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.tool.as_ref().is_none_or(|t| &entry.tool == t)
+            && self.session.as_ref().is_none_or(|s| &entry.session == s)
+    }
+}
+
+/// The last `limit` entries in `dir` matching `filter`, oldest first.
+/// Lines that aren't valid entries are skipped.
+pub fn read(dir: &Path, filter: &AuditFilter, limit: usize) -> Vec<AuditEntry> {
+    let mut entries = VecDeque::new();
+    for path in files(dir) {
+        let Ok(content) = std::fs::read_to_string(&path) else { continue };
+        for entry in content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+            .filter(|e| filter.matches(e))
+        {
+            if entries.len() == limit {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+    entries.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: &str, session: &str, data: serde_json::Value) -> SystemEvent {
+        SystemEvent {
+            run_id: session.into(),
+            event_type: kind.into(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_pairs_use_and_result() {
+        let mut audit = ToolAudit::new(Path::new("unused"));
+        let use_event = |tool: &str, args: &str| {
+            event("tool_use", "telegram:1", serde_json::json!({ "tool": tool, "args": args }))
+        };
+        assert!(audit.observe(&use_event("shell", r#"{"command":"ls"}"#)).is_none());
+        assert!(audit.observe(&use_event("read_file", "not json")).is_none());
+
+        let entry = audit
+            .observe(&event(
+                "tool_result",
+                "telegram:1",
+                serde_json::json!({ "tool": "read_file", "result_len": 12, "elapsed_ms": 3, "success": false, "error": "Error: no such file" }),
+            ))
+            .unwrap();
+        assert_eq!(entry.args, serde_json::json!("not json"));
+        assert_eq!(entry.session, "telegram:1");
+        assert!(!entry.success);
+        assert_eq!(entry.error.as_deref(), Some("Error: no such file"));
+
+        let entry = audit
+            .observe(&event(
+                "tool_result",
+                "telegram:1",
+                serde_json::json!({ "tool": "shell", "result_len": 40, "elapsed_ms": 25, "success": true, "error": null }),
+            ))
+            .unwrap();
+        assert_eq!(entry.args, serde_json::json!({ "command": "ls" }));
+        assert_eq!((entry.result_len, entry.duration_ms), (40, 25));
+        assert!(entry.success && entry.error.is_none());
+        assert!(audit.pending["telegram:1"].is_empty());
+    }
+
+    #[test]
+    fn test_secrets_and_payloads_are_redacted() {
+        let mut audit = ToolAudit::new(Path::new("unused"))
+            .with_policy(EnvPolicy::new(Vec::new(), vec!["MY_DB_URL".into()]));
+        let mut logged = |tool: &str, args: Value| {
+            let data = json!({ "tool": tool, "args": args.to_string() });
+            audit.observe(&event("tool_use", "cli:a", data));
+            audit
+                .observe(&event("tool_result", "cli:a", json!({ "tool": tool })))
+                .unwrap()
+                .args
+        };
+
+        let set = |name: &str| json!({ "action": "set", "name": name, "value": "sk-live-123" });
+        assert_eq!(logged("env", set("OPENAI_API_KEY"))["value"], REDACTED);
+        assert_eq!(logged("env", set("MY_DB_URL"))["value"], REDACTED);
+        assert_eq!(logged("env", set("RUST_LOG"))["value"], "sk-live-123");
+
+        let write = logged("write_file", json!({ "path": "a.env", "content": "TOKEN=hunter2" }));
+        assert_eq!(write, json!({ "path": "a.env", "content": "<13 chars>" }));
+
+        let fetch = logged("web_fetch", json!({ "url": "https://x.test", "headers": { "Authorization": "Bearer abc" } }));
+        assert_eq!(fetch["headers"]["Authorization"], REDACTED);
+        assert_eq!(fetch["url"], "https://x.test");
+
+        let long = logged("shell", json!({ "command": "x".repeat(500) }));
+        assert!(long["command"].as_str().unwrap().ends_with("… (500 chars)"));
+    }
+
+    #[test]
+    fn test_append_and_read_filtered() {
+        let dir = tempfile::tempdir().unwrap();
+        for (i, tool) in ["shell", "web_search", "shell"].iter().enumerate() {
+            let entry = AuditEntry {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                session: format!("s{}", i % 2),
+                tool: tool.to_string(),
+                args: serde_json::json!({}),
+                result_len: i as u64,
+                duration_ms: 1,
+                success: true,
+                error: None,
+            };
+            append(dir.path(), &entry).unwrap();
+        }
+        assert_eq!(files(dir.path()), vec![current_file(dir.path())]);
+
+        let shell = AuditFilter {
+            tool: Some("shell".into()),
+            ..Default::default()
+        };
+        let found = read(dir.path(), &shell, 10);
+        assert_eq!(found.iter().map(|e| e.result_len).collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(read(dir.path(), &shell, 1)[0].result_len, 2);
+
+        let session = AuditFilter {
+            session: Some("s1".into()),
+            ..Default::default()
+        };
+        assert_eq!(read(dir.path(), &session, 10)[0].tool, "web_search");
+    }
+}
@@ -1,22 +1,35 @@
-//! Discord bot channel — direct HTTP API (Gateway-less for minimal dependencies).
+//! Discord bot channel — HTTP API plus an optional Gateway connection.
 //!
-//! Uses Discord's HTTP API with Gateway websocket for receiving messages.
-//! Lightweight implementation without heavy SDK dependencies.
+//! Replies always go through the HTTP API. Incoming messages arrive either
+//! over the Gateway websocket (`use_gateway`, sees DMs and guild channels)
+//! or by polling the bot's DM channels, which needs no websocket at all.
+//! In guild channels the bot only answers when @mentioned.
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{debug, error, info, warn};
 
 use zenclaw_core::agent::Agent;
+use zenclaw_core::channel::{Channel, ReplyFormat, ReplyKind, StatusMessage};
+use zenclaw_core::config::ReconnectSettings;
 use zenclaw_core::error::{Result, ZenClawError};
 use zenclaw_core::memory::MemoryStore;
 use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::ratelimit::{too_fast_message, RateLimiter};
 use zenclaw_core::bus::EventBus;
 
+/// Gateway intents: GUILD_MESSAGES | DIRECT_MESSAGES | MESSAGE_CONTENT.
+const GATEWAY_INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+/// Used when `/gateway/bot` can't be reached.
+const DEFAULT_GATEWAY_URL: &str = "wss://gateway.discord.gg";
+
 /// Discord bot configuration.
 #[derive(Debug, Clone)]
 pub struct DiscordConfig {
@@ -24,12 +37,24 @@ pub struct DiscordConfig {
     pub bot_token: String,
     /// Allowed user IDs (empty = allow everyone).
     pub allowed_users: Vec<String>,
+    /// Prefix/suffix added to replies.
+    pub reply_format: ReplyFormat,
+    /// Onboarding message sent the first time a channel is seen.
+    pub greeting: Option<String>,
+    /// Receive messages over the Gateway websocket (DMs and guild channels)
+    /// instead of polling DM channels. Needs the Message Content intent
+    /// enabled in the Developer Portal.
+    pub use_gateway: bool,
+    /// Per-channel message limit (None = unlimited).
+    pub rate_limit: Option<Arc<RateLimiter>>,
+    /// Reconnection backoff while the Discord API is unreachable.
+    pub reconnect: ReconnectSettings,
 }
 
-/// Discord bot channel — uses HTTP API polling.
+/// Discord bot channel — Gateway websocket or DM polling.
 ///
-/// Note: This is a simplified implementation that polls for messages.
-/// For production, consider using Discord Gateway (WebSocket).
+/// Cheap to clone: the HTTP client is shared.
+#[derive(Clone)]
 pub struct DiscordChannel {
     config: DiscordConfig,
     client: Client,
@@ -52,8 +77,64 @@ impl DiscordChannel {
         }
     }
 
+    /// Get bot user info.
+    async fn get_me(&self) -> Result<DiscordUser> {
+        let url = format!("{}/users/@me", self.api_base);
+        let resp: DiscordUser = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.config.bot_token))
+            .send()
+            .await?
+            .json()
+            .await
+            .map_err(|e| ZenClawError::Provider(format!("Discord getMe failed: {}", e)))?;
+        Ok(resp)
+    }
+}
+
+#[async_trait]
+impl Channel for DiscordChannel {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn send(&self, channel_id: &str, text: &str) -> Result<String> {
+        // Discord's limit is 2000 characters
+        let mut last_id = String::new();
+        for chunk in split_message(text, 1900) {
+            last_id = send_message(&self.client, &self.api_base, &self.config.bot_token, channel_id, &chunk)
+                .await?
+                .id;
+        }
+        Ok(last_id)
+    }
+
+    async fn edit(&self, channel_id: &str, message_id: &str, text: &str) -> Result<()> {
+        edit_message(&self.client, &self.api_base, &self.config.bot_token, channel_id, message_id, text).await
+    }
+
+    async fn delete(&self, channel_id: &str, message_id: &str) -> Result<()> {
+        delete_message(&self.client, &self.api_base, &self.config.bot_token, channel_id, message_id).await
+    }
+
+    async fn typing(&self, channel_id: &str) -> Result<()> {
+        let url = format!("{}/channels/{}/typing", self.api_base, channel_id);
+        self.client
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.config.bot_token))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// Delay between edits to avoid hitting Discord API rate limits.
+    fn status_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
     /// Start the Discord bot.
-    pub async fn start<P, M>(
+    async fn start<P, M>(
         &mut self,
         agent: Arc<Agent>,
         provider: Arc<P>,
@@ -66,224 +147,379 @@ impl DiscordChannel {
         // Verify token
         let me = self.get_me().await?;
         self.bot_user_id = Some(me.id.clone());
-        info!("🎮 Discord bot started: {}#{}", me.username, me.discriminator.unwrap_or_default());
+        info!("🎮 Discord bot started: {}#{}", me.username, me.discriminator.clone().unwrap_or_default());
 
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
         self.shutdown_tx = Some(shutdown_tx);
 
-        let client = self.client.clone();
-        let api_base = self.api_base.clone();
-        let bot_token = self.config.bot_token.clone();
-        let bot_user_id = me.id;
-        let allowed_users = self.config.allowed_users.clone();
-
-        // Spawn message polling task
-        // Uses DM channels — the bot listens to direct messages
-        tokio::spawn(async move {
-            let mut last_message_ids: std::collections::HashMap<String, String> =
-                std::collections::HashMap::new();
-
-            loop {
-                if shutdown_rx.try_recv().is_ok() {
-                    info!("Discord bot shutting down...");
-                    break;
+        let model = agent.config.model.clone().unwrap_or_else(|| provider.default_model().to_string());
+        let responder = Arc::new(Responder {
+            channel: self.clone(),
+            bot_user_id: me.id,
+            bot_name: me.username,
+            allowed_users: self.config.allowed_users.clone(),
+            reply_format: self.config.reply_format.clone(),
+            greeting: self.config.greeting.clone(),
+            rate_limit: self.config.rate_limit.clone(),
+            model,
+            agent,
+            provider,
+            memory,
+        });
+
+        if self.config.use_gateway {
+            let url = match gateway_url(&self.client, &self.api_base, &self.config.bot_token).await {
+                Ok(url) => url,
+                Err(e) => {
+                    warn!("Discord /gateway/bot failed ({}), using the default URL", e);
+                    DEFAULT_GATEWAY_URL.to_string()
                 }
+            };
+
+            // Messages are handled one at a time, off the websocket task, so a
+            // long agent turn never delays the heartbeat
+            let (msg_tx, mut msg_rx) = mpsc::unbounded_channel::<DiscordMessage>();
+            let token = self.config.bot_token.clone();
+            tokio::spawn(run_gateway(url, token, self.config.reconnect, msg_tx, shutdown_rx));
+            tokio::spawn(async move {
+                while let Some(msg) = msg_rx.recv().await {
+                    let Some(channel_id) = msg.channel_id.clone() else { continue };
+                    let content = if msg.guild_id.is_some() {
+                        // Guild channels: only answer when mentioned
+                        if !responder.is_mentioned(&msg) {
+                            continue;
+                        }
+                        strip_mention(&msg.content, &responder.bot_user_id)
+                    } else {
+                        msg.content.clone()
+                    };
+                    responder.handle(&channel_id, &msg.author, &content).await;
+                }
+            });
+        } else {
+            tokio::spawn(run_polling(responder, shutdown_rx));
+        }
+
+        Ok(())
+    }
+
+    /// Stop the bot.
+    async fn stop(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+    }
+}
+
+/// Runs agent turns for incoming messages and posts the replies.
+struct Responder<P, M> {
+    channel: DiscordChannel,
+    bot_user_id: String,
+    bot_name: String,
+    allowed_users: Vec<String>,
+    reply_format: ReplyFormat,
+    greeting: Option<String>,
+    rate_limit: Option<Arc<RateLimiter>>,
+    model: String,
+    agent: Arc<Agent>,
+    provider: Arc<P>,
+    memory: Arc<M>,
+}
+
+impl<P: LlmProvider + 'static, M: MemoryStore + 'static> Responder<P, M> {
+    fn is_mentioned(&self, msg: &DiscordMessage) -> bool {
+        msg.mentions.iter().any(|u| u.id == self.bot_user_id)
+    }
+
+    /// Answer one message: greeting, chat commands, or an agent turn with a
+    /// live status message.
+    async fn handle(&self, channel_id: &str, author: &DiscordUser, content: &str) {
+        // Skip our own (and other bots') messages
+        if author.id == self.bot_user_id || author.bot.unwrap_or(false) {
+            return;
+        }
+
+        // Check allowed users
+        if !self.allowed_users.is_empty() && !self.allowed_users.contains(&author.id) {
+            return;
+        }
+
+        if content.is_empty() {
+            return;
+        }
+
+        let session_key = format!("discord:{}", channel_id);
+
+        info!(
+            "📨 [Discord] {}: {}",
+            author.username,
+            content.chars().take(80).collect::<String>()
+        );
+
+        if let Some(hello) = super::first_contact_greeting(
+            self.memory.as_ref(),
+            self.greeting.as_deref(),
+            &session_key,
+            "discord",
+            &self.bot_name,
+            &self.model,
+        ).await {
+            let _ = self.channel.send(channel_id, &hello).await;
+        }
+
+        if let Some(reply) = zenclaw_core::locale::command(self.memory.as_ref(), &session_key, content).await {
+            let _ = self
+                .channel
+                .send(channel_id, &self.reply_format.apply(ReplyKind::Command, "discord", &reply))
+                .await;
+            return;
+        }
+
+        if let Some(limiter) = &self.rate_limit
+            && let Err(wait) = limiter.check(&session_key)
+        {
+            let _ = self
+                .channel
+                .send(channel_id, &self.reply_format.apply(ReplyKind::Error, "discord", &too_fast_message(wait)))
+                .await;
+            return;
+        }
+
+        let bus = EventBus::new(32);
+        let status = StatusMessage::start(&self.channel, channel_id, &bus).await;
+
+        // Process through agent
+        let result = self
+            .agent
+            .process(
+                self.provider.as_ref(),
+                self.memory.as_ref(),
+                content,
+                &session_key,
+                Some(&bus),
+            )
+            .await;
+
+        status.finish(&self.channel).await;
+
+        match result {
+            Ok(response) => {
+                let response = self.reply_format.apply(ReplyKind::Response, "discord", &response);
+                if let Err(e) = self.channel.send(channel_id, &response).await {
+                    error!("Failed to send Discord reply: {}", e);
+                }
+            }
+            Err(e) => {
+                error!("Agent error: {}", e);
+                let _ = self
+                    .channel
+                    .send(channel_id, &self.reply_format.apply(ReplyKind::Error, "discord", &format!("❌ Error: {}", e)))
+                    .await;
+            }
+        }
+    }
+}
 
-                // Get DM channels
-                match get_dm_channels(&client, &api_base, &bot_token).await {
-                    Ok(channels) => {
-                        for channel in channels {
-                            let channel_id = &channel.id;
-
-                            // Get recent messages
-                            let after = last_message_ids
-                                .get(channel_id)
-                                .map(|s| s.as_str());
-
-                            match get_messages(
-                                &client,
-                                &api_base,
-                                &bot_token,
-                                channel_id,
-                                after,
-                            )
-                            .await
-                            {
-                                Ok(messages) => {
-                                    for msg in messages.iter().rev() {
-                                        // Skip bot's own messages
-                                        if msg.author.id == bot_user_id {
-                                            continue;
-                                        }
-
-                                        // Check allowed users
-                                        if !allowed_users.is_empty()
-                                            && !allowed_users.contains(&msg.author.id)
-                                        {
-                                            continue;
-                                        }
-
-                                        let content = &msg.content;
-                                        if content.is_empty() {
-                                            continue;
-                                        }
-
-                                        let session_key =
-                                            format!("discord:{}", channel_id);
-
-                                        info!(
-                                            "📨 [Discord] {}: {}",
-                                            msg.author.username,
-                                            if content.len() > 80 {
-                                                &content[..80]
-                                            } else {
-                                                content
-                                            }
-                                        );
-
-                                        // Send initial thinking message
-                                        let mut initial_msg_id = None;
-                                        if let Ok(msg) = send_message(
-                                            &client,
-                                            &api_base,
-                                            &bot_token,
-                                            channel_id,
-                                            "🧠 *Process Started...*",
-                                        )
-                                        .await {
-                                            initial_msg_id = Some(msg.id);
-                                        }
-
-                                        let bus = EventBus::new(32);
-                                        let mut rx = bus.subscribe_system();
-                                        let bg_client = client.clone();
-                                        let bg_api_base = api_base.clone();
-                                        let bg_bot_token = bot_token.clone();
-                                        let bg_channel_id = channel_id.to_string();
-                                        let msg_id_clone = initial_msg_id.clone();
-                                        
-                                        let _bg_task = tokio::spawn(async move {
-                                            if let Some(msg_id) = msg_id_clone {
-                                                let mut last_status = String::new();
-                                                while let Ok(event) = rx.recv().await {
-                                                    if let Some(msg) = event.format_status() {
-                                                        let new_status_msg = format!("*{}*", msg);
-                                                        if new_status_msg != last_status {
-                                                            last_status = new_status_msg.clone();
-                                                            let _ = edit_message(
-                                                                &bg_client,
-                                                                &bg_api_base,
-                                                                &bg_bot_token,
-                                                                &bg_channel_id,
-                                                                &msg_id,
-                                                                &new_status_msg,
-                                                            ).await;
-                                                            
-                                                            // Delay to avoid hitting Discord API rate limits
-                                                            tokio::time::sleep(Duration::from_millis(1000)).await;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        });
-
-                                        // Process through agent
-                                        match agent
-                                            .process(
-                                                provider.as_ref(),
-                                                memory.as_ref(),
-                                                content,
-                                                &session_key,
-                                                Some(&bus),
-                                            )
-                                            .await
-                                        {
-                                            Ok(response) => {
-                                                if let Some(msg_id) = initial_msg_id {
-                                                    let _ = delete_message(&client, &api_base, &bot_token, channel_id, &msg_id).await;
-                                                }
-
-                                                // Split long messages (Discord limit: 2000)
-                                                for chunk in split_message(&response, 1900) {
-                                                    let _ = send_message(
-                                                        &client,
-                                                        &api_base,
-                                                        &bot_token,
-                                                        channel_id,
-                                                        &chunk,
-                                                    )
-                                                    .await;
-                                                }
-                                            }
-                                            Err(e) => {
-                                                if let Some(msg_id) = initial_msg_id {
-                                                    let _ = delete_message(&client, &api_base, &bot_token, channel_id, &msg_id).await;
-                                                }
-
-                                                error!("Agent error: {}", e);
-                                                let _ = send_message(
-                                                    &client,
-                                                    &api_base,
-                                                    &bot_token,
-                                                    channel_id,
-                                                    &format!("❌ Error: {}", e),
-                                                )
-                                                .await;
-                                            }
-                                        }
-
-                                        // Update last seen message
-                                        last_message_ids.insert(
-                                            channel_id.clone(),
-                                            msg.id.clone(),
-                                        );
-                                    }
-
-                                    // Update last message ID even if no new messages
-                                    if let Some(latest) = messages.first() {
-                                        last_message_ids
-                                            .entry(channel_id.clone())
-                                            .or_insert_with(|| latest.id.clone());
-                                    }
-                                }
-                                Err(e) => {
-                                    debug!("Error getting messages for {}: {}", channel_id, e);
-                                }
+/// Poll the bot's DM channels for new messages.
+async fn run_polling<P, M>(responder: Arc<Responder<P, M>>, mut shutdown_rx: mpsc::Receiver<()>)
+where
+    P: LlmProvider + 'static,
+    M: MemoryStore + 'static,
+{
+    let channel = &responder.channel;
+    let (client, api_base, bot_token) = (&channel.client, &channel.api_base, &channel.config.bot_token);
+    let mut last_message_ids: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
+    let mut backoff = super::Backoff::new(channel.config.reconnect);
+
+    loop {
+        if shutdown_rx.try_recv().is_ok() {
+            info!("Discord bot shutting down...");
+            break;
+        }
+
+        // Get DM channels
+        match get_dm_channels(client, api_base, bot_token).await {
+            Ok(channels) => {
+                backoff.reset();
+                for dm in channels {
+                    let channel_id = &dm.id;
+
+                    // Get recent messages
+                    let after = last_message_ids
+                        .get(channel_id)
+                        .map(|s| s.as_str());
+
+                    match get_messages(client, api_base, bot_token, channel_id, after).await {
+                        Ok(messages) => {
+                            for msg in messages.iter().rev() {
+                                responder.handle(channel_id, &msg.author, &msg.content).await;
+
+                                // Update last seen message
+                                last_message_ids.insert(channel_id.clone(), msg.id.clone());
+                            }
+
+                            // Update last message ID even if no new messages
+                            if let Some(latest) = messages.first() {
+                                last_message_ids
+                                    .entry(channel_id.clone())
+                                    .or_insert_with(|| latest.id.clone());
                             }
                         }
-                    }
-                    Err(e) => {
-                        warn!("Error getting DM channels: {}", e);
+                        Err(e) => {
+                            debug!("Error getting messages for {}: {}", channel_id, e);
+                        }
                     }
                 }
-
-                // Poll interval
-                tokio::time::sleep(Duration::from_secs(2)).await;
             }
-        });
+            Err(e) => {
+                let delay = backoff.fail();
+                warn!(
+                    "Discord polling failed: {} — reconnecting in {}s (attempt {})",
+                    e,
+                    delay.as_secs(),
+                    backoff.failures()
+                );
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        }
 
-        Ok(())
+        // Poll interval
+        tokio::time::sleep(Duration::from_secs(2)).await;
     }
+}
 
-    /// Get bot user info.
-    async fn get_me(&self) -> Result<DiscordUser> {
-        let url = format!("{}/users/@me", self.api_base);
-        let resp: DiscordUser = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bot {}", self.config.bot_token))
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| ZenClawError::Provider(format!("Discord getMe failed: {}", e)))?;
-        Ok(resp)
+// ─── Gateway ───────────────────────────────────────────────
+
+/// Keep a Gateway session open, reconnecting when it drops, and forward
+/// every `MESSAGE_CREATE` to `messages`.
+async fn run_gateway(
+    url: String,
+    token: String,
+    reconnect: ReconnectSettings,
+    messages: mpsc::UnboundedSender<DiscordMessage>,
+    mut shutdown_rx: mpsc::Receiver<()>,
+) {
+    let mut backoff = super::Backoff::new(reconnect);
+    loop {
+        let connected_at = std::time::Instant::now();
+        tokio::select! {
+            _ = shutdown_rx.recv() => {
+                info!("Discord bot shutting down...");
+                break;
+            }
+            result = gateway_session(&url, &token, &messages) => {
+                // A session that stayed up for a while ends the failure streak
+                if connected_at.elapsed() > backoff.cap() {
+                    backoff.reset();
+                }
+                match result {
+                    Ok(()) => {
+                        info!("Discord Gateway asked to reconnect");
+                        backoff.reset();
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    Err(e) => {
+                        let delay = backoff.fail();
+                        warn!(
+                            "Discord Gateway connection lost: {} — reconnecting in {}s (attempt {})",
+                            e,
+                            delay.as_secs(),
+                            backoff.failures()
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+        }
     }
+}
 
-    /// Stop the bot.
-    pub async fn stop(&mut self) {
-        if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(()).await;
+/// One Gateway connection: hello → identify → heartbeat + dispatch.
+/// Returns `Ok` when Discord asks for a reconnect.
+async fn gateway_session(
+    url: &str,
+    token: &str,
+    messages: &mpsc::UnboundedSender<DiscordMessage>,
+) -> anyhow::Result<()> {
+    let (ws, _) = tokio_tungstenite::connect_async(format!("{}/?v=10&encoding=json", url)).await?;
+    let (mut sink, mut stream) = ws.split();
+
+    // The first frame is HELLO with the heartbeat interval
+    let hello = match stream.next().await {
+        Some(Ok(WsMessage::Text(text))) => serde_json::from_str::<GatewayPayload>(&text)?,
+        other => anyhow::bail!("expected HELLO, got {:?}", other),
+    };
+    let interval_ms = hello.d["heartbeat_interval"].as_u64().unwrap_or(41_250);
+
+    let identify = serde_json::json!({
+        "op": 2,
+        "d": {
+            "token": token,
+            "intents": GATEWAY_INTENTS,
+            "properties": { "os": std::env::consts::OS, "browser": "zenclaw", "device": "zenclaw" },
+        },
+    });
+    sink.send(WsMessage::Text(identify.to_string())).await?;
+
+    let mut heartbeat = tokio::time::interval(Duration::from_millis(interval_ms));
+    heartbeat.tick().await; // the first tick fires immediately
+    let mut sequence: Option<u64> = None;
+    let mut acked = true;
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if !acked {
+                    anyhow::bail!("heartbeat not acknowledged");
+                }
+                acked = false;
+                let beat = serde_json::json!({ "op": 1, "d": sequence });
+                sink.send(WsMessage::Text(beat.to_string())).await?;
+            }
+            frame = stream.next() => {
+                let text = match frame {
+                    Some(Ok(WsMessage::Text(text))) => text,
+                    Some(Ok(WsMessage::Close(reason))) => anyhow::bail!("closed by Discord: {:?}", reason),
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                    None => anyhow::bail!("stream ended"),
+                };
+                let payload: GatewayPayload = match serde_json::from_str(&text) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        debug!("Unparseable Gateway frame: {}", e);
+                        continue;
+                    }
+                };
+                if payload.s.is_some() {
+                    sequence = payload.s;
+                }
+
+                match payload.op {
+                    // Dispatch
+                    0 => match payload.t.as_deref() {
+                        Some("READY") => info!("🎮 Discord Gateway connected"),
+                        Some("MESSAGE_CREATE") => {
+                            if let Ok(msg) = serde_json::from_value::<DiscordMessage>(payload.d) {
+                                let _ = messages.send(msg);
+                            }
+                        }
+                        _ => {}
+                    },
+                    // Heartbeat request
+                    1 => {
+                        let beat = serde_json::json!({ "op": 1, "d": sequence });
+                        sink.send(WsMessage::Text(beat.to_string())).await?;
+                    }
+                    // Reconnect / invalid session: start a fresh session
+                    7 | 9 => return Ok(()),
+                    // Heartbeat ACK
+                    11 => acked = true,
+                    _ => {}
+                }
+            }
         }
     }
 }
@@ -295,6 +531,8 @@ struct DiscordUser {
     id: String,
     username: String,
     discriminator: Option<String>,
+    #[serde(default)]
+    bot: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -309,6 +547,28 @@ struct DiscordMessage {
     id: String,
     content: String,
     author: DiscordUser,
+    /// Set on Gateway events.
+    #[serde(default)]
+    channel_id: Option<String>,
+    /// Set for messages in a guild (server) channel.
+    #[serde(default)]
+    guild_id: Option<String>,
+    #[serde(default)]
+    mentions: Vec<DiscordUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GatewayPayload {
+    op: u8,
+    #[serde(default)]
+    d: serde_json::Value,
+    s: Option<u64>,
+    t: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GatewayBot {
+    url: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -318,6 +578,19 @@ struct SendMessageBody {
 
 // ─── API Helpers ───────────────────────────────────────────
 
+async fn gateway_url(client: &Client, api_base: &str, token: &str) -> Result<String> {
+    let url = format!("{}/gateway/bot", api_base);
+    let resp: GatewayBot = client
+        .get(&url)
+        .header("Authorization", format!("Bot {}", token))
+        .send()
+        .await?
+        .json()
+        .await
+        .map_err(|e| ZenClawError::Provider(format!("Discord gateway error: {}", e)))?;
+    Ok(resp.url)
+}
+
 async fn get_dm_channels(
     client: &Client,
     api_base: &str,
@@ -417,6 +690,15 @@ async fn delete_message(
     Ok(())
 }
 
+/// Remove `<@id>` / `<@!id>` mentions of the bot from a message.
+fn strip_mention(content: &str, bot_user_id: &str) -> String {
+    content
+        .replace(&format!("<@{}>", bot_user_id), "")
+        .replace(&format!("<@!{}>", bot_user_id), "")
+        .trim()
+        .to_string()
+}
+
 /// Split a long message into chunks.
 fn split_message(text: &str, max_len: usize) -> Vec<String> {
     if text.len() <= max_len {
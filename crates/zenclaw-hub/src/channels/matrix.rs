@@ -0,0 +1,443 @@
+//! Matrix bot channel — client-server API long polling.
+//!
+//! Uses `/sync` with a `since` token to receive room messages and replies
+//! with `PUT /rooms/{roomId}/send/m.room.message`. Works with any homeserver
+//! (Synapse, Dendrite, Conduit...) given a bot account's access token.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use zenclaw_core::agent::Agent;
+use zenclaw_core::bus::EventBus;
+use zenclaw_core::channel::{ReplyFormat, ReplyKind, STATUS_PLACEHOLDER};
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::memory::MemoryStore;
+use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::ratelimit::{too_fast_message, RateLimiter};
+
+/// Long-poll timeout passed to `/sync`.
+const SYNC_TIMEOUT_MS: u64 = 30_000;
+
+/// Matrix bot configuration.
+#[derive(Debug, Clone)]
+pub struct MatrixConfig {
+    /// Homeserver base URL (e.g. https://matrix.example.org)
+    pub homeserver_url: String,
+    /// Access token of the bot account
+    pub access_token: String,
+    /// Allowed room IDs (if empty, answer in every joined room)
+    pub allowed_rooms: Vec<String>,
+    /// Prefix/suffix added to replies.
+    pub reply_format: ReplyFormat,
+    /// Onboarding message sent the first time a room is seen.
+    pub greeting: Option<String>,
+    /// Per-room message limit (None = unlimited).
+    pub rate_limit: Option<Arc<RateLimiter>>,
+}
+
+/// Matrix bot channel — uses `/sync` long polling.
+pub struct MatrixChannel {
+    config: MatrixConfig,
+    client: Client,
+    bot_user_id: Option<String>,
+    shutdown_tx: Option<mpsc::Sender<()>>,
+}
+
+impl MatrixChannel {
+    pub fn new(config: MatrixConfig) -> Self {
+        Self {
+            config,
+            client: Client::builder()
+                .timeout(Duration::from_millis(SYNC_TIMEOUT_MS) + Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            bot_user_id: None,
+            shutdown_tx: None,
+        }
+    }
+
+    /// Start the Matrix bot sync task.
+    pub async fn start<P, M>(
+        &mut self,
+        agent: Arc<Agent>,
+        provider: Arc<P>,
+        memory: Arc<M>,
+    ) -> Result<()>
+    where
+        P: LlmProvider + 'static,
+        M: MemoryStore + 'static,
+    {
+        let api = MatrixApi {
+            client: self.client.clone(),
+            base: format!(
+                "{}/_matrix/client/v3",
+                self.config.homeserver_url.trim_end_matches('/')
+            ),
+            token: self.config.access_token.clone(),
+        };
+
+        let user_id = match api.whoami().await {
+            Ok(id) => id,
+            Err(e) => return Err(ZenClawError::Provider(format!("Matrix auth failed: {}", e))),
+        };
+        self.bot_user_id = Some(user_id.clone());
+        info!("🟩 Matrix bot started: {}", user_id);
+
+        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        self.shutdown_tx = Some(shutdown_tx);
+
+        let allowed_rooms = self.config.allowed_rooms.clone();
+        let reply_format = self.config.reply_format.clone();
+        let greeting = self.config.greeting.clone();
+        let rate_limit = self.config.rate_limit.clone();
+        let model = agent.config.model.clone().unwrap_or_else(|| provider.default_model().to_string());
+
+        tokio::spawn(async move {
+            // The first sync only fetches a token: history from before startup is skipped
+            let mut since: Option<String> = None;
+
+            loop {
+                if shutdown_rx.try_recv().is_ok() {
+                    info!("Matrix bot shutting down...");
+                    break;
+                }
+
+                let sync = match api.sync(since.as_deref()).await {
+                    Ok(sync) => sync,
+                    Err(e) => {
+                        warn!("Matrix sync failed: {}", e);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        continue;
+                    }
+                };
+                let first_sync = since.is_none();
+                since = Some(sync.next_batch);
+
+                // Join rooms we're invited to (if allowed)
+                for room_id in sync.rooms.invite.keys() {
+                    if !allowed_rooms.is_empty() && !allowed_rooms.contains(room_id) {
+                        continue;
+                    }
+                    match api.join(room_id).await {
+                        Ok(()) => info!("Matrix: joined {}", room_id),
+                        Err(e) => warn!("Matrix: failed to join {}: {}", room_id, e),
+                    }
+                }
+
+                if first_sync {
+                    continue;
+                }
+
+                for (room_id, room) in sync.rooms.join {
+                    if !allowed_rooms.is_empty() && !allowed_rooms.contains(&room_id) {
+                        continue;
+                    }
+
+                    for event in room.timeline.events {
+                        if event.event_type != "m.room.message" || event.sender == user_id {
+                            continue;
+                        }
+                        if event.content.msgtype.as_deref() != Some("m.text") {
+                            continue;
+                        }
+                        let content = event.content.body.unwrap_or_default();
+                        if content.is_empty() {
+                            continue;
+                        }
+
+                        let session_key = format!("matrix:{}", room_id);
+
+                        info!(
+                            "📨 [Matrix] {}: {}",
+                            event.sender,
+                            content.chars().take(80).collect::<String>()
+                        );
+
+                        if let Some(hello) = super::first_contact_greeting(
+                            memory.as_ref(),
+                            greeting.as_deref(),
+                            &session_key,
+                            "matrix",
+                            &user_id,
+                            &model,
+                        ).await {
+                            let _ = api.send(&room_id, &hello).await;
+                        }
+
+                        if let Some(reply) = zenclaw_core::locale::command(memory.as_ref(), &session_key, &content).await {
+                            let _ = api
+                                .send(&room_id, &reply_format.apply(ReplyKind::Command, "matrix", &reply))
+                                .await;
+                            continue;
+                        }
+
+                        if let Some(limiter) = &rate_limit
+                            && let Err(wait) = limiter.check(&session_key)
+                        {
+                            let _ = api
+                                .send(&room_id, &reply_format.apply(ReplyKind::Error, "matrix", &too_fast_message(wait)))
+                                .await;
+                            continue;
+                        }
+
+                        let bus = EventBus::new(32);
+
+                        // Send initial message
+                        let initial_id = api.send(&room_id, STATUS_PLACEHOLDER).await.ok();
+
+                        let id_clone = initial_id.clone();
+                        let bg_api = api.clone();
+                        let bg_room_id = room_id.clone();
+                        let mut rx = bus.subscribe_system();
+
+                        let _bg_task = tokio::spawn(async move {
+                            if let Some(event_id) = id_clone {
+                                let mut last_status = String::new();
+                                while let Ok(event) = rx.recv().await {
+                                    if event.event_type == "agent_done" {
+                                        break; // Turn finished; the status message is about to be redacted
+                                    }
+                                    if let Some(msg) = event.format_status()
+                                        && msg != last_status
+                                    {
+                                        last_status = msg.clone();
+                                        let _ = bg_api.edit(&bg_room_id, &event_id, &msg).await;
+                                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                                    }
+                                }
+                            }
+                        });
+
+                        let result = agent
+                            .process(provider.as_ref(), memory.as_ref(), &content, &session_key, Some(&bus))
+                            .await;
+
+                        if let Some(event_id) = initial_id {
+                            let _ = api.redact(&room_id, &event_id).await;
+                        }
+
+                        match result {
+                            Ok(response) => {
+                                let response = reply_format.apply(ReplyKind::Response, "matrix", &response);
+                                let _ = api.send(&room_id, &response).await;
+                            }
+                            Err(e) => {
+                                error!("Agent error: {}", e);
+                                let _ = api
+                                    .send(
+                                        &room_id,
+                                        &reply_format.apply(ReplyKind::Error, "matrix", &format!("❌ Error: {}", e)),
+                                    )
+                                    .await;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+    }
+}
+
+// ─── API Types ───────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct WhoAmIResponse {
+    user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SyncResponse {
+    next_batch: String,
+    #[serde(default)]
+    rooms: SyncRooms,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SyncRooms {
+    #[serde(default)]
+    join: std::collections::HashMap<String, JoinedRoom>,
+    #[serde(default)]
+    invite: std::collections::HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JoinedRoom {
+    #[serde(default)]
+    timeline: Timeline,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Timeline {
+    #[serde(default)]
+    events: Vec<RoomEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RoomEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    sender: String,
+    #[serde(default)]
+    content: MessageContent,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageContent {
+    msgtype: Option<String>,
+    body: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SendResponse {
+    event_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MatrixError {
+    errcode: Option<String>,
+    error: Option<String>,
+}
+
+// ─── API Helpers ─────────────────────────────────────────────
+
+/// Transaction IDs only need to be unique per access token and process run.
+static TXN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn txn_id() -> String {
+    format!(
+        "zc{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        TXN_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Percent-encode a path segment (room IDs contain `!` and `:`).
+fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+struct MatrixApi {
+    client: Client,
+    /// `{homeserver}/_matrix/client/v3`
+    base: String,
+    token: String,
+}
+
+impl MatrixApi {
+    async fn request<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let resp = request
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+
+        if !status.is_success() {
+            let detail = serde_json::from_str::<MatrixError>(&body)
+                .ok()
+                .map(|e| format!("{} {}", e.errcode.unwrap_or_default(), e.error.unwrap_or_default()))
+                .unwrap_or(body);
+            return Err(ZenClawError::Provider(format!("Matrix API error ({}): {}", status, detail.trim())));
+        }
+        serde_json::from_str(&body).map_err(|e| ZenClawError::Provider(e.to_string()))
+    }
+
+    async fn whoami(&self) -> Result<String> {
+        let url = format!("{}/account/whoami", self.base);
+        let resp: WhoAmIResponse = self.request(self.client.get(&url)).await?;
+        Ok(resp.user_id)
+    }
+
+    async fn sync(&self, since: Option<&str>) -> Result<SyncResponse> {
+        let url = match since {
+            Some(token) => format!("{}/sync?timeout={}&since={}", self.base, SYNC_TIMEOUT_MS, encode(token)),
+            // Nothing to wait for on the initial sync, and no history needed
+            None => format!(
+                "{}/sync?timeout=0&filter={}",
+                self.base,
+                encode(r#"{"room":{"timeline":{"limit":1}}}"#)
+            ),
+        };
+        self.request(self.client.get(&url)).await
+    }
+
+    async fn join(&self, room_id: &str) -> Result<()> {
+        let url = format!("{}/join/{}", self.base, encode(room_id));
+        let _: serde_json::Value = self
+            .request(self.client.post(&url).json(&serde_json::json!({})))
+            .await?;
+        Ok(())
+    }
+
+    /// Send a text message; returns its event ID.
+    async fn send(&self, room_id: &str, text: &str) -> Result<String> {
+        self.send_content(
+            room_id,
+            serde_json::json!({
+                "msgtype": "m.text",
+                "body": text,
+            }),
+        )
+        .await
+    }
+
+    /// Replace the text of a previously sent message.
+    async fn edit(&self, room_id: &str, event_id: &str, text: &str) -> Result<String> {
+        self.send_content(
+            room_id,
+            serde_json::json!({
+                "msgtype": "m.text",
+                "body": format!("* {}", text),
+                "m.new_content": { "msgtype": "m.text", "body": text },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": event_id },
+            }),
+        )
+        .await
+    }
+
+    async fn send_content(&self, room_id: &str, content: serde_json::Value) -> Result<String> {
+        let url = format!(
+            "{}/rooms/{}/send/m.room.message/{}",
+            self.base,
+            encode(room_id),
+            txn_id()
+        );
+        let resp: SendResponse = self.request(self.client.put(&url).json(&content)).await?;
+        Ok(resp.event_id)
+    }
+
+    async fn redact(&self, room_id: &str, event_id: &str) -> Result<()> {
+        let url = format!(
+            "{}/rooms/{}/redact/{}/{}",
+            self.base,
+            encode(room_id),
+            encode(event_id),
+            txn_id()
+        );
+        let _: serde_json::Value = self
+            .request(self.client.put(&url).json(&serde_json::json!({})))
+            .await?;
+        Ok(())
+    }
+}
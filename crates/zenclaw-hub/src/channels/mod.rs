@@ -2,8 +2,155 @@ pub mod telegram;
 pub mod discord;
 pub mod whatsapp;
 pub mod slack;
+pub mod matrix;
+pub mod webhook;
 
 pub use telegram::{TelegramChannel, TelegramConfig};
 pub use discord::{DiscordChannel, DiscordConfig};
 pub use whatsapp::WhatsAppChannel;
 pub use slack::{SlackChannel, SlackConfig};
+pub use matrix::{MatrixChannel, MatrixConfig};
+pub use webhook::{WebhookChannel, WebhookConfig};
+
+use std::time::Duration;
+
+use zenclaw_core::config::ReconnectSettings;
+use zenclaw_core::memory::MemoryStore;
+use zenclaw_core::prompt::PromptVars;
+
+/// Render the first-contact greeting for a session, if one is configured
+/// and this session hasn't been greeted yet (tracked as a memory fact).
+///
+/// The template may use `{bot}`, `{model}` and `{channel}`.
+pub(crate) async fn first_contact_greeting(
+    memory: &dyn MemoryStore,
+    greeting: Option<&str>,
+    session_key: &str,
+    channel: &str,
+    bot: &str,
+    model: &str,
+) -> Option<String> {
+    let template = greeting.filter(|g| !g.trim().is_empty())?;
+
+    let key = format!("greeted:{}", session_key);
+    if memory.get_fact(&key).await.ok().flatten().is_some() {
+        return None;
+    }
+    if let Err(e) = memory.save_fact(&key, &chrono::Utc::now().to_rfc3339()).await {
+        tracing::warn!("Failed to record greeting for {}: {}", session_key, e);
+    }
+
+    let mut vars = PromptVars::new();
+    vars.set("bot", bot).set("model", model).set("channel", channel);
+    Some(vars.render(template))
+}
+
+/// Reconnection delay of a poller: doubles on each consecutive failure up
+/// to the configured cap, and starts over after a success.
+pub(crate) struct Backoff {
+    base: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+impl Backoff {
+    pub fn new(settings: ReconnectSettings) -> Self {
+        let base = Duration::from_secs(settings.base_secs.max(1));
+        Self {
+            base,
+            max: Duration::from_secs(settings.max_secs).max(base),
+            failures: 0,
+        }
+    }
+
+    /// Record a failure and return how long to wait before retrying.
+    pub fn fail(&mut self) -> Duration {
+        let delay = self
+            .base
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(self.max);
+        self.failures = self.failures.saturating_add(1);
+        delay
+    }
+
+    /// Consecutive failures so far.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn cap(&self) -> Duration {
+        self.max
+    }
+
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_to_cap_and_resets() {
+        let mut backoff = Backoff::new(ReconnectSettings { base_secs: 2, max_secs: 10 });
+        let delays: Vec<u64> = (0..5).map(|_| backoff.fail().as_secs()).collect();
+        assert_eq!(delays, vec![2, 4, 8, 10, 10]);
+        assert_eq!(backoff.failures(), 5);
+
+        backoff.reset();
+        assert_eq!(backoff.fail(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_backoff_clamps_settings() {
+        let mut backoff = Backoff::new(ReconnectSettings { base_secs: 0, max_secs: 0 });
+        assert_eq!(backoff.fail(), Duration::from_secs(1));
+        assert_eq!(backoff.fail(), Duration::from_secs(1));
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::sync::{Arc, Mutex};
+
+    use axum::http::{HeaderMap, StatusCode, Uri, header};
+
+    /// A request received by [`server`].
+    #[derive(Debug, Clone)]
+    pub struct Received {
+        pub path: String,
+        pub authorization: Option<String>,
+        pub body: serde_json::Value,
+    }
+
+    /// Stand-in for a bridge or bot API: answers every request with
+    /// `status` and the JSON `reply`, and records what it received.
+    pub async fn server(status: u16, reply: &'static str) -> (String, Arc<Mutex<Vec<Received>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        let app = axum::Router::new().fallback(move |uri: Uri, headers: HeaderMap, body: String| {
+            let log = log.clone();
+            async move {
+                log.lock().unwrap().push(Received {
+                    path: uri.path().to_string(),
+                    authorization: headers
+                        .get(header::AUTHORIZATION)
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string),
+                    body: serde_json::from_str(&body).unwrap_or_default(),
+                });
+                (
+                    StatusCode::from_u16(status).unwrap(),
+                    [(header::CONTENT_TYPE, "application/json")],
+                    reply,
+                )
+            }
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, received)
+    }
+}
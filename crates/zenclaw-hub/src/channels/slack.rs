@@ -6,15 +6,19 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use zenclaw_core::agent::Agent;
+use zenclaw_core::channel::{Channel, ReplyFormat, ReplyKind, StatusMessage};
+use zenclaw_core::config::ReconnectSettings;
 use zenclaw_core::error::{Result, ZenClawError};
 use zenclaw_core::memory::MemoryStore;
 use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::ratelimit::{too_fast_message, RateLimiter};
 use zenclaw_core::bus::EventBus;
 
 /// Slack bot configuration.
@@ -24,9 +28,20 @@ pub struct SlackConfig {
     pub bot_token: String,
     /// Allowed channel/user IDs to listen to (if empty, listen to all joined channels)
     pub allowed_channels: Vec<String>,
+    /// Prefix/suffix added to replies.
+    pub reply_format: ReplyFormat,
+    /// Onboarding message sent the first time a channel is seen.
+    pub greeting: Option<String>,
+    /// Per-channel message limit (None = unlimited).
+    pub rate_limit: Option<Arc<RateLimiter>>,
+    /// Reconnection backoff while the Slack API is unreachable.
+    pub reconnect: ReconnectSettings,
 }
 
 /// Slack bot channel — uses HTTP API polling.
+///
+/// Cheap to clone: the HTTP client is shared.
+#[derive(Clone)]
 pub struct SlackChannel {
     config: SlackConfig,
     client: Client,
@@ -49,8 +64,40 @@ impl SlackChannel {
         }
     }
 
+    /// Use another Web API base (e.g. `https://slack-gov.com/api`).
+    pub fn with_api_base(mut self, base: &str) -> Self {
+        self.api_base = base.trim_end_matches('/').to_string();
+        self
+    }
+
+}
+
+#[async_trait]
+impl Channel for SlackChannel {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    /// Works without [`start`](Channel::start), e.g. for scheduled job results.
+    async fn send(&self, channel: &str, text: &str) -> Result<String> {
+        post_text(&self.client, &self.api_base, &self.config.bot_token, channel, text).await
+    }
+
+    async fn edit(&self, channel: &str, ts: &str, text: &str) -> Result<()> {
+        edit_message(&self.client, &self.api_base, &self.config.bot_token, channel, ts, text).await
+    }
+
+    async fn delete(&self, channel: &str, ts: &str) -> Result<()> {
+        delete_message(&self.client, &self.api_base, &self.config.bot_token, channel, ts).await
+    }
+
+    /// `chat.update` is rate limited to about one call per second.
+    fn status_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
     /// Start the Slack bot polling task.
-    pub async fn start<P, M>(
+    async fn start<P, M>(
         &mut self,
         agent: Arc<Agent>,
         provider: Arc<P>,
@@ -73,15 +120,19 @@ impl SlackChannel {
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
         self.shutdown_tx = Some(shutdown_tx);
 
-        let client = self.client.clone();
-        let api_base = self.api_base.clone();
-        let bot_token = self.config.bot_token.clone();
+        let channel = self.clone();
         let bot_user_id = user_id;
         let allowed_channels = self.config.allowed_channels.clone();
+        let reply_format = self.config.reply_format.clone();
+        let greeting = self.config.greeting.clone();
+        let rate_limit = self.config.rate_limit.clone();
+        let bot_name = user;
+        let model = agent.config.model.clone().unwrap_or_else(|| provider.default_model().to_string());
 
         tokio::spawn(async move {
             let mut last_message_ts: std::collections::HashMap<String, String> =
                 std::collections::HashMap::new();
+            let mut backoff = super::Backoff::new(channel.config.reconnect);
 
             loop {
                 if shutdown_rx.try_recv().is_ok() {
@@ -90,10 +141,11 @@ impl SlackChannel {
                 }
 
                 // Get joined channels
-                match joined_channels(&client, &api_base, &bot_token).await {
+                match joined_channels(&channel.client, &channel.api_base, &channel.config.bot_token).await {
                     Ok(channels) => {
-                        for channel in channels {
-                            let channel_id = &channel.id;
+                        backoff.reset();
+                        for joined in channels {
+                            let channel_id = &joined.id;
 
                             if !allowed_channels.is_empty() && !allowed_channels.contains(channel_id) {
                                 continue;
@@ -101,7 +153,7 @@ impl SlackChannel {
 
                             let oldest = last_message_ts.get(channel_id).map(|s| s.as_str());
 
-                            match get_messages(&client, &api_base, &bot_token, channel_id, oldest).await {
+                            match get_messages(&channel.client, &channel.api_base, &channel.config.bot_token, channel_id, oldest).await {
                                 Ok(messages) => {
                                     for msg in messages.iter().rev() {
                                         if let Some(user) = &msg.user {
@@ -123,78 +175,58 @@ impl SlackChannel {
                                             if content.len() > 80 { &content[..80] } else { &content }
                                         );
 
-                                        let bus = EventBus::new(32);
-                                        
-                                        // Send initial message
-                                        let mut initial_ts = None;
-                                        if let Ok(resp) = send_message(&client, &api_base, &bot_token, channel_id, "🧠 _Process Started..._").await {
-                                            initial_ts = resp.ts;
+                                        if let Some(hello) = super::first_contact_greeting(
+                                            memory.as_ref(),
+                                            greeting.as_deref(),
+                                            &session_key,
+                                            "slack",
+                                            &bot_name,
+                                            &model,
+                                        ).await {
+                                            let _ = channel.send(channel_id, &hello).await;
                                         }
 
-                                        let ts_clone = initial_ts.clone();
-                                        let bg_client = client.clone();
-                                        let bg_api_base = api_base.clone();
-                                        let bg_bot_token = bot_token.clone();
-                                        let bg_channel_id = channel_id.to_string();
-                                        let mut rx = bus.subscribe_system();
-
-                                        let _bg_task = tokio::spawn(async move {
-                                            if let Some(ts) = ts_clone {
-                                                let mut last_status = String::new();
-                                                while let Ok(event) = rx.recv().await {
-                                                    if let Some(msg) = event.format_status() {
-                                                        let new_status_msg = format!("_{}_", msg);
-                                                        if new_status_msg != last_status {
-                                                            last_status = new_status_msg.clone();
-                                                            let _ = edit_message(
-                                                                &bg_client,
-                                                                &bg_api_base,
-                                                                &bg_bot_token,
-                                                                &bg_channel_id,
-                                                                &ts,
-                                                                &new_status_msg,
-                                                            ).await;
-                                                            tokio::time::sleep(Duration::from_millis(1000)).await;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        });
+                                        if let Some(reply) = zenclaw_core::locale::command(memory.as_ref(), &session_key, &content).await {
+                                            let _ = channel
+                                                .send(channel_id, &reply_format.apply(ReplyKind::Command, "slack", &reply))
+                                                .await;
+                                            last_message_ts.insert(channel_id.clone(), msg.ts.clone());
+                                            continue;
+                                        }
+
+                                        if let Some(limiter) = &rate_limit
+                                            && let Err(wait) = limiter.check(&session_key)
+                                        {
+                                            let _ = channel
+                                                .send(channel_id, &reply_format.apply(ReplyKind::Error, "slack", &too_fast_message(wait)))
+                                                .await;
+                                            last_message_ts.insert(channel_id.clone(), msg.ts.clone());
+                                            continue;
+                                        }
 
-                                        match agent.process(
+                                        let bus = EventBus::new(32);
+                                        let status = StatusMessage::start(&channel, channel_id, &bus).await;
+                                        let result = agent.process(
                                             provider.as_ref(),
                                             memory.as_ref(),
                                             &content,
                                             &session_key,
                                             Some(&bus),
-                                        ).await {
-                                            Ok(response) => {
-                                                if let Some(ts) = initial_ts {
-                                                    let _ = delete_message(&client, &api_base, &bot_token, channel_id, &ts).await;
-                                                }
+                                        ).await;
+                                        status.finish(&channel).await;
 
-                                                for chunk in split_message(&response, 3000) {
-                                                    let _ = send_message(
-                                                        &client,
-                                                        &api_base,
-                                                        &bot_token,
-                                                        channel_id,
-                                                        &chunk,
-                                                    ).await;
+                                        match result {
+                                            Ok(response) => {
+                                                let response = reply_format.apply(ReplyKind::Response, "slack", &response);
+                                                if let Err(e) = channel.send(channel_id, &response).await {
+                                                    error!("Failed to send Slack reply: {}", e);
                                                 }
                                             }
                                             Err(e) => {
-                                                if let Some(ts) = initial_ts {
-                                                    let _ = delete_message(&client, &api_base, &bot_token, channel_id, &ts).await;
-                                                }
                                                 error!("Agent error: {}", e);
-                                                let _ = send_message(
-                                                    &client,
-                                                    &api_base,
-                                                    &bot_token,
-                                                    channel_id,
-                                                    &format!("❌ Error: {}", e),
-                                                ).await;
+                                                let _ = channel
+                                                    .send(channel_id, &reply_format.apply(ReplyKind::Error, "slack", &format!("❌ Error: {}", e)))
+                                                    .await;
                                             }
                                         }
 
@@ -214,7 +246,15 @@ impl SlackChannel {
                         }
                     }
                     Err(e) => {
-                        warn!("Error getting Slack channels: {}", e);
+                        let delay = backoff.fail();
+                        warn!(
+                            "Slack polling failed: {} — reconnecting in {}s (attempt {})",
+                            e,
+                            delay.as_secs(),
+                            backoff.failures()
+                        );
+                        tokio::time::sleep(delay).await;
+                        continue;
                     }
                 }
                 tokio::time::sleep(Duration::from_secs(3)).await;
@@ -224,7 +264,7 @@ impl SlackChannel {
         Ok(())
     }
 
-    pub async fn stop(&mut self) {
+    async fn stop(&mut self) {
         if let Some(tx) = self.shutdown_tx.take() {
             let _ = tx.send(()).await;
         }
@@ -270,6 +310,53 @@ struct SlackMessage {
 #[derive(Debug, Deserialize)]
 struct SlackPostResponse {
     ts: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Post `text` to `channel` without running the bot (e.g. scheduled job results).
+pub async fn notify(bot_token: &str, channel: &str, text: &str) -> Result<()> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap_or_default();
+    post_text(&client, "https://slack.com/api", bot_token, channel, text).await?;
+    Ok(())
+}
+
+/// A channel, DM or user id (`C024BE91L`) or a channel name (`#ops-alerts`).
+fn is_valid_channel(channel: &str) -> bool {
+    let is_id = channel.len() >= 8
+        && channel.starts_with(['C', 'G', 'D', 'U', 'W'])
+        && channel.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    let name = channel.strip_prefix('#').unwrap_or(channel);
+    let is_name = !name.is_empty()
+        && name.len() <= 80
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    is_id || is_name
+}
+
+/// Post a message of any length, reporting Slack's error code on failure.
+/// Returns the `ts` of the last message posted.
+async fn post_text(client: &Client, api_base: &str, token: &str, channel: &str, text: &str) -> Result<String> {
+    if !is_valid_channel(channel) {
+        return Err(ZenClawError::Config(format!(
+            "invalid Slack channel '{}' (use an id like C024BE91L or a #name)",
+            channel
+        )));
+    }
+    let mut last_ts = String::new();
+    for chunk in split_message(text, 3000) {
+        let resp = send_message(client, api_base, token, channel, &chunk).await?;
+        last_ts = resp.ts.ok_or_else(|| {
+            ZenClawError::Provider(format!(
+                "Slack rejected the message to {}: {}",
+                channel,
+                resp.error.unwrap_or_else(|| "no reason given".into())
+            ))
+        })?;
+    }
+    Ok(last_ts)
 }
 
 // ─── API Helpers ─────────────────────────────────────────────
@@ -405,3 +492,56 @@ fn split_message(text: &str, max_len: usize) -> Vec<String> {
     if !current.is_empty() { chunks.push(current); }
     chunks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::mock;
+
+    fn channel(api_base: &str) -> SlackChannel {
+        SlackChannel::new(SlackConfig {
+            bot_token: "xoxb-test".into(),
+            allowed_channels: Vec::new(),
+            reply_format: ReplyFormat::default(),
+            greeting: None,
+            rate_limit: None,
+            reconnect: ReconnectSettings::default(),
+        })
+        .with_api_base(api_base)
+    }
+
+    #[test]
+    fn test_is_valid_channel() {
+        assert!(is_valid_channel("C024BE91L"));
+        assert!(is_valid_channel("#ops-alerts"));
+        assert!(is_valid_channel("general"));
+        assert!(!is_valid_channel("#"));
+        assert!(!is_valid_channel("Ops Alerts"));
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_message() {
+        let (url, received) = mock::server(200, r#"{"ok":true,"ts":"1700000000.000100"}"#).await;
+        let ts = channel(&url).send("C024BE91L", "Daily report").await.unwrap();
+        assert_eq!(ts, "1700000000.000100");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].path, "/chat.postMessage");
+        assert_eq!(received[0].authorization.as_deref(), Some("Bearer xoxb-test"));
+        assert_eq!(
+            received[0].body,
+            serde_json::json!({ "channel": "C024BE91L", "text": "Daily report" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_surfaces_api_error() {
+        let (url, received) = mock::server(200, r#"{"ok":false,"error":"channel_not_found"}"#).await;
+        let err = channel(&url).send("C024BE91L", "hi").await.unwrap_err();
+        assert!(err.to_string().contains("channel_not_found"), "{}", err);
+
+        assert!(channel(&url).send("Ops Alerts", "hi").await.is_err());
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+}
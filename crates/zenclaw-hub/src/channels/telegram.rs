@@ -1,22 +1,36 @@
 //! Telegram bot channel — direct HTTP API, no heavy SDK.
 //!
 //! Uses raw Telegram Bot API via reqwest for minimal binary size.
-//! Supports: text messages, typing indicator, markdown formatting.
+//! Supports: text messages, photos and image documents (for vision models),
+//! typing indicator, markdown formatting.
+//! In group chats the bot only answers when @mentioned or replied to.
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use zenclaw_core::agent::Agent;
+use zenclaw_core::channel::{Channel, ReplyFormat, ReplyKind, StatusMessage};
+use zenclaw_core::config::ReconnectSettings;
 use zenclaw_core::error::{Result, ZenClawError};
 use zenclaw_core::memory::MemoryStore;
 use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::ratelimit::{too_fast_message, RateLimiter};
 use zenclaw_core::bus::EventBus;
 
+/// Bot commands, for prefix expansion and "did you mean" hints.
+const COMMANDS: &[&str] = &["/start", "/clear", "/reset", "/help", "/lang"];
+
+/// Largest image forwarded to the model (Telegram bots can fetch up to 20 MB).
+const MAX_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+/// Prompt used for an image sent without a caption.
+const DEFAULT_IMAGE_PROMPT: &str = "Describe this image.";
+
 /// Telegram bot configuration.
 #[derive(Debug, Clone)]
 pub struct TelegramConfig {
@@ -26,9 +40,20 @@ pub struct TelegramConfig {
     pub allowed_users: Vec<i64>,
     /// Polling timeout in seconds.
     pub poll_timeout: u64,
+    /// Prefix/suffix added to replies.
+    pub reply_format: ReplyFormat,
+    /// Onboarding message sent the first time a chat is seen.
+    pub greeting: Option<String>,
+    /// Per-chat message limit (None = unlimited).
+    pub rate_limit: Option<Arc<RateLimiter>>,
+    /// Reconnection backoff while the Telegram API is unreachable.
+    pub reconnect: ReconnectSettings,
 }
 
 /// Telegram bot channel — runs as a long-polling service.
+///
+/// Cheap to clone: the HTTP client is shared.
+#[derive(Clone)]
 pub struct TelegramChannel {
     config: TelegramConfig,
     client: Client,
@@ -54,8 +79,81 @@ impl TelegramChannel {
         }
     }
 
+    /// Use another Bot API server (e.g. a self-hosted `telegram-bot-api`).
+    pub fn with_api_base(mut self, base: &str) -> Self {
+        self.api_base = format!("{}/bot{}", base.trim_end_matches('/'), self.config.bot_token);
+        self
+    }
+
+    /// Get bot info to verify token.
+    async fn get_me(&self) -> Result<TgUser> {
+        let url = format!("{}/getMe", self.api_base);
+        let resp: TgResponse<TgUser> = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .json()
+            .await
+            .map_err(|e| ZenClawError::Provider(format!("Telegram getMe failed: {}", e)))?;
+
+        if !resp.ok {
+            return Err(ZenClawError::Provider(
+                "Telegram bot token is invalid".into(),
+            ));
+        }
+        resp.result
+            .ok_or_else(|| ZenClawError::Provider("No result from getMe".into()))
+    }
+}
+
+/// Parse a chat id given as a string.
+fn parse_chat_id(chat: &str) -> Result<i64> {
+    chat.trim()
+        .parse()
+        .ok()
+        .filter(|&id| id != 0)
+        .ok_or_else(|| ZenClawError::Config(format!("invalid Telegram chat id '{}'", chat)))
+}
+
+fn parse_message_id(message_id: &str) -> Result<i64> {
+    message_id
+        .parse()
+        .map_err(|_| ZenClawError::Config(format!("invalid Telegram message id '{}'", message_id)))
+}
+
+#[async_trait]
+impl Channel for TelegramChannel {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    /// Works without [`start`](Channel::start), e.g. for scheduled job results.
+    async fn send(&self, chat: &str, text: &str) -> Result<String> {
+        let id = send_text(&self.client, &self.api_base, parse_chat_id(chat)?, text, None).await?;
+        Ok(id.to_string())
+    }
+
+    async fn edit(&self, chat: &str, message_id: &str, text: &str) -> Result<()> {
+        edit_message(&self.client, &self.api_base, parse_chat_id(chat)?, parse_message_id(message_id)?, text).await
+    }
+
+    async fn delete(&self, chat: &str, message_id: &str) -> Result<()> {
+        delete_message(&self.client, &self.api_base, parse_chat_id(chat)?, parse_message_id(message_id)?).await
+    }
+
+    async fn typing(&self, chat: &str) -> Result<()> {
+        let url = format!("{}/sendChatAction", self.api_base);
+        self.client
+            .post(&url)
+            .json(&serde_json::json!({ "chat_id": parse_chat_id(chat)?, "action": "typing" }))
+            .send()
+            .await?;
+        Ok(())
+    }
+
     /// Start the Telegram bot — runs in background, returns immediately.
-    pub async fn start<P, M>(
+    async fn start<P, M>(
         &mut self,
         agent: Arc<Agent>,
         provider: Arc<P>,
@@ -67,19 +165,27 @@ impl TelegramChannel {
     {
         // Verify token works
         let me = self.get_me().await?;
-        info!("🤖 Telegram bot started: @{}", me.username.unwrap_or_default());
+        let bot_id = me.id;
+        let bot_username = me.username.unwrap_or_default();
+        info!("🤖 Telegram bot started: @{}", bot_username);
 
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
         self.shutdown_tx = Some(shutdown_tx);
 
-        let client = self.client.clone();
-        let api_base = self.api_base.clone();
+        let channel = self.clone();
         let allowed_users = self.config.allowed_users.clone();
         let poll_timeout = self.config.poll_timeout;
+        let reply_format = self.config.reply_format.clone();
+        let greeting = self.config.greeting.clone();
+        let rate_limit = self.config.rate_limit.clone();
+        let model = agent.config.model.clone().unwrap_or_else(|| provider.default_model().to_string());
+        // With a vision model configured, the agent routes image turns to it
+        let sees_images = provider.supports_vision(&model) || agent.config.vision_model.is_some();
 
         // Spawn polling task
         tokio::spawn(async move {
             let mut offset: i64 = 0;
+            let mut backoff = super::Backoff::new(channel.config.reconnect);
 
             loop {
                 // Check shutdown
@@ -89,8 +195,9 @@ impl TelegramChannel {
                 }
 
                 // Long poll for updates
-                match get_updates(&client, &api_base, offset, poll_timeout).await {
+                match get_updates(&channel.client, &channel.api_base, offset, poll_timeout).await {
                     Ok(updates) => {
+                        backoff.reset();
                         for update in updates {
                             if let Some(msg) = update.message {
                                 offset = update.update_id + 1;
@@ -103,12 +210,19 @@ impl TelegramChannel {
                                     continue;
                                 }
 
-                                let text = msg.text.clone().unwrap_or_default();
-                                if text.is_empty() {
+                                if !msg.is_addressed_to(bot_id, &bot_username) {
+                                    debug!("Ignoring group message not addressed to the bot");
+                                    continue;
+                                }
+
+                                let text = strip_mention(msg.text_or_caption(), &bot_username);
+                                let text = zenclaw_core::command::expand(&text, COMMANDS);
+                                if text.is_empty() && msg.attachment().is_none() {
                                     continue;
                                 }
 
                                 let chat_id = msg.chat.id;
+                                let chat = chat_id.to_string();
                                 let session_key = format!("telegram:{}", chat_id);
                                 
                                 info!(
@@ -118,14 +232,33 @@ impl TelegramChannel {
                                     if text.len() > 80 { &text[..80] } else { &text }
                                 );
 
+                                // First contact: onboarding message (replaces the /start welcome)
+                                if let Some(hello) = super::first_contact_greeting(
+                                    memory.as_ref(),
+                                    greeting.as_deref(),
+                                    &session_key,
+                                    "telegram",
+                                    &format!("@{}", bot_username),
+                                    &model,
+                                ).await {
+                                    let _ = channel.send(&chat, &hello).await;
+                                    if text == "/start" {
+                                        continue;
+                                    }
+                                }
+
                                 // Handle commands
+                                if let Some(reply) = zenclaw_core::locale::command(memory.as_ref(), &session_key, &text).await {
+                                    let _ = channel.send(&chat, &reply_format.apply(ReplyKind::Command, "telegram", &reply)).await;
+                                    continue;
+                                }
                                 if text.starts_with('/') {
                                     match text.as_str() {
                                         "/start" => {
                                             let welcome = "⚡ *ZenClaw AI* — Build AI the simple way\\!\n\nSend me any message and I'll help you\\. 🦀";
                                             let _ = send_message(
-                                                &client,
-                                                &api_base,
+                                                &channel.client,
+                                                &channel.api_base,
                                                 chat_id,
                                                 welcome,
                                                 Some("MarkdownV2"),
@@ -135,119 +268,102 @@ impl TelegramChannel {
                                         }
                                         "/clear" | "/reset" => {
                                             let _ = memory.clear_history(&session_key).await;
-                                            let _ = send_message(
-                                                &client,
-                                                &api_base,
-                                                chat_id,
-                                                "🗑️ Conversation history cleared.",
-                                                None,
-                                            )
-                                            .await;
+                                            let _ = channel.send(&chat, &reply_format.apply(ReplyKind::Command, "telegram", "🗑️ Conversation history cleared.")).await;
                                             continue;
                                         }
                                         "/help" => {
-                                            let help = "Available commands:\n/start — Welcome message\n/clear — Clear conversation\n/help — This help message\n\nOr just send any message!";
-                                            let _ = send_message(
-                                                &client,
-                                                &api_base,
-                                                chat_id,
-                                                help,
-                                                None,
-                                            )
-                                            .await;
+                                            let help = "Available commands:\n/start — Welcome message\n/clear — Clear conversation\n/lang <code> — Reply language (e.g. /lang id, /lang auto)\n/help — This help message\n\nOr just send any message!";
+                                            let _ = channel.send(&chat, &reply_format.apply(ReplyKind::Command, "telegram", help)).await;
                                             continue;
                                         }
-                                        _ => {} // Fall through to agent
+                                        other => {
+                                            // Likely typo of a command → hint; anything else goes to the agent
+                                            let cmd = other.split_whitespace().next().unwrap_or(other);
+                                            if let Some(hint) = zenclaw_core::command::suggest(cmd, COMMANDS) {
+                                                let reply = format!("Unknown command {}. Did you mean {}?", cmd, hint);
+                                                let _ = channel.send(&chat, &reply_format.apply(ReplyKind::Command, "telegram", &reply)).await;
+                                                continue;
+                                            }
+                                        }
                                     }
                                 }
 
-                                // Send initial thinking message
-                                let mut initial_msg_id = None;
-                                if let Ok(resp) = send_message(
-                                    &client,
-                                    &api_base,
-                                    chat_id,
-                                    "🧠 *Process Started...*",
-                                    Some("Markdown"),
-                                ).await {
-                                    initial_msg_id = resp.result.map(|m| m.message_id);
+                                if let Some(limiter) = &rate_limit
+                                    && let Err(wait) = limiter.check(&session_key)
+                                {
+                                    let _ = channel.send(&chat, &reply_format.apply(ReplyKind::Error, "telegram", &too_fast_message(wait))).await;
+                                    continue;
                                 }
 
-                                let bus = EventBus::new(32);
-                                let mut rx = bus.subscribe_system();
-                                let bg_client = client.clone();
-                                let bg_api_base = api_base.clone();
-                                let msg_id_clone = initial_msg_id;
-                                
-                                let _bg_task = tokio::spawn(async move {
-                                    if let Some(msg_id) = msg_id_clone {
-                                        let mut last_status = String::new();
-                                        while let Ok(event) = rx.recv().await {
-                                            if let Some(msg) = event.format_status() {
-                                                // Telegram supports markdown, let's make it look slightly distinct or just use the system default
-                                                let new_status_msg = format!("*{}*", msg);
-                                                if new_status_msg != last_status {
-                                                    last_status = new_status_msg.clone();
-                                                    let _ = edit_message(
-                                                        &bg_client,
-                                                        &bg_api_base,
-                                                        chat_id,
-                                                        msg_id,
-                                                        &new_status_msg,
-                                                        Some("Markdown")
-                                                    ).await;
-                                                    
-                                                    // Slight delay to avoid hitting Telegram API rate limits too hard
-                                                    tokio::time::sleep(Duration::from_millis(500)).await;
+                                // Images go to the model inline; other files aren't supported
+                                let mut media = Vec::new();
+                                let mut text = text;
+                                if let Some(attachment) = msg.attachment() {
+                                    let note = match attachment {
+                                        Attachment::Image { .. } if !sees_images => Some(format!(
+                                            "🖼️ The current model ({}) can't see images. Switch to a vision-capable model or describe the image in text.",
+                                            model
+                                        )),
+                                        Attachment::Image { size: Some(size), .. } if size > MAX_IMAGE_BYTES => Some(format!(
+                                            "🖼️ That image is too large ({} MB, max {} MB).",
+                                            size / (1024 * 1024),
+                                            MAX_IMAGE_BYTES / (1024 * 1024)
+                                        )),
+                                        Attachment::Image { file_id, mime_type, .. } => {
+                                            match download_data_url(&channel.client, &channel.api_base, file_id, mime_type).await {
+                                                Ok(url) => {
+                                                    media.push(url);
+                                                    None
+                                                }
+                                                Err(e) => {
+                                                    warn!("Failed to download Telegram image: {}", e);
+                                                    Some("🖼️ Couldn't download the image, please try again.".to_string())
                                                 }
                                             }
                                         }
+                                        Attachment::File { name } => Some(format!(
+                                            "📎 I can only read images, not files like {}. Paste the text into a message instead.",
+                                            name
+                                        )),
+                                    };
+
+                                    if let Some(note) = note {
+                                        let _ = channel.send(&chat, &reply_format.apply(ReplyKind::Error, "telegram", &note)).await;
+                                        if text.is_empty() {
+                                            continue;
+                                        }
+                                    } else if text.is_empty() {
+                                        text = DEFAULT_IMAGE_PROMPT.to_string();
                                     }
-                                });
+                                }
+
+                                // Live status message while the agent works
+                                let bus = EventBus::new(32);
+                                let status = StatusMessage::start(&channel, &chat, &bus).await;
 
-                                // Process through agent
-                                match agent.process(
+                                let result = agent.process_with_media(
                                     provider.as_ref(),
                                     memory.as_ref(),
                                     &text,
+                                    media,
                                     &session_key,
                                     Some(&bus),
-                                ).await {
-                                    Ok(response) => {
-                                        // Once done, delete the "loading" message
-                                        if let Some(msg_id) = initial_msg_id {
-                                            let _ = delete_message(&client, &api_base, chat_id, msg_id).await;
-                                        }
+                                ).await;
+                                status.finish(&channel).await;
 
+                                match result {
+                                    Ok(response) => {
                                         info!("📤 [{}] Response: {} chars", chat_id, response.len());
-
-                                        // Split long messages (Telegram limit: 4096)
-                                        for chunk in split_message(&response, 4000) {
-                                            if let Err(e) = send_message(
-                                                &client,
-                                                &api_base,
-                                                chat_id,
-                                                &chunk,
-                                                None,
-                                            )
-                                            .await
-                                            {
-                                                error!("Failed to send message: {}", e);
-                                            }
+                                        let response = reply_format.apply(ReplyKind::Response, "telegram", &response);
+                                        if let Err(e) = channel.send(&chat, &response).await {
+                                            error!("Failed to send message: {}", e);
                                         }
                                     }
                                     Err(e) => {
-                                        if let Some(msg_id) = initial_msg_id {
-                                            let _ = delete_message(&client, &api_base, chat_id, msg_id).await;
-                                        }
-
                                         error!("Agent error: {}", e);
-                                        let _ = send_message(
-                                            &client,
-                                            &api_base,
-                                            chat_id,
-                                            &format!("❌ Error: {}", e),
-                                            None,
+                                        let _ = channel.send(
+                                            &chat,
+                                            &reply_format.apply(ReplyKind::Error, "telegram", &format!("❌ Error: {}", e)),
                                         )
                                         .await;
                                     }
@@ -256,8 +372,14 @@ impl TelegramChannel {
                         }
                     }
                     Err(e) => {
-                        error!("Polling error: {}. Retrying in 5s...", e);
-                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        let delay = backoff.fail();
+                        warn!(
+                            "Telegram polling failed: {} — reconnecting in {}s (attempt {})",
+                            e,
+                            delay.as_secs(),
+                            backoff.failures()
+                        );
+                        tokio::time::sleep(delay).await;
                     }
                 }
             }
@@ -266,29 +388,8 @@ impl TelegramChannel {
         Ok(())
     }
 
-    /// Get bot info to verify token.
-    async fn get_me(&self) -> Result<TgUser> {
-        let url = format!("{}/getMe", self.api_base);
-        let resp: TgResponse<TgUser> = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(|e| ZenClawError::Provider(format!("Telegram getMe failed: {}", e)))?;
-
-        if !resp.ok {
-            return Err(ZenClawError::Provider(
-                "Telegram bot token is invalid".into(),
-            ));
-        }
-        resp.result
-            .ok_or_else(|| ZenClawError::Provider("No result from getMe".into()))
-    }
-
     /// Stop the bot.
-    pub async fn stop(&mut self) {
+    async fn stop(&mut self) {
         if let Some(tx) = self.shutdown_tx.take() {
             let _ = tx.send(()).await;
         }
@@ -301,6 +402,8 @@ impl TelegramChannel {
 struct TgResponse<T> {
     ok: bool,
     result: Option<T>,
+    #[serde(default)]
+    description: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -313,11 +416,90 @@ struct TgUpdate {
 struct TgMessage {
     message_id: i64,
     text: Option<String>,
+    /// Text sent along with a photo or document.
+    caption: Option<String>,
+    /// Photo in several sizes, smallest first.
+    photo: Option<Vec<TgPhotoSize>>,
+    document: Option<TgDocument>,
     chat: TgChat,
     from: Option<TgUser>,
+    reply_to_message: Option<Box<TgMessage>>,
+}
+
+/// A file attached to a message.
+enum Attachment<'a> {
+    Image {
+        file_id: &'a str,
+        mime_type: &'a str,
+        size: Option<u64>,
+    },
+    /// Any non-image document.
+    File { name: &'a str },
 }
 
 impl TgMessage {
+    fn text_or_caption(&self) -> &str {
+        self.text
+            .as_deref()
+            .or(self.caption.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// The attached photo (largest size) or document, if any.
+    fn attachment(&self) -> Option<Attachment<'_>> {
+        if let Some(photo) = self.photo.as_ref().and_then(|sizes| sizes.last()) {
+            return Some(Attachment::Image {
+                file_id: &photo.file_id,
+                // Telegram re-encodes photos as JPEG
+                mime_type: "image/jpeg",
+                size: photo.file_size,
+            });
+        }
+        let doc = self.document.as_ref()?;
+        match doc.mime_type.as_deref() {
+            Some(mime) if mime.starts_with("image/") => Some(Attachment::Image {
+                file_id: &doc.file_id,
+                mime_type: mime,
+                size: doc.file_size,
+            }),
+            _ => Some(Attachment::File {
+                name: doc.file_name.as_deref().unwrap_or("this one"),
+            }),
+        }
+    }
+
+    /// Private chats always address the bot. In groups the bot must be
+    /// @mentioned, replied to, or sent a command.
+    fn is_addressed_to(&self, bot_id: i64, bot_username: &str) -> bool {
+        if self.chat.is_private() {
+            return true;
+        }
+
+        let replied_to_bot = self
+            .reply_to_message
+            .as_ref()
+            .and_then(|m| m.from.as_ref())
+            .map(|u| u.id == bot_id)
+            .unwrap_or(false);
+        if replied_to_bot {
+            return true;
+        }
+
+        let text = self.text_or_caption();
+        if text.starts_with('/') {
+            // `/cmd@OtherBot` is meant for someone else
+            return match text.split_whitespace().next().and_then(|c| c.split_once('@')) {
+                Some((_, target)) => target.eq_ignore_ascii_case(bot_username),
+                None => true,
+            };
+        }
+
+        !bot_username.is_empty()
+            && text
+                .to_lowercase()
+                .contains(&format!("@{}", bot_username.to_lowercase()))
+    }
+
     fn sender_id(&self) -> i64 {
         self.from.as_ref().map(|u| u.id).unwrap_or(0)
     }
@@ -334,9 +516,37 @@ impl TgMessage {
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct TgPhotoSize {
+    file_id: String,
+    file_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TgDocument {
+    file_id: String,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    file_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TgFile {
+    file_path: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct TgChat {
     id: i64,
+    /// "private", "group", "supergroup" or "channel".
+    #[serde(rename = "type", default)]
+    chat_type: String,
+}
+
+impl TgChat {
+    fn is_private(&self) -> bool {
+        self.chat_type.is_empty() || self.chat_type == "private"
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -354,6 +564,40 @@ struct SendMessageBody {
     parse_mode: Option<String>,
 }
 
+/// Send `text` to `chat_id` without running the bot (e.g. scheduled job results).
+pub async fn notify(bot_token: &str, chat_id: i64, text: &str) -> Result<()> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap_or_default();
+    let api_base = format!("https://api.telegram.org/bot{}", bot_token);
+    send_text(&client, &api_base, chat_id, text, Some("Markdown")).await?;
+    Ok(())
+}
+
+/// Send a message of any length (Telegram limit: 4096), reporting
+/// Telegram's reason on failure. Returns the id of the last part.
+async fn send_text(client: &Client, api_base: &str, chat_id: i64, text: &str, parse_mode: Option<&str>) -> Result<i64> {
+    if chat_id == 0 {
+        return Err(ZenClawError::Config("invalid Telegram chat id 0".into()));
+    }
+    let mut last_id = 0;
+    for chunk in split_message(text, 4000) {
+        let resp = send_message(client, api_base, chat_id, &chunk, parse_mode).await?;
+        match resp.result {
+            Some(msg) if resp.ok => last_id = msg.message_id,
+            _ => {
+                return Err(ZenClawError::Provider(format!(
+                    "Telegram rejected the message to chat {}: {}",
+                    chat_id,
+                    resp.description.unwrap_or_else(|| "no reason given".into())
+                )));
+            }
+        }
+    }
+    Ok(last_id)
+}
+
 // ─── API Helpers ───────────────────────────────────────────
 
 async fn get_updates(
@@ -379,6 +623,35 @@ async fn get_updates(
     Ok(resp.result.unwrap_or_default())
 }
 
+/// Download a file sent to the bot as a `data:` URL.
+///
+/// Telegram file URLs contain the bot token, so the bytes are inlined
+/// instead of passing the URL on to the model provider.
+async fn download_data_url(client: &Client, api_base: &str, file_id: &str, mime_type: &str) -> Result<String> {
+    use base64::Engine;
+
+    let url = format!("{}/getFile?file_id={}", api_base, file_id);
+    let resp: TgResponse<TgFile> = client
+        .get(&url)
+        .send()
+        .await?
+        .json()
+        .await
+        .map_err(|e| ZenClawError::Provider(format!("getFile parse error: {}", e)))?;
+    let path = resp
+        .result
+        .and_then(|f| f.file_path)
+        .ok_or_else(|| ZenClawError::Provider("getFile returned no file_path".into()))?;
+
+    let file_url = format!("{}/{}", api_base.replacen("/bot", "/file/bot", 1), path);
+    let bytes = client.get(&file_url).send().await?.error_for_status()?.bytes().await?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime_type,
+        base64::engine::general_purpose::STANDARD.encode(&bytes)
+    ))
+}
+
 async fn send_message(
     client: &Client,
     api_base: &str,
@@ -417,8 +690,6 @@ struct EditMessageBody {
     chat_id: i64,
     message_id: i64,
     text: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    parse_mode: Option<String>,
 }
 
 async fn edit_message(
@@ -427,14 +698,12 @@ async fn edit_message(
     chat_id: i64,
     message_id: i64,
     text: &str,
-    parse_mode: Option<&str>,
 ) -> Result<()> {
     let url = format!("{}/editMessageText", api_base);
     let body = EditMessageBody {
         chat_id,
         message_id,
         text: text.to_string(),
-        parse_mode: parse_mode.map(String::from),
     };
 
     let _ = client.post(&url).json(&body).send().await;
@@ -459,6 +728,22 @@ async fn delete_message(
     Ok(())
 }
 
+/// Remove `@botname` mentions (and `/cmd@botname` suffixes) from a message.
+fn strip_mention(text: &str, bot_username: &str) -> String {
+    if bot_username.is_empty() {
+        return text.trim().to_string();
+    }
+    let mention = format!("@{}", bot_username.to_ascii_lowercase());
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.to_ascii_lowercase().find(&mention) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos + mention.len()..];
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
 /// Split a long message into chunks at line boundaries.
 fn split_message(text: &str, max_len: usize) -> Vec<String> {
     if text.len() <= max_len {
@@ -495,3 +780,47 @@ fn split_message(text: &str, max_len: usize) -> Vec<String> {
 
     chunks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::mock;
+
+    fn channel(api_base: &str) -> TelegramChannel {
+        TelegramChannel::new(TelegramConfig {
+            bot_token: "123:abc".into(),
+            allowed_users: Vec::new(),
+            poll_timeout: 30,
+            reply_format: ReplyFormat::default(),
+            greeting: None,
+            rate_limit: None,
+            reconnect: ReconnectSettings::default(),
+        })
+        .with_api_base(api_base)
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_message() {
+        let (url, received) = mock::server(200, r#"{"ok":true,"result":{"message_id":7,"chat":{"id":42,"type":"private"}}}"#).await;
+        let id = channel(&url).send("42", "Daily report").await.unwrap();
+        assert_eq!(id, "7");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].path, "/bot123:abc/sendMessage");
+        assert_eq!(
+            received[0].body,
+            serde_json::json!({ "chat_id": 42, "text": "Daily report" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_surfaces_api_error() {
+        let (url, _) = mock::server(400, r#"{"ok":false,"description":"Bad Request: chat not found"}"#).await;
+        let err = channel(&url).send("42", "hi").await.unwrap_err();
+        assert!(err.to_string().contains("chat not found"), "{}", err);
+
+        assert!(channel(&url).send("0", "hi").await.is_err());
+        assert!(channel(&url).send("@someone", "hi").await.is_err());
+    }
+}
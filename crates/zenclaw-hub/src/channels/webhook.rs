@@ -0,0 +1,169 @@
+//! Outbound webhook channel — POST every agent turn to a URL.
+//!
+//! Subscribes to an [`EventBus`] and forwards each `agent_done` event
+//! (input, output, tools called, token usage) as JSON to a configured URL.
+//! When a secret is set, the body is signed with HMAC-SHA256 in the
+//! `X-ZenClaw-Signature: sha256=<hex>` header.
+//!
+//! Deliveries go through a small in-memory queue: a failed POST is retried
+//! with backoff, so a short outage of the receiving end loses no events.
+//! When the queue is full the oldest event is dropped.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Client;
+use tokio::sync::{broadcast, Mutex, Notify};
+use tracing::{debug, info, warn};
+
+use zenclaw_core::bus::{EventBus, SystemEvent};
+
+/// Events kept while the endpoint is unreachable.
+const MAX_QUEUE: usize = 100;
+/// Longest wait between two delivery attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Outbound webhook configuration.
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Endpoint receiving the JSON POSTs.
+    pub url: String,
+    /// Shared secret for the `X-ZenClaw-Signature` header (None = unsigned).
+    pub secret: Option<String>,
+}
+
+/// Outbound webhook — forwards agent turns to an HTTP endpoint.
+pub struct WebhookChannel {
+    config: WebhookConfig,
+    client: Client,
+    queue: Mutex<VecDeque<serde_json::Value>>,
+    notify: Notify,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            client: Client::builder()
+                .user_agent("zenclaw")
+                .timeout(Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            queue: Mutex::new(VecDeque::new()),
+            notify: Notify::new(),
+        })
+    }
+
+    /// Start forwarding turns published on `bus` — runs in background,
+    /// returns immediately.
+    pub fn start(self: &Arc<Self>, bus: &EventBus) {
+        info!("🪝 Outbound webhook → {}", self.config.url);
+
+        let mut rx = bus.subscribe_system();
+        let collector = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) if event.event_type == "agent_done" => collector.enqueue(&event).await,
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Webhook subscriber lagged, {} event(s) skipped", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let sender = self.clone();
+        tokio::spawn(async move { sender.deliver_loop().await });
+    }
+
+    /// Number of events waiting to be delivered.
+    pub async fn pending(&self) -> usize {
+        self.queue.lock().await.len()
+    }
+
+    async fn enqueue(&self, event: &SystemEvent) {
+        let payload = serde_json::json!({
+            "event": "turn",
+            "session": event.run_id,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "success": event.data["success"],
+            "error": event.data["error"],
+            "input": event.data["input"],
+            "output": event.data["output"],
+            "tools": event.data["tools"],
+            "iterations": event.data["iterations"],
+            "elapsed_ms": event.data["elapsed_ms"],
+            "usage": {
+                "prompt_tokens": event.data["prompt_tokens"],
+                "completion_tokens": event.data["completion_tokens"],
+                "total_tokens": event.data["total_tokens"],
+                "estimated": event.data["estimated"],
+            },
+        });
+
+        let mut queue = self.queue.lock().await;
+        if queue.len() >= MAX_QUEUE {
+            queue.pop_front();
+            warn!("Webhook queue full — dropped the oldest event");
+        }
+        queue.push_back(payload);
+        drop(queue);
+        self.notify.notify_one();
+    }
+
+    /// Deliver queued events in order; the head stays queued until it succeeds.
+    async fn deliver_loop(&self) {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            let next = self.queue.lock().await.front().cloned();
+            let Some(payload) = next else {
+                self.notify.notified().await;
+                continue;
+            };
+
+            match self.post(&payload).await {
+                Ok(()) => {
+                    self.queue.lock().await.pop_front();
+                    backoff = Duration::from_secs(1);
+                }
+                Err(e) => {
+                    warn!("Webhook delivery to {} failed: {} — retrying in {}s", self.config.url, e, backoff.as_secs());
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    async fn post(&self, payload: &serde_json::Value) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(payload)?;
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(secret) = self.config.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header("X-ZenClaw-Signature", format!("sha256={}", sign(secret, &body)));
+        }
+
+        let resp = request.body(body).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            anyhow::bail!("HTTP {}", status);
+        }
+        debug!("Webhook delivered to {} ({})", self.config.url, status);
+        Ok(())
+    }
+}
+
+/// HMAC-SHA256 of `body` as lowercase hex.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("{:x}", mac.finalize().into_bytes())
+}
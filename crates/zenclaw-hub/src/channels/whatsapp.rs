@@ -9,7 +9,7 @@
 //!
 //! The bridge exposes:
 //! - GET  /messages — poll for new messages
-//! - POST /send     — send a message
+//! - POST /send     — send a message (`{"to": "<id>@c.us", "message": "..."}`)
 //!
 //! You can use any Baileys-based bridge, e.g. whatsapp-web.js or wa-automate-nodejs.
 
@@ -20,9 +20,11 @@ use serde::Deserialize;
 use tracing::{error, info, warn};
 
 use zenclaw_core::agent::Agent;
+use zenclaw_core::channel::{ReplyFormat, ReplyKind};
 use zenclaw_core::error::{Result, ZenClawError};
 use zenclaw_core::memory::MemoryStore;
 use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::ratelimit::{too_fast_message, RateLimiter};
 use tokio::process::Command;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
@@ -43,12 +45,48 @@ pub struct WaMessage {
 
 
 
+/// Chat id the bridge sends to: a phone number in international format
+/// (`+62 812-3456-7890` → `6281234567890@c.us`), or a WhatsApp id as it
+/// appears in incoming messages (`...@c.us`, `...@g.us`).
+pub fn chat_id(to: &str) -> Result<String> {
+    let invalid = || {
+        ZenClawError::Config(format!(
+            "invalid WhatsApp number '{}' (use international format, e.g. +6281234567890)",
+            to
+        ))
+    };
+    let to = to.trim();
+
+    if let Some((user, server)) = to.split_once('@') {
+        let user_ok = !user.is_empty() && user.chars().all(|c| c.is_ascii_digit() || c == '-');
+        return if user_ok && matches!(server, "c.us" | "g.us" | "s.whatsapp.net") {
+            Ok(to.to_string())
+        } else {
+            Err(invalid())
+        };
+    }
+
+    let number = to.strip_prefix('+').unwrap_or(to);
+    if number.chars().any(|c| !(c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')'))) {
+        return Err(invalid());
+    }
+    let digits: String = number.chars().filter(char::is_ascii_digit).collect();
+    // E.164 numbers have at most 15 digits
+    if !(7..=15).contains(&digits.len()) {
+        return Err(invalid());
+    }
+    Ok(format!("{}@c.us", digits))
+}
+
 /// WhatsApp channel adapter.
 pub struct WhatsAppChannel {
     bridge_url: String,
     client: reqwest::Client,
     allowed_numbers: Option<HashSet<String>>,
     poll_interval_ms: u64,
+    reply_format: ReplyFormat,
+    greeting: Option<String>,
+    rate_limit: Option<Arc<RateLimiter>>,
     shutdown_tx: Option<tokio::sync::mpsc::Sender<()>>,
 }
 
@@ -59,6 +97,9 @@ impl WhatsAppChannel {
             client: reqwest::Client::new(),
             allowed_numbers: None,
             poll_interval_ms: 2000,
+            reply_format: ReplyFormat::default(),
+            greeting: None,
+            rate_limit: None,
             shutdown_tx: None,
         }
     }
@@ -75,6 +116,46 @@ impl WhatsAppChannel {
         self
     }
 
+    /// Set the prefix/suffix added to replies.
+    pub fn with_reply_format(mut self, format: ReplyFormat) -> Self {
+        self.reply_format = format;
+        self
+    }
+
+    /// Set the onboarding message sent the first time a number is seen.
+    pub fn with_greeting(mut self, greeting: Option<String>) -> Self {
+        self.greeting = greeting;
+        self
+    }
+
+    /// Limit how fast each number may send messages.
+    pub fn with_rate_limit(mut self, limiter: Option<Arc<RateLimiter>>) -> Self {
+        self.rate_limit = limiter;
+        self
+    }
+
+    /// Send `text` to `number` without an incoming message (e.g. scheduled
+    /// job results). Works without [`start`](Self::start) as long as the
+    /// bridge is running.
+    pub async fn send(&self, number: &str, text: &str) -> Result<()> {
+        let to = chat_id(number)?;
+        let resp = self
+            .client
+            .post(format!("{}/send", self.bridge_url))
+            .json(&serde_json::json!({ "to": to, "message": text }))
+            .send()
+            .await
+            .map_err(|e| ZenClawError::Provider(format!("WhatsApp bridge unreachable at {}: {}", self.bridge_url, e)))?;
+
+        let status = resp.status();
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        if !status.is_success() || body["success"] == false {
+            let reason = body["error"].as_str().map(str::to_string).unwrap_or_else(|| status.to_string());
+            return Err(ZenClawError::Provider(format!("WhatsApp bridge couldn't send to {}: {}", to, reason)));
+        }
+        Ok(())
+    }
+
     /// Start the WhatsApp bot loop — runs in background, returns immediately.
     pub async fn start(
         &mut self,
@@ -163,6 +244,10 @@ impl WhatsAppChannel {
         let client = self.client.clone();
         let allowed_numbers = self.allowed_numbers.clone();
         let poll_interval_ms = self.poll_interval_ms;
+        let reply_format = self.reply_format.clone();
+        let greeting = self.greeting.clone();
+        let rate_limit = self.rate_limit.clone();
+        let model = agent.config.model.clone().unwrap_or_else(|| provider.default_model().to_string());
 
         // Clone shared app components
         let agent = agent.clone();
@@ -196,16 +281,45 @@ impl WhatsAppChannel {
                                 let provider_ref = provider.as_ref();
                                 let memory_ref = memory.as_ref();
 
+                                if let Some(hello) = super::first_contact_greeting(
+                                    memory_ref,
+                                    greeting.as_deref(),
+                                    &session_key,
+                                    "whatsapp",
+                                    "ZenClaw",
+                                    &model,
+                                ).await {
+                                    let send_url = format!("{}/send", bridge_url);
+                                    let _ = client.post(&send_url).json(&serde_json::json!({ "to": msg.from, "message": hello })).send().await;
+                                }
+
+                                if let Some(reply) = zenclaw_core::locale::command(memory_ref, &session_key, &msg.body).await {
+                                    let send_url = format!("{}/send", bridge_url);
+                                    let reply = reply_format.apply(ReplyKind::Command, "whatsapp", &reply);
+                                    let _ = client.post(&send_url).json(&serde_json::json!({ "to": msg.from, "message": reply })).send().await;
+                                    continue;
+                                }
+
+                                if let Some(limiter) = &rate_limit
+                                    && let Err(wait) = limiter.check(&session_key)
+                                {
+                                    let send_url = format!("{}/send", bridge_url);
+                                    let reply = reply_format.apply(ReplyKind::Error, "whatsapp", &too_fast_message(wait));
+                                    let _ = client.post(&send_url).json(&serde_json::json!({ "to": msg.from, "message": reply })).send().await;
+                                    continue;
+                                }
+
                                 match agent.process(provider_ref, memory_ref, &msg.body, &session_key, None).await {
                                     Ok(response) => {
                                         info!("📤 → {}: {}...", sender, if response.len() > 80 { &response[..80] } else { &response });
+                                        let response = reply_format.apply(ReplyKind::Response, "whatsapp", &response);
                                         let send_url = format!("{}/send", bridge_url);
                                         let _ = client.post(&send_url).json(&serde_json::json!({ "to": msg.from, "message": response })).send().await;
                                     }
                                     Err(e) => {
                                         error!("Agent error for {}: {}", sender, e);
                                         let send_url = format!("{}/send", bridge_url);
-                                        let _ = client.post(&send_url).json(&serde_json::json!({ "to": msg.from, "message": format!("❌ Error: {}", e) })).send().await;
+                                        let _ = client.post(&send_url).json(&serde_json::json!({ "to": msg.from, "message": reply_format.apply(ReplyKind::Error, "whatsapp", &format!("❌ Error: {}", e)) })).send().await;
                                     }
                                 }
                             }
@@ -230,3 +344,44 @@ impl WhatsAppChannel {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::mock;
+
+    #[test]
+    fn test_chat_id() {
+        assert_eq!(chat_id("+62 812-3456-7890").unwrap(), "6281234567890@c.us");
+        assert_eq!(chat_id("14155550123").unwrap(), "14155550123@c.us");
+        assert_eq!(chat_id("120363025246125486@g.us").unwrap(), "120363025246125486@g.us");
+        assert!(chat_id("12345").is_err());
+        assert!(chat_id("call me maybe").is_err());
+        assert!(chat_id("6281234567890@example.com").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_to_bridge() {
+        let (url, received) = mock::server(200, r#"{"success":true}"#).await;
+        WhatsAppChannel::new(&url).send("+6281234567890", "Daily report").await.unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].path, "/send");
+        assert_eq!(
+            received[0].body,
+            serde_json::json!({ "to": "6281234567890@c.us", "message": "Daily report" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_surfaces_bridge_error() {
+        let (url, _) = mock::server(500, r#"{"error":"Evaluation failed: wid error"}"#).await;
+        let err = WhatsAppChannel::new(&url).send("6281234567890", "hi").await.unwrap_err();
+        assert!(err.to_string().contains("wid error"), "{}", err);
+
+        // Invalid numbers never reach the bridge
+        let (url, received) = mock::server(200, r#"{"success":true}"#).await;
+        assert!(WhatsAppChannel::new(&url).send("not-a-number", "hi").await.is_err());
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Readiness checks — verify ZenClaw can reach what it depends on.
+//!
+//! Used by `zenclaw doctor` and `GET /health?deep=true`. Unlike the status
+//! screen, these checks talk to the provider, Ollama and the database
+//! instead of only looking at the configuration.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use zenclaw_core::memory::MemoryStore;
+use zenclaw_core::message::ChatMessage;
+use zenclaw_core::provider::{ChatRequest, LlmProvider};
+
+use crate::memory::SqliteMemory;
+
+/// Longest wait for the provider ping.
+pub const PROVIDER_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest wait for Ollama to answer.
+const OLLAMA_TIMEOUT: Duration = Duration::from_secs(2);
+/// Where a local Ollama listens by default.
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// API key environment variables, by provider.
+pub const API_KEY_VARS: &[(&str, &str)] = &[
+    ("openai", "OPENAI_API_KEY"),
+    ("anthropic", "ANTHROPIC_API_KEY"),
+    ("gemini", "GEMINI_API_KEY"),
+    ("gemini", "GOOGLE_API_KEY"),
+    ("openrouter", "OPENROUTER_API_KEY"),
+    ("groq", "GROQ_API_KEY"),
+];
+
+/// Outcome of one check, ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn icon(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        }
+    }
+}
+
+/// One readiness check.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Results of a doctor run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn push(&mut self, check: Check) {
+        self.checks.push(check);
+    }
+
+    /// Worst status of all checks (Pass when there are none).
+    pub fn status(&self) -> CheckStatus {
+        self.checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Pass)
+    }
+
+    /// One line per check, e.g. `✅ provider — openai replied in 420ms`.
+    pub fn render(&self) -> String {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        self.checks
+            .iter()
+            .map(|c| format!("{} {:width$}  {}", c.status.icon(), c.name, c.detail, width = width))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Ask the provider for a 1-token completion.
+pub async fn check_provider(provider: &dyn LlmProvider, model: Option<&str>) -> Check {
+    let model = model.unwrap_or(provider.default_model()).to_string();
+    let request = ChatRequest {
+        messages: vec![ChatMessage::user("ping")],
+        model: Some(model.clone()),
+        max_tokens: 1,
+        temperature: 0.0,
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    match tokio::time::timeout(PROVIDER_TIMEOUT, provider.chat(request)).await {
+        Ok(Ok(_)) => Check::new(
+            "provider",
+            CheckStatus::Pass,
+            format!("{} ({}) replied in {}ms", provider.name(), model, started.elapsed().as_millis()),
+        ),
+        Ok(Err(e)) => Check::new("provider", CheckStatus::Fail, format!("{} ({}): {}", provider.name(), model, e)),
+        Err(_) => Check::new(
+            "provider",
+            CheckStatus::Fail,
+            format!("{} ({}) didn't reply within {}s", provider.name(), model, PROVIDER_TIMEOUT.as_secs()),
+        ),
+    }
+}
+
+/// Check that Ollama answers at `base_url`. Unreachable is only a failure
+/// when Ollama is the configured provider (`required`).
+pub async fn check_ollama(base_url: &str, required: bool) -> Check {
+    let url = format!("{}/api/tags", base_url.trim_end_matches('/').trim_end_matches("/v1"));
+    let result = match reqwest::Client::builder().timeout(OLLAMA_TIMEOUT).build() {
+        Ok(client) => client.get(&url).send().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    match result {
+        Ok(resp) if resp.status().is_success() => {
+            let models = resp
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|v| v["models"].as_array().map(|m| m.len()))
+                .unwrap_or(0);
+            Check::new("ollama", CheckStatus::Pass, format!("{} is up, {} model(s) pulled", base_url, models))
+        }
+        Ok(resp) => Check::new("ollama", CheckStatus::Fail, format!("{} answered {}", base_url, resp.status())),
+        Err(e) => {
+            let status = if required { CheckStatus::Fail } else { CheckStatus::Warn };
+            Check::new("ollama", status, format!("{} not reachable ({})", base_url, e))
+        }
+    }
+}
+
+/// Open the SQLite memory at `path` and read from it.
+pub fn check_database(path: &Path) -> Check {
+    match SqliteMemory::open(path).and_then(|m| m.stats()) {
+        Ok(stats) => Check::new(
+            "database",
+            CheckStatus::Pass,
+            format!("{} — {} sessions, {} messages", path.display(), stats.sessions, stats.messages),
+        ),
+        Err(e) => Check::new("database", CheckStatus::Fail, format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Read a fact back from `memory` (any backend).
+pub async fn check_memory(memory: &dyn MemoryStore) -> Check {
+    match memory.get_fact("doctor:ping").await {
+        Ok(_) => Check::new("database", CheckStatus::Pass, "memory store answers"),
+        Err(e) => Check::new("database", CheckStatus::Fail, e.to_string()),
+    }
+}
+
+/// Which API key environment variables are set. Warns when none are,
+/// unless the provider runs locally.
+pub fn check_api_keys(provider_name: &str) -> Check {
+    let set: Vec<&str> = API_KEY_VARS
+        .iter()
+        .map(|(_, var)| *var)
+        .filter(|var| std::env::var(var).is_ok_and(|v| !v.trim().is_empty()))
+        .collect();
+
+    let local = matches!(provider_name, "ollama" | "lmstudio");
+    match (set.is_empty(), local) {
+        (false, _) => Check::new("api keys", CheckStatus::Pass, format!("set: {}", set.join(", "))),
+        (true, true) => Check::new("api keys", CheckStatus::Pass, "none set (not needed for a local provider)"),
+        (true, false) => Check::new(
+            "api keys",
+            CheckStatus::Warn,
+            "no API key environment variable set (the key in the config file is still used)",
+        ),
+    }
+}
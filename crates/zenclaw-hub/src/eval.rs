@@ -0,0 +1,304 @@
+//! Eval harness — compare providers/models on your own prompts.
+//!
+//! Reads prompts from a JSONL file (one `{"prompt": "..."}` object per line,
+//! optional `id` and `expected`), runs every prompt against every target in
+//! parallel, and records latency, token usage, estimated cost and output.
+//! An optional judge model scores each answer from 1 to 10.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::message::ChatMessage;
+use zenclaw_core::provider::{ChatRequest, LlmProvider};
+
+/// One prompt of an eval set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalPrompt {
+    /// Identifier in the report (defaults to the line number).
+    #[serde(default)]
+    pub id: String,
+    pub prompt: String,
+    /// Reference answer, shown to the judge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+/// A provider + model to evaluate.
+#[derive(Clone)]
+pub struct EvalTarget {
+    /// Label in the report, e.g. `groq:llama-3.3-70b-versatile`.
+    pub label: String,
+    pub model: String,
+    pub provider: Arc<dyn LlmProvider>,
+}
+
+/// Outcome of one prompt on one target.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalResult {
+    pub prompt_id: String,
+    pub target: String,
+    pub latency_ms: u64,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    /// Estimated USD cost (None = unknown price).
+    pub cost_usd: Option<f64>,
+    pub output: String,
+    pub error: Option<String>,
+    /// Judge score from 1 to 10.
+    pub score: Option<u8>,
+}
+
+/// Eval run settings.
+pub struct EvalOptions {
+    /// Requests in flight at once.
+    pub concurrency: usize,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    /// Model scoring the answers (None = no scoring).
+    pub judge: Option<EvalTarget>,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            max_tokens: 1024,
+            temperature: 0.2,
+            judge: None,
+        }
+    }
+}
+
+/// Load an eval set from a JSONL file. Blank lines and `#` comments are skipped.
+pub fn load_prompts(path: &Path) -> Result<Vec<EvalPrompt>> {
+    let content = std::fs::read_to_string(path)?;
+    let mut prompts = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut prompt: EvalPrompt = serde_json::from_str(line)
+            .map_err(|e| ZenClawError::Other(format!("{}:{}: {}", path.display(), i + 1, e)))?;
+        if prompt.id.is_empty() {
+            prompt.id = (i + 1).to_string();
+        }
+        prompts.push(prompt);
+    }
+    Ok(prompts)
+}
+
+/// Run every prompt against every target. Results are ordered by prompt,
+/// then target. `on_done` is called after each finished run (for progress).
+pub async fn run(
+    prompts: &[EvalPrompt],
+    targets: &[EvalTarget],
+    options: &EvalOptions,
+    on_done: impl Fn(&EvalResult) + Send + Sync + 'static,
+) -> Vec<EvalResult> {
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let on_done = Arc::new(on_done);
+    let mut tasks = Vec::new();
+
+    for prompt in prompts {
+        for target in targets {
+            let semaphore = semaphore.clone();
+            let on_done = on_done.clone();
+            let prompt = prompt.clone();
+            let target = target.clone();
+            let judge = options.judge.clone();
+            let (max_tokens, temperature) = (options.max_tokens, options.temperature);
+
+            tasks.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                let mut result = run_one(&prompt, &target, max_tokens, temperature).await;
+                if let Some(judge) = judge
+                    && result.error.is_none()
+                {
+                    result.score = score(&judge, &prompt, &result.output).await;
+                }
+                on_done(&result);
+                result
+            }));
+        }
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        match task.await {
+            Ok(result) => results.push(result),
+            Err(e) => warn!("Eval task failed: {}", e),
+        }
+    }
+    results
+}
+
+async fn run_one(prompt: &EvalPrompt, target: &EvalTarget, max_tokens: u32, temperature: f32) -> EvalResult {
+    let request = ChatRequest {
+        messages: vec![ChatMessage::user(&prompt.prompt)],
+        model: Some(target.model.clone()),
+        max_tokens,
+        temperature,
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let response = target.provider.chat(request).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut result = EvalResult {
+        prompt_id: prompt.id.clone(),
+        target: target.label.clone(),
+        latency_ms,
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        cost_usd: None,
+        output: String::new(),
+        error: None,
+        score: None,
+    };
+    match response {
+        Ok(resp) => {
+            result.prompt_tokens = resp.usage.prompt_tokens;
+            result.completion_tokens = resp.usage.completion_tokens;
+            result.cost_usd = zenclaw_core::tokens::estimate_cost(
+                &target.model,
+                resp.usage.prompt_tokens,
+                resp.usage.completion_tokens,
+            );
+            result.output = resp.content.unwrap_or_default();
+        }
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    result
+}
+
+/// Ask the judge to rate an answer from 1 to 10.
+async fn score(judge: &EvalTarget, prompt: &EvalPrompt, output: &str) -> Option<u8> {
+    let reference = prompt
+        .expected
+        .as_deref()
+        .map(|e| format!("\n\nReference answer:\n{}", e))
+        .unwrap_or_default();
+    let instruction = format!(
+        "Rate the answer to the question below for correctness, completeness and clarity on a scale from 1 (useless) to 10 (excellent). Reply with the number only.\n\nQuestion:\n{}{}\n\nAnswer:\n{}",
+        prompt.prompt, reference, output
+    );
+    let request = ChatRequest {
+        messages: vec![ChatMessage::user(&instruction)],
+        model: Some(judge.model.clone()),
+        max_tokens: 8,
+        temperature: 0.0,
+        ..Default::default()
+    };
+
+    let reply = judge.provider.chat(request).await.ok()?.content?;
+    let digits: String = reply
+        .trim()
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse::<u8>().ok().filter(|s| (1..=10).contains(s))
+}
+
+/// Per-target totals of a run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TargetSummary {
+    pub target: String,
+    pub runs: usize,
+    pub errors: usize,
+    pub avg_latency_ms: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: Option<f64>,
+    pub avg_score: Option<f64>,
+}
+
+/// Summarize results per target, in first-seen order.
+pub fn summarize(results: &[EvalResult]) -> Vec<TargetSummary> {
+    let mut summaries: Vec<TargetSummary> = Vec::new();
+
+    for r in results {
+        let idx = match summaries.iter().position(|s| s.target == r.target) {
+            Some(i) => i,
+            None => {
+                summaries.push(TargetSummary {
+                    target: r.target.clone(),
+                    ..Default::default()
+                });
+                summaries.len() - 1
+            }
+        };
+        let s = &mut summaries[idx];
+        s.runs += 1;
+        if r.error.is_some() {
+            s.errors += 1;
+        }
+        s.avg_latency_ms += r.latency_ms;
+        s.prompt_tokens += r.prompt_tokens as u64;
+        s.completion_tokens += r.completion_tokens as u64;
+        if let Some(cost) = r.cost_usd {
+            s.cost_usd = Some(s.cost_usd.unwrap_or(0.0) + cost);
+        }
+    }
+
+    for s in &mut summaries {
+        s.avg_latency_ms /= s.runs.max(1) as u64;
+        let scores: Vec<u8> = results
+            .iter()
+            .filter(|r| r.target == s.target)
+            .filter_map(|r| r.score)
+            .collect();
+        if !scores.is_empty() {
+            s.avg_score = Some(scores.iter().map(|&v| v as f64).sum::<f64>() / scores.len() as f64);
+        }
+    }
+    summaries
+}
+
+/// Markdown comparison report: a summary table, then every output.
+pub fn report_markdown(prompts: &[EvalPrompt], results: &[EvalResult]) -> String {
+    let mut out = String::from("# ZenClaw eval report\n\n");
+    out.push_str(&format!(
+        "{} prompt(s), generated {}\n\n",
+        prompts.len(),
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    ));
+
+    out.push_str("| Target | Runs | Errors | Avg latency | Tokens (in/out) | Est. cost | Avg score |\n");
+    out.push_str("|---|---|---|---|---|---|---|\n");
+    for s in summarize(results) {
+        out.push_str(&format!(
+            "| {} | {} | {} | {:.2}s | {}/{} | {} | {} |\n",
+            s.target,
+            s.runs,
+            s.errors,
+            s.avg_latency_ms as f64 / 1000.0,
+            s.prompt_tokens,
+            s.completion_tokens,
+            s.cost_usd.map(|c| format!("${:.4}", c)).unwrap_or_else(|| "—".into()),
+            s.avg_score.map(|v| format!("{:.1}", v)).unwrap_or_else(|| "—".into()),
+        ));
+    }
+
+    for prompt in prompts {
+        out.push_str(&format!("\n## Prompt {}\n\n> {}\n", prompt.id, prompt.prompt.replace('\n', "\n> ")));
+        for r in results.iter().filter(|r| r.prompt_id == prompt.id) {
+            let score = r.score.map(|s| format!(", score {}/10", s)).unwrap_or_default();
+            out.push_str(&format!("\n### {} ({:.2}s{})\n\n", r.target, r.latency_ms as f64 / 1000.0, score));
+            match &r.error {
+                Some(e) => out.push_str(&format!("**Error:** {}\n", e)),
+                None => out.push_str(&format!("{}\n", r.output.trim())),
+            }
+        }
+    }
+    out
+}
@@ -2,16 +2,23 @@
 //!
 //! Full AI agent implementation with providers, tools, persistent memory,
 //! channel adapters (Telegram, Discord, WhatsApp), skills, plugins, RAG,
-//! multi-agent router, REST API server, middleware, metrics, and auto-updater.
+//! multi-agent router, scheduled prompts, REST API server, middleware, metrics,
+//! and auto-updater.
 
 pub mod api;
+pub mod audit;
 pub mod channels;
+pub mod doctor;
+pub mod eval;
 pub mod memory;
 pub mod metrics;
 pub mod middleware;
 pub mod plugins;
+pub mod prompts;
 pub mod providers;
+pub mod remote_prompt;
 pub mod router;
+pub mod scheduler;
 pub mod skills;
 pub mod tools;
 pub mod updater;
@@ -1,5 +1,5 @@
 pub mod sqlite;
 pub mod rag;
 
-pub use sqlite::SqliteMemory;
-pub use rag::RagStore;
+pub use sqlite::{MemoryStats, SqliteMemory, SqliteOptions};
+pub use rag::{ChunkOptions, RagStore, ScoredChunk, SourceInfo};
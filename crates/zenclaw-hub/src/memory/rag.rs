@@ -2,12 +2,24 @@
 //!
 //! Provides full-text search across conversation history and documents.
 //! Uses SQLite FTS5 — no external vector DB needed, keeps the binary tiny.
+//!
+//! With an [`EmbeddingProvider`] attached, [`RagStore::ingest_document`]
+//! also stores one embedding per chunk (as a BLOB next to the text) and
+//! [`RagStore::semantic_search`] ranks chunks by cosine similarity. All
+//! vectors in a store must have the same dimension.
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, TransactionBehavior};
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::memory::MemoryStore;
+use zenclaw_core::message::Role;
+use zenclaw_core::provider::EmbeddingProvider;
+use zenclaw_core::tokens::estimate_bpe;
+
+use super::SqliteOptions;
 
 /// A searchable document chunk.
 #[derive(Debug, Clone)]
@@ -19,19 +31,69 @@ pub struct Document {
     pub rank: f64,
 }
 
+/// An ingested source (file, URL, ...) and how many chunks it was split into.
+#[derive(Debug, Clone)]
+pub struct SourceInfo {
+    /// Id of the first chunk.
+    pub id: i64,
+    pub source: String,
+    pub chunks: usize,
+    pub created_at: String,
+}
+
+/// A chunk returned by [`RagStore::semantic_search`].
+#[derive(Debug, Clone)]
+pub struct ScoredChunk {
+    pub document: Document,
+    /// Cosine similarity to the query (-1..1), or the inverted FTS5 rank
+    /// when the store has no embeddings to compare against.
+    pub score: f32,
+}
+
+/// How [`RagStore::ingest_document`] splits text, in (estimated) tokens.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkOptions {
+    pub chunk_size: usize,
+    pub overlap: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 512,
+            overlap: 64,
+        }
+    }
+}
+
 /// RAG store — full-text search powered by SQLite FTS5.
 pub struct RagStore {
     conn: Mutex<Connection>,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+    chunking: ChunkOptions,
 }
 
 impl RagStore {
     /// Open or create a RAG store.
+    ///
+    /// Like `memory.db`, the file is in WAL mode with a busy timeout: every
+    /// bot and `zenclaw serve` opens it, and their writes must wait for each
+    /// other instead of failing with "database is locked".
     pub fn open(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path)
-            .map_err(|e| ZenClawError::Memory(format!("RAG DB open failed: {}", e)))?;
+        Self::open_with_timeout(path, SqliteOptions::default().busy_timeout)
+    }
+
+    /// Open or create a RAG store whose statements wait up to `busy_timeout`
+    /// for locks held by other connections.
+    pub fn open_with_timeout(path: &Path, busy_timeout: Duration) -> Result<Self> {
+        let open_err = |e: rusqlite::Error| ZenClawError::Memory(format!("RAG DB open failed: {}", e));
+        let conn = Connection::open(path).map_err(open_err)?;
+        conn.busy_timeout(busy_timeout).map_err(open_err)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .map_err(|e| ZenClawError::Memory(format!("RAG DB WAL error: {}", e)))?;
 
         Self::init_schema(&conn)?;
-        Ok(Self { conn: Mutex::new(conn) })
+        Ok(Self::with_conn(conn))
     }
 
     /// Create an in-memory RAG store (for testing).
@@ -40,7 +102,32 @@ impl RagStore {
             .map_err(|e| ZenClawError::Memory(format!("RAG DB memory open failed: {}", e)))?;
 
         Self::init_schema(&conn)?;
-        Ok(Self { conn: Mutex::new(conn) })
+        Ok(Self::with_conn(conn))
+    }
+
+    fn with_conn(conn: Connection) -> Self {
+        Self {
+            conn: Mutex::new(conn),
+            embedder: None,
+            chunking: ChunkOptions::default(),
+        }
+    }
+
+    /// Open a store that embeds chunks with `embedder`.
+    pub fn open_with_embedder(path: &Path, embedder: Arc<dyn EmbeddingProvider>) -> Result<Self> {
+        Ok(Self::open(path)?.with_embedder(embedder))
+    }
+
+    /// Embed chunks on ingestion and enable [`semantic_search`](Self::semantic_search).
+    pub fn with_embedder(mut self, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Set how [`ingest_document`](Self::ingest_document) splits text.
+    pub fn with_chunking(mut self, options: ChunkOptions) -> Self {
+        self.chunking = options;
+        self
     }
 
     fn init_schema(conn: &Connection) -> Result<()> {
@@ -77,6 +164,16 @@ impl RagStore {
                 VALUES ('delete', old.id, old.content, old.source, old.metadata);
                 INSERT INTO documents_fts(rowid, content, source, metadata)
                 VALUES (new.id, new.content, new.source, new.metadata);
+            END;
+
+            CREATE TABLE IF NOT EXISTS embeddings (
+                doc_id INTEGER PRIMARY KEY,
+                model TEXT NOT NULL,
+                vector BLOB NOT NULL
+            );
+
+            CREATE TRIGGER IF NOT EXISTS embeddings_ad AFTER DELETE ON documents BEGIN
+                DELETE FROM embeddings WHERE doc_id = old.id;
             END;",
         )
         .map_err(|e| ZenClawError::Memory(format!("RAG schema creation failed: {}", e)))?;
@@ -115,6 +212,151 @@ impl RagStore {
         Ok(ids)
     }
 
+    /// Chunk `text`, embed every chunk (when an embedder is set) and store
+    /// it under `source`. Ingesting the same source again replaces the old
+    /// chunks. `metadata` is appended to each chunk's `chunk:i/n` label.
+    /// Returns the number of chunks stored.
+    pub async fn ingest_document(&self, source: &str, text: &str, metadata: &str) -> Result<usize> {
+        let chunks = chunk_tokens(text, self.chunking.chunk_size, self.chunking.overlap);
+        if chunks.is_empty() {
+            return Ok(0);
+        }
+
+        // Embed before touching the database so a provider error leaves the
+        // previous copy of the source intact.
+        let vectors = match &self.embedder {
+            Some(embedder) => {
+                let mut vectors = Vec::with_capacity(chunks.len());
+                for batch in chunks.chunks(embedder.max_batch().max(1)) {
+                    let embedded = embedder.embed(batch).await?;
+                    if embedded.len() != batch.len() {
+                        return Err(ZenClawError::Provider(format!(
+                            "Expected {} embeddings, got {}",
+                            batch.len(),
+                            embedded.len()
+                        )));
+                    }
+                    vectors.extend(embedded);
+                }
+                Some(vectors)
+            }
+            None => None,
+        };
+
+        let mut conn = self.conn.lock().unwrap();
+        if let Some(vectors) = &vectors {
+            check_dimensions(&conn, source, vectors)?;
+        }
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| ZenClawError::Memory(format!("RAG transaction failed: {}", e)))?;
+        tx.execute("DELETE FROM documents WHERE source = ?1", params![source])
+            .map_err(|e| ZenClawError::Memory(format!("RAG delete failed: {}", e)))?;
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut meta = format!("chunk:{}/{}", i + 1, chunks.len());
+            if !metadata.trim().is_empty() {
+                meta.push(' ');
+                meta.push_str(metadata.trim());
+            }
+            tx.execute(
+                "INSERT INTO documents (source, content, metadata) VALUES (?1, ?2, ?3)",
+                params![source, chunk, meta],
+            )
+            .map_err(|e| ZenClawError::Memory(format!("RAG index failed: {}", e)))?;
+
+            if let (Some(vectors), Some(embedder)) = (&vectors, &self.embedder) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO embeddings (doc_id, model, vector) VALUES (?1, ?2, ?3)",
+                    params![tx.last_insert_rowid(), embedder.model(), encode_vector(&vectors[i])],
+                )
+                .map_err(|e| ZenClawError::Memory(format!("RAG embedding insert failed: {}", e)))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| ZenClawError::Memory(format!("RAG commit failed: {}", e)))?;
+
+        Ok(chunks.len())
+    }
+
+    /// Read a text file and [`ingest_document`](Self::ingest_document) it,
+    /// using the path as the source.
+    pub async fn ingest_file(&self, path: &Path, metadata: &str) -> Result<usize> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| ZenClawError::Other(format!("Cannot read {}: {}", path.display(), e)))?;
+        self.ingest_document(&path.display().to_string(), &text, metadata).await
+    }
+
+    /// The `k` chunks most similar to `query`, best first.
+    ///
+    /// Falls back to full-text search when no embedder is set or nothing
+    /// was embedded with the current model.
+    pub async fn semantic_search(&self, query: &str, k: usize) -> Result<Vec<ScoredChunk>> {
+        let Some(embedder) = &self.embedder else {
+            return self.keyword_search(query, k);
+        };
+
+        let stored = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare(
+                    "SELECT d.id, d.source, d.content, d.metadata, e.vector
+                     FROM embeddings e
+                     JOIN documents d ON d.id = e.doc_id
+                     WHERE e.model = ?1",
+                )
+                .map_err(|e| ZenClawError::Memory(format!("RAG search prepare failed: {}", e)))?;
+            stmt.query_map(params![embedder.model()], |row| {
+                Ok((
+                    Document {
+                        id: row.get(0)?,
+                        source: row.get(1)?,
+                        content: row.get(2)?,
+                        metadata: row.get(3)?,
+                        rank: 0.0,
+                    },
+                    row.get::<_, Vec<u8>>(4)?,
+                ))
+            })
+            .map_err(|e| ZenClawError::Memory(format!("RAG search failed: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect::<Vec<_>>()
+        };
+        if stored.is_empty() {
+            return self.keyword_search(query, k);
+        }
+
+        let query_vector = embedder
+            .embed(&[query.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ZenClawError::Provider("Empty embedding response".into()))?;
+
+        let mut scored: Vec<ScoredChunk> = stored
+            .into_iter()
+            .map(|(mut document, blob)| {
+                let score = cosine(&query_vector, &decode_vector(&blob));
+                document.rank = score as f64;
+                ScoredChunk { document, score }
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored.truncate(k);
+        Ok(scored)
+    }
+
+    fn keyword_search(&self, query: &str, k: usize) -> Result<Vec<ScoredChunk>> {
+        Ok(self
+            .search(query, k)?
+            .into_iter()
+            .map(|document| ScoredChunk {
+                score: -document.rank as f32,
+                document,
+            })
+            .collect())
+    }
+
     /// Search for relevant documents.
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Document>> {
         let conn = self.conn.lock().unwrap();
@@ -155,6 +397,58 @@ impl RagStore {
         Ok(count as usize)
     }
 
+    /// List ingested sources, oldest first.
+    pub fn list_sources(&self) -> Result<Vec<SourceInfo>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT MIN(id), source, COUNT(*), COALESCE(MIN(created_at), '')
+                 FROM documents
+                 GROUP BY source
+                 ORDER BY MIN(id)",
+            )
+            .map_err(|e| ZenClawError::Memory(format!("RAG list prepare failed: {}", e)))?;
+
+        let sources = stmt
+            .query_map([], |row| {
+                Ok(SourceInfo {
+                    id: row.get(0)?,
+                    source: row.get(1)?,
+                    chunks: row.get::<_, i64>(2)? as usize,
+                    created_at: row.get(3)?,
+                })
+            })
+            .map_err(|e| ZenClawError::Memory(format!("RAG list failed: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(sources)
+    }
+
+    /// All chunks of a source, in ingestion order.
+    pub fn chunks(&self, source: &str) -> Result<Vec<Document>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, source, content, metadata FROM documents WHERE source = ?1 ORDER BY id")
+            .map_err(|e| ZenClawError::Memory(format!("RAG chunks prepare failed: {}", e)))?;
+
+        let chunks = stmt
+            .query_map(params![source], |row| {
+                Ok(Document {
+                    id: row.get(0)?,
+                    source: row.get(1)?,
+                    content: row.get(2)?,
+                    metadata: row.get(3)?,
+                    rank: 0.0,
+                })
+            })
+            .map_err(|e| ZenClawError::Memory(format!("RAG chunks failed: {}", e)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(chunks)
+    }
+
     /// Delete documents by source.
     pub fn delete_by_source(&self, source: &str) -> Result<usize> {
         let conn = self.conn.lock().unwrap();
@@ -164,6 +458,35 @@ impl RagStore {
         Ok(deleted)
     }
 
+    /// Copy a conversation from `memory` into the store so later sessions can
+    /// retrieve it. The transcript (user and assistant turns only) is chunked
+    /// under the source `session:<key>`; exporting the same session again
+    /// replaces the previous copy. Returns the number of chunks indexed.
+    pub async fn ingest_session(&self, memory: &dyn MemoryStore, session_key: &str) -> Result<usize> {
+        let history = memory.get_history(session_key, 10_000).await?;
+
+        let transcript = history
+            .iter()
+            .filter_map(|msg| {
+                let speaker = match msg.role {
+                    Role::User => "User",
+                    Role::Assistant => "Assistant",
+                    _ => return None,
+                };
+                let text = msg.content.as_deref()?.trim();
+                (!text.is_empty()).then(|| format!("{}: {}", speaker, text))
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let source = format!("session:{}", session_key);
+        self.delete_by_source(&source)?;
+        if transcript.is_empty() {
+            return Ok(0);
+        }
+        Ok(self.index_chunked(&source, &transcript, 200, 30)?.len())
+    }
+
     /// Build a RAG context string from search results.
     pub fn build_context(&self, query: &str, max_results: usize) -> Result<String> {
         let results = self.search(query, max_results)?;
@@ -216,9 +539,113 @@ fn chunk_text(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
     chunks
 }
 
+/// Split text into chunks of about `chunk_size` tokens, each starting
+/// `overlap` tokens before the end of the previous one. Short texts are
+/// kept as-is.
+fn chunk_tokens(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<(&str, usize)> = text
+        .split_whitespace()
+        .map(|w| (w, estimate_bpe(w).max(1)))
+        .collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    if words.iter().map(|w| w.1).sum::<usize>() <= chunk_size {
+        return vec![text.trim().to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < words.len() {
+        let mut end = start;
+        let mut tokens = 0;
+        while end < words.len() && (end == start || tokens + words[end].1 <= chunk_size) {
+            tokens += words[end].1;
+            end += 1;
+        }
+        chunks.push(words[start..end].iter().map(|w| w.0).collect::<Vec<_>>().join(" "));
+        if end >= words.len() {
+            break;
+        }
+
+        // Step back for the overlap, but always move forward
+        let mut next = end;
+        let mut kept = 0;
+        while next > start + 1 && kept + words[next - 1].1 <= overlap {
+            next -= 1;
+            kept += words[next].1;
+        }
+        start = next;
+    }
+    chunks
+}
+
+/// Reject vectors whose dimension differs from each other or from the
+/// vectors already stored (ignoring `source`, which is being replaced) —
+/// cosine scores across dimensions are meaningless.
+fn check_dimensions(conn: &Connection, source: &str, vectors: &[Vec<f32>]) -> Result<()> {
+    let Some(first) = vectors.first() else {
+        return Ok(());
+    };
+    let dims = first.len();
+    if let Some(bad) = vectors.iter().find(|v| v.len() != dims) {
+        return Err(ZenClawError::Memory(format!(
+            "Embedding dimension mismatch within a batch: {} vs {}",
+            dims,
+            bad.len()
+        )));
+    }
+
+    let stored: Option<i64> = conn
+        .query_row(
+            "SELECT length(e.vector) / 4 FROM embeddings e
+             JOIN documents d ON d.id = e.doc_id
+             WHERE d.source != ?1
+             LIMIT 1",
+            params![source],
+            |row| row.get(0),
+        )
+        .ok();
+    if let Some(stored) = stored
+        && stored as usize != dims
+    {
+        return Err(ZenClawError::Memory(format!(
+            "Embedding dimension mismatch: the store holds {}-dim vectors, got {}. Use the same embedding model or a new store.",
+            stored, dims
+        )));
+    }
+    Ok(())
+}
+
+/// Vectors are stored as little-endian f32 bytes.
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use async_trait::async_trait;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -243,4 +670,140 @@ mod tests {
 
         assert_eq!(store.count().unwrap(), 2);
     }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rag.db");
+        // Separate connections on one file, like a bot and `zenclaw serve`
+        let stores: Vec<Arc<RagStore>> = (0..4).map(|_| Arc::new(RagStore::open(&path).unwrap())).collect();
+
+        let writers: Vec<_> = stores
+            .iter()
+            .enumerate()
+            .map(|(i, store)| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    for j in 0..25 {
+                        store.index(&format!("doc{}.md", i), &format!("chunk {} of writer {}", j, i), "")?;
+                        // Replaces the source's chunks in one transaction
+                        futures::executor::block_on(store.ingest_document(&format!("notes{}.md", i), "meeting notes", ""))?;
+                        store.search("chunk", 3)?;
+                    }
+                    Ok::<_, ZenClawError>(())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        assert_eq!(stores[0].count().unwrap(), 100 + 4);
+    }
+
+    #[test]
+    fn test_list_sources() {
+        let store = RagStore::in_memory().unwrap();
+        store.index("a.md", "first", "").unwrap();
+        store.index_chunked("b.md", "one two three four five six seven eight", 3, 0).unwrap();
+
+        let sources = store.list_sources().unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].source, "a.md");
+        assert_eq!(sources[0].chunks, 1);
+        assert_eq!(sources[1].chunks, store.chunks("b.md").unwrap().len());
+
+        store.delete_by_source("a.md").unwrap();
+        assert_eq!(store.list_sources().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_chunk_tokens() {
+        let text = (1..=200).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ");
+        let chunks = chunk_tokens(&text, 100, 20);
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].starts_with("word1 "));
+        assert!(chunks.last().unwrap().ends_with("word200"));
+
+        // Consecutive chunks share their boundary words
+        let last_of_first = chunks[0].split(' ').next_back().unwrap();
+        assert!(chunks[1].split(' ').any(|w| w == last_of_first));
+
+        assert_eq!(chunk_tokens("short text", 512, 64), vec!["short text"]);
+        assert!(chunk_tokens("   ", 512, 64).is_empty());
+    }
+
+    #[test]
+    fn test_vector_roundtrip_and_cosine() {
+        let v = vec![0.5, -1.25, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&v)), v);
+        assert!((cosine(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    /// Embeds text as (mentions rust, mentions cats, constant) — or a
+    /// vector of `dims` ones when set.
+    struct KeywordEmbedder {
+        dims: Option<usize>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedder {
+        fn model(&self) -> &str {
+            "fake-embed"
+        }
+
+        fn max_batch(&self) -> usize {
+            1
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    if let Some(dims) = self.dims {
+                        return vec![1.0; dims];
+                    }
+                    let t = t.to_lowercase();
+                    vec![t.contains("rust") as u8 as f32, t.contains("cat") as u8 as f32, 0.1]
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search() {
+        let store = RagStore::in_memory()
+            .unwrap()
+            .with_embedder(Arc::new(KeywordEmbedder { dims: None }));
+
+        store.ingest_document("lang.md", "Rust is a systems language", "lang").await.unwrap();
+        store.ingest_document("pets.md", "Cats sleep most of the day", "").await.unwrap();
+
+        let results = store.semantic_search("tell me about my cat", 2).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].document.source, "pets.md");
+        assert!(results[0].score > results[1].score);
+        assert_eq!(store.chunks("lang.md").unwrap()[0].metadata, "chunk:1/1 lang");
+
+        // Re-ingesting replaces the chunks and their vectors
+        store.ingest_document("lang.md", "Rust again", "").await.unwrap();
+        assert_eq!(store.count().unwrap(), 2);
+        let results = store.semantic_search("rust", 5).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].document.content, "Rust again");
+    }
+
+    #[tokio::test]
+    async fn test_dimension_mismatch_rejected() {
+        let tmp = NamedTempFile::new().unwrap();
+        let small = RagStore::open_with_embedder(tmp.path(), Arc::new(KeywordEmbedder { dims: Some(768) })).unwrap();
+        small.ingest_document("a.md", "first document", "").await.unwrap();
+        drop(small);
+
+        let large = RagStore::open_with_embedder(tmp.path(), Arc::new(KeywordEmbedder { dims: Some(1536) })).unwrap();
+        let err = large.ingest_document("b.md", "second document", "").await.unwrap_err();
+        assert!(err.to_string().contains("dimension mismatch"));
+        assert_eq!(large.count().unwrap(), 1);
+    }
 }
@@ -1,99 +1,413 @@
 //! SQLite-backed persistent memory store.
 
 use async_trait::async_trait;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, TransactionBehavior};
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
 
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tracing::{info, warn};
 use zenclaw_core::error::{Result, ZenClawError};
-use zenclaw_core::memory::MemoryStore;
-use zenclaw_core::message::ChatMessage;
+use zenclaw_core::memory::{merge_session, parse_session, HistoryMatch, MemoryStore, SessionEntry};
+use zenclaw_core::message::{ChatMessage, Role};
+
+/// Size and usage figures for a [`SqliteMemory`] database.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStats {
+    /// Distinct conversation sessions.
+    pub sessions: u64,
+    /// Stored history messages across all sessions.
+    pub messages: u64,
+    /// Stored facts.
+    pub facts: u64,
+    /// Database size on disk (page_count × page_size).
+    pub db_bytes: u64,
+    /// Timestamp of the oldest message (UTC, `YYYY-MM-DD HH:MM:SS`).
+    pub oldest: Option<String>,
+    /// Timestamp of the newest message.
+    pub newest: Option<String>,
+}
+
+/// Connection settings for [`SqliteMemory`].
+#[derive(Debug, Clone)]
+pub struct SqliteOptions {
+    /// Read-only connections kept open next to the single writer.
+    pub readers: usize,
+    /// How long a statement waits for a lock held by another connection
+    /// (or another process) before failing with "database is locked".
+    pub busy_timeout: Duration,
+}
+
+impl Default for SqliteOptions {
+    fn default() -> Self {
+        Self {
+            readers: 4,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Schema changes, oldest first. A database's `schema_version` is the
+/// number of steps applied to it. Released steps must never change: a new
+/// feature adds a step.
+const MIGRATIONS: &[&str] = &[
+    // 1: history and facts
+    "CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        session_key TEXT NOT NULL,
+        role TEXT NOT NULL,
+        content TEXT,
+        tool_calls TEXT,
+        tool_call_id TEXT,
+        name TEXT,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_session ON history(session_key);
+
+    CREATE TABLE IF NOT EXISTS facts (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );",
+    // 2: key-value facts of the memory tool
+    "CREATE TABLE IF NOT EXISTS kv (
+        scope TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (scope, key)
+    );",
+    // 3: full-text index of history, kept in sync by triggers and built
+    // from the messages already there
+    "CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+        content, content='history', content_rowid='id'
+    );
+    CREATE TRIGGER IF NOT EXISTS history_fts_insert AFTER INSERT ON history BEGIN
+        INSERT INTO history_fts(rowid, content) VALUES (new.id, new.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_fts_delete AFTER DELETE ON history BEGIN
+        INSERT INTO history_fts(history_fts, rowid, content) VALUES ('delete', old.id, old.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_fts_update AFTER UPDATE ON history BEGIN
+        INSERT INTO history_fts(history_fts, rowid, content) VALUES ('delete', old.id, old.content);
+        INSERT INTO history_fts(rowid, content) VALUES (new.id, new.content);
+    END;
+    INSERT INTO history_fts(history_fts) VALUES ('rebuild');",
+];
+
+/// Schema version of the databases this build writes.
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+/// Bring the schema up to [`SCHEMA_VERSION`], applying the missing steps in
+/// one transaction. A database without `schema_version` (from before
+/// versioning) is version 0; its tables are kept, as every step is
+/// idempotent. A database from a newer build is refused, not touched.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let err = |e: rusqlite::Error| ZenClawError::Memory(format!("SQLite migration error: {}", e));
+    // Immediate: two processes opening the same file migrate one at a time
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(err)?;
+    tx.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL);")
+        .map_err(err)?;
+    let version = tx
+        .query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map_err(err)? as usize;
+
+    if version > SCHEMA_VERSION {
+        warn!(
+            "Memory database is schema version {}, newer than this build ({}); not opening it",
+            version, SCHEMA_VERSION
+        );
+        return Err(ZenClawError::Memory(format!(
+            "The memory database was written by a newer ZenClaw (schema version {}, this build knows up to {}). Update ZenClaw to use it.",
+            version, SCHEMA_VERSION
+        )));
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    for (i, step) in MIGRATIONS.iter().enumerate().skip(version) {
+        tx.execute_batch(step)
+            .map_err(|e| ZenClawError::Memory(format!("SQLite migration {} failed: {}", i + 1, e)))?;
+    }
+    tx.execute("DELETE FROM schema_version", []).map_err(err)?;
+    tx.execute(
+        "INSERT INTO schema_version (version) VALUES (?1)",
+        rusqlite::params![SCHEMA_VERSION as i64],
+    )
+    .map_err(err)?;
+    tx.commit().map_err(err)?;
+    info!("Memory database migrated from schema version {} to {}", version, SCHEMA_VERSION);
+    Ok(())
+}
+
+/// `query` as an FTS5 expression: every word as a prefix ("database" finds
+/// "databases"), quoted so punctuation and operators are matched literally.
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
 /// SQLite memory store — persistent conversation history & facts.
 ///
 /// Perfect for STB and embedded Linux — tiny footprint, no external services.
+///
+/// The database runs in WAL mode: all writes go through one connection (so
+/// they are serialized inside the process), while reads use a small pool of
+/// extra connections and never block on a writer. `busy_timeout` covers
+/// other processes sharing the file (e.g. a Discord and a Telegram bot).
 pub struct SqliteMemory {
-    conn: Mutex<Connection>,
+    writer: Mutex<Connection>,
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
     rag: Option<crate::memory::RagStore>,
 }
 
 impl SqliteMemory {
-    /// Open or create a SQLite database.
+    /// Open or create a SQLite database with default [`SqliteOptions`].
     pub fn open(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path)
-            .map_err(|e| ZenClawError::Memory(format!("SQLite open error: {}", e)))?;
-
-        // Create tables
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                session_key TEXT NOT NULL,
-                role TEXT NOT NULL,
-                content TEXT,
-                tool_calls TEXT,
-                tool_call_id TEXT,
-                name TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            );
-            CREATE INDEX IF NOT EXISTS idx_history_session ON history(session_key);
-
-            CREATE TABLE IF NOT EXISTS facts (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            );",
-        )
-        .map_err(|e| ZenClawError::Memory(format!("SQLite init error: {}", e)))?;
+        Self::open_with(path, &SqliteOptions::default())
+    }
+
+    /// Open or create a SQLite database.
+    pub fn open_with(path: &Path, options: &SqliteOptions) -> Result<Self> {
+        let open_err = |e: rusqlite::Error| ZenClawError::Memory(format!("SQLite open error: {}", e));
+
+        let mut writer = Connection::open(path).map_err(open_err)?;
+        writer.busy_timeout(options.busy_timeout).map_err(open_err)?;
+        writer
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .map_err(|e| ZenClawError::Memory(format!("SQLite WAL error: {}", e)))?;
+        migrate(&mut writer)?;
+
+        let readers = (0..options.readers)
+            .map(|_| {
+                let conn = Connection::open_with_flags(
+                    path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )
+                .map_err(open_err)?;
+                conn.busy_timeout(options.busy_timeout).map_err(open_err)?;
+                Ok(Mutex::new(conn))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        let rag = path.parent().map(|p| p.join("rag.db")).and_then(|p| crate::memory::RagStore::open(&p).ok());
+        let rag = path
+            .parent()
+            .map(|p| p.join("rag.db"))
+            .and_then(|p| crate::memory::RagStore::open_with_timeout(&p, options.busy_timeout).ok());
 
         Ok(Self {
-            conn: Mutex::new(conn),
+            writer: Mutex::new(writer),
+            readers,
+            next_reader: AtomicUsize::new(0),
             rag,
         })
     }
 
     /// Create an in-memory SQLite database (for testing).
     pub fn in_memory() -> Result<Self> {
-        let conn = Connection::open_in_memory()
+        let mut conn = Connection::open_in_memory()
             .map_err(|e| ZenClawError::Memory(format!("SQLite error: {}", e)))?;
 
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS history (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                session_key TEXT NOT NULL,
-                role TEXT NOT NULL,
-                content TEXT,
-                tool_calls TEXT,
-                tool_call_id TEXT,
-                name TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            );
-            CREATE INDEX IF NOT EXISTS idx_history_session ON history(session_key);
-
-            CREATE TABLE IF NOT EXISTS facts (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            );",
-        )
-        .map_err(|e| ZenClawError::Memory(format!("SQLite init error: {}", e)))?;
+        migrate(&mut conn)?;
 
         let rag = crate::memory::RagStore::in_memory().ok();
 
+        // An in-memory database is private to its connection: no reader pool
         Ok(Self {
-            conn: Mutex::new(conn),
+            writer: Mutex::new(conn),
+            readers: Vec::new(),
+            next_reader: AtomicUsize::new(0),
             rag,
         })
     }
+
+    fn writer(&self) -> MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A free reader connection (round-robin), or the writer if there is no pool.
+    fn reader(&self) -> MutexGuard<'_, Connection> {
+        if self.readers.is_empty() {
+            return self.writer();
+        }
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for i in 0..self.readers.len() {
+            if let Ok(conn) = self.readers[(start + i) % self.readers.len()].try_lock() {
+                return conn;
+            }
+        }
+        self.readers[start % self.readers.len()]
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SqliteMemory {
+    /// Gather counts and size of the database (for `zenclaw status`).
+    pub fn stats(&self) -> Result<MemoryStats> {
+        let conn = self.reader();
+        let err = |e: rusqlite::Error| ZenClawError::Memory(e.to_string());
+
+        let (sessions, messages, oldest, newest) = conn
+            .query_row(
+                "SELECT COUNT(DISTINCT session_key), COUNT(*), MIN(created_at), MAX(created_at) FROM history",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                },
+            )
+            .map_err(err)?;
+        let facts: i64 = conn
+            .query_row("SELECT COUNT(*) FROM facts", [], |row| row.get(0))
+            .map_err(err)?;
+        let page_count: i64 = conn
+            .query_row("PRAGMA page_count", [], |row| row.get(0))
+            .map_err(err)?;
+        let page_size: i64 = conn
+            .query_row("PRAGMA page_size", [], |row| row.get(0))
+            .map_err(err)?;
+
+        Ok(MemoryStats {
+            sessions: sessions as u64,
+            messages: messages as u64,
+            facts: facts as u64,
+            db_bytes: (page_count * page_size) as u64,
+            oldest,
+            newest,
+        })
+    }
+}
+
+/// `created_at` format of the history table (UTC).
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Build a message from `role, content, tool_calls, tool_call_id, name` columns.
+fn message_from_row(row: &rusqlite::Row) -> rusqlite::Result<ChatMessage> {
+    let role: String = row.get(0)?;
+    let tool_calls_json: Option<String> = row.get(2)?;
+
+    let role = match role.as_str() {
+        "system" => Role::System,
+        "user" => Role::User,
+        "assistant" => Role::Assistant,
+        "tool" => Role::Tool,
+        "summary" => Role::Summary,
+        _ => Role::User,
+    };
+
+    Ok(ChatMessage {
+        role,
+        content: row.get(1)?,
+        media: Vec::new(),
+        tool_calls: tool_calls_json.and_then(|j| serde_json::from_str(&j).ok()),
+        tool_call_id: row.get(3)?,
+        name: row.get(4)?,
+    })
+}
+
+/// Insert a history row; `created_at` None = now.
+fn insert_message(
+    conn: &Connection,
+    session_key: &str,
+    message: &ChatMessage,
+    created_at: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let role = format!("{:?}", message.role).to_lowercase();
+    let tool_calls_json = message
+        .tool_calls
+        .as_ref()
+        .map(|tc| serde_json::to_string(tc).unwrap_or_default());
+    let created_at = created_at
+        .unwrap_or_else(Utc::now)
+        .format(TIMESTAMP_FORMAT)
+        .to_string();
+
+    conn.execute(
+        "INSERT INTO history (session_key, role, content, tool_calls, tool_call_id, name, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            session_key,
+            role,
+            message.content,
+            tool_calls_json,
+            message.tool_call_id,
+            message.name,
+            created_at,
+        ],
+    )
+    .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+    Ok(())
+}
+
+impl SqliteMemory {
+    /// All messages of a session with their timestamps, oldest first.
+    fn session_entries(&self, session_key: &str) -> Result<Vec<SessionEntry>> {
+        let conn = self.reader();
+        let mut stmt = conn
+            .prepare(
+                "SELECT role, content, tool_calls, tool_call_id, name, created_at
+                 FROM history WHERE session_key = ?1
+                 ORDER BY id",
+            )
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+
+        let entries = stmt
+            .query_map(rusqlite::params![session_key], |row| {
+                let created_at: Option<String> = row.get(5)?;
+                Ok(SessionEntry {
+                    message: message_from_row(row)?,
+                    timestamp: created_at
+                        .and_then(|t| NaiveDateTime::parse_from_str(&t, TIMESTAMP_FORMAT).ok())
+                        .map(|t| t.and_utc()),
+                })
+            })
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(entries)
+    }
+
+    /// Swap a session's history for `entries`, keeping their timestamps.
+    fn write_entries(&self, session_key: &str, entries: &[SessionEntry]) -> Result<()> {
+        let mut conn = self.writer();
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        tx.execute(
+            "DELETE FROM history WHERE session_key = ?1",
+            rusqlite::params![session_key],
+        )
+        .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+
+        for entry in entries {
+            insert_message(&tx, session_key, &entry.message, entry.timestamp)?;
+        }
+
+        tx.commit().map_err(|e| ZenClawError::Memory(e.to_string()))
+    }
 }
 
 #[async_trait]
 impl MemoryStore for SqliteMemory {
     async fn get_history(&self, session_key: &str, limit: usize) -> Result<Vec<ChatMessage>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
         let mut stmt = conn
             .prepare(
                 "SELECT role, content, tool_calls, tool_call_id, name
@@ -103,33 +417,7 @@ impl MemoryStore for SqliteMemory {
             .map_err(|e| ZenClawError::Memory(e.to_string()))?;
 
         let rows = stmt
-            .query_map(rusqlite::params![session_key, limit], |row| {
-                let role: String = row.get(0)?;
-                let content: Option<String> = row.get(1)?;
-                let tool_calls_json: Option<String> = row.get(2)?;
-                let tool_call_id: Option<String> = row.get(3)?;
-                let name: Option<String> = row.get(4)?;
-
-                let role_enum = match role.as_str() {
-                    "system" => zenclaw_core::message::Role::System,
-                    "user" => zenclaw_core::message::Role::User,
-                    "assistant" => zenclaw_core::message::Role::Assistant,
-                    "tool" => zenclaw_core::message::Role::Tool,
-                    _ => zenclaw_core::message::Role::User,
-                };
-
-                let tool_calls = tool_calls_json
-                    .and_then(|j| serde_json::from_str(&j).ok());
-
-                Ok(ChatMessage {
-                    role: role_enum,
-                    content,
-                    media: Vec::new(),
-                    tool_calls,
-                    tool_call_id,
-                    name,
-                })
-            })
+            .query_map(rusqlite::params![session_key, limit], message_from_row)
             .map_err(|e| ZenClawError::Memory(e.to_string()))?;
 
         let mut messages: Vec<ChatMessage> = rows
@@ -147,49 +435,32 @@ impl MemoryStore for SqliteMemory {
         user_message: &str,
         assistant_response: &str,
     ) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
+        let mut conn = self.writer();
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        tx.execute(
             "INSERT INTO history (session_key, role, content) VALUES (?1, 'user', ?2)",
             rusqlite::params![session_key, user_message],
         )
         .map_err(|e| ZenClawError::Memory(e.to_string()))?;
 
-        conn.execute(
+        tx.execute(
             "INSERT INTO history (session_key, role, content) VALUES (?1, 'assistant', ?2)",
             rusqlite::params![session_key, assistant_response],
         )
         .map_err(|e| ZenClawError::Memory(e.to_string()))?;
 
-        Ok(())
+        tx.commit().map_err(|e| ZenClawError::Memory(e.to_string()))
     }
 
     async fn save_message(&self, session_key: &str, message: &ChatMessage) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        let role = format!("{:?}", message.role).to_lowercase();
-        let tool_calls_json = message
-            .tool_calls
-            .as_ref()
-            .map(|tc| serde_json::to_string(tc).unwrap_or_default());
-
-        conn.execute(
-            "INSERT INTO history (session_key, role, content, tool_calls, tool_call_id, name)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![
-                session_key,
-                role,
-                message.content,
-                tool_calls_json,
-                message.tool_call_id,
-                message.name,
-            ],
-        )
-        .map_err(|e| ZenClawError::Memory(e.to_string()))?;
-
-        Ok(())
+        let conn = self.writer();
+        insert_message(&conn, session_key, message, None)
     }
 
     async fn clear_history(&self, session_key: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
         conn.execute(
             "DELETE FROM history WHERE session_key = ?1",
             rusqlite::params![session_key],
@@ -198,8 +469,49 @@ impl MemoryStore for SqliteMemory {
         Ok(())
     }
 
+    async fn delete_last_turn(&self, session_key: &str) -> Result<bool> {
+        let conn = self.writer();
+        let deleted = conn
+            .execute(
+                "DELETE FROM history WHERE session_key = ?1 AND id >= (
+                     SELECT MAX(id) FROM history WHERE session_key = ?1 AND role = 'user'
+                 )",
+                rusqlite::params![session_key],
+            )
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        Ok(deleted > 0)
+    }
+
+    async fn replace_history(&self, session_key: &str, messages: &[ChatMessage]) -> Result<()> {
+        let entries: Vec<SessionEntry> = messages
+            .iter()
+            .map(|m| SessionEntry {
+                message: m.clone(),
+                timestamp: None,
+            })
+            .collect();
+        self.write_entries(session_key, &entries)
+    }
+
+    async fn export_session(&self, session_key: &str) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.session_entries(session_key)?)?)
+    }
+
+    async fn import_session(&self, session_key: &str, json: &str, replace: bool) -> Result<usize> {
+        let imported = parse_session(json)?;
+        if replace {
+            self.write_entries(session_key, &imported)?;
+            return Ok(imported.len());
+        }
+        let (merged, added) = merge_session(self.session_entries(session_key)?, imported);
+        if added > 0 {
+            self.write_entries(session_key, &merged)?;
+        }
+        Ok(added)
+    }
+
     async fn save_fact(&self, key: &str, value: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
         conn.execute(
             "INSERT OR REPLACE INTO facts (key, value, updated_at)
              VALUES (?1, ?2, CURRENT_TIMESTAMP)",
@@ -210,7 +522,7 @@ impl MemoryStore for SqliteMemory {
     }
 
     async fn get_fact(&self, key: &str) -> Result<Option<String>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
         let result = conn
             .query_row(
                 "SELECT value FROM facts WHERE key = ?1",
@@ -222,7 +534,7 @@ impl MemoryStore for SqliteMemory {
     }
 
     async fn search_facts(&self, query: &str, limit: usize) -> Result<Vec<(String, String)>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
         let pattern = format!("%{}%", query);
         let mut stmt = conn
             .prepare(
@@ -243,6 +555,88 @@ impl MemoryStore for SqliteMemory {
         Ok(results)
     }
 
+    async fn search_history(&self, query: &str, session_key: Option<&str>, limit: usize) -> Result<Vec<HistoryMatch>> {
+        let query = fts_query(query);
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.reader();
+        let mut stmt = conn
+            .prepare(
+                "SELECT h.role, h.content, h.tool_calls, h.tool_call_id, h.name, h.created_at, h.session_key
+                 FROM history_fts JOIN history h ON h.id = history_fts.rowid
+                 WHERE history_fts MATCH ?1
+                   AND h.role IN ('user', 'assistant')
+                   AND (?2 IS NULL OR h.session_key = ?2)
+                 ORDER BY rank
+                 LIMIT ?3",
+            )
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+
+        let found = stmt
+            .query_map(rusqlite::params![query, session_key, limit], |row| {
+                let created_at: Option<String> = row.get(5)?;
+                Ok(HistoryMatch {
+                    session_key: row.get(6)?,
+                    entry: SessionEntry {
+                        message: message_from_row(row)?,
+                        timestamp: created_at
+                            .and_then(|t| NaiveDateTime::parse_from_str(&t, TIMESTAMP_FORMAT).ok())
+                            .map(|t| t.and_utc()),
+                    },
+                })
+            })
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(found)
+    }
+
+    async fn kv_get(&self, scope: &str, key: &str) -> Result<Option<String>> {
+        let conn = self.reader();
+        conn.query_row(
+            "SELECT value FROM kv WHERE scope = ?1 AND key = ?2",
+            rusqlite::params![scope, key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| ZenClawError::Memory(e.to_string()))
+    }
+
+    async fn kv_set(&self, scope: &str, key: &str, value: &str) -> Result<()> {
+        let conn = self.writer();
+        conn.execute(
+            "INSERT OR REPLACE INTO kv (scope, key, value, updated_at)
+             VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+            rusqlite::params![scope, key, value],
+        )
+        .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn kv_delete(&self, scope: &str, key: &str) -> Result<bool> {
+        let conn = self.writer();
+        let deleted = conn
+            .execute("DELETE FROM kv WHERE scope = ?1 AND key = ?2", rusqlite::params![scope, key])
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        Ok(deleted > 0)
+    }
+
+    async fn kv_list(&self, scope: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.reader();
+        let mut stmt = conn
+            .prepare("SELECT key, value FROM kv WHERE scope = ?1 ORDER BY key")
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        let results = stmt
+            .query_map(rusqlite::params![scope], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(results)
+    }
+
     async fn search_knowledge(&self, query: &str, limit: usize) -> Result<Option<String>> {
         if let Some(rag) = &self.rag {
             let context = rag.build_context(query, limit)?;
@@ -256,3 +650,215 @@ impl MemoryStore for SqliteMemory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+        // Two stores on one file, like two bot processes sharing memory.db
+        let stores = [
+            Arc::new(SqliteMemory::open(&path).unwrap()),
+            Arc::new(SqliteMemory::open(&path).unwrap()),
+        ];
+
+        let mut tasks = Vec::new();
+        for i in 0..32 {
+            let memory = stores[i % 2].clone();
+            tasks.push(tokio::spawn(async move {
+                let session = format!("dm:{}", i % 4);
+                for j in 0..10 {
+                    memory
+                        .save_turn(&session, &format!("question {}", j), &format!("answer {}", j))
+                        .await?;
+                    memory.get_history(&session, 50).await?;
+                }
+                memory.save_fact(&format!("fact:{}", i), "value").await
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        for s in 0..4 {
+            let history = stores[0].get_history(&format!("dm:{}", s), 1000).await.unwrap();
+            assert_eq!(history.len(), 8 * 10 * 2);
+        }
+        assert_eq!(stores[1].stats().unwrap().facts, 32);
+    }
+
+    #[tokio::test]
+    async fn test_delete_last_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = SqliteMemory::open(&dir.path().join("memory.db")).unwrap();
+        assert!(!memory.delete_last_turn("cli:s").await.unwrap());
+
+        memory.save_turn("cli:s", "q0", "a0").await.unwrap();
+        memory.save_turn("cli:s", "q1", "a1").await.unwrap();
+        memory.save_turn("other", "q", "a").await.unwrap();
+
+        assert!(memory.delete_last_turn("cli:s").await.unwrap());
+        let history = memory.get_history("cli:s", 100).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content.as_deref(), Some("a0"));
+        assert_eq!(memory.get_history("other", 100).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_replace_history_keeps_summary_role() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = SqliteMemory::open(&dir.path().join("memory.db")).unwrap();
+        for i in 0..5 {
+            memory.save_turn("cli:s", &format!("q{}", i), &format!("a{}", i)).await.unwrap();
+        }
+
+        let recent = memory.get_history("cli:s", 2).await.unwrap();
+        let mut compacted = vec![ChatMessage::summary("Asked q0..q3")];
+        compacted.extend(recent);
+        memory.replace_history("cli:s", &compacted).await.unwrap();
+
+        let history = memory.get_history("cli:s", 100).await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].role, zenclaw_core::message::Role::Summary);
+        assert_eq!(history[2].content.as_deref(), Some("a4"));
+    }
+
+    #[tokio::test]
+    async fn test_search_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+        {
+            // A database from before the full-text index
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            insert_message(&conn, "cli:old", &ChatMessage::user("Which database should we use?"), None).unwrap();
+        }
+
+        let memory = SqliteMemory::open(&path).unwrap();
+        memory.save_turn("telegram:1", "Is Postgres fine for the database?", "Yes, Postgres works well.").await.unwrap();
+        memory.save_turn("telegram:2", "Any news?", "Nothing about databases.").await.unwrap();
+
+        let found = memory.search_history("database", None, 10).await.unwrap();
+        let mut sessions: Vec<&str> = found.iter().map(|m| m.session_key.as_str()).collect();
+        sessions.sort();
+        assert_eq!(sessions, ["cli:old", "telegram:1", "telegram:2"]);
+        assert!(found.iter().all(|m| m.entry.timestamp.is_some()));
+
+        let found = memory.search_history("postgres \"works", Some("telegram:1"), 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].entry.message.content.as_deref(), Some("Yes, Postgres works well."));
+
+        // Deleted messages leave the index
+        memory.clear_history("telegram:1").await.unwrap();
+        assert!(memory.search_history("postgres", None, 10).await.unwrap().is_empty());
+        assert!(memory.search_history("  ", None, 10).await.unwrap().is_empty());
+    }
+
+    fn schema_version(path: &Path) -> i64 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT version FROM schema_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_v0_database_migrates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+        {
+            // Unversioned: history and facts only, with data in them
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            insert_message(&conn, "cli:s", &ChatMessage::user("Remember the staging server"), None).unwrap();
+            conn.execute("INSERT INTO facts (key, value) VALUES ('lang:cli:s', 'id')", []).unwrap();
+        }
+
+        let memory = SqliteMemory::open(&path).unwrap();
+        assert_eq!(schema_version(&path), SCHEMA_VERSION as i64);
+        assert_eq!(memory.get_history("cli:s", 10).await.unwrap().len(), 1);
+        assert_eq!(memory.get_fact("lang:cli:s").await.unwrap().as_deref(), Some("id"));
+        assert_eq!(memory.search_history("staging", None, 10).await.unwrap().len(), 1);
+        memory.kv_set("cli:s", "server", "staging-1").await.unwrap();
+        drop(memory);
+
+        // Reopening finds nothing to do
+        let memory = SqliteMemory::open(&path).unwrap();
+        assert_eq!(schema_version(&path), SCHEMA_VERSION as i64);
+        assert_eq!(memory.search_history("staging", None, 10).await.unwrap().len(), 1);
+        assert_eq!(memory.kv_get("cli:s", "server").await.unwrap().as_deref(), Some("staging-1"));
+    }
+
+    #[test]
+    fn test_newer_database_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+        drop(SqliteMemory::open(&path).unwrap());
+        let newer = SCHEMA_VERSION as i64 + 1;
+        Connection::open(&path)
+            .unwrap()
+            .execute("UPDATE schema_version SET version = ?1", [newer])
+            .unwrap();
+
+        let err = SqliteMemory::open(&path).err().unwrap();
+        assert!(err.to_string().contains("newer ZenClaw"), "{}", err);
+        assert_eq!(schema_version(&path), newer);
+    }
+
+    #[tokio::test]
+    async fn test_kv_is_scoped() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = SqliteMemory::open(&dir.path().join("memory.db")).unwrap();
+        memory.kv_set("cli:s", "name", "Ana").await.unwrap();
+        memory.kv_set("cli:s", "name", "Ana Lima").await.unwrap();
+        memory.kv_set("cli:s", "deploy", "fly.io").await.unwrap();
+        memory.kv_set("other", "name", "Budi").await.unwrap();
+
+        assert_eq!(memory.kv_get("cli:s", "name").await.unwrap().as_deref(), Some("Ana Lima"));
+        assert_eq!(memory.kv_get("cli:s", "missing").await.unwrap(), None);
+        assert_eq!(
+            memory.kv_list("cli:s").await.unwrap(),
+            vec![("deploy".to_string(), "fly.io".to_string()), ("name".to_string(), "Ana Lima".to_string())]
+        );
+
+        assert!(memory.kv_delete("cli:s", "name").await.unwrap());
+        assert!(!memory.kv_delete("cli:s", "name").await.unwrap());
+        assert_eq!(memory.kv_get("other", "name").await.unwrap().as_deref(), Some("Budi"));
+    }
+
+    #[tokio::test]
+    async fn test_import_merges_by_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = SqliteMemory::open(&dir.path().join("memory.db")).unwrap();
+        let at = |secs: i64| Some(DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap());
+        let entry = |role: &str, text: &str, secs: i64| SessionEntry {
+            message: if role == "user" { ChatMessage::user(text) } else { ChatMessage::assistant(text) },
+            timestamp: at(secs),
+        };
+
+        let local = vec![entry("user", "q1", 10), entry("assistant", "a1", 11), entry("user", "q3", 30)];
+        memory.import_session("cli:s", &serde_json::to_string(&local).unwrap(), true).await.unwrap();
+
+        // The other machine has q2/a2 in between, plus q1/a1 again
+        let remote = vec![entry("user", "q1", 10), entry("assistant", "a1", 11), entry("user", "q2", 20), entry("assistant", "a2", 21)];
+        let added = memory.import_session("cli:s", &serde_json::to_string(&remote).unwrap(), false).await.unwrap();
+        assert_eq!(added, 2);
+
+        let order: Vec<String> = memory
+            .get_history("cli:s", 100)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|m| m.content)
+            .collect();
+        assert_eq!(order, ["q1", "a1", "q2", "a2", "q3"]);
+
+        // Re-importing an export changes nothing
+        let export = memory.export_session("cli:s").await.unwrap();
+        assert_eq!(memory.import_session("cli:s", &export, false).await.unwrap(), 0);
+        assert_eq!(memory.export_session("cli:s").await.unwrap(), export);
+    }
+}
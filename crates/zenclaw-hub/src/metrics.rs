@@ -1,9 +1,21 @@
 //! Runtime metrics — track request counts, latencies, errors.
+//!
+//! [`Metrics`] backs the JSON status and dashboard heartbeat;
+//! [`PrometheusMetrics`] is filled from agent events and served as
+//! Prometheus text on `GET /metrics`.
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
+use prometheus::{
+    exponential_buckets, Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts,
+    Registry, TextEncoder,
+};
+use tracing::warn;
+
+use zenclaw_core::bus::{EventBus, SystemEvent};
+
 /// Global metrics collector.
 #[derive(Debug, Default)]
 pub struct Metrics {
@@ -15,6 +27,8 @@ pub struct Metrics {
     pub tool_calls: AtomicU64,
     pub rag_queries: AtomicU64,
     pub webhook_events: AtomicU64,
+    /// Unix timestamp (seconds) of the last request, 0 = none yet.
+    pub last_request_at: AtomicI64,
     start_time: Option<Instant>,
 }
 
@@ -28,6 +42,8 @@ impl Metrics {
 
     pub fn record_request(&self, success: bool) {
         self.requests_total.fetch_add(1, Ordering::Relaxed);
+        self.last_request_at
+            .store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
         if success {
             self.requests_success.fetch_add(1, Ordering::Relaxed);
         } else {
@@ -47,6 +63,14 @@ impl Metrics {
         self.webhook_events.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Time of the last request, if any.
+    pub fn last_request(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self.last_request_at.load(Ordering::Relaxed) {
+            0 => None,
+            ts => chrono::DateTime::from_timestamp(ts, 0),
+        }
+    }
+
     pub fn uptime_secs(&self) -> u64 {
         self.start_time
             .map(|t| t.elapsed().as_secs())
@@ -75,13 +99,166 @@ impl Metrics {
             "tool_calls": self.tool_calls.load(Ordering::Relaxed),
             "rag_queries": self.rag_queries.load(Ordering::Relaxed),
             "webhook_events": self.webhook_events.load(Ordering::Relaxed),
+            "last_request": self.last_request().map(|t| t.to_rfc3339()),
         })
     }
 }
 
+/// One-line liveness summary for dashboards.
+pub fn heartbeat_line(metrics: &Metrics) -> String {
+    let uptime = metrics.uptime_secs();
+    let last = metrics
+        .last_request()
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "never".to_string());
+
+    format!(
+        "💓 [{}] alive · up {}h {}m {}s · requests {} (errors {}) · last request {}",
+        chrono::Local::now().format("%H:%M:%S"),
+        uptime / 3600,
+        (uptime % 3600) / 60,
+        uptime % 60,
+        metrics.requests_total.load(Ordering::Relaxed),
+        metrics.requests_error.load(Ordering::Relaxed),
+        last,
+    )
+}
+
 /// Shared metrics instance.
 pub type SharedMetrics = Arc<Metrics>;
 
 pub fn new_metrics() -> SharedMetrics {
     Arc::new(Metrics::new())
 }
+
+/// Periodically push a heartbeat line into `tx` until the receiver is dropped.
+pub fn spawn_heartbeat(
+    metrics: SharedMetrics,
+    interval: std::time::Duration,
+    tx: tokio::sync::mpsc::Sender<String>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if tx.send(heartbeat_line(&metrics)).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Prometheus metrics, updated from `agent_done` events on the agent's
+/// [`EventBus`] so the agent loop itself stays free of instrumentation.
+pub struct PrometheusMetrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    tool_calls: IntCounterVec,
+    provider_errors: IntCounter,
+    latency: Histogram,
+    tokens: Histogram,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        let requests = IntCounterVec::new(
+            Opts::new("zenclaw_requests_total", "Agent turns, by outcome"),
+            &["status"],
+        )
+        .expect("valid metric");
+        let tool_calls = IntCounterVec::new(
+            Opts::new("zenclaw_tool_invocations_total", "Tool calls made by the agent, by tool"),
+            &["tool"],
+        )
+        .expect("valid metric");
+        let provider_errors = IntCounter::new(
+            "zenclaw_provider_errors_total",
+            "LLM provider calls that failed after retries",
+        )
+        .expect("valid metric");
+        let latency = Histogram::with_opts(
+            HistogramOpts::new("zenclaw_request_duration_seconds", "Time to answer one agent turn")
+                .buckets(exponential_buckets(0.25, 2.0, 10).expect("valid buckets")),
+        )
+        .expect("valid metric");
+        let tokens = Histogram::with_opts(
+            HistogramOpts::new("zenclaw_response_tokens", "Completion tokens per agent turn")
+                .buckets(exponential_buckets(16.0, 2.0, 10).expect("valid buckets")),
+        )
+        .expect("valid metric");
+
+        let registry = Registry::new();
+        for collector in [
+            Box::new(requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(tool_calls.clone()),
+            Box::new(provider_errors.clone()),
+            Box::new(latency.clone()),
+            Box::new(tokens.clone()),
+        ] {
+            registry.register(collector).expect("unique metric names");
+        }
+
+        Self {
+            registry,
+            requests,
+            tool_calls,
+            provider_errors,
+            latency,
+            tokens,
+        }
+    }
+
+    /// Record one system event; everything but `agent_done` is ignored.
+    pub fn observe(&self, event: &SystemEvent) {
+        if event.event_type != "agent_done" {
+            return;
+        }
+        let data = &event.data;
+
+        let status = if data["success"].as_bool().unwrap_or(false) { "success" } else { "error" };
+        self.requests.with_label_values(&[status]).inc();
+
+        for tool in data["tools"].as_array().into_iter().flatten().filter_map(|t| t.as_str()) {
+            self.tool_calls.with_label_values(&[tool]).inc();
+        }
+        self.provider_errors
+            .inc_by(data["provider_errors"].as_u64().unwrap_or(0));
+        self.latency
+            .observe(data["elapsed_ms"].as_u64().unwrap_or(0) as f64 / 1000.0);
+        if let Some(tokens) = data["completion_tokens"].as_u64() {
+            self.tokens.observe(tokens as f64);
+        }
+    }
+
+    /// Update from `bus` in the background until it is dropped.
+    pub fn spawn_collector(self: &Arc<Self>, bus: &EventBus) -> tokio::task::JoinHandle<()> {
+        let mut rx = bus.subscribe_system();
+        let metrics = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => metrics.observe(&event),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Metrics collector missed {} events", n);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buf) {
+            warn!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buf).unwrap_or_default()
+    }
+}
+
+impl Default for PrometheusMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
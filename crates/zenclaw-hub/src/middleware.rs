@@ -2,75 +2,55 @@
 
 use axum::{
     body::Body,
-    extract::Request,
-    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
+    Router,
 };
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use std::time::Instant;
 use tracing::info;
 
-/// Rate limiter state.
-#[derive(Clone)]
-pub struct RateLimiter {
-    requests: Arc<Mutex<HashMap<String, Vec<Instant>>>>,
-    max_requests: usize,
-    window: Duration,
-}
-
-impl RateLimiter {
-    pub fn new(max_requests: usize, window_secs: u64) -> Self {
-        Self {
-            requests: Arc::new(Mutex::new(HashMap::new())),
-            max_requests,
-            window: Duration::from_secs(window_secs),
-        }
-    }
-
-    /// Check if a request should be allowed.
-    async fn check(&self, key: &str) -> bool {
-        let mut requests = self.requests.lock().await;
-        let now = Instant::now();
+use zenclaw_core::ratelimit::RateLimiter;
 
-        let entry = requests.entry(key.to_string()).or_default();
+use crate::metrics::SharedMetrics;
 
-        // Remove expired entries
-        entry.retain(|t| now.duration_since(*t) < self.window);
-
-        if entry.len() >= self.max_requests {
-            false
-        } else {
-            entry.push(now);
-            true
-        }
-    }
+/// What [`rate_limit_middleware`] needs to tell clients apart.
+#[derive(Clone)]
+pub struct RateLimitState {
+    limiter: Arc<RateLimiter>,
+    /// Key on the API key: only true when auth is on, so the key has
+    /// already been checked before it reaches the limiter.
+    by_api_key: bool,
 }
 
-/// Rate limiting middleware.
+/// Per-client rate limiting middleware.
+///
+/// Clients are told apart by the API key when auth is on, otherwise by
+/// their address: the peer address, or `X-Forwarded-For` when the peer is
+/// a trusted proxy. Over the limit they get 429 with `Retry-After`.
 pub async fn rate_limit_middleware(
+    State(state): State<RateLimitState>,
     headers: HeaderMap,
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    // Use IP or API key as rate limit key
-    let key = headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string();
-
-    // Simple in-memory rate limiter (60 req/min)
-    static LIMITER: std::sync::OnceLock<RateLimiter> = std::sync::OnceLock::new();
-    let limiter = LIMITER.get_or_init(|| RateLimiter::new(60, 60));
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let key = client_key(&state, &headers, peer);
 
-    if !limiter.check(&key).await {
+    if let Err(retry_after) = state.limiter.check(&key) {
+        let secs = retry_after.as_secs().max(1);
         return (
             StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, secs.to_string())],
             axum::Json(serde_json::json!({
-                "error": "Rate limit exceeded. Max 60 requests per minute."
+                "error": format!("Rate limit exceeded. Retry in {}s.", secs)
             })),
         )
             .into_response();
@@ -79,21 +59,68 @@ pub async fn rate_limit_middleware(
     next.run(request).await
 }
 
+/// Rate-limit every route of `router` (None = no limit). Pass the same
+/// `api_key` the routes are protected with.
+pub fn rate_limit<S>(router: Router<S>, limiter: Option<Arc<RateLimiter>>, api_key: Option<&str>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    match limiter {
+        Some(limiter) => {
+            let state = RateLimitState {
+                limiter,
+                by_api_key: api_key.is_some_and(|k| !k.is_empty()),
+            };
+            router.route_layer(axum::middleware::from_fn_with_state(state, rate_limit_middleware))
+        }
+        None => router,
+    }
+}
+
+/// Identify the caller for rate limiting.
+fn client_key(state: &RateLimitState, headers: &HeaderMap, peer: Option<IpAddr>) -> String {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).filter(|v| !v.is_empty());
+
+    if state.by_api_key
+        && let Some(token) = header("authorization")
+            .and_then(|a| a.strip_prefix("Bearer "))
+            .or_else(|| header("x-api-key"))
+    {
+        // Don't keep raw keys in memory longer than needed
+        let digest = Sha256::digest(token.as_bytes());
+        return format!("key:{:x}", digest);
+    }
+
+    let Some(mut ip) = peer else { return "unknown".to_string() };
+    // Walk the proxy chain back from the peer; the first hop we don't
+    // trust is the client. Entries before it may be made up.
+    if state.limiter.is_trusted_proxy(ip)
+        && let Some(forwarded) = header("x-forwarded-for")
+    {
+        for hop in forwarded.rsplit(',') {
+            let Ok(hop) = hop.trim().parse::<IpAddr>() else { break };
+            ip = hop;
+            if !state.limiter.is_trusted_proxy(hop) {
+                break;
+            }
+        }
+    }
+    format!("ip:{}", ip)
+}
+
 /// API key authentication middleware.
+///
+/// Accepts `Authorization: Bearer <key>` or `X-API-Key: <key>`; anything
+/// else gets 401. With no key configured every request passes.
 pub async fn auth_middleware(
+    State(expected): State<Option<Arc<str>>>,
     headers: HeaderMap,
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    // Check for API key in config
-    let expected_key = std::env::var("ZENCLAW_API_KEY").ok();
-
-    // If no API key set, allow all requests
-    if expected_key.is_none() {
+    let Some(expected) = expected else {
         return next.run(request).await;
-    }
-
-    let expected = expected_key.unwrap();
+    };
 
     // Check Authorization header
     let auth = headers
@@ -111,7 +138,7 @@ pub async fn auth_middleware(
             .unwrap_or("")
     };
 
-    if token != expected {
+    if !constant_time_eq(token.as_bytes(), expected.as_bytes()) {
         return (
             StatusCode::UNAUTHORIZED,
             axum::Json(serde_json::json!({
@@ -124,6 +151,22 @@ pub async fn auth_middleware(
     next.run(request).await
 }
 
+/// Put every route of `router` behind [`auth_middleware`].
+///
+/// Routes merged in afterwards stay public.
+pub fn require_api_key<S>(router: Router<S>, api_key: Option<&str>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let key: Option<Arc<str>> = api_key.filter(|k| !k.is_empty()).map(Arc::from);
+    router.route_layer(axum::middleware::from_fn_with_state(key, auth_middleware))
+}
+
+/// Compare without leaking how many leading bytes matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 /// Request logging middleware.
 pub async fn logging_middleware(request: Request<Body>, next: Next) -> Response {
     let method = request.method().clone();
@@ -145,3 +188,101 @@ pub async fn logging_middleware(request: Request<Body>, next: Next) -> Response
 
     response
 }
+
+/// Request metrics middleware — counts requests and 5xx errors.
+pub async fn metrics_middleware(
+    State(metrics): State<SharedMetrics>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    metrics.record_request(!response.status().is_server_error());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let protected = require_api_key(Router::new().route("/v1/chat", get(|| async { "ok" })), Some("secret"));
+        Router::new().route("/health", get(|| async { "ok" })).merge(protected)
+    }
+
+    async fn status(uri: &str, auth: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri(uri);
+        if let Some(auth) = auth {
+            request = request.header("authorization", auth);
+        }
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_returns_retry_after() {
+        let limited = rate_limit(
+            Router::new().route("/v1/chat", get(|| async { "ok" })),
+            Some(Arc::new(RateLimiter::new(1, 1))),
+            None,
+        );
+        let request = || Request::builder().uri("/v1/chat").header("x-api-key", "k").body(Body::empty()).unwrap();
+
+        let first = limited.clone().oneshot(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let second = limited.oneshot(request()).await.unwrap();
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(second.headers().contains_key(header::RETRY_AFTER));
+    }
+
+    fn key(by_api_key: bool, peer: &str, headers: &[(&'static str, &str)]) -> String {
+        let proxies = vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+        let state = RateLimitState {
+            limiter: Arc::new(RateLimiter::new(60, 1).with_trusted_proxies(proxies)),
+            by_api_key,
+        };
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(*name, value.parse().unwrap());
+        }
+        client_key(&state, &map, Some(peer.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_client_key_without_auth_is_the_address() {
+        // Made-up keys and forwarded addresses don't get a fresh bucket
+        assert_eq!(key(false, "203.0.113.9", &[("authorization", "Bearer random")]), "ip:203.0.113.9");
+        assert_eq!(key(false, "203.0.113.9", &[("x-api-key", "random")]), "ip:203.0.113.9");
+        assert_eq!(key(false, "203.0.113.9", &[("x-forwarded-for", "198.51.100.7")]), "ip:203.0.113.9");
+
+        assert!(key(true, "203.0.113.9", &[("x-api-key", "secret")]).starts_with("key:"));
+    }
+
+    #[test]
+    fn test_forwarded_for_only_from_trusted_proxies() {
+        let xff = |value| key(false, "10.0.0.1", &[("x-forwarded-for", value)]);
+        assert_eq!(xff("198.51.100.7"), "ip:198.51.100.7");
+        assert_eq!(xff("198.51.100.7, 10.0.0.2"), "ip:198.51.100.7");
+        // The client can prepend anything; the last untrusted hop wins
+        assert_eq!(xff("192.0.2.1, 198.51.100.7"), "ip:198.51.100.7");
+        assert_eq!(xff("garbage"), "ip:10.0.0.1");
+        assert_eq!(key(false, "10.0.0.1", &[]), "ip:10.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_wrong_key_is_rejected() {
+        assert_eq!(status("/v1/chat", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/v1/chat", Some("Bearer wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/v1/chat", Some("secret")).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_correct_key_passes_and_health_is_public() {
+        assert_eq!(status("/v1/chat", Some("Bearer secret")).await, StatusCode::OK);
+        assert_eq!(status("/health", None).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,565 @@
+//! Plugin system — dynamic tool extension.
+//!
+//! Plugins are external tools defined by a manifest plus any executable.
+//! This allows extending ZenClaw without recompiling.
+//!
+//! Plugin structure:
+//! ```text
+//! plugins/
+//! └── my_plugin/
+//!     ├── plugin.toml     # Manifest (or plugin.json)
+//!     └── run.sh          # Executable script
+//! ```
+//!
+//! Manifest fields:
+//! - `name` — tool name: letters, digits, `_` and `-`, at most 64 characters
+//! - `description` — what the tool does, shown to the model
+//! - `version` — optional, `MAJOR.MINOR.PATCH`
+//! - `parameters` — optional JSON Schema of the arguments (an `object` schema)
+//! - `command` — executable or script to run, optionally with arguments
+//!   (default `run.sh`). The first word is looked up in the plugin directory,
+//!   then on `PATH`; it runs with the plugin directory as working directory.
+//!
+//! The tool arguments arrive as JSON on stdin (and in `ZENCLAW_ARGS`); whatever
+//! the command prints to stdout is the tool result.
+//!
+//! Instead of `command`, a manifest may name a WebAssembly module with `wasm`
+//! (needs the `wasm` feature). It runs sandboxed — see [`wasm`] for the ABI
+//! and the `[capabilities]` it can opt into.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::tool::Tool;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Manifest file names, in order of preference.
+pub const MANIFEST_FILES: &[&str] = &["plugin.toml", "plugin.json"];
+
+/// Longest a plugin command may run.
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Plugin manifest (`plugin.toml` or `plugin.json`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// Plugin name (used as tool name).
+    pub name: String,
+    /// Description shown to LLM.
+    pub description: String,
+    /// JSON Schema for parameters.
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+    /// Command to execute (first word relative to the plugin dir, or on PATH).
+    #[serde(default = "default_command")]
+    pub command: String,
+    /// Author info.
+    pub author: Option<String>,
+    /// Version.
+    pub version: Option<String>,
+    /// WebAssembly module to run instead of `command` (relative to the plugin dir).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm: Option<String>,
+    /// What a WASM plugin may access (nothing by default).
+    #[serde(default)]
+    pub capabilities: WasmCapabilities,
+}
+
+/// Opt-in sandbox permissions of a WASM plugin (`[capabilities]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WasmCapabilities {
+    /// Link WASI: stderr, clocks and randomness. Without it the module can
+    /// only compute on its input.
+    #[serde(default)]
+    pub wasi: bool,
+    /// Host environment variables passed through (needs `wasi`).
+    #[serde(default)]
+    pub env: Vec<String>,
+    /// Plugin-relative directories mounted read-only (needs `wasi`).
+    #[serde(default)]
+    pub read_dirs: Vec<String>,
+    /// Linear memory limit in MiB (default 64).
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+    /// Instruction budget per call, in wasmtime fuel units (default 10 billion).
+    #[serde(default)]
+    pub max_fuel: Option<u64>,
+}
+
+fn default_command() -> String {
+    "run.sh".to_string()
+}
+
+fn default_parameters() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+impl PluginManifest {
+    /// Read the manifest in `dir`. Errors say which file and what is wrong.
+    pub fn read(dir: &Path) -> std::result::Result<Self, String> {
+        let path = MANIFEST_FILES
+            .iter()
+            .map(|f| dir.join(f))
+            .find(|p| p.is_file())
+            .ok_or_else(|| format!("no {} found", MANIFEST_FILES.join(" or ")))?;
+        let content = std::fs::read_to_string(&path).map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+
+        let file = path.file_name().and_then(|f| f.to_str()).unwrap_or_default();
+        if path.extension().is_some_and(|e| e == "toml") {
+            toml::from_str(&content).map_err(|e| format!("{}: {}", file, e.to_string().trim()))
+        } else {
+            serde_json::from_str(&content).map_err(|e| format!("{}: {}", file, e))
+        }
+    }
+
+    /// Check the manifest against the schema and that `command` can run.
+    /// Returns the resolved program on success.
+    pub fn validate(&self, dir: &Path) -> std::result::Result<PathBuf, String> {
+        if self.name.is_empty() || self.name.len() > 64 {
+            return Err(format!("name must be 1-64 characters, got {}", self.name.len()));
+        }
+        if let Some(c) = self.name.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-')) {
+            return Err(format!(
+                "name '{}' contains '{}' (only letters, digits, '_' and '-' are allowed)",
+                self.name, c
+            ));
+        }
+        if self.description.trim().is_empty() {
+            return Err("description is empty".into());
+        }
+        if let Some(version) = &self.version {
+            let parts: Vec<&str> = version.split('.').collect();
+            if parts.len() != 3 || parts.iter().any(|p| p.parse::<u64>().is_err()) {
+                return Err(format!("version '{}' is not MAJOR.MINOR.PATCH", version));
+            }
+        }
+        validate_parameters(&self.parameters)?;
+        match &self.wasm {
+            Some(module) => self.validate_wasm(dir, module),
+            None => self.program(dir),
+        }
+    }
+
+    fn validate_wasm(&self, dir: &Path, module: &str) -> std::result::Result<PathBuf, String> {
+        let path = dir.join(module);
+        if !path.is_file() {
+            return Err(format!("wasm module '{}' not found in {}", module, dir.display()));
+        }
+        let caps = &self.capabilities;
+        if !caps.wasi && (!caps.env.is_empty() || !caps.read_dirs.is_empty()) {
+            return Err("capabilities.env and capabilities.read_dirs need capabilities.wasi = true".into());
+        }
+        for sub in &caps.read_dirs {
+            let rel = Path::new(sub);
+            if rel.is_absolute() || rel.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+                return Err(format!("capabilities.read_dirs entry '{}' must stay inside the plugin directory", sub));
+            }
+            if !dir.join(rel).is_dir() {
+                return Err(format!("capabilities.read_dirs entry '{}' is not a directory", sub));
+            }
+        }
+        if cfg!(not(feature = "wasm")) {
+            return Err("WASM plugins need ZenClaw built with the `wasm` feature".into());
+        }
+        Ok(path)
+    }
+
+    /// The executable `command` starts, resolved against the plugin dir, then PATH.
+    fn program(&self, dir: &Path) -> std::result::Result<PathBuf, String> {
+        let first = self
+            .command
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| "command is empty".to_string())?;
+
+        let local = dir.join(first);
+        if local.is_file() {
+            if !is_executable(&local) {
+                return Err(format!("command '{}' is not executable (chmod +x {})", first, local.display()));
+            }
+            return Ok(local);
+        }
+        if first.contains('/') || first.contains('\\') {
+            return Err(format!("command '{}' not found in {}", first, dir.display()));
+        }
+        find_on_path(first).ok_or_else(|| format!("command '{}' not found in the plugin directory or on PATH", first))
+    }
+}
+
+fn validate_parameters(schema: &Value) -> std::result::Result<(), String> {
+    let Some(obj) = schema.as_object() else {
+        return Err("parameters must be a JSON Schema object".into());
+    };
+    if obj.get("type").and_then(Value::as_str) != Some("object") {
+        return Err("parameters.type must be \"object\"".into());
+    }
+    let properties = match obj.get("properties") {
+        None => return Ok(()),
+        Some(Value::Object(props)) => props,
+        Some(_) => return Err("parameters.properties must be an object".into()),
+    };
+    for (name, prop) in properties {
+        if !prop.is_object() {
+            return Err(format!("parameters.properties.{} must be an object", name));
+        }
+    }
+    if let Some(required) = obj.get("required") {
+        let Some(required) = required.as_array() else {
+            return Err("parameters.required must be an array".into());
+        };
+        for field in required {
+            match field.as_str() {
+                Some(f) if properties.contains_key(f) => {}
+                Some(f) => return Err(format!("parameters.required lists '{}', which isn't in properties", f)),
+                None => return Err("parameters.required must contain strings".into()),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+fn find_on_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths).find_map(|dir| {
+        let candidate = dir.join(program);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        #[cfg(windows)]
+        {
+            let exe = dir.join(format!("{}.exe", program));
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+        None
+    })
+}
+
+/// How a plugin runs.
+enum Runner {
+    /// External program; `PluginManifest::command` supplies its arguments.
+    Command(PathBuf),
+    #[cfg(feature = "wasm")]
+    Wasm(std::sync::Arc<wasm::WasmPlugin>),
+}
+
+/// A plugin-based tool — runs external scripts or sandboxed WASM modules.
+pub struct PluginTool {
+    manifest: PluginManifest,
+    plugin_dir: PathBuf,
+    runner: Runner,
+}
+
+impl PluginTool {
+    /// Load and validate a plugin from its directory.
+    pub async fn load(dir: &Path) -> Result<Self> {
+        let invalid = |reason: String| ZenClawError::Config(format!("invalid plugin {}: {}", dir.display(), reason));
+        let manifest = PluginManifest::read(dir).map_err(invalid)?;
+        let path = manifest.validate(dir).map_err(invalid)?;
+
+        // Without the `wasm` feature, `validate` already rejects WASM manifests
+        #[cfg(feature = "wasm")]
+        let runner = if manifest.wasm.is_some() {
+            let module = wasm::WasmPlugin::load(&path, dir, &manifest.capabilities).map_err(invalid)?;
+            Runner::Wasm(std::sync::Arc::new(module))
+        } else {
+            Runner::Command(path)
+        };
+        #[cfg(not(feature = "wasm"))]
+        let runner = Runner::Command(path);
+
+        info!("Loaded plugin: {} v{}", manifest.name, manifest.version.as_deref().unwrap_or("0.0"));
+
+        Ok(Self {
+            manifest,
+            plugin_dir: dir.to_path_buf(),
+            runner,
+        })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    async fn run_command(&self, program: &Path, args_json: String) -> Result<String> {
+        let exec_error = |message: String| ZenClawError::ToolExecution {
+            tool: self.manifest.name.clone(),
+            message,
+        };
+
+        let mut child = tokio::process::Command::new(program)
+            .args(self.manifest.command.split_whitespace().skip(1))
+            .current_dir(&self.plugin_dir)
+            .env("ZENCLAW_ARGS", &args_json)
+            .env("ZENCLAW_PLUGIN", "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| exec_error(format!("exec error: {}", e)))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A plugin that ignores stdin may exit before reading it
+            let _ = stdin.write_all(args_json.as_bytes()).await;
+        }
+
+        let output = match tokio::time::timeout(PLUGIN_TIMEOUT, child.wait_with_output()).await {
+            Ok(result) => result.map_err(|e| exec_error(format!("exec error: {}", e)))?,
+            Err(_) => {
+                return Ok(format!(
+                    "Plugin error: {} didn't finish within {}s",
+                    self.manifest.name,
+                    PLUGIN_TIMEOUT.as_secs()
+                ));
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        if output.status.success() {
+            Ok(stdout)
+        } else {
+            Ok(format!("Plugin error (exit {}):\n{}\n{}", output.status.code().unwrap_or(-1), stdout, stderr))
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for PluginTool {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.description
+    }
+
+    fn parameters(&self) -> Value {
+        self.manifest.parameters.clone()
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let args_json = serde_json::to_string(&args).unwrap_or_default();
+        match &self.runner {
+            Runner::Command(program) => self.run_command(program, args_json).await,
+            #[cfg(feature = "wasm")]
+            Runner::Wasm(module) => {
+                let module = module.clone();
+                let result = tokio::task::spawn_blocking(move || module.call(&args_json))
+                    .await
+                    .map_err(|e| ZenClawError::ToolExecution {
+                        tool: self.manifest.name.clone(),
+                        message: format!("wasm task failed: {}", e),
+                    })?;
+                Ok(result.unwrap_or_else(|e| format!("Plugin error: {}", e)))
+            }
+        }
+    }
+}
+
+/// Plugin manager — discovers and loads plugins.
+pub struct PluginManager {
+    plugins_dir: PathBuf,
+}
+
+impl PluginManager {
+    pub fn new(plugins_dir: &Path) -> Self {
+        Self {
+            plugins_dir: plugins_dir.to_path_buf(),
+        }
+    }
+
+    /// Discover and load all valid plugins; invalid ones are skipped with a warning.
+    pub async fn load_all(&self) -> Vec<PluginTool> {
+        let mut plugins = Vec::new();
+
+        if !self.plugins_dir.exists() {
+            // Create plugins dir + example
+            fs::create_dir_all(&self.plugins_dir).await.ok();
+            self.create_example_plugin().await;
+        }
+
+        for path in plugin_dirs(&self.plugins_dir) {
+            match PluginTool::load(&path).await {
+                Ok(plugin) => plugins.push(plugin),
+                Err(e) => warn!("Skipping plugin: {}", e),
+            }
+        }
+
+        plugins
+    }
+
+    /// Validate every plugin under `dir` (or `dir` itself if it holds a
+    /// manifest) without loading them. Each entry is the plugin directory
+    /// and its manifest, or why it is invalid.
+    pub fn validate(dir: &Path) -> Vec<(PathBuf, std::result::Result<PluginManifest, String>)> {
+        let dirs = if has_manifest(dir) {
+            vec![dir.to_path_buf()]
+        } else {
+            plugin_dirs(dir)
+        };
+        dirs.into_iter()
+            .map(|path| {
+                let result = PluginManifest::read(&path).and_then(|m| m.validate(&path).map(|_| m));
+                (path, result)
+            })
+            .collect()
+    }
+
+    /// Create an example plugin for reference.
+    async fn create_example_plugin(&self) {
+        let example_dir = self.plugins_dir.join("example_hello");
+        fs::create_dir_all(&example_dir).await.ok();
+
+        let manifest = r#"# ZenClaw plugin manifest
+name = "hello_plugin"
+description = "An example plugin that greets users. Shows how to create ZenClaw plugins."
+version = "1.0.0"
+author = "ZenClaw"
+command = "run.sh"
+
+[parameters]
+type = "object"
+required = ["name"]
+
+[parameters.properties.name]
+type = "string"
+description = "Name to greet"
+"#;
+
+        let script = r#"#!/bin/sh
+# Example ZenClaw plugin
+# Arguments arrive on stdin as JSON; stdout is the tool result
+
+ARGS=$(cat)
+
+# Parse the "name" from JSON args (using simple grep/sed)
+NAME=$(echo "$ARGS" | grep -o '"name":"[^"]*"' | sed 's/"name":"//;s/"//')
+
+if [ -z "$NAME" ]; then
+    NAME="World"
+fi
+
+echo "Hello, $NAME! 👋"
+echo "This is an example ZenClaw plugin."
+echo "Args: $ARGS"
+"#;
+
+        fs::write(example_dir.join("plugin.toml"), manifest).await.ok();
+        fs::write(example_dir.join("run.sh"), script).await.ok();
+
+        // Make executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(metadata) = std::fs::metadata(example_dir.join("run.sh")) {
+                let mut perms = metadata.permissions();
+                perms.set_mode(0o755);
+                std::fs::set_permissions(example_dir.join("run.sh"), perms).ok();
+            }
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.plugins_dir
+    }
+}
+
+fn has_manifest(dir: &Path) -> bool {
+    MANIFEST_FILES.iter().any(|f| dir.join(f).is_file())
+}
+
+/// Subdirectories of `dir` that contain a manifest, sorted.
+fn plugin_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && has_manifest(p))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, parameters: Value) -> PluginManifest {
+        PluginManifest {
+            name: name.into(),
+            description: "test plugin".into(),
+            parameters,
+            command: "sh".into(),
+            author: None,
+            version: Some("1.2.3".into()),
+            wasm: None,
+            capabilities: WasmCapabilities::default(),
+        }
+    }
+
+    #[test]
+    fn test_validate_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(manifest("check_docker", default_parameters()).validate(dir.path()).is_ok());
+
+        let err = manifest("check docker", default_parameters()).validate(dir.path()).unwrap_err();
+        assert!(err.contains("contains ' '"), "{}", err);
+
+        let err = manifest("ok", json!({"type": "string"})).validate(dir.path()).unwrap_err();
+        assert!(err.contains("parameters.type"), "{}", err);
+
+        let schema = json!({"type": "object", "properties": {"a": {"type": "string"}}, "required": ["b"]});
+        let err = manifest("ok", schema).validate(dir.path()).unwrap_err();
+        assert!(err.contains("'b'"), "{}", err);
+
+        let mut bad = manifest("ok", default_parameters());
+        bad.version = Some("1.0".into());
+        assert!(bad.validate(dir.path()).unwrap_err().contains("version"));
+
+        let mut missing = manifest("ok", default_parameters());
+        missing.command = "does_not_exist.sh".into();
+        assert!(missing.validate(dir.path()).unwrap_err().contains("not found"));
+    }
+
+    #[test]
+    fn test_read_toml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("plugin.json"), r#"{"name": "a", "description": "d"}"#).unwrap();
+        let m = PluginManifest::read(dir.path()).unwrap();
+        assert_eq!(m.command, "run.sh");
+        assert_eq!(m.parameters["type"], "object");
+
+        // TOML wins when both exist, and errors name the file
+        std::fs::write(dir.path().join("plugin.toml"), "name = \"b\"\n").unwrap();
+        let err = PluginManifest::read(dir.path()).unwrap_err();
+        assert!(err.starts_with("plugin.toml:") && err.contains("description"), "{}", err);
+
+        std::fs::write(dir.path().join("plugin.toml"), "name = \"b\"\ndescription = \"d\"\n").unwrap();
+        assert_eq!(PluginManifest::read(dir.path()).unwrap().name, "b");
+    }
+}
@@ -0,0 +1,158 @@
+//! WebAssembly plugins — sandboxed tools run with wasmtime.
+//!
+//! A module is a tool when it exports:
+//! - `memory` — its linear memory
+//! - `alloc(len: i32) -> i32` — reserve `len` bytes for the input
+//! - `call(ptr: i32, len: i32) -> i64` — handle the tool-call JSON (UTF-8)
+//!   at `ptr`/`len` and return the result string packed as
+//!   `(result_ptr << 32) | result_len`
+//!
+//! Reactor modules exporting `_initialize` have it called first. Every call
+//! gets a fresh instance, so no state leaks between calls.
+//!
+//! By default nothing is linked: the module can only compute on its input.
+//! `[capabilities] wasi = true` links WASI preview 1 with stderr, clocks and
+//! randomness; `env` and `read_dirs` then expose chosen variables and
+//! read-only directories. There is no network access either way.
+
+use std::path::Path;
+
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtxBuilder};
+
+use super::WasmCapabilities;
+
+/// Default linear memory limit per instance.
+const DEFAULT_MAX_MEMORY_MB: u64 = 64;
+/// Default instruction budget per call.
+const DEFAULT_MAX_FUEL: u64 = 10_000_000_000;
+/// Largest result read back from the guest.
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+struct HostState {
+    /// Present only when the manifest grants `wasi`.
+    wasi: Option<WasiP1Ctx>,
+    limits: StoreLimits,
+}
+
+/// A compiled WASM tool.
+pub struct WasmPlugin {
+    engine: Engine,
+    module: Module,
+    linker: Linker<HostState>,
+    capabilities: WasmCapabilities,
+    plugin_dir: std::path::PathBuf,
+}
+
+impl WasmPlugin {
+    /// Compile the module at `path` and check it exports the plugin ABI.
+    pub fn load(path: &Path, plugin_dir: &Path, capabilities: &WasmCapabilities) -> Result<Self, String> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| format!("wasm engine: {:#}", e))?;
+        let module = Module::from_file(&engine, path).map_err(|e| format!("{}: {:#}", path.display(), e))?;
+
+        for export in ["memory", "alloc", "call"] {
+            if module.get_export(export).is_none() {
+                return Err(format!("{} doesn't export `{}` (see the plugin ABI)", path.display(), export));
+            }
+        }
+
+        let mut linker = Linker::new(&engine);
+        if capabilities.wasi {
+            preview1::add_to_linker_sync(&mut linker, |state: &mut HostState| {
+                state.wasi.as_mut().expect("WASI context is set when wasi is granted")
+            })
+            .map_err(|e| format!("wasi: {:#}", e))?;
+        } else if let Some(import) = module.imports().find(|i| i.module().starts_with("wasi")) {
+            return Err(format!(
+                "{} imports {}::{} but capabilities.wasi is off",
+                path.display(),
+                import.module(),
+                import.name()
+            ));
+        }
+
+        Ok(Self {
+            engine,
+            module,
+            linker,
+            capabilities: capabilities.clone(),
+            plugin_dir: plugin_dir.to_path_buf(),
+        })
+    }
+
+    /// Run one tool call. Blocking — call it from `spawn_blocking`.
+    pub fn call(&self, args_json: &str) -> Result<String, String> {
+        let mut store = Store::new(&self.engine, self.host_state()?);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(self.capabilities.max_fuel.unwrap_or(DEFAULT_MAX_FUEL))
+            .map_err(|e| e.to_string())?;
+
+        self.run(&mut store, args_json).map_err(|e| match e.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => "plugin exceeded its compute budget (capabilities.max_fuel)".to_string(),
+            _ => format!("{:#}", e),
+        })
+    }
+
+    fn run(&self, store: &mut Store<HostState>, args_json: &str) -> wasmtime::Result<String> {
+        let instance = self.linker.instantiate(&mut *store, &self.module)?;
+        if let Ok(init) = instance.get_typed_func::<(), ()>(&mut *store, "_initialize") {
+            init.call(&mut *store, ())?;
+        }
+
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("module exports no `memory`"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "alloc")?;
+        let call = instance.get_typed_func::<(i32, i32), i64>(&mut *store, "call")?;
+
+        let input = args_json.as_bytes();
+        let len = i32::try_from(input.len()).map_err(|_| wasmtime::Error::msg("arguments too large"))?;
+        let ptr = alloc.call(&mut *store, len)?;
+        memory.write(&mut *store, ptr as u32 as usize, input)?;
+
+        let packed = call.call(&mut *store, (ptr, len))? as u64;
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if out_len > MAX_OUTPUT_BYTES {
+            return Err(wasmtime::Error::msg(format!(
+                "result is {} bytes (limit {})",
+                out_len, MAX_OUTPUT_BYTES
+            )));
+        }
+
+        let mut output = vec![0u8; out_len];
+        memory.read(&*store, out_ptr, &mut output)?;
+        String::from_utf8(output).map_err(|_| wasmtime::Error::msg("result is not valid UTF-8"))
+    }
+
+    fn host_state(&self) -> Result<HostState, String> {
+        let caps = &self.capabilities;
+        let limits = StoreLimitsBuilder::new()
+            .memory_size((caps.max_memory_mb.unwrap_or(DEFAULT_MAX_MEMORY_MB) * 1024 * 1024) as usize)
+            .instances(1)
+            .build();
+
+        let wasi = if caps.wasi {
+            let mut builder = WasiCtxBuilder::new();
+            builder.inherit_stderr();
+            for var in &caps.env {
+                if let Ok(value) = std::env::var(var) {
+                    builder.env(var, value);
+                }
+            }
+            for dir in &caps.read_dirs {
+                builder
+                    .preopened_dir(self.plugin_dir.join(dir), dir, DirPerms::READ, FilePerms::READ)
+                    .map_err(|e| format!("can't mount {}: {:#}", dir, e))?;
+            }
+            Some(builder.build_p1())
+        } else {
+            None
+        };
+
+        Ok(HostState { wasi, limits })
+    }
+}
@@ -0,0 +1,143 @@
+//! Prompt library — named, reusable prompt snippets.
+//!
+//! Snippets are markdown files in the prompts directory (`code-review.md`,
+//! `explain-like-5.md`, ...) with an optional `description:` frontmatter.
+//! Unlike skills they don't touch the system prompt: `/use <name> <args>`
+//! expands the snippet into a regular user message.
+//!
+//! Templates may use `{args}` for the text after the snippet name, plus the
+//! usual [`PromptVars`] like `{date}`. A template without `{args}` gets the
+//! arguments appended on a new line.
+
+use std::path::{Path, PathBuf};
+
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::prompt::PromptVars;
+
+use crate::skills::parse_frontmatter;
+
+/// A saved prompt snippet.
+#[derive(Debug, Clone)]
+pub struct PromptSnippet {
+    /// Snippet name (filename without .md).
+    pub name: String,
+    /// One-line description (from frontmatter).
+    pub description: String,
+    /// Prompt template.
+    pub template: String,
+    /// File path.
+    pub path: PathBuf,
+}
+
+impl PromptSnippet {
+    /// Render the snippet with `args` as `{args}`.
+    pub fn expand(&self, args: &str) -> String {
+        let mut vars = PromptVars::new();
+        let now = chrono::Local::now();
+        vars.set("args", args)
+            .set("date", now.format("%Y-%m-%d (%A)").to_string())
+            .set("time", now.format("%H:%M").to_string());
+
+        let rendered = vars.render(&self.template);
+        if self.template.contains("{args}") || args.is_empty() {
+            rendered
+        } else {
+            format!("{}\n\n{}", rendered.trim_end(), args)
+        }
+    }
+}
+
+/// Prompt library — stores snippets as files in a directory.
+pub struct PromptLibrary {
+    dir: PathBuf,
+}
+
+impl PromptLibrary {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// All snippets, sorted by name.
+    pub fn list(&self) -> Result<Vec<PromptSnippet>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(rd) => rd,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut snippets: Vec<PromptSnippet> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+            .filter_map(|p| load(&p).ok())
+            .collect();
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(snippets)
+    }
+
+    /// Get a snippet by name.
+    pub fn get(&self, name: &str) -> Result<Option<PromptSnippet>> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        load(&path).map(Some)
+    }
+
+    /// Create or replace a snippet.
+    pub fn save(&self, name: &str, description: &str, template: &str) -> Result<PromptSnippet> {
+        let path = self.path(name)?;
+        std::fs::create_dir_all(&self.dir)?;
+
+        let content = if description.trim().is_empty() {
+            format!("{}\n", template.trim_end())
+        } else {
+            format!("---\ndescription: {}\n---\n\n{}\n", description.trim(), template.trim_end())
+        };
+        std::fs::write(&path, content)?;
+        load(&path)
+    }
+
+    /// Delete a snippet. Returns false if it didn't exist.
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(&path)?;
+        Ok(true)
+    }
+
+    /// Snippet names are plain identifiers (`code-review`), never paths.
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ZenClawError::Other(format!(
+                "Invalid snippet name '{}': use letters, digits, '-' and '_'",
+                name
+            )));
+        }
+        Ok(self.dir.join(format!("{}.md", name)))
+    }
+}
+
+fn load(path: &Path) -> Result<PromptSnippet> {
+    let content = std::fs::read_to_string(path)?;
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let (_, description, body) = parse_frontmatter(&content);
+
+    Ok(PromptSnippet {
+        name,
+        description: description.unwrap_or_default(),
+        template: body.trim_end().to_string(),
+        path: path.to_path_buf(),
+    })
+}
@@ -0,0 +1,380 @@
+//! Anthropic provider — native Messages API (`/v1/messages`).
+//!
+//! Anthropic's schema differs from OpenAI's: the system prompt is a
+//! top-level field, tool calls are `tool_use` content blocks, and tool
+//! results go back as `tool_result` blocks in a user message.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::info;
+
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::message::{ChatMessage, FunctionCall, LlmResponse, Role, TokenUsage, ToolCall};
+use zenclaw_core::provider::{ChatRequest, LlmProvider, ProviderConfig};
+
+use super::retry::{send_with_retry, RetryPolicy};
+
+const API_VERSION: &str = "2023-06-01";
+
+/// Anthropic Messages API provider.
+pub struct AnthropicProvider {
+    client: Client,
+    config: ProviderConfig,
+    api_url: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(config: ProviderConfig) -> Self {
+        let api_base = config
+            .api_base
+            .clone()
+            .unwrap_or_else(|| "https://api.anthropic.com/v1".to_string());
+
+        let api_url = format!("{}/messages", api_base.trim_end_matches('/'));
+
+        Self {
+            client: Client::new(),
+            config,
+            api_url,
+        }
+    }
+
+    /// Create a provider for Anthropic.
+    pub fn anthropic(api_key: &str, model: &str) -> Self {
+        Self::new(ProviderConfig {
+            provider: "anthropic".to_string(),
+            model: model.to_string(),
+            api_key: Some(api_key.to_string()),
+            api_base: None,
+            ..Default::default()
+        })
+    }
+
+    /// Set extra parameters merged into every request body.
+    pub fn with_model_params(mut self, params: Option<serde_json::Value>) -> Self {
+        self.config.model_params = params;
+        self
+    }
+
+    /// Set how often transient errors (429, 5xx, timeouts) are retried.
+    pub fn with_retry(mut self, max_retries: u32, retry_base_ms: u64) -> Self {
+        self.config.max_retries = max_retries;
+        self.config.retry_base_ms = retry_base_ms;
+        self
+    }
+
+    /// Declare whether the configured model accepts images (None = guess
+    /// from its name).
+    pub fn with_vision(mut self, vision: Option<bool>) -> Self {
+        self.config.vision = vision;
+        self
+    }
+
+    /// Build the JSON request body (model params merged in).
+    fn build_body(&self, request: ChatRequest) -> serde_json::Value {
+        let model = request
+            .model
+            .unwrap_or_else(|| self.config.model.clone());
+
+        info!("Calling {} model: {}", self.config.provider, model);
+
+        let (system, messages) = convert_messages(&request.messages);
+
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": request.max_tokens,
+            "temperature": request.temperature.min(1.0),
+        });
+        let obj = body.as_object_mut().expect("body is an object");
+        if !system.is_empty() {
+            obj.insert("system".into(), serde_json::json!(system));
+        }
+        if !request.tools.is_empty() {
+            let tools: Vec<serde_json::Value> = request
+                .tools
+                .iter()
+                .map(|t| {
+                    serde_json::json!({
+                        "name": t.function.name,
+                        "description": t.function.description,
+                        "input_schema": t.function.parameters,
+                    })
+                })
+                .collect();
+            obj.insert("tools".into(), serde_json::json!(tools));
+        }
+        if let Some(serde_json::Value::Object(extra)) = self.config.model_params.as_ref() {
+            for (k, v) in extra {
+                obj.insert(k.clone(), v.clone());
+            }
+        }
+        body
+    }
+}
+
+/// Split out the system prompt and convert the rest to Anthropic messages.
+///
+/// Anthropic wants strictly alternating user/assistant turns, so consecutive
+/// messages of the same role (e.g. several tool results) are merged.
+fn convert_messages(messages: &[ChatMessage]) -> (String, Vec<serde_json::Value>) {
+    let mut system = Vec::new();
+    let mut out: Vec<serde_json::Value> = Vec::new();
+
+    for m in messages {
+        let (role, blocks) = match m.role {
+            Role::System | Role::Summary => {
+                if let Some(text) = m.content.as_deref().filter(|t| !t.is_empty()) {
+                    system.push(text.to_string());
+                }
+                continue;
+            }
+            Role::User => {
+                let mut blocks = Vec::new();
+                for url in &m.media {
+                    blocks.push(image_block(url));
+                }
+                if let Some(text) = m.content.as_deref().filter(|t| !t.is_empty()) {
+                    blocks.push(serde_json::json!({ "type": "text", "text": text }));
+                }
+                ("user", blocks)
+            }
+            Role::Assistant => {
+                let mut blocks = Vec::new();
+                if let Some(text) = m.content.as_deref().filter(|t| !t.trim().is_empty()) {
+                    blocks.push(serde_json::json!({ "type": "text", "text": text }));
+                }
+                for call in m.tool_calls.iter().flatten() {
+                    let input: serde_json::Value = serde_json::from_str(&call.function.arguments)
+                        .unwrap_or_else(|_| serde_json::json!({}));
+                    blocks.push(serde_json::json!({
+                        "type": "tool_use",
+                        "id": call.id,
+                        "name": call.function.name,
+                        "input": input,
+                    }));
+                }
+                ("assistant", blocks)
+            }
+            Role::Tool => (
+                "user",
+                vec![serde_json::json!({
+                    "type": "tool_result",
+                    "tool_use_id": m.tool_call_id.clone().unwrap_or_default(),
+                    "content": m.content.clone().unwrap_or_default(),
+                })],
+            ),
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+
+        match out.last_mut() {
+            Some(last) if last["role"] == role => {
+                if let Some(content) = last["content"].as_array_mut() {
+                    content.extend(blocks);
+                }
+            }
+            _ => out.push(serde_json::json!({ "role": role, "content": blocks })),
+        }
+    }
+
+    (system.join("\n\n"), out)
+}
+
+/// Image block from a `data:` URL (inline base64) or a plain URL.
+fn image_block(url: &str) -> serde_json::Value {
+    if let Some(rest) = url.strip_prefix("data:")
+        && let Some((meta, data)) = rest.split_once(',')
+    {
+        let media_type = meta.trim_end_matches(";base64");
+        return serde_json::json!({
+            "type": "image",
+            "source": { "type": "base64", "media_type": media_type, "data": data },
+        });
+    }
+    serde_json::json!({
+        "type": "image",
+        "source": { "type": "url", "url": url },
+    })
+}
+
+// ─── API Types ──────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    model: String,
+    content: Vec<ContentBlock>,
+    stop_reason: Option<String>,
+    usage: Option<ApiUsage>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct ApiUsage {
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    error: ApiErrorDetail,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorDetail {
+    message: String,
+}
+
+// ─── Trait Implementation ───────────────────────────────
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        &self.config.provider
+    }
+
+    fn default_model(&self) -> &str {
+        &self.config.model
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.config.supports_vision(model)
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+        let body = self.build_body(request);
+        let api_key = self.config.api_key.as_deref().unwrap_or("");
+
+        let resp = send_with_retry(RetryPolicy::from_config(&self.config), || {
+            self.client
+                .post(&self.api_url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", API_VERSION)
+                .header("Content-Type", "application/json")
+                .json(&body)
+        })
+        .await?;
+
+        let status = resp.status();
+        let body_text = resp.text().await?;
+        super::trace::response(status, &body_text);
+
+        if !status.is_success() {
+            if let Ok(err) = serde_json::from_str::<ApiError>(&body_text) {
+                return Err(ZenClawError::Provider(format!(
+                    "anthropic API error ({}): {}",
+                    status, err.error.message
+                )));
+            }
+            return Err(ZenClawError::Provider(format!(
+                "anthropic API error ({}): {}",
+                status,
+                &body_text[..body_text.len().min(200)]
+            )));
+        }
+
+        let api_resp: ApiResponse = serde_json::from_str(&body_text).map_err(|e| {
+            ZenClawError::Provider(format!(
+                "Failed to parse response: {} — body: {}",
+                e,
+                &body_text[..body_text.len().min(200)]
+            ))
+        })?;
+
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for block in api_resp.content {
+            match block {
+                ContentBlock::Text { text: t } => text.push_str(&t),
+                ContentBlock::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                    id,
+                    r#type: "function".to_string(),
+                    function: FunctionCall {
+                        name,
+                        arguments: input.to_string(),
+                    },
+                }),
+                ContentBlock::Other => {}
+            }
+        }
+
+        let usage = api_resp
+            .usage
+            .map(|u| {
+                let prompt = u.input_tokens.unwrap_or(0);
+                let completion = u.output_tokens.unwrap_or(0);
+                TokenUsage {
+                    prompt_tokens: prompt,
+                    completion_tokens: completion,
+                    total_tokens: prompt + completion,
+                }
+            })
+            .unwrap_or_default();
+
+        // Map to the OpenAI-style finish reasons the agent loop checks
+        let finish_reason = match api_resp.stop_reason.as_deref() {
+            Some("max_tokens") => "length",
+            Some("tool_use") => "tool_calls",
+            _ => "stop",
+        };
+
+        Ok(LlmResponse {
+            content: (!text.is_empty()).then_some(text),
+            tool_calls,
+            model: api_resp.model,
+            usage,
+            finish_reason: finish_reason.to_string(),
+        })
+    }
+
+    /// Models from `GET /v1/models`, newest first.
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/models?limit=100", self.api_url.trim_end_matches("/messages"));
+        let resp = super::trace::send(
+            self.client
+                .get(&url)
+                .header("x-api-key", self.config.api_key.as_deref().unwrap_or(""))
+                .header("anthropic-version", API_VERSION),
+        )
+        .await?;
+
+        let status = resp.status();
+        let body_text = resp.text().await?;
+        super::trace::response(status, &body_text);
+        if !status.is_success() {
+            let message = serde_json::from_str::<ApiError>(&body_text)
+                .map(|e| e.error.message)
+                .unwrap_or_else(|_| body_text[..body_text.len().min(200)].to_string());
+            return Err(ZenClawError::Provider(format!("anthropic API error ({}): {}", status, message)));
+        }
+
+        let list: ModelList = serde_json::from_str(&body_text)?;
+        Ok(list.data.into_iter().map(|m| m.id).collect())
+    }
+}
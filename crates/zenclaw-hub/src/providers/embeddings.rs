@@ -0,0 +1,172 @@
+//! Embedding providers — OpenAI-compatible `/embeddings` and Ollama.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::provider::{EmbeddingProvider, ProviderConfig};
+
+use super::retry::{send_with_retry, RetryPolicy};
+
+/// OpenAI-compatible embeddings API (OpenAI, Gemini, OpenRouter, ...).
+pub struct OpenAiEmbeddings {
+    client: Client,
+    config: ProviderConfig,
+    api_url: String,
+}
+
+impl OpenAiEmbeddings {
+    pub fn new(config: ProviderConfig) -> Self {
+        let api_base = config
+            .api_base
+            .clone()
+            .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+
+        let api_url = format!("{}/embeddings", api_base.trim_end_matches('/'));
+
+        Self {
+            client: Client::new(),
+            config,
+            api_url,
+        }
+    }
+
+    /// Create embeddings for OpenAI (`text-embedding-3-small` by default).
+    pub fn openai(api_key: &str, model: Option<&str>) -> Self {
+        Self::new(ProviderConfig {
+            provider: "openai".to_string(),
+            model: model.unwrap_or("text-embedding-3-small").to_string(),
+            api_key: Some(api_key.to_string()),
+            api_base: None,
+            ..Default::default()
+        })
+    }
+
+    /// Set how often transient errors (429, 5xx, timeouts) are retried.
+    pub fn with_retry(mut self, max_retries: u32, retry_base_ms: u64) -> Self {
+        self.config.max_retries = max_retries;
+        self.config.retry_base_ms = retry_base_ms;
+        self
+    }
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddings {
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    fn max_batch(&self) -> usize {
+        // The API takes up to 2048 inputs; smaller requests retry cheaper
+        256
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let body = serde_json::json!({ "model": self.config.model, "input": texts });
+        let api_key = self.config.api_key.as_deref().unwrap_or("");
+
+        let resp = send_with_retry(RetryPolicy::from_config(&self.config), || {
+            self.client
+                .post(&self.api_url)
+                .header("Authorization", format!("Bearer {}", api_key))
+                .json(&body)
+        })
+        .await?;
+
+        let status = resp.status();
+        let body_text = resp.text().await?;
+        if !status.is_success() {
+            return Err(ZenClawError::Provider(format!(
+                "{} embeddings error ({}): {}",
+                self.config.provider,
+                status,
+                &body_text[..body_text.len().min(200)]
+            )));
+        }
+
+        let mut parsed: EmbeddingResponse = serde_json::from_str(&body_text)
+            .map_err(|e| ZenClawError::Provider(format!("Failed to parse embeddings: {}", e)))?;
+        if parsed.data.len() != texts.len() {
+            return Err(ZenClawError::Provider(format!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                parsed.data.len()
+            )));
+        }
+        parsed.data.sort_by_key(|d| d.index);
+        Ok(parsed.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// Ollama's native `/api/embeddings` endpoint (one text per request).
+pub struct OllamaEmbeddings {
+    client: Client,
+    model: String,
+    api_url: String,
+}
+
+impl OllamaEmbeddings {
+    /// `base_url` defaults to `http://localhost:11434`.
+    pub fn new(model: &str, base_url: Option<&str>) -> Self {
+        let base = base_url
+            .unwrap_or("http://localhost:11434")
+            .trim_end_matches('/')
+            .trim_end_matches("/v1");
+        Self {
+            client: Client::new(),
+            model: model.to_string(),
+            api_url: format!("{}/api/embeddings", base),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OllamaEmbedding {
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddings {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            let resp = self
+                .client
+                .post(&self.api_url)
+                .json(&serde_json::json!({ "model": self.model, "prompt": text }))
+                .send()
+                .await?;
+
+            let status = resp.status();
+            let body_text = resp.text().await?;
+            if !status.is_success() {
+                return Err(ZenClawError::Provider(format!(
+                    "ollama embeddings error ({}): {}",
+                    status,
+                    &body_text[..body_text.len().min(200)]
+                )));
+            }
+            let parsed: OllamaEmbedding = serde_json::from_str(&body_text)
+                .map_err(|e| ZenClawError::Provider(format!("Failed to parse embeddings: {}", e)))?;
+            vectors.push(parsed.embedding);
+        }
+        Ok(vectors)
+    }
+}
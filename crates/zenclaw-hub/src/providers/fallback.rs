@@ -1,89 +1,217 @@
-//! Provider with automatic model fallback.
+//! Provider chain with automatic failover.
 //!
-//! Wraps any LlmProvider and tries alternative models if the primary fails.
+//! Tries each provider in order and moves to the next one only when the
+//! error is transient (network failure, 5xx, rate limit). Errors like a bad
+//! API key or an invalid request are returned at once.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use async_trait::async_trait;
 use tracing::{info, warn};
 
 use zenclaw_core::error::{Result, ZenClawError};
-use zenclaw_core::message::LlmResponse;
+use zenclaw_core::message::{ChatMessage, LlmResponse};
 use zenclaw_core::provider::{ChatRequest, LlmProvider};
 
-/// Provider wrapper that supports automatic model fallback.
+/// Chain of providers; the first one is the primary.
 ///
-/// If the primary model fails, it tries the fallback models in order.
-pub struct FallbackProvider<P: LlmProvider> {
-    inner: P,
-    fallback_models: Vec<String>,
+/// The request's model only applies to the primary — fallbacks use their
+/// own default model.
+pub struct FallbackProvider {
+    providers: Vec<Arc<dyn LlmProvider>>,
 }
 
-impl<P: LlmProvider> FallbackProvider<P> {
-    /// Create a new fallback provider.
-    pub fn new(inner: P, fallbacks: Vec<String>) -> Self {
-        Self {
-            inner,
-            fallback_models: fallbacks,
+impl FallbackProvider {
+    /// Create a chain from `providers`, tried in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `providers` is empty.
+    pub fn new(providers: Vec<Arc<dyn LlmProvider>>) -> Self {
+        assert!(!providers.is_empty(), "FallbackProvider needs at least one provider");
+        Self { providers }
+    }
+
+    fn primary(&self) -> &dyn LlmProvider {
+        self.providers[0].as_ref()
+    }
+
+    /// Request for the provider at `index`.
+    fn request_for(index: usize, request: &ChatRequest) -> ChatRequest {
+        if index == 0 {
+            request.clone()
+        } else {
+            ChatRequest {
+                model: None,
+                ..request.clone()
+            }
         }
     }
 }
 
+/// Worth trying the next provider: network errors, rate limits and 5xx.
+fn is_retriable(err: &ZenClawError) -> bool {
+    match err {
+        ZenClawError::Network(_) => true,
+        ZenClawError::Provider(msg) => ["(429", "(500", "(502", "(503", "(504"]
+            .iter()
+            .any(|code| msg.contains(code)),
+        _ => false,
+    }
+}
+
 #[async_trait]
-impl<P: LlmProvider> LlmProvider for FallbackProvider<P> {
+impl LlmProvider for FallbackProvider {
     fn name(&self) -> &str {
-        self.inner.name()
+        self.primary().name()
     }
 
     fn default_model(&self) -> &str {
-        self.inner.default_model()
+        self.primary().default_model()
     }
 
     async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
-        // Try primary model first
-        match self.inner.chat(request.clone()).await {
-            Ok(resp) => return Ok(resp),
-            Err(e) => {
-                if self.fallback_models.is_empty() {
-                    return Err(e);
+        let last = self.providers.len() - 1;
+        for (i, provider) in self.providers.iter().enumerate() {
+            match provider.chat(Self::request_for(i, &request)).await {
+                Ok(resp) => {
+                    info!("Request served by {} ({})", provider.name(), resp.model);
+                    return Ok(resp);
+                }
+                Err(e) if i < last && is_retriable(&e) => {
+                    warn!(
+                        "{} failed: {} — falling back to {}",
+                        provider.name(),
+                        e,
+                        self.providers[i + 1].name()
+                    );
                 }
-                warn!(
-                    "Primary model failed: {}. Trying {} fallback(s)...",
-                    e,
-                    self.fallback_models.len()
-                );
+                Err(e) => return Err(e),
             }
         }
+        unreachable!("the last provider always returns")
+    }
 
-        // Try fallback models
-        for (i, model) in self.fallback_models.iter().enumerate() {
-            info!(
-                "Trying fallback model {}/{}: {}",
-                i + 1,
-                self.fallback_models.len(),
-                model
-            );
-
-            let fallback_request = ChatRequest {
-                model: Some(model.clone()),
-                ..request.clone()
-            };
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<LlmResponse> {
+        // Falling back is only safe while nothing has been streamed yet
+        let streamed = AtomicBool::new(false);
+        let mut tracked = |text: &str| {
+            streamed.store(true, Ordering::Relaxed);
+            on_text(text);
+        };
 
-            match self.inner.chat(fallback_request).await {
+        let last = self.providers.len() - 1;
+        for (i, provider) in self.providers.iter().enumerate() {
+            match provider.chat_stream(Self::request_for(i, &request), &mut tracked).await {
                 Ok(resp) => {
-                    info!("Fallback model {} succeeded", model);
+                    info!("Request served by {} ({})", provider.name(), resp.model);
                     return Ok(resp);
                 }
-                Err(e) => {
-                    warn!("Fallback model {} failed: {}", model, e);
+                Err(e) if i < last && is_retriable(&e) && !streamed.load(Ordering::Relaxed) => {
+                    warn!(
+                        "{} failed: {} — falling back to {}",
+                        provider.name(),
+                        e,
+                        self.providers[i + 1].name()
+                    );
                 }
+                Err(e) => return Err(e),
             }
         }
-
-        Err(ZenClawError::Provider(
-            "All models (primary + fallbacks) failed".to_string(),
-        ))
+        unreachable!("the last provider always returns")
     }
 
     async fn list_models(&self) -> Result<Vec<String>> {
-        self.inner.list_models().await
+        self.primary().list_models().await
+    }
+
+    fn count_tokens(&self, messages: &[ChatMessage]) -> usize {
+        self.primary().count_tokens(messages)
+    }
+
+    fn context_window(&self, model: &str) -> usize {
+        self.primary().context_window(model)
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.primary().supports_vision(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::message::TokenUsage;
+
+    /// Fails with `error` if set, otherwise answers with its name.
+    struct MockProvider {
+        name: &'static str,
+        error: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for MockProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn default_model(&self) -> &str {
+            "mock-model"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+            if let Some(error) = self.error {
+                return Err(ZenClawError::Provider(error.to_string()));
+            }
+            Ok(LlmResponse {
+                content: Some(format!("hello from {}", self.name)),
+                tool_calls: Vec::new(),
+                model: request.model.unwrap_or_else(|| self.default_model().to_string()),
+                usage: TokenUsage::default(),
+                finish_reason: "stop".into(),
+            })
+        }
+    }
+
+    fn chain(first_error: &'static str) -> FallbackProvider {
+        FallbackProvider::new(vec![
+            Arc::new(MockProvider {
+                name: "first",
+                error: Some(first_error),
+            }),
+            Arc::new(MockProvider {
+                name: "second",
+                error: None,
+            }),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_on_server_error() {
+        let request = ChatRequest {
+            model: Some("first-only-model".into()),
+            ..Default::default()
+        };
+        let resp = chain("first API error (503 Service Unavailable): overloaded")
+            .chat(request)
+            .await
+            .unwrap();
+        assert_eq!(resp.content.as_deref(), Some("hello from second"));
+        // The primary's model isn't forced on the fallback
+        assert_eq!(resp.model, "mock-model");
+    }
+
+    #[tokio::test]
+    async fn test_client_error_is_not_retried() {
+        let err = chain("first API error (401 Unauthorized): bad key")
+            .chat(ChatRequest::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("401"));
     }
 }
@@ -1,5 +1,11 @@
 pub mod openai;
+pub mod anthropic;
 pub mod fallback;
+pub mod embeddings;
+pub(crate) mod retry;
+pub(crate) mod trace;
 
 pub use openai::OpenAiProvider;
+pub use anthropic::AnthropicProvider;
 pub use fallback::FallbackProvider;
+pub use embeddings::{OllamaEmbeddings, OpenAiEmbeddings};
@@ -1,13 +1,17 @@
 //! OpenAI-compatible provider — works with OpenAI, Ollama, LM Studio, etc.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use zenclaw_core::error::{Result, ZenClawError};
-use zenclaw_core::message::{FunctionCall, LlmResponse, TokenUsage, ToolCall};
-use zenclaw_core::provider::{ChatRequest, LlmProvider, ProviderConfig};
+use zenclaw_core::message::{ChatMessage, FunctionCall, LlmResponse, Role, TokenUsage, ToolCall};
+use zenclaw_core::provider::{ChatRequest, LlmProvider, ProviderConfig, SystemRole};
+
+use super::retry::{send_with_retry, RetryPolicy};
 
 /// OpenAI-compatible provider.
 ///
@@ -32,11 +36,13 @@ impl OpenAiProvider {
 
         let api_url = format!("{}/chat/completions", api_base.trim_end_matches('/'));
 
-        Self {
+        let provider = Self {
             client: Client::new(),
             config,
             api_url,
-        }
+        };
+        provider.warn_ignored_auth();
+        provider
     }
 
     /// Create a provider for Ollama (local).
@@ -93,6 +99,213 @@ impl OpenAiProvider {
             ..Default::default()
         })
     }
+
+    /// Set extra parameters merged into every request body.
+    pub fn with_model_params(mut self, params: Option<serde_json::Value>) -> Self {
+        self.config.model_params = params;
+        self
+    }
+
+    /// Force the role used for system prompts (None = automatic per model).
+    pub fn with_system_role(mut self, role: Option<SystemRole>) -> Self {
+        self.config.system_role = role;
+        self
+    }
+
+    /// Set how often transient errors (429, 5xx, timeouts) are retried.
+    pub fn with_retry(mut self, max_retries: u32, retry_base_ms: u64) -> Self {
+        self.config.max_retries = max_retries;
+        self.config.retry_base_ms = retry_base_ms;
+        self
+    }
+
+    /// Declare whether the configured model accepts images (None = guess
+    /// from its name).
+    pub fn with_vision(mut self, vision: Option<bool>) -> Self {
+        self.config.vision = vision;
+        self
+    }
+
+    /// Set extra headers sent with every request. An `Authorization` entry
+    /// replaces the API key's only if `override_auth` is true.
+    pub fn with_headers(mut self, headers: HashMap<String, String>, override_auth: bool) -> Self {
+        self.config.headers = headers;
+        self.config.override_auth = override_auth;
+        self.warn_ignored_auth();
+        self
+    }
+
+    fn warn_ignored_auth(&self) {
+        let custom_auth = self.config.headers.keys().any(|name| name.eq_ignore_ascii_case("authorization"));
+        if custom_auth && !self.config.override_auth {
+            warn!(
+                "Ignoring the custom Authorization header for {} (set override_auth_header to use it)",
+                self.config.provider
+            );
+        }
+    }
+
+    /// Add the API key and the custom headers to a request.
+    fn authorize(&self, builder: RequestBuilder) -> RequestBuilder {
+        let mut builder = if self.config.overrides_auth() {
+            builder
+        } else {
+            let api_key = self.config.api_key.as_deref().unwrap_or("");
+            builder.header("Authorization", format!("Bearer {}", api_key))
+        };
+        for (name, value) in self.config.extra_headers() {
+            builder = builder.header(name, value);
+        }
+        builder
+    }
+
+    /// Build the JSON request body (model params merged in).
+    fn build_body(&self, request: ChatRequest) -> Result<serde_json::Value> {
+        let model = request
+            .model
+            .unwrap_or_else(|| self.config.model.clone());
+
+        info!("Calling {} model: {}", self.config.provider, model);
+
+        // Convert messages to API format
+        let mut messages: Vec<serde_json::Value> = request
+            .messages
+            .iter()
+            .map(|m| {
+                let mut val = serde_json::to_value(m).unwrap_or_default();
+                if let Some(obj) = val.as_object_mut() {
+                    // Remove internal media array from sending to LLM
+                    obj.remove("media");
+                    // Summaries are plain system context for the API
+                    if m.role == Role::Summary {
+                        obj.insert("role".to_string(), serde_json::json!("system"));
+                    }
+                    
+                    if !m.media.is_empty() {
+                        let mut parts = vec![];
+                        if let Some(text) = &m.content {
+                            parts.push(serde_json::json!({"type": "text", "text": text}));
+                        }
+                        for url in &m.media {
+                            parts.push(serde_json::json!({
+                                "type": "image_url",
+                                "image_url": { "url": url }
+                            }));
+                        }
+                        obj.insert("content".to_string(), serde_json::json!(parts));
+                    }
+                }
+                val
+            })
+            .collect();
+
+        let system_role = self.config.system_role.unwrap_or_else(|| SystemRole::for_model(&model));
+        map_system_role(&mut messages, system_role);
+
+        // Convert tools to API format
+        let tools: Vec<serde_json::Value> = request
+            .tools
+            .iter()
+            .map(|t| serde_json::to_value(t).unwrap_or_default())
+            .collect();
+
+        let mut max_tokens = request.max_tokens;
+        if self.config.provider == "groq" {
+            // Groq free tier has very tight Tokens Per Minute limits (e.g. 6000 TPM limit).
+            // It calculates request cost as: input_tokens + max_tokens.
+            // If max_tokens is 4096, it instantly throws a 429 Too Many Requests.
+            // We clamp it to 1024 to survive the free tier limits.
+            max_tokens = max_tokens.min(1024);
+        }
+
+        let body = ApiRequest {
+            model: model.clone(),
+            messages,
+            tools,
+            max_tokens,
+            temperature: request.temperature,
+        };
+
+
+        let mut body = serde_json::to_value(&body)?;
+        if let (Some(obj), Some(serde_json::Value::Object(extra))) =
+            (body.as_object_mut(), self.config.model_params.as_ref())
+        {
+            for (k, v) in extra {
+                obj.insert(k.clone(), v.clone());
+            }
+        }
+        Ok(body)
+    }
+
+    fn api_error(&self, status: reqwest::StatusCode, body_text: &str) -> ZenClawError {
+        // Try to parse error
+        if let Ok(err) = serde_json::from_str::<ApiError>(body_text) {
+            return ZenClawError::Provider(format!(
+                "{} API error ({}): {}",
+                self.config.provider, status, err.error.message
+            ));
+        }
+        ZenClawError::Provider(format!(
+            "{} API error ({}): {}",
+            self.config.provider,
+            status,
+            &body_text[..body_text.len().min(200)]
+        ))
+    }
+}
+
+/// False for model ids that clearly aren't chat models (embeddings, audio,
+/// images, moderation, legacy completions).
+fn is_chat_model(id: &str) -> bool {
+    const NON_CHAT: &[&str] = &[
+        "embed", "whisper", "tts", "dall-e", "moderation", "davinci", "babbage", "transcribe", "image", "audio",
+        "realtime", "search",
+    ];
+    let id = id.to_lowercase();
+    !NON_CHAT.iter().any(|marker| id.contains(marker))
+}
+
+/// Re-map `system` messages for models that want a different role.
+fn map_system_role(messages: &mut Vec<serde_json::Value>, role: SystemRole) {
+    match role {
+        SystemRole::System => {}
+        SystemRole::Developer => {
+            for msg in messages.iter_mut() {
+                if msg["role"] == "system" {
+                    msg["role"] = serde_json::json!("developer");
+                }
+            }
+        }
+        SystemRole::User => {
+            let mut instructions = Vec::new();
+            messages.retain(|msg| {
+                if msg["role"] == "system" {
+                    if let Some(text) = msg["content"].as_str() {
+                        instructions.push(text.to_string());
+                    }
+                    false
+                } else {
+                    true
+                }
+            });
+            if instructions.is_empty() {
+                return;
+            }
+
+            let preamble = format!("[Instructions]\n{}\n[/Instructions]\n\n", instructions.join("\n\n"));
+            match messages.iter_mut().find(|m| m["role"] == "user") {
+                Some(first_user) => match &mut first_user["content"] {
+                    serde_json::Value::String(text) => text.insert_str(0, &preamble),
+                    serde_json::Value::Array(parts) => {
+                        parts.insert(0, serde_json::json!({"type": "text", "text": preamble}));
+                    }
+                    other => *other = serde_json::json!(preamble),
+                },
+                None => messages.insert(0, serde_json::json!({"role": "user", "content": preamble})),
+            }
+        }
+    }
 }
 
 
@@ -147,6 +360,26 @@ struct ApiUsage {
     total_tokens: Option<u32>,
 }
 
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaTags {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
 #[derive(Deserialize)]
 struct ApiError {
     error: ApiErrorDetail,
@@ -157,106 +390,223 @@ struct ApiErrorDetail {
     message: String,
 }
 
-#[async_trait]
-impl LlmProvider for OpenAiProvider {
-    fn name(&self) -> &str {
-        &self.config.provider
+/// One `data:` event of a streamed response.
+#[derive(Deserialize)]
+struct StreamChunk {
+    model: Option<String>,
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    usage: Option<ApiUsage>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: StreamDelta,
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct StreamDelta {
+    content: Option<String>,
+    tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// A tool-call fragment. The first one for an `index` carries the id and
+/// name; later ones only append to `arguments`.
+#[derive(Deserialize)]
+struct ToolCallDelta {
+    index: Option<usize>,
+    id: Option<String>,
+    r#type: Option<String>,
+    function: Option<FunctionDelta>,
+}
+
+#[derive(Deserialize)]
+struct FunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+/// Reassembles streamed chunks into a complete [`LlmResponse`].
+#[derive(Default)]
+struct StreamAccumulator {
+    content: String,
+    /// Tool calls by stream index, in order of first appearance.
+    tool_calls: Vec<(usize, ToolCall)>,
+    model: Option<String>,
+    usage: Option<ApiUsage>,
+    finish_reason: Option<String>,
+}
+
+impl StreamAccumulator {
+    /// Absorb every complete SSE line in `buf`, passing text deltas to
+    /// `on_text`. A trailing partial line stays in `buf` for the next read.
+    /// Returns true once `[DONE]` arrives; anything after it is ignored.
+    fn push_lines(&mut self, buf: &mut Vec<u8>, on_text: &mut dyn FnMut(&str)) -> bool {
+        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            super::trace::stream_line(line.trim_end());
+            let Some(data) = line.trim().strip_prefix("data:") else { continue };
+            let data = data.trim();
+            if data == "[DONE]" {
+                return true;
+            }
+            match serde_json::from_str::<StreamChunk>(data) {
+                Ok(chunk) => {
+                    if let Some(text) = self.push(chunk) {
+                        on_text(&text);
+                    }
+                }
+                Err(e) => debug!("Skipping unparseable stream chunk: {} — {}", e, data),
+            }
+        }
+        false
     }
 
-    fn default_model(&self) -> &str {
-        &self.config.model
+    /// Absorb a chunk; returns its text delta (if any) for display.
+    fn push(&mut self, chunk: StreamChunk) -> Option<String> {
+        if self.model.is_none() {
+            self.model = chunk.model.filter(|m| !m.is_empty());
+        }
+        if chunk.usage.is_some() {
+            self.usage = chunk.usage;
+        }
+
+        let mut text = None;
+        for choice in chunk.choices {
+            if let Some(reason) = choice.finish_reason {
+                self.finish_reason = Some(reason);
+            }
+            for delta in choice.delta.tool_calls.unwrap_or_default() {
+                self.push_tool_call(delta);
+            }
+            if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+                self.content.push_str(&content);
+                text.get_or_insert_with(String::new).push_str(&content);
+            }
+        }
+        text
     }
 
-    async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
-        let model = request
-            .model
-            .unwrap_or_else(|| self.config.model.clone());
+    fn push_tool_call(&mut self, delta: ToolCallDelta) {
+        // Some servers omit `index` and send each call whole: a new id means a new call
+        let index = delta.index.unwrap_or_else(|| match (&delta.id, self.tool_calls.last()) {
+            (Some(id), Some((i, last))) if &last.id == id || last.id.is_empty() => *i,
+            (None, Some((i, _))) => *i,
+            _ => self.tool_calls.len(),
+        });
+
+        let pos = match self.tool_calls.iter().position(|(i, _)| *i == index) {
+            Some(pos) => pos,
+            None => {
+                self.tool_calls.push((
+                    index,
+                    ToolCall {
+                        id: String::new(),
+                        r#type: "function".to_string(),
+                        function: FunctionCall {
+                            name: String::new(),
+                            arguments: String::new(),
+                        },
+                    },
+                ));
+                self.tool_calls.len() - 1
+            }
+        };
+        let call = &mut self.tool_calls[pos].1;
 
-        info!("Calling {} model: {}", self.config.provider, model);
+        if let Some(id) = delta.id.filter(|id| !id.is_empty()) {
+            call.id = id;
+        }
+        if let Some(kind) = delta.r#type.filter(|t| !t.is_empty()) {
+            call.r#type = kind;
+        }
+        if let Some(function) = delta.function {
+            if let Some(name) = function.name {
+                call.function.name.push_str(&name);
+            }
+            if let Some(args) = function.arguments {
+                call.function.arguments.push_str(&args);
+            }
+        }
+    }
 
-        // Convert messages to API format
-        let messages: Vec<serde_json::Value> = request
-            .messages
-            .iter()
-            .map(|m| {
-                let mut val = serde_json::to_value(m).unwrap_or_default();
-                if let Some(obj) = val.as_object_mut() {
-                    // Remove internal media array from sending to LLM
-                    obj.remove("media");
-                    
-                    if !m.media.is_empty() {
-                        let mut parts = vec![];
-                        if let Some(text) = &m.content {
-                            parts.push(serde_json::json!({"type": "text", "text": text}));
-                        }
-                        for url in &m.media {
-                            parts.push(serde_json::json!({
-                                "type": "image_url",
-                                "image_url": { "url": url }
-                            }));
-                        }
-                        obj.insert("content".to_string(), serde_json::json!(parts));
-                    }
+    fn finish(self, fallback_model: &str) -> LlmResponse {
+        let tool_calls: Vec<ToolCall> = self
+            .tool_calls
+            .into_iter()
+            .filter(|(_, call)| !call.function.name.is_empty())
+            .map(|(index, mut call)| {
+                if call.id.is_empty() {
+                    call.id = format!("call_{}", index);
                 }
-                val
+                if call.function.arguments.trim().is_empty() {
+                    call.function.arguments = "{}".to_string();
+                }
+                call
             })
             .collect();
 
-        // Convert tools to API format
-        let tools: Vec<serde_json::Value> = request
-            .tools
-            .iter()
-            .map(|t| serde_json::to_value(t).unwrap_or_default())
-            .collect();
+        let finish_reason = match self.finish_reason {
+            Some(reason) => reason,
+            None if !tool_calls.is_empty() => "tool_calls".to_string(),
+            None => "stop".to_string(),
+        };
 
-        let mut max_tokens = request.max_tokens;
-        if self.config.provider == "groq" {
-            // Groq free tier has very tight Tokens Per Minute limits (e.g. 6000 TPM limit).
-            // It calculates request cost as: input_tokens + max_tokens.
-            // If max_tokens is 4096, it instantly throws a 429 Too Many Requests.
-            // We clamp it to 1024 to survive the free tier limits.
-            max_tokens = max_tokens.min(1024);
+        let usage = self.usage.map(|u| TokenUsage {
+            prompt_tokens: u.prompt_tokens.unwrap_or(0),
+            completion_tokens: u.completion_tokens.unwrap_or(0),
+            total_tokens: u.total_tokens.unwrap_or(0),
+        }).unwrap_or_default();
+
+        LlmResponse {
+            content: (!self.content.is_empty()).then_some(self.content),
+            tool_calls,
+            model: self.model.unwrap_or_else(|| fallback_model.to_string()),
+            usage,
+            finish_reason,
         }
+    }
+}
 
-        let body = ApiRequest {
-            model: model.clone(),
-            messages,
-            tools,
-            max_tokens,
-            temperature: request.temperature,
-        };
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        &self.config.provider
+    }
 
+    fn default_model(&self) -> &str {
+        &self.config.model
+    }
 
-        let api_key = self.config.api_key.as_deref().unwrap_or("");
+    fn supports_vision(&self, model: &str) -> bool {
+        self.config.supports_vision(model)
+    }
 
-        let resp = self
-            .client
-            .post(&self.api_url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+    fn count_tokens(&self, messages: &[ChatMessage]) -> usize {
+        zenclaw_core::tokens::estimate_messages_with(messages, zenclaw_core::tokens::estimate_bpe)
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+        let body = self.build_body(request)?;
+
+        let resp = send_with_retry(RetryPolicy::from_config(&self.config), || {
+            self.authorize(self.client.post(&self.api_url))
+                .header("Content-Type", "application/json")
+                .json(&body)
+        })
+        .await?;
 
         let status = resp.status();
         let body_text = resp.text().await?;
+        super::trace::response(status, &body_text);
 
         debug!("API response status: {}, body length: {}", status, body_text.len());
 
         if !status.is_success() {
-            // Try to parse error
-            if let Ok(err) = serde_json::from_str::<ApiError>(&body_text) {
-                return Err(ZenClawError::Provider(format!(
-                    "{} API error ({}): {}",
-                    self.config.provider, status, err.error.message
-                )));
-            }
-            return Err(ZenClawError::Provider(format!(
-                "{} API error ({}): {}",
-                self.config.provider,
-                status,
-                &body_text[..body_text.len().min(200)]
-            )));
+            return Err(self.api_error(status, &body_text));
         }
 
         let api_resp: ApiResponse = serde_json::from_str(&body_text).map_err(|e| {
@@ -301,4 +651,199 @@ impl LlmProvider for OpenAiProvider {
             finish_reason: choice.finish_reason.unwrap_or_else(|| "stop".to_string()),
         })
     }
+
+    /// Chat models from `GET /models`, or the pulled models from `/api/tags`
+    /// for Ollama. Sorted by id.
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let api_base = self.api_url.trim_end_matches("/chat/completions");
+
+        let mut models = if self.config.provider == "ollama" {
+            let url = format!("{}/api/tags", api_base.trim_end_matches("/v1"));
+            let resp = super::trace::send(self.client.get(&url)).await?;
+            let status = resp.status();
+            let body_text = resp.text().await?;
+            super::trace::response(status, &body_text);
+            if !status.is_success() {
+                return Err(self.api_error(status, &body_text));
+            }
+            let tags: OllamaTags = serde_json::from_str(&body_text)?;
+            tags.models.into_iter().map(|m| m.name).collect::<Vec<_>>()
+        } else {
+            let resp = super::trace::send(self.authorize(self.client.get(format!("{}/models", api_base)))).await?;
+            let status = resp.status();
+            let body_text = resp.text().await?;
+            super::trace::response(status, &body_text);
+            if !status.is_success() {
+                return Err(self.api_error(status, &body_text));
+            }
+            let list: ModelList = serde_json::from_str(&body_text)?;
+            list.data
+                .into_iter()
+                .map(|m| m.id.trim_start_matches("models/").to_string())
+                .filter(|id| is_chat_model(id))
+                .collect()
+        };
+        models.sort();
+        models.dedup();
+        Ok(models)
+    }
+
+    /// Requests a streamed (SSE) response and calls `on_text` with each
+    /// content delta as it arrives.
+    ///
+    /// Tool calls stream as fragments (name first, arguments in pieces, keyed
+    /// by `index`); they are reassembled and returned in the final response
+    /// instead of being passed to `on_text`.
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) + Send),
+    ) -> Result<LlmResponse> {
+        let mut body = self.build_body(request)?;
+        if let Some(obj) = body.as_object_mut() {
+            obj.insert("stream".into(), serde_json::json!(true));
+            obj.entry("stream_options")
+                .or_insert_with(|| serde_json::json!({ "include_usage": true }));
+        }
+
+        let mut resp = send_with_retry(RetryPolicy::from_config(&self.config), || {
+            self.authorize(self.client.post(&self.api_url))
+                .header("Content-Type", "application/json")
+                .json(&body)
+        })
+        .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body_text = resp.text().await?;
+            super::trace::response(status, &body_text);
+            return Err(self.api_error(status, &body_text));
+        }
+
+        let mut acc = StreamAccumulator::default();
+        // Raw bytes: a chunk may end in the middle of a UTF-8 character
+        let mut buf: Vec<u8> = Vec::new();
+        while let Some(bytes) = resp.chunk().await? {
+            buf.extend_from_slice(&bytes);
+            if acc.push_lines(&mut buf, on_text) {
+                break;
+            }
+        }
+
+        Ok(acc.finish(&self.config.model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Feed `events` as SSE `data:` lines, one read each.
+    fn stream(events: &[serde_json::Value]) -> (LlmResponse, String) {
+        let mut acc = StreamAccumulator::default();
+        let mut text = String::new();
+        for event in events {
+            let mut buf = format!("data: {}\n\n", event).into_bytes();
+            acc.push_lines(&mut buf, &mut |t| text.push_str(t));
+        }
+        (acc.finish("fallback"), text)
+    }
+
+    fn tool_delta(index: usize, id: Option<&str>, name: Option<&str>, args: &str) -> serde_json::Value {
+        let mut call = json!({ "index": index, "function": { "arguments": args } });
+        if let Some(id) = id {
+            call["id"] = json!(id);
+            call["type"] = json!("function");
+        }
+        if let Some(name) = name {
+            call["function"]["name"] = json!(name);
+        }
+        json!({ "model": "gpt-4o", "choices": [{ "delta": { "tool_calls": [call] } }] })
+    }
+
+    #[test]
+    fn test_arguments_split_across_chunks() {
+        let (resp, text) = stream(&[
+            tool_delta(0, Some("call_abc"), Some("weather"), ""),
+            tool_delta(0, None, None, "{\"ci"),
+            tool_delta(0, None, None, "ty\": \"Jak"),
+            tool_delta(0, None, None, "arta\"}"),
+            json!({ "choices": [{ "delta": {}, "finish_reason": "tool_calls" }] }),
+        ]);
+
+        assert!(text.is_empty());
+        assert_eq!(resp.tool_calls.len(), 1);
+        let call = &resp.tool_calls[0];
+        assert_eq!(call.id, "call_abc");
+        assert_eq!(call.function.name, "weather");
+        assert_eq!(call.function.arguments, r#"{"city": "Jakarta"}"#);
+        assert_eq!(resp.finish_reason, "tool_calls");
+        assert_eq!(resp.model, "gpt-4o");
+    }
+
+    #[test]
+    fn test_interleaved_tool_calls_keep_their_ids() {
+        // Ids and names only come with each call's first fragment
+        let (resp, _) = stream(&[
+            tool_delta(0, Some("call_1"), Some("read_file"), "{\"path\":"),
+            tool_delta(1, Some("call_2"), Some("list_dir"), "{\"pa"),
+            tool_delta(0, None, None, " \"a.txt\"}"),
+            tool_delta(1, None, None, "th\": \".\"}"),
+        ]);
+
+        let calls: Vec<(&str, &str, &str)> = resp
+            .tool_calls
+            .iter()
+            .map(|c| (c.id.as_str(), c.function.name.as_str(), c.function.arguments.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                ("call_1", "read_file", r#"{"path": "a.txt"}"#),
+                ("call_2", "list_dir", r#"{"path": "."}"#),
+            ]
+        );
+        // No finish_reason sent: inferred from the calls
+        assert_eq!(resp.finish_reason, "tool_calls");
+    }
+
+    #[test]
+    fn test_done_in_the_middle_of_a_buffer() {
+        let text_delta = |t: &str| json!({ "choices": [{ "delta": { "content": t } }] });
+        let mut buf = format!(
+            "data: {}\n\ndata: {}\n\ndata: [DONE]\n\ndata: {}\n\n",
+            text_delta("Hel"),
+            text_delta("lo"),
+            text_delta(" ignored")
+        )
+        .into_bytes();
+
+        let mut acc = StreamAccumulator::default();
+        let mut text = String::new();
+        assert!(acc.push_lines(&mut buf, &mut |t| text.push_str(t)));
+        assert_eq!(text, "Hello");
+        let resp = acc.finish("fallback");
+        assert_eq!(resp.content.as_deref(), Some("Hello"));
+        assert_eq!(resp.model, "fallback");
+        assert_eq!(resp.finish_reason, "stop");
+    }
+
+    #[test]
+    fn test_partial_lines_wait_for_the_next_read() {
+        let line = format!("data: {}\n", json!({ "choices": [{ "delta": { "content": "héllo" } }] }));
+        // Split inside the two-byte 'é'
+        let split = line.find('é').unwrap() + 1;
+        let (first, second) = line.as_bytes().split_at(split);
+
+        let mut acc = StreamAccumulator::default();
+        let mut text = String::new();
+        let mut buf = first.to_vec();
+        assert!(!acc.push_lines(&mut buf, &mut |t| text.push_str(t)));
+        assert!(text.is_empty());
+        buf.extend_from_slice(second);
+        assert!(!acc.push_lines(&mut buf, &mut |t| text.push_str(t)));
+        assert_eq!(text, "héllo");
+        assert!(buf.is_empty());
+    }
 }
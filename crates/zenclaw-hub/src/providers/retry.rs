@@ -0,0 +1,213 @@
+//! Retry with exponential backoff for transient provider errors.
+//!
+//! Rate limits (429), server errors (500/502/503) and timeouts or refused
+//! connections are retried with jittered exponential backoff, honouring a
+//! `Retry-After` header. Anything else (400, 401, unknown model...) is
+//! returned at once so callers fail fast.
+
+use std::time::Duration;
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use tracing::warn;
+
+use zenclaw_core::error::Result;
+use zenclaw_core::provider::ProviderConfig;
+
+/// Longest backoff we compute ourselves.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+/// Longest `Retry-After` we are willing to wait.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// How often and how long to wait between attempts.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    pub max_retries: u32,
+    pub base: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &ProviderConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base: Duration::from_millis(config.retry_base_ms),
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based): `base * 2^attempt`,
+    /// jittered to 50–100%, unless the server said how long to wait.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(wait) = retry_after {
+            return wait.min(MAX_RETRY_AFTER);
+        }
+        let backoff = self
+            .base
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_DELAY);
+        backoff.mul_f64(0.5 + jitter() * 0.5)
+    }
+}
+
+fn is_retriable(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503)
+}
+
+/// Pseudo-random value in [0, 1) — enough to spread out retries.
+fn jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 1000) as f64 / 1000.0
+}
+
+/// `Retry-After` in seconds (the HTTP-date form is ignored).
+fn retry_after(resp: &Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Send the request built by `build`, retrying transient failures.
+///
+/// The last response is returned as-is when retries run out, so the caller
+/// reports the real API error. Every attempt is logged by [`super::trace`]
+/// when tracing is on.
+pub(crate) async fn send_with_retry(
+    policy: RetryPolicy,
+    build: impl Fn() -> RequestBuilder,
+) -> Result<Response> {
+    let mut attempt = 0;
+    loop {
+        let last = attempt >= policy.max_retries;
+        match super::trace::send(build()).await {
+            Ok(resp) if !last && is_retriable(resp.status()) => {
+                let delay = policy.delay(attempt, retry_after(&resp));
+                warn!(
+                    "Provider returned {} — retry {}/{} in {}ms",
+                    resp.status(),
+                    attempt + 1,
+                    policy.max_retries,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Ok(resp) => return Ok(resp),
+            Err(e) if !last && (e.is_timeout() || e.is_connect()) => {
+                let delay = policy.delay(attempt, None);
+                warn!(
+                    "Provider request failed: {} — retry {}/{} in {}ms",
+                    e,
+                    attempt + 1,
+                    policy.max_retries,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e.into()),
+        }
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `responses` in order (the last one repeats), counting requests.
+    async fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { break };
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let (status, body) = responses[n.min(responses.len() - 1)];
+
+                // Read until the end of the headers plus the announced body
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let read = socket.read(&mut chunk).await.unwrap_or(0);
+                    if read == 0 {
+                        break;
+                    }
+                    buf.extend_from_slice(&chunk[..read]);
+                    let text = String::from_utf8_lossy(&buf);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let headers = text[..end].to_ascii_lowercase();
+                        let length = headers
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .and_then(|v| v.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if buf.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+
+                let reply = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(reply.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, hits)
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let (url, hits) = mock_server(vec![(503, "{}"), (503, "{}"), (200, r#"{"ok":true}"#)]).await;
+        let client = reqwest::Client::new();
+
+        let resp = send_with_retry(fast_policy(), || client.post(&url).json(&serde_json::json!({"q": 1})))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.text().await.unwrap(), r#"{"ok":true}"#);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_error_fails_fast() {
+        let (url, hits) = mock_server(vec![(401, r#"{"error":{"message":"bad key"}}"#)]).await;
+        let client = reqwest::Client::new();
+
+        let resp = send_with_retry(fast_policy(), || client.post(&url)).await.unwrap();
+        assert_eq!(resp.status(), 401);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (url, hits) = mock_server(vec![(429, "{}")]).await;
+        let client = reqwest::Client::new();
+
+        let resp = send_with_retry(fast_policy(), || client.post(&url)).await.unwrap();
+        assert_eq!(resp.status(), 429);
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+    }
+}
@@ -0,0 +1,121 @@
+//! Opt-in wire logging for provider calls.
+//!
+//! With `ZENCLAW_TRACE_PROVIDER=1`, every request sent to a provider
+//! (method, URL, headers, body) and every raw response is logged at `debug`.
+//! Credentials never reach the log: auth headers and key-like query
+//! parameters (`?key=...`, as some APIs take them) are replaced by `***`.
+
+use reqwest::{Request, RequestBuilder, Response, StatusCode, Url};
+use tracing::debug;
+
+/// Environment variable that turns tracing on.
+pub(crate) const TRACE_ENV: &str = "ZENCLAW_TRACE_PROVIDER";
+
+const REDACTED: &str = "***";
+
+/// Headers that carry credentials (compared lowercase).
+const SECRET_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "api-key",
+    "x-goog-api-key",
+];
+
+/// Query parameters that carry credentials (compared lowercase).
+const SECRET_PARAMS: &[&str] = &["key", "api_key", "apikey", "api-key", "access_token", "token"];
+
+/// True if `ZENCLAW_TRACE_PROVIDER` is set to `1` / `true` / `yes` / `on`.
+pub(crate) fn enabled() -> bool {
+    std::env::var(TRACE_ENV).is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes" | "on"))
+}
+
+/// Send a provider request, logging it first if tracing is on.
+pub(crate) async fn send(builder: RequestBuilder) -> reqwest::Result<Response> {
+    if !enabled() {
+        return builder.send().await;
+    }
+    let (client, request) = builder.build_split();
+    let request = request?;
+    debug!("Provider request:\n{}", describe_request(&request));
+    client.execute(request).await
+}
+
+/// Log a raw (non-streamed) response body, if tracing is on.
+pub(crate) fn response(status: StatusCode, body: &str) {
+    if enabled() {
+        debug!("Provider response: {}\n{}", status, body);
+    }
+}
+
+/// Log one raw line of a streamed response, if tracing is on.
+pub(crate) fn stream_line(line: &str) {
+    if enabled() {
+        debug!("Provider stream: {}", line);
+    }
+}
+
+fn describe_request(req: &Request) -> String {
+    let mut out = format!("{} {}", req.method(), redact_url(req.url()));
+    for (name, value) in req.headers() {
+        let value = if SECRET_HEADERS.contains(&name.as_str()) {
+            REDACTED
+        } else {
+            value.to_str().unwrap_or("<binary>")
+        };
+        out.push_str(&format!("\n{}: {}", name, value));
+    }
+    if let Some(body) = req.body().and_then(|b| b.as_bytes()) {
+        out.push_str("\n\n");
+        out.push_str(&String::from_utf8_lossy(body));
+    }
+    out
+}
+
+/// `url` with the values of key-like query parameters replaced by `***`.
+fn redact_url(url: &Url) -> String {
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let secret = SECRET_PARAMS.contains(&name.to_ascii_lowercase().as_str());
+            (name.into_owned(), if secret { REDACTED.to_string() } else { value.into_owned() })
+        })
+        .collect();
+    let mut redacted = url.clone();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_secrets_are_redacted() {
+        let req = reqwest::Client::new()
+            .post("https://generativelanguage.googleapis.com/v1beta/models?key=AIzaSECRET&alt=sse")
+            .header("Authorization", "Bearer sk-SECRET")
+            .header("x-api-key", "sk-ant-SECRET")
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "model": "gpt-4o-mini", "messages": [] }))
+            .build()
+            .unwrap();
+
+        let logged = describe_request(&req);
+        assert!(!logged.contains("SECRET"), "{}", logged);
+        assert!(logged.starts_with("POST https://generativelanguage.googleapis.com/v1beta/models?key=***&alt=sse"));
+        assert!(logged.contains("authorization: ***"));
+        assert!(logged.contains("x-api-key: ***"));
+        assert!(logged.contains("content-type: application/json"));
+        assert!(logged.contains(r#""model":"gpt-4o-mini""#));
+    }
+
+    #[test]
+    fn test_url_without_query_is_unchanged() {
+        let url = Url::parse("https://api.openai.com/v1/chat/completions").unwrap();
+        assert_eq!(redact_url(&url), "https://api.openai.com/v1/chat/completions");
+    }
+}
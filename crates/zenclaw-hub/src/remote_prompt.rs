@@ -0,0 +1,129 @@
+//! Remote system prompt — fetch the agent's prompt from a URL.
+//!
+//! Lets an ops team update the prompt of a fleet of bots without
+//! redeploying. The last good copy is cached on disk together with its
+//! `ETag`/`Last-Modified` validators: unchanged prompts cost a `304`, and a
+//! failed fetch falls back to the cache.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+/// Cached copy of a remote prompt.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptCache {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    prompt: String,
+}
+
+/// A system prompt served from a URL.
+pub struct RemotePrompt {
+    url: String,
+    cache_path: PathBuf,
+    client: reqwest::Client,
+}
+
+impl RemotePrompt {
+    /// `cache_dir` holds the last fetched copy (`system_prompt_cache.json`).
+    pub fn new(url: &str, cache_dir: &Path) -> Self {
+        Self {
+            url: url.to_string(),
+            cache_path: cache_dir.join("system_prompt_cache.json"),
+            client: reqwest::Client::builder()
+                .user_agent("zenclaw")
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Fetch the prompt, revalidating the cached copy. Returns the cached
+    /// prompt if the server is unreachable, or None if nothing is cached.
+    pub async fn fetch(&self) -> Option<String> {
+        let cache = self.read_cache();
+
+        let mut request = self.client.get(&self.url);
+        if let Some(ref c) = cache {
+            if let Some(ref etag) = c.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(ref modified) = c.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, modified);
+            }
+        }
+
+        let resp = match request.send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                warn!("System prompt fetch from {} failed: {} — using cached copy", self.url, e);
+                return cache.map(|c| c.prompt);
+            }
+        };
+
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            debug!("System prompt at {} unchanged", self.url);
+            return cache.map(|c| c.prompt);
+        }
+        if !status.is_success() {
+            warn!("System prompt fetch from {} returned {} — using cached copy", self.url, status);
+            return cache.map(|c| c.prompt);
+        }
+
+        let header = |name: reqwest::header::HeaderName| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+        match resp.text().await {
+            Ok(prompt) if !prompt.trim().is_empty() => {
+                info!("Loaded system prompt from {} ({} chars)", self.url, prompt.len());
+                self.write_cache(&PromptCache {
+                    url: self.url.clone(),
+                    etag,
+                    last_modified,
+                    prompt: prompt.clone(),
+                });
+                Some(prompt)
+            }
+            Ok(_) => {
+                warn!("System prompt at {} is empty — using cached copy", self.url);
+                cache.map(|c| c.prompt)
+            }
+            Err(e) => {
+                warn!("Failed to read system prompt from {}: {} — using cached copy", self.url, e);
+                cache.map(|c| c.prompt)
+            }
+        }
+    }
+
+    /// The cache is only valid for the URL it was fetched from.
+    fn read_cache(&self) -> Option<PromptCache> {
+        let raw = std::fs::read_to_string(&self.cache_path).ok()?;
+        serde_json::from_str::<PromptCache>(&raw)
+            .ok()
+            .filter(|c| c.url == self.url)
+    }
+
+    fn write_cache(&self, cache: &PromptCache) {
+        if let Some(parent) = self.cache_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(cache)
+            && let Err(e) = std::fs::write(&self.cache_path, json)
+        {
+            warn!("Failed to cache system prompt: {}", e);
+        }
+    }
+}
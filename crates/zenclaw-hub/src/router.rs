@@ -3,13 +3,23 @@
 //! The AgentRouter dispatches messages to specialized sub-agents
 //! based on intent detection. Each agent can have different skills,
 //! tools, and models optimized for their domain.
+//!
+//! Intent is detected by a short LLM classification call (see
+//! [`AgentRouter::classify`]); keyword matching and the default agent
+//! cover ambiguous or failed classifications.
 
-use tracing::info;
+use tracing::{info, warn};
 
 use zenclaw_core::agent::Agent;
 use zenclaw_core::error::{Result, ZenClawError};
 use zenclaw_core::memory::MemoryStore;
-use zenclaw_core::provider::LlmProvider;
+use zenclaw_core::message::ChatMessage;
+use zenclaw_core::provider::{ChatRequest, LlmProvider};
+
+/// Instructions for the classification call; the agent list is appended.
+const CLASSIFY_PROMPT: &str = "You route user messages to the best specialist agent. \
+Reply with ONLY the name of one agent from the list below — no punctuation, no explanation. \
+If none fits clearly, reply with: none";
 
 /// A named agent with routing rules.
 pub struct AgentSlot {
@@ -95,6 +105,60 @@ impl AgentRouter {
         &self.agents[0]
     }
 
+    /// Ask the model which agent should handle `message`.
+    ///
+    /// Returns None when the reply names no agent, names several, or the call
+    /// fails — callers fall back to [`route`](Self::route).
+    pub async fn classify(&self, provider: &dyn LlmProvider, message: &str) -> Option<&AgentSlot> {
+        if self.agents.len() < 2 {
+            return self.agents.first();
+        }
+
+        let mut system = format!("{}\n\nAgents:\n", CLASSIFY_PROMPT);
+        for slot in &self.agents {
+            system.push_str(&format!("- {}: {}\n", slot.name, slot.description));
+        }
+
+        let request = ChatRequest {
+            messages: vec![ChatMessage::system(&system), ChatMessage::user(message)],
+            model: self.agents[0].agent.config.model.clone(),
+            max_tokens: 16,
+            temperature: 0.0,
+            ..Default::default()
+        };
+
+        let reply = match provider.chat(request).await {
+            Ok(response) => response.content.unwrap_or_default().to_lowercase(),
+            Err(e) => {
+                warn!("Router classification failed: {}", e);
+                return None;
+            }
+        };
+
+        let words: Vec<&str> = reply
+            .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+            .filter(|w| !w.is_empty())
+            .collect();
+        let mut named = self
+            .agents
+            .iter()
+            .filter(|s| words.contains(&s.name.to_lowercase().as_str()));
+
+        match (named.next(), named.next()) {
+            (Some(slot), None) => Some(slot),
+            _ => None,
+        }
+    }
+
+    /// Pick the agent for `message`: LLM classification first, then keywords,
+    /// then the default agent.
+    pub async fn select(&self, provider: &dyn LlmProvider, message: &str) -> &AgentSlot {
+        match self.classify(provider, message).await {
+            Some(slot) => slot,
+            None => self.route(message),
+        }
+    }
+
     /// Process a message through the router.
     pub async fn process(
         &self,
@@ -121,7 +185,7 @@ impl AgentRouter {
             ));
         }
 
-        let slot = self.route(message);
+        let slot = self.select(provider, message).await;
         info!("🔀 Routing to agent: {} — {}", slot.name, slot.description);
 
         let response = slot
@@ -132,6 +196,30 @@ impl AgentRouter {
         Ok((slot.name.clone(), response))
     }
 
+    /// Run a task on a specific agent by name (explicit delegation).
+    pub async fn ask(
+        &self,
+        name: &str,
+        provider: &dyn LlmProvider,
+        memory: &dyn MemoryStore,
+        task: &str,
+        session_key: &str,
+    ) -> Result<String> {
+        let slot = self.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.agents.iter().map(|s| s.name.as_str()).collect();
+            ZenClawError::Other(format!(
+                "Unknown agent '{}'. Available: {}",
+                name,
+                known.join(", ")
+            ))
+        })?;
+
+        info!("🤝 Delegating to agent: {} — {}", slot.name, slot.description);
+        slot.agent
+            .process(provider, memory, task, session_key, None)
+            .await
+    }
+
     /// List all registered agents.
     pub fn list(&self) -> Vec<(&str, &str)> {
         self.agents
@@ -0,0 +1,447 @@
+//! Scheduled prompts — run agent prompts on a cron schedule.
+//!
+//! Jobs are stored in SQLite (`schedule.db`) by `zenclaw schedule add` and
+//! fired by `zenclaw daemon`, which sends each due prompt through the agent
+//! and delivers the answer to Telegram, Slack, WhatsApp or the log.
+//!
+//! Unlike [`crate::tools::CronTool`], which runs shell commands the model
+//! schedules for itself, these jobs are set up by the user.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use tracing::{error, info, warn};
+
+use zenclaw_core::agent::Agent;
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::memory::MemoryStore;
+use zenclaw_core::provider::LlmProvider;
+
+use crate::channels::{slack, telegram, whatsapp, WhatsAppChannel};
+
+/// How often the daemon looks for due jobs.
+pub const TICK: Duration = Duration::from_secs(30);
+
+/// Day-of-week names, indexed by the standard cron number (0 and 7 = Sunday).
+const WEEKDAYS: [&str; 8] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+
+/// Parse a standard 5-field cron expression (`min hour dom mon dow`).
+///
+/// The `cron` crate wants seconds first and numbers Sunday as 1, so the
+/// expression is rewritten before parsing: `0 9 * * 1-5` → `0 0 9 * * MON-FRI`.
+pub fn parse_cron(expr: &str) -> Result<Schedule> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    if fields.len() != 5 {
+        return Err(ZenClawError::Config(format!(
+            "cron expression '{}' must have 5 fields (minute hour day-of-month month day-of-week)",
+            expr
+        )));
+    }
+
+    let dow = weekday_names(fields[4])
+        .ok_or_else(|| ZenClawError::Config(format!("invalid day-of-week '{}' in '{}'", fields[4], expr)))?;
+    let translated = format!("0 {} {} {} {} {}", fields[0], fields[1], fields[2], fields[3], dow);
+    Schedule::from_str(&translated).map_err(|e| ZenClawError::Config(format!("invalid cron expression '{}': {}", expr, e)))
+}
+
+/// Replace day-of-week numbers with names, leaving step values alone.
+fn weekday_names(field: &str) -> Option<String> {
+    let items: Option<Vec<String>> = field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            let range: Option<Vec<String>> = range
+                .split('-')
+                .map(|part| match part.parse::<usize>() {
+                    Ok(n) => WEEKDAYS.get(n).map(|d| d.to_string()),
+                    Err(_) => Some(part.to_string()),
+                })
+                .collect();
+            let range = range?.join("-");
+            Some(match step {
+                Some(step) => format!("{}/{}", range, step),
+                None => range,
+            })
+        })
+        .collect();
+    items.map(|items| items.join(","))
+}
+
+/// Where a job's answer goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delivery {
+    /// Written to the daemon's log.
+    Log,
+    /// Sent to a Telegram chat id.
+    Telegram(i64),
+    /// Posted to a Slack channel id.
+    Slack(String),
+    /// Sent through the WhatsApp bridge to a chat id (`<number>@c.us`).
+    WhatsApp(String),
+}
+
+impl FromStr for Delivery {
+    type Err = ZenClawError;
+
+    /// `log`, `telegram:<chat_id>`, `slack:<channel_id>` or `whatsapp:<number>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.split_once(':') {
+            None if s.eq_ignore_ascii_case("log") => Ok(Delivery::Log),
+            Some(("telegram", id)) => id
+                .trim()
+                .parse()
+                .map(Delivery::Telegram)
+                .map_err(|_| ZenClawError::Config(format!("invalid Telegram chat id '{}'", id))),
+            Some(("slack", channel)) if !channel.trim().is_empty() => Ok(Delivery::Slack(channel.trim().to_string())),
+            Some(("whatsapp", number)) => whatsapp::chat_id(number).map(Delivery::WhatsApp),
+            _ => Err(ZenClawError::Config(format!(
+                "unknown delivery target '{}' (use log, telegram:<chat_id>, slack:<channel> or whatsapp:<number>)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Delivery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Delivery::Log => write!(f, "log"),
+            Delivery::Telegram(id) => write!(f, "telegram:{}", id),
+            Delivery::Slack(channel) => write!(f, "slack:{}", channel),
+            Delivery::WhatsApp(chat_id) => write!(f, "whatsapp:{}", chat_id),
+        }
+    }
+}
+
+/// A stored scheduled prompt.
+#[derive(Debug, Clone)]
+pub struct ScheduledJob {
+    pub id: i64,
+    /// 5-field cron expression, as entered.
+    pub cron: String,
+    pub prompt: String,
+    pub delivery: Delivery,
+    /// Run missed occurrences once when the daemon starts.
+    pub catch_up: bool,
+    pub next_run: DateTime<Utc>,
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl ScheduledJob {
+    /// Memory session the job's conversation is kept in.
+    pub fn session_key(&self) -> String {
+        format!("schedule:{}", self.id)
+    }
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        let delivery: String = row.get(3)?;
+        let timestamp = |secs: i64| DateTime::from_timestamp(secs, 0).unwrap_or_default();
+        Ok(Self {
+            id: row.get(0)?,
+            cron: row.get(1)?,
+            prompt: row.get(2)?,
+            delivery: delivery.parse().unwrap_or(Delivery::Log),
+            catch_up: row.get(4)?,
+            next_run: timestamp(row.get(5)?),
+            last_run: row.get::<_, Option<i64>>(6)?.map(timestamp),
+        })
+    }
+}
+
+/// Next occurrence of `schedule` strictly after `after`.
+fn next_after(schedule: &Schedule, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    schedule
+        .after(&after)
+        .next()
+        .ok_or_else(|| ZenClawError::Config("cron expression has no future runs".into()))
+}
+
+const JOB_COLUMNS: &str = "id, cron, prompt, delivery, catch_up, next_run, last_run";
+
+/// SQLite store of scheduled jobs.
+pub struct ScheduleStore {
+    conn: Mutex<Connection>,
+}
+
+impl ScheduleStore {
+    /// Open or create the schedule database.
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path).map_err(|e| ZenClawError::Memory(format!("Schedule DB open failed: {}", e)))?;
+        Self::with_conn(conn)
+    }
+
+    /// In-memory store (for testing).
+    pub fn in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory().map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        Self::with_conn(conn)
+    }
+
+    fn with_conn(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS scheduled_jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                cron TEXT NOT NULL,
+                prompt TEXT NOT NULL,
+                delivery TEXT NOT NULL DEFAULT 'log',
+                catch_up INTEGER NOT NULL DEFAULT 0,
+                next_run INTEGER NOT NULL,
+                last_run INTEGER,
+                created_at INTEGER NOT NULL
+            );",
+        )
+        .map_err(|e| ZenClawError::Memory(format!("Schedule DB init failed: {}", e)))?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Store a job; its first run is the next occurrence after now.
+    pub fn add(&self, cron: &str, prompt: &str, delivery: &Delivery, catch_up: bool) -> Result<ScheduledJob> {
+        let schedule = parse_cron(cron)?;
+        let now = Utc::now();
+        let next_run = next_after(&schedule, now)?;
+
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO scheduled_jobs (cron, prompt, delivery, catch_up, next_run, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![cron, prompt, delivery.to_string(), catch_up, next_run.timestamp(), now.timestamp()],
+        )
+        .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+
+        Ok(ScheduledJob {
+            id: conn.last_insert_rowid(),
+            cron: cron.to_string(),
+            prompt: prompt.to_string(),
+            delivery: delivery.clone(),
+            catch_up,
+            next_run,
+            last_run: None,
+        })
+    }
+
+    /// All jobs, soonest first.
+    pub fn list(&self) -> Result<Vec<ScheduledJob>> {
+        self.query(&format!("SELECT {} FROM scheduled_jobs ORDER BY next_run", JOB_COLUMNS), params![])
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<ScheduledJob>> {
+        self.conn()
+            .query_row(
+                &format!("SELECT {} FROM scheduled_jobs WHERE id = ?1", JOB_COLUMNS),
+                [id],
+                ScheduledJob::from_row,
+            )
+            .optional()
+            .map_err(|e| ZenClawError::Memory(e.to_string()))
+    }
+
+    /// Delete a job. Returns false if it didn't exist.
+    pub fn remove(&self, id: i64) -> Result<bool> {
+        let deleted = self
+            .conn()
+            .execute("DELETE FROM scheduled_jobs WHERE id = ?1", [id])
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        Ok(deleted > 0)
+    }
+
+    /// Jobs whose next run is at or before `now`.
+    pub fn due(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledJob>> {
+        self.query(
+            &format!("SELECT {} FROM scheduled_jobs WHERE next_run <= ?1 ORDER BY next_run", JOB_COLUMNS),
+            params![now.timestamp()],
+        )
+    }
+
+    /// Record that `job` ran at `ran_at` and move it to its next occurrence.
+    pub fn mark_run(&self, job: &ScheduledJob, ran_at: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let next_run = next_after(&parse_cron(&job.cron)?, ran_at)?;
+        self.conn()
+            .execute(
+                "UPDATE scheduled_jobs SET last_run = ?1, next_run = ?2 WHERE id = ?3",
+                params![ran_at.timestamp(), next_run.timestamp(), job.id],
+            )
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        Ok(next_run)
+    }
+
+    /// On daemon start: move overdue jobs without `catch_up` to their next
+    /// occurrence, so only catch-up jobs fire for time spent offline.
+    /// Returns how many jobs were skipped ahead.
+    pub fn skip_missed(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut skipped = 0;
+        for job in self.due(now)?.into_iter().filter(|j| !j.catch_up) {
+            let next_run = next_after(&parse_cron(&job.cron)?, now)?;
+            self.conn()
+                .execute(
+                    "UPDATE scheduled_jobs SET next_run = ?1 WHERE id = ?2",
+                    params![next_run.timestamp(), job.id],
+                )
+                .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+            skipped += 1;
+        }
+        Ok(skipped)
+    }
+
+    fn query(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<ScheduledJob>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql).map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        let rows = stmt
+            .query_map(params, ScheduledJob::from_row)
+            .map_err(|e| ZenClawError::Memory(e.to_string()))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| ZenClawError::Memory(e.to_string()))
+    }
+}
+
+/// Bot tokens (and the WhatsApp bridge) used to deliver job results.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    pub telegram_token: Option<String>,
+    pub slack_token: Option<String>,
+    pub whatsapp_bridge: Option<String>,
+}
+
+impl Notifier {
+    pub async fn deliver(&self, job: &ScheduledJob, text: &str) -> Result<()> {
+        match &job.delivery {
+            Delivery::Log => {
+                info!("📬 Job #{} ({}):\n{}", job.id, job.prompt, text);
+                Ok(())
+            }
+            Delivery::Telegram(chat_id) => {
+                let token = self
+                    .telegram_token
+                    .as_deref()
+                    .ok_or_else(|| ZenClawError::Config("no Telegram bot token configured".into()))?;
+                telegram::notify(token, *chat_id, text).await
+            }
+            Delivery::Slack(channel) => {
+                let token = self
+                    .slack_token
+                    .as_deref()
+                    .ok_or_else(|| ZenClawError::Config("no Slack bot token configured".into()))?;
+                slack::notify(token, channel, text).await
+            }
+            Delivery::WhatsApp(chat_id) => {
+                let bridge = self
+                    .whatsapp_bridge
+                    .as_deref()
+                    .ok_or_else(|| ZenClawError::Config("no WhatsApp bridge URL configured".into()))?;
+                WhatsAppChannel::new(bridge).send(chat_id, text).await
+            }
+        }
+    }
+}
+
+/// Run scheduled jobs until the process is stopped.
+pub async fn run_daemon(
+    store: &ScheduleStore,
+    agent: &Agent,
+    provider: &dyn LlmProvider,
+    memory: &dyn MemoryStore,
+    notifier: &Notifier,
+) -> Result<()> {
+    let skipped = store.skip_missed(Utc::now())?;
+    if skipped > 0 {
+        info!("⏭️  Skipped missed runs of {} job(s) without catch_up", skipped);
+    }
+
+    loop {
+        let now = Utc::now();
+        for job in store.due(now)? {
+            info!("⏰ Running job #{}: {}", job.id, job.prompt);
+            let text = match agent.process(provider, memory, &job.prompt, &job.session_key(), None).await {
+                Ok(answer) => answer,
+                Err(e) => {
+                    error!("Job #{} failed: {}", job.id, e);
+                    format!("⚠️ Scheduled prompt failed: {}", e)
+                }
+            };
+            if let Err(e) = notifier.deliver(&job, &text).await {
+                warn!("Couldn't deliver job #{} to {}: {}", job.id, job.delivery, e);
+            }
+            // Advance from now, not from the missed slot: a catch-up job runs once, not once per miss
+            match store.mark_run(&job, Utc::now()) {
+                Ok(next) => info!("Job #{} next runs at {}", job.id, next.to_rfc3339()),
+                Err(e) => error!("Couldn't reschedule job #{}: {}", job.id, e),
+            }
+        }
+        tokio::time::sleep(TICK).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Timelike, Weekday};
+
+    #[test]
+    fn test_parse_standard_cron() {
+        let schedule = parse_cron("0 9 * * 1-5").unwrap();
+        // Saturday 2024-06-01 → next weekday 9:00 is Monday
+        let next = next_after(&schedule, Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()).unwrap();
+        assert_eq!(next.weekday(), Weekday::Mon);
+        assert_eq!((next.hour(), next.minute()), (9, 0));
+
+        let sunday = parse_cron("30 8 * * 0").unwrap();
+        let next = next_after(&sunday, Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()).unwrap();
+        assert_eq!(next.weekday(), Weekday::Sun);
+
+        assert!(parse_cron("*/15 * * * *").is_ok());
+        assert!(parse_cron("0 9 * *").is_err());
+        assert!(parse_cron("0 0 9 * * *").is_err());
+        assert!(parse_cron("0 9 * * 8").is_err());
+    }
+
+    #[test]
+    fn test_delivery_roundtrip() {
+        for target in ["log", "telegram:-100123", "slack:C024BE91L", "whatsapp:6281234567890@c.us"] {
+            assert_eq!(target.parse::<Delivery>().unwrap().to_string(), target);
+        }
+        assert_eq!(
+            "whatsapp:+62 812 3456 7890".parse::<Delivery>().unwrap(),
+            Delivery::WhatsApp("6281234567890@c.us".into())
+        );
+        assert!("telegram:abc".parse::<Delivery>().is_err());
+        assert!("whatsapp:12".parse::<Delivery>().is_err());
+        assert!("email:me@example.com".parse::<Delivery>().is_err());
+    }
+
+    #[test]
+    fn test_skip_missed_respects_catch_up() {
+        let store = ScheduleStore::in_memory().unwrap();
+        let skip = store.add("0 * * * *", "hourly", &Delivery::Log, false).unwrap();
+        let catch_up = store.add("0 * * * *", "hourly catch-up", &Delivery::Log, true).unwrap();
+
+        // Pretend the daemon was down for three hours
+        let later = skip.next_run + chrono::Duration::hours(3);
+        assert_eq!(store.due(later).unwrap().len(), 2);
+        assert_eq!(store.skip_missed(later).unwrap(), 1);
+
+        let due = store.due(later).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, catch_up.id);
+
+        let next = store.mark_run(&due[0], later).unwrap();
+        assert!(next > later);
+        assert!(store.due(later).unwrap().is_empty());
+        assert!(store.get(catch_up.id).unwrap().unwrap().last_run.is_some());
+
+        assert!(store.remove(skip.id).unwrap());
+        assert!(!store.remove(skip.id).unwrap());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+}
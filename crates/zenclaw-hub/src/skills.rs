@@ -3,11 +3,38 @@
 //! Skills are markdown files that provide the agent with specialized knowledge
 //! and instructions. They are loaded from the skills directory and injected
 //! into the system prompt.
-
+//!
+//! Front-matter may also scope the agent's tools while the skill is active:
+//!
+//! ```markdown
+//! ---
+//! title: Writer
+//! description: Drafting and editing prose.
+//! tools_allow: [read_file, write_file, edit_file]
+//! tools_deny: [exec]
+//! ---
+//! ```
+//!
+//! and declare inputs, asked for on activation and substituted for
+//! `{{name}}` placeholders in the body:
+//!
+//! ```markdown
+//! inputs:
+//!   - name: language
+//!     prompt: Translate into which language?
+//!     required: true
+//! ```
+
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 
-use zenclaw_core::error::Result;
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::tool::{Policy, ToolRegistry};
+
+/// Largest skill file accepted by [`fetch_skill_source`].
+const MAX_SKILL_BYTES: usize = 256 * 1024;
 
 /// A loaded skill.
 #[derive(Debug, Clone)]
@@ -20,10 +47,89 @@ pub struct Skill {
     pub description: String,
     /// Full markdown content.
     pub content: String,
+    /// Tools this skill needs (`tools_allow`; empty = no restriction).
+    pub tools_allow: Vec<String>,
+    /// Tools this skill must not use (`tools_deny`).
+    pub tools_deny: Vec<String>,
+    /// Parameters filled in when the skill is activated.
+    pub inputs: Vec<SkillInput>,
     /// File path.
     pub path: PathBuf,
 }
 
+/// A skill parameter, substituted for `{{name}}` in the skill body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillInput {
+    pub name: String,
+    /// Question shown to the user (defaults to the name).
+    pub prompt: String,
+    /// Activation fails without a value.
+    pub required: bool,
+}
+
+/// Tools available while a set of skills is active.
+///
+/// Allow-lists are combined (union) and so are deny-lists; a denied tool
+/// stays denied even if another skill allows it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolScope {
+    /// Only these tools may be used (None = no active skill restricts them).
+    pub allow: Option<Vec<String>>,
+    /// These tools are refused.
+    pub deny: Vec<String>,
+}
+
+impl ToolScope {
+    /// Combine the tool lists of `skills`.
+    pub fn from_skills<'a>(skills: impl IntoIterator<Item = &'a Skill>) -> Self {
+        let mut scope = Self::default();
+        for skill in skills {
+            if !skill.tools_allow.is_empty() {
+                let allow = scope.allow.get_or_insert_with(Vec::new);
+                for tool in &skill.tools_allow {
+                    if !allow.contains(tool) {
+                        allow.push(tool.clone());
+                    }
+                }
+            }
+            for tool in &skill.tools_deny {
+                if !scope.deny.contains(tool) {
+                    scope.deny.push(tool.clone());
+                }
+            }
+        }
+        scope
+    }
+
+    /// True when no skill restricts the tool set.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_none() && self.deny.is_empty()
+    }
+
+    /// Restrict `tools` to this scope. An existing allow-list is narrowed,
+    /// never widened.
+    pub fn apply(&self, tools: &mut ToolRegistry) {
+        let known = tools.names();
+        for name in self.allow.iter().flatten().chain(&self.deny) {
+            if !known.contains(&name.as_str()) {
+                tracing::warn!("Skill references unknown tool '{}'", name);
+            }
+        }
+
+        if let Some(allow) = &self.allow {
+            let names: Vec<String> = known
+                .iter()
+                .filter(|name| tools.is_allowed(name) && allow.iter().any(|a| a == *name))
+                .map(|name| name.to_string())
+                .collect();
+            tools.set_allowed(&names);
+        }
+        for name in &self.deny {
+            tools.set_policy(name, Policy::Denied);
+        }
+    }
+}
+
 /// Skill manager — loads and manages skills from a directory.
 pub struct SkillManager {
     skills_dir: PathBuf,
@@ -84,6 +190,9 @@ impl SkillManager {
             title: title.unwrap_or_else(|| name.replace('_', " ")),
             description: description.unwrap_or_default(),
             content: body.to_string(),
+            tools_allow: frontmatter_list(&content, "tools_allow"),
+            tools_deny: frontmatter_list(&content, "tools_deny"),
+            inputs: frontmatter_inputs(&content),
             path: path.to_path_buf(),
         })
     }
@@ -187,18 +296,49 @@ You are a talented creative writer. When creating content:
         self.skills.iter().find(|s| s.name == name)
     }
 
-    /// Build a system prompt section from active skills.
-    pub fn build_prompt(&self, active_skills: &[String]) -> String {
+    /// Build a system prompt section from active skills, substituting the
+    /// resolved `inputs` for their `{{name}}` placeholders. Fails if a
+    /// required input has no value.
+    pub fn build_prompt(&self, active_skills: &[String], inputs: &HashMap<String, String>) -> Result<String> {
         let mut prompt = String::new();
 
         for skill_name in active_skills {
             if let Some(skill) = self.get(skill_name) {
+                let mut content = skill.content.clone();
+                for input in &skill.inputs {
+                    let value = inputs.get(&input.name).map(|v| v.trim()).unwrap_or_default();
+                    if value.is_empty() && input.required {
+                        return Err(ZenClawError::Config(format!(
+                            "skill '{}' needs the input '{}' ({})",
+                            skill.name, input.name, input.prompt
+                        )));
+                    }
+                    content = content.replace(&format!("{{{{{}}}}}", input.name), value);
+                }
                 prompt.push_str(&format!("\n\n## Skill: {}\n\n", skill.title));
-                prompt.push_str(&skill.content);
+                prompt.push_str(&content);
             }
         }
 
-        prompt
+        Ok(prompt)
+    }
+
+    /// Inputs declared by the active skills, once per name.
+    pub fn inputs(&self, active_skills: &[String]) -> Vec<&SkillInput> {
+        let mut inputs: Vec<&SkillInput> = Vec::new();
+        for skill in active_skills.iter().filter_map(|name| self.get(name)) {
+            for input in &skill.inputs {
+                if !inputs.iter().any(|i| i.name == input.name) {
+                    inputs.push(input);
+                }
+            }
+        }
+        inputs
+    }
+
+    /// Combined tool scope of the active skills.
+    pub fn tool_scope(&self, active_skills: &[String]) -> ToolScope {
+        ToolScope::from_skills(active_skills.iter().filter_map(|name| self.get(name)))
     }
 
     /// Get the skills directory path.
@@ -207,17 +347,24 @@ You are a talented creative writer. When creating content:
     }
 
     /// Save a skill to disk.
-    pub async fn save_skill(&mut self, name: &str, title: &str, description: &str, content: &str) -> Result<()> {
+    pub async fn save_skill(&mut self, name: &str, skill: &ParsedSkill) -> Result<()> {
         let path = self.skills_dir.join(format!("{}.md", name));
-        let full_content = format!(
-            "---\ntitle: {}\ndescription: {}\n---\n\n{}",
-            title, description, content
-        );
+        let full_content = render_skill(skill);
         fs::write(&path, full_content).await?;
         self.load_all().await?;
         Ok(())
     }
 
+    /// Write a skill file, front-matter included, to `dest` for sharing.
+    pub async fn export_skill(&self, name: &str, dest: &Path) -> Result<()> {
+        let skill = self
+            .get(name)
+            .ok_or_else(|| ZenClawError::Other(format!("skill '{}' not found", name)))?;
+        let full_content = render_skill(&ParsedSkill::from(skill));
+        fs::write(dest, full_content).await?;
+        Ok(())
+    }
+
     /// Delete a skill from disk.
     pub async fn delete_skill(&mut self, name: &str) -> Result<()> {
         let path = self.skills_dir.join(format!("{}.md", name));
@@ -229,8 +376,278 @@ You are a talented creative writer. When creating content:
     }
 }
 
+/// Contents of a skill file, as checked by [`parse_skill_markdown`] and
+/// written by [`SkillManager::save_skill`].
+#[derive(Debug, Clone, Default)]
+pub struct ParsedSkill {
+    pub title: String,
+    pub description: String,
+    pub content: String,
+    pub tools_allow: Vec<String>,
+    pub tools_deny: Vec<String>,
+    pub inputs: Vec<SkillInput>,
+}
+
+impl From<&Skill> for ParsedSkill {
+    fn from(skill: &Skill) -> Self {
+        Self {
+            title: skill.title.clone(),
+            description: skill.description.clone(),
+            content: skill.content.clone(),
+            tools_allow: skill.tools_allow.clone(),
+            tools_deny: skill.tools_deny.clone(),
+            inputs: skill.inputs.clone(),
+        }
+    }
+}
+
+/// Validate skill markdown: `---` front-matter with a `title` and a
+/// `description`, followed by a non-empty body.
+pub fn parse_skill_markdown(text: &str) -> std::result::Result<ParsedSkill, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let head = text.trim_start().get(..15).unwrap_or_default().to_lowercase();
+    if head.starts_with("<!doctype") || head.starts_with("<html") {
+        return Err("this is an HTML page, not skill markdown (use the raw file URL)".into());
+    }
+    if text.contains('\0') {
+        return Err("this is a binary file, not skill markdown".into());
+    }
+    if !text.starts_with("---") || text[3..].find("---").is_none() {
+        return Err("missing front-matter: the file must start with a `---` block holding title and description".into());
+    }
+
+    let (title, description, body) = parse_frontmatter(text);
+    let title = title.filter(|t| !t.is_empty()).ok_or("front-matter has no `title:`")?;
+    let description = description
+        .filter(|d| !d.is_empty())
+        .ok_or("front-matter has no `description:`")?;
+    if body.trim().is_empty() {
+        return Err("the skill has no instructions after the front-matter".into());
+    }
+
+    Ok(ParsedSkill {
+        title,
+        description,
+        content: body.to_string(),
+        tools_allow: frontmatter_list(text, "tools_allow"),
+        tools_deny: frontmatter_list(text, "tools_deny"),
+        inputs: frontmatter_inputs(text),
+    })
+}
+
+/// True for names usable as a skill file name (`[a-z0-9_-]`, up to 64 chars).
+pub fn is_valid_skill_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+/// Skill name derived from a URL or path: `https://x/y/Code-Review.md` → `code-review`.
+pub fn skill_name_from_source(source: &str) -> String {
+    let last = source
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(source)
+        .trim_end_matches('/')
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default();
+    let stem = last.strip_suffix(".md").unwrap_or(last);
+    stem.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Read skill markdown from an `http(s)://` URL or a local path.
+pub async fn fetch_skill_source(source: &str) -> Result<String> {
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        let resp = client.get(source).send().await?;
+        if !resp.status().is_success() {
+            return Err(ZenClawError::Other(format!("{} returned {}", source, resp.status())));
+        }
+        resp.text().await?
+    } else {
+        fs::read_to_string(source).await?
+    };
+
+    if text.len() > MAX_SKILL_BYTES {
+        return Err(ZenClawError::Other(format!(
+            "{} is {} KB — skills are limited to {} KB",
+            source,
+            text.len() / 1024,
+            MAX_SKILL_BYTES / 1024
+        )));
+    }
+    Ok(text)
+}
+
+/// Skill file contents: front-matter (optional fields only when set) and body.
+fn render_skill(skill: &ParsedSkill) -> String {
+    let mut out = format!("---\ntitle: {}\ndescription: {}\n", skill.title, skill.description);
+    if !skill.tools_allow.is_empty() {
+        out.push_str(&format!("tools_allow: [{}]\n", skill.tools_allow.join(", ")));
+    }
+    if !skill.tools_deny.is_empty() {
+        out.push_str(&format!("tools_deny: [{}]\n", skill.tools_deny.join(", ")));
+    }
+    if !skill.inputs.is_empty() {
+        out.push_str("inputs:\n");
+        for input in &skill.inputs {
+            out.push_str(&format!(
+                "  - name: {}\n    prompt: \"{}\"\n    required: {}\n",
+                input.name,
+                input.prompt.replace('"', "'"),
+                input.required
+            ));
+        }
+    }
+    out.push_str("---\n\n");
+    out.push_str(&skill.content);
+    out
+}
+
+/// The text between the opening and closing `---`, if any.
+fn frontmatter_block(content: &str) -> Option<&str> {
+    content
+        .strip_prefix("---")
+        .and_then(|rest| rest.find("---").map(|end| &rest[..end]))
+}
+
+/// The value after `key:` if `line` sets `key`.
+fn strip_key<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.trim()
+        .strip_prefix(key)
+        .and_then(|rest| rest.trim_start().strip_prefix(':'))
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').trim_matches('\'').to_string()
+}
+
+/// Split on `sep`, ignoring separators inside quotes or `{...}`.
+fn split_top_level(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut quote, mut start) = (0usize, None, 0);
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => depth = depth.saturating_sub(1),
+            (None, c) if c == sep && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Read a list from front-matter, written inline (`key: [a, b]` or
+/// `key: a, b`) or as a block of `- item` lines.
+fn frontmatter_list(content: &str, key: &str) -> Vec<String> {
+    let Some(frontmatter) = frontmatter_block(content) else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    let mut lines = frontmatter.lines();
+    while let Some(line) = lines.next() {
+        let Some(rest) = strip_key(line, key) else {
+            continue;
+        };
+
+        let inline = rest.trim().trim_start_matches('[').trim_end_matches(']');
+        if inline.is_empty() {
+            for line in lines.by_ref() {
+                match line.trim().strip_prefix('-') {
+                    Some(item) => items.push(unquote(item)),
+                    None => break,
+                }
+            }
+        } else {
+            items.extend(inline.split(',').map(unquote));
+        }
+        break;
+    }
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Read `inputs` from front-matter, either inline
+/// (`inputs: [{name: lang, prompt: "Language?", required: true}]`) or as a
+/// block list of `- name: ...` entries with indented `prompt:`/`required:`.
+fn frontmatter_inputs(content: &str) -> Vec<SkillInput> {
+    let Some(frontmatter) = frontmatter_block(content) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<Vec<&str>> = Vec::new();
+    let mut lines = frontmatter.lines();
+    while let Some(line) = lines.next() {
+        let Some(rest) = strip_key(line, "inputs") else {
+            continue;
+        };
+
+        let rest = rest.trim();
+        if rest.is_empty() {
+            for line in lines.by_ref() {
+                let trimmed = line.trim();
+                if let Some(field) = trimmed.strip_prefix('-') {
+                    entries.push(vec![field]);
+                } else if line.starts_with([' ', '\t'])
+                    && !trimmed.is_empty()
+                    && let Some(entry) = entries.last_mut()
+                {
+                    entry.push(trimmed);
+                } else {
+                    break;
+                }
+            }
+        } else {
+            let list = rest.strip_prefix('[').unwrap_or(rest);
+            let list = list.strip_suffix(']').unwrap_or(list);
+            for item in split_top_level(list, ',') {
+                let item = item.trim();
+                let item = item.strip_prefix('{').unwrap_or(item);
+                let item = item.strip_suffix('}').unwrap_or(item);
+                if !item.trim().is_empty() {
+                    entries.push(split_top_level(item, ','));
+                }
+            }
+        }
+        break;
+    }
+
+    entries
+        .into_iter()
+        .filter_map(|fields| {
+            let mut fields: HashMap<&str, String> = fields
+                .iter()
+                .filter_map(|field| field.split_once(':'))
+                .map(|(key, value)| (key.trim(), unquote(value)))
+                .collect();
+            let name = fields.remove("name").filter(|n| !n.is_empty())?;
+            Some(SkillInput {
+                prompt: fields.remove("prompt").filter(|p| !p.is_empty()).unwrap_or_else(|| name.clone()),
+                required: fields.get("required").is_some_and(|r| r == "true" || r == "yes"),
+                name,
+            })
+        })
+        .collect()
+}
+
 /// Parse YAML frontmatter from markdown.
-fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, &str) {
+pub(crate) fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, &str) {
     if !content.starts_with("---") {
         return (None, None, content);
     }
@@ -258,3 +675,124 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, &str) {
         (None, None, content)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skill_markdown() {
+        let skill = parse_skill_markdown("---\ntitle: Reviewer\ndescription: Reviews code\n---\n\n# Review\nBe thorough.").unwrap();
+        assert_eq!(skill.title, "Reviewer");
+        assert_eq!(skill.description, "Reviews code");
+        assert!(skill.content.starts_with("# Review"));
+
+        assert!(parse_skill_markdown("# Just markdown").unwrap_err().contains("front-matter"));
+        assert!(parse_skill_markdown("---\ntitle: T\n---\nbody").unwrap_err().contains("description"));
+        assert!(parse_skill_markdown("---\ntitle: T\ndescription: D\n---\n  \n").unwrap_err().contains("no instructions"));
+        assert!(parse_skill_markdown("<!DOCTYPE html><html>").unwrap_err().contains("HTML"));
+    }
+
+    fn skill(allow: &[&str], deny: &[&str]) -> Skill {
+        Skill {
+            name: "s".into(),
+            title: "S".into(),
+            description: String::new(),
+            content: String::new(),
+            tools_allow: allow.iter().map(|t| t.to_string()).collect(),
+            tools_deny: deny.iter().map(|t| t.to_string()).collect(),
+            inputs: Vec::new(),
+            path: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn test_frontmatter_tool_lists() {
+        let inline = "---\ntitle: W\ntools_allow: [read_file, \"write_file\"]\ntools_deny: exec\n---\nbody";
+        assert_eq!(frontmatter_list(inline, "tools_allow"), vec!["read_file", "write_file"]);
+        assert_eq!(frontmatter_list(inline, "tools_deny"), vec!["exec"]);
+
+        let block = "---\ntitle: W\ntools_allow:\n  - read_file\n  - grep\ndescription: D\n---\nbody";
+        assert_eq!(frontmatter_list(block, "tools_allow"), vec!["read_file", "grep"]);
+        assert!(frontmatter_list(block, "tools_deny").is_empty());
+        assert!(frontmatter_list("no front-matter", "tools_allow").is_empty());
+
+        let rendered = render_skill(&ParsedSkill {
+            title: "W".into(),
+            description: "D".into(),
+            content: "body".into(),
+            tools_allow: vec!["read_file".into()],
+            ..Default::default()
+        });
+        assert_eq!(frontmatter_list(&rendered, "tools_allow"), vec!["read_file"]);
+        assert_eq!(parse_frontmatter(&rendered).2, "body");
+    }
+
+    #[test]
+    fn test_frontmatter_inputs() {
+        let inline = "---\ntitle: T\ninputs: [{name: language, prompt: \"Language, please?\", required: true}, {name: tone}]\n---\nbody";
+        assert_eq!(
+            frontmatter_inputs(inline),
+            vec![
+                SkillInput { name: "language".into(), prompt: "Language, please?".into(), required: true },
+                SkillInput { name: "tone".into(), prompt: "tone".into(), required: false },
+            ]
+        );
+
+        let skill = ParsedSkill {
+            title: "T".into(),
+            description: "D".into(),
+            content: "Translate into {{language}}.".into(),
+            inputs: frontmatter_inputs(inline),
+            ..Default::default()
+        };
+        let rendered = render_skill(&skill);
+        assert_eq!(frontmatter_inputs(&rendered), skill.inputs);
+        assert_eq!(frontmatter_list(&rendered, "tools_allow"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_build_prompt_substitutes_inputs() {
+        let mut translate = skill(&[], &[]);
+        translate.name = "translate".into();
+        translate.content = "Translate into {{language}}. {{tone}}".into();
+        translate.inputs = frontmatter_inputs("---\ninputs:\n  - name: language\n    prompt: Which language?\n    required: true\n  - name: tone\n---\n");
+        let mgr = SkillManager {
+            skills_dir: PathBuf::new(),
+            skills: vec![translate],
+        };
+        let active = vec!["translate".to_string()];
+        assert_eq!(mgr.inputs(&active).len(), 2);
+
+        let inputs = HashMap::from([("language".to_string(), "French".to_string())]);
+        let prompt = mgr.build_prompt(&active, &inputs).unwrap();
+        assert!(prompt.ends_with("Translate into French. "));
+
+        let err = mgr.build_prompt(&active, &HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("needs the input 'language'"));
+    }
+
+    #[test]
+    fn test_tool_scope_union_and_deny_wins() {
+        let writing = skill(&["read_file", "write_file"], &["exec"]);
+        let coding = skill(&["read_file", "exec"], &[]);
+        let unrestricted = skill(&[], &[]);
+
+        let scope = ToolScope::from_skills([&writing, &coding, &unrestricted]);
+        assert_eq!(
+            scope.allow.as_deref(),
+            Some(&["read_file".to_string(), "write_file".into(), "exec".into()][..])
+        );
+        assert_eq!(scope.deny, vec!["exec"]);
+        assert!(ToolScope::from_skills([&unrestricted]).is_empty());
+    }
+
+    #[test]
+    fn test_skill_name_from_source() {
+        assert_eq!(skill_name_from_source("https://example.com/skills/Code-Review.md?raw=1"), "code-review");
+        assert_eq!(skill_name_from_source("./my skills/data.md"), "data");
+        assert_eq!(skill_name_from_source("C:\\skills\\Ops Guide.md"), "ops_guide");
+        assert!(is_valid_skill_name("code-review"));
+        assert!(!is_valid_skill_name("../etc"));
+    }
+}
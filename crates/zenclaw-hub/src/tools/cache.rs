@@ -0,0 +1,153 @@
+//! Small in-memory TTL + LRU cache for tool responses.
+//!
+//! Agent loops often repeat the same search or fetch within a few minutes;
+//! serving those from memory is faster and avoids rate limits. The cache is
+//! bounded so long-running bots don't grow without limit.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default time an entry stays valid.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(300);
+/// Default maximum number of entries.
+pub const DEFAULT_CAPACITY: usize = 100;
+
+struct Entry {
+    value: String,
+    inserted: Instant,
+    /// Value of `Inner::clock` at the last access (for LRU eviction).
+    used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    clock: u64,
+}
+
+/// Thread-safe response cache. A zero TTL or capacity disables it.
+pub struct ResponseCache {
+    ttl: Duration,
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// A cache that never stores anything (e.g. for tests).
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO, 0)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.capacity > 0
+    }
+
+    /// Cached value for `key`, if present and not expired.
+    pub fn get(&self, key: &str) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut inner = self.inner.lock().ok()?;
+        inner.clock += 1;
+        let now = inner.clock;
+        match inner.entries.get_mut(key) {
+            Some(entry) if entry.inserted.elapsed() < self.ttl => {
+                entry.used = now;
+                Some(entry.value.clone())
+            }
+            Some(_) => {
+                inner.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store `value`, evicting expired entries and then the least recently used one if full.
+    pub fn insert(&self, key: &str, value: String) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Ok(mut inner) = self.inner.lock() else { return };
+        inner.clock += 1;
+        let now = inner.clock;
+
+        if !inner.entries.contains_key(key) && inner.entries.len() >= self.capacity {
+            let ttl = self.ttl;
+            inner.entries.retain(|_, e| e.inserted.elapsed() < ttl);
+            if inner.entries.len() >= self.capacity
+                && let Some(oldest) = inner
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.used)
+                    .map(|(k, _)| k.clone())
+            {
+                inner.entries.remove(&oldest);
+            }
+        }
+        inner.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                inserted: Instant::now(),
+                used: now,
+            },
+        );
+    }
+
+    /// Number of stored entries (expired ones included until evicted).
+    pub fn len(&self) -> usize {
+        self.inner.lock().map(|i| i.entries.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = ResponseCache::new(DEFAULT_TTL, 2);
+        cache.insert("a", "1".into());
+        cache.insert("b", "2".into());
+        assert_eq!(cache.get("a").as_deref(), Some("1"));
+
+        cache.insert("c", "3".into());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").as_deref(), Some("1"));
+        assert_eq!(cache.get("c").as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_expired_and_disabled() {
+        let cache = ResponseCache::new(Duration::from_millis(20), 10);
+        cache.insert("a", "1".into());
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(cache.get("a").is_none());
+
+        let off = ResponseCache::disabled();
+        off.insert("a", "1".into());
+        assert!(off.get("a").is_none());
+        assert!(off.is_empty());
+    }
+}
@@ -0,0 +1,345 @@
+//! Calculator tool — evaluate arithmetic exactly instead of letting the model guess.
+//!
+//! A small shunting-yard evaluator: `+ - * / % ^`, parentheses, unary minus,
+//! the constants `pi` and `e`, and common one-argument functions.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use zenclaw_core::error::Result;
+use zenclaw_core::tool::Tool;
+
+/// Evaluate math expressions.
+pub struct CalcTool {
+    /// Decimal places in the result (trailing zeros are dropped).
+    pub precision: usize,
+}
+
+impl CalcTool {
+    pub fn new() -> Self {
+        Self { precision: 10 }
+    }
+
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+}
+
+impl Default for CalcTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for CalcTool {
+    fn name(&self) -> &str {
+        "calculator"
+    }
+
+    fn description(&self) -> &str {
+        "Evaluate a math expression exactly. Use it for ANY arithmetic instead of computing in your head. \
+        Supports + - * / % ^ (power), parentheses, pi, e, and sqrt, abs, sin, cos, tan, asin, acos, atan, \
+        ln, log (base 10), log2, exp, floor, ceil, round. Angles are in radians."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "The expression, e.g. '(1250 * 0.15 + 30) / 4' or 'sqrt(2) * sin(pi / 4)'"
+                }
+            },
+            "required": ["expression"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let expression = args["expression"].as_str().unwrap_or("").trim();
+        if expression.is_empty() {
+            return Ok("Error: 'expression' is required.".into());
+        }
+        Ok(match evaluate(expression) {
+            Ok(value) => format!("{} = {}", expression, format_number(value, self.precision)),
+            Err(e) => format!("Error: {} (in '{}')", e, expression),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Num(f64),
+    /// Binary operator, or `~` for unary minus.
+    Op(char),
+    Func(fn(f64) -> f64),
+    LParen,
+    RParen,
+}
+
+fn function(name: &str) -> Option<fn(f64) -> f64> {
+    Some(match name {
+        "sqrt" => f64::sqrt,
+        "abs" => f64::abs,
+        "sin" => f64::sin,
+        "cos" => f64::cos,
+        "tan" => f64::tan,
+        "asin" => f64::asin,
+        "acos" => f64::acos,
+        "atan" => f64::atan,
+        "ln" => f64::ln,
+        "log" => f64::log10,
+        "log2" => f64::log2,
+        "exp" => f64::exp,
+        "floor" => f64::floor,
+        "ceil" => f64::ceil,
+        "round" => f64::round,
+        _ => return None,
+    })
+}
+
+/// (precedence, right-associative)
+fn precedence(op: char) -> (u8, bool) {
+    match op {
+        '+' | '-' => (1, false),
+        '*' | '/' | '%' => (2, false),
+        '~' => (3, true),
+        _ => (4, true), // '^'
+    }
+}
+
+fn tokenize(expr: &str) -> std::result::Result<Vec<Token>, String> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    // True where a number, '(' or function may come next
+    let mut expect_operand = true;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            if !expect_operand {
+                return Err(format!("missing operator before '{}'", c));
+            }
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Scientific notation: 1e3, 2.5E-4
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && matches!(chars[j], '+' | '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse().map_err(|_| format!("invalid number '{}'", text))?;
+            tokens.push(Token::Num(value));
+            expect_operand = false;
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() {
+            if !expect_operand {
+                return Err(format!("missing operator before '{}'", c));
+            }
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect::<String>().to_lowercase();
+            match name.as_str() {
+                "pi" => tokens.push(Token::Num(std::f64::consts::PI)),
+                "e" => tokens.push(Token::Num(std::f64::consts::E)),
+                _ => {
+                    let f = function(&name).ok_or_else(|| format!("unknown name '{}'", name))?;
+                    if chars[i..].iter().find(|c| !c.is_whitespace()) != Some(&'(') {
+                        return Err(format!("function '{}' needs parentheses, e.g. {}(2)", name, name));
+                    }
+                    tokens.push(Token::Func(f));
+                    continue; // still expecting its '('
+                }
+            }
+            expect_operand = false;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                if !expect_operand {
+                    return Err("missing operator before '('".into());
+                }
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                if expect_operand {
+                    return Err("missing operand before ')'".into());
+                }
+                tokens.push(Token::RParen);
+            }
+            '-' | '+' if expect_operand => {
+                if c == '-' {
+                    tokens.push(Token::Op('~'));
+                }
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                if expect_operand {
+                    return Err(format!("missing operand before '{}'", c));
+                }
+                tokens.push(Token::Op(c));
+                expect_operand = true;
+            }
+            _ => return Err(format!("unexpected character '{}'", c)),
+        }
+        i += 1;
+    }
+
+    if expect_operand {
+        return Err("expression ends with an operator".into());
+    }
+    Ok(tokens)
+}
+
+/// Reorder tokens into reverse Polish notation.
+fn to_rpn(tokens: Vec<Token>) -> std::result::Result<Vec<Token>, String> {
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut stack: Vec<Token> = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Num(_) => output.push(token),
+            Token::Func(_) | Token::LParen => stack.push(token),
+            Token::Op('~') => stack.push(token), // prefix: nothing to its left to pop
+            Token::Op(op) => {
+                let (prec, right) = precedence(op);
+                while let Some(&Token::Op(top)) = stack.last() {
+                    let (top_prec, _) = precedence(top);
+                    if top_prec > prec || (top_prec == prec && !right) {
+                        output.push(stack.pop().expect("checked above"));
+                    } else {
+                        break;
+                    }
+                }
+                stack.push(token);
+            }
+            Token::RParen => {
+                loop {
+                    match stack.pop() {
+                        Some(Token::LParen) => break,
+                        Some(t) => output.push(t),
+                        None => return Err("unbalanced parentheses: unexpected ')'".into()),
+                    }
+                }
+                if let Some(&Token::Func(_)) = stack.last() {
+                    output.push(stack.pop().expect("checked above"));
+                }
+            }
+        }
+    }
+
+    while let Some(token) = stack.pop() {
+        if matches!(token, Token::LParen) {
+            return Err("unbalanced parentheses: missing ')'".into());
+        }
+        output.push(token);
+    }
+    Ok(output)
+}
+
+/// Evaluate an expression such as `2 * (3 + 4) ^ 2`.
+pub fn evaluate(expr: &str) -> std::result::Result<f64, String> {
+    let rpn = to_rpn(tokenize(expr)?)?;
+    let mut stack: Vec<f64> = Vec::new();
+    let malformed = || "malformed expression".to_string();
+
+    for token in rpn {
+        match token {
+            Token::Num(n) => stack.push(n),
+            Token::Op('~') => {
+                let a = stack.pop().ok_or_else(malformed)?;
+                stack.push(-a);
+            }
+            Token::Func(f) => {
+                let a = stack.pop().ok_or_else(malformed)?;
+                stack.push(f(a));
+            }
+            Token::Op(op) => {
+                let b = stack.pop().ok_or_else(malformed)?;
+                let a = stack.pop().ok_or_else(malformed)?;
+                stack.push(match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' | '%' if b == 0.0 => return Err("division by zero".into()),
+                    '/' => a / b,
+                    '%' => a % b,
+                    _ => a.powf(b),
+                });
+            }
+            Token::LParen | Token::RParen => return Err(malformed()),
+        }
+    }
+
+    match stack.as_slice() {
+        [value] if value.is_finite() => Ok(*value),
+        [_] => Err("result is not a finite real number".into()),
+        _ => Err(malformed()),
+    }
+}
+
+/// Round to `precision` decimals and drop trailing zeros.
+fn format_number(value: f64, precision: usize) -> String {
+    let text = format!("{:.*}", precision, value);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    if text == "-0" { "0".into() } else { text.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operator_precedence() {
+        assert_eq!(evaluate("2 + 3 * 4").unwrap(), 14.0);
+        assert_eq!(evaluate("(2 + 3) * 4").unwrap(), 20.0);
+        assert_eq!(evaluate("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert_eq!(evaluate("-2 ^ 2").unwrap(), -4.0);
+        assert_eq!(evaluate("2 ^ -1").unwrap(), 0.5);
+        assert_eq!(evaluate("10 - 4 - 3").unwrap(), 3.0);
+        assert_eq!(evaluate("17 % 5 * 2").unwrap(), 4.0);
+        assert_eq!(evaluate("sqrt(16) + log(1000) - abs(-1)").unwrap(), 6.0);
+        assert_eq!(evaluate("1.5e2 / 3").unwrap(), 50.0);
+        assert_eq!(format_number(evaluate("sin(pi)").unwrap(), 10), "0");
+        assert_eq!(format_number(evaluate("1 / 3").unwrap(), 4), "0.3333");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(evaluate("(1 + 2").unwrap_err().contains("unbalanced"));
+        assert!(evaluate("1 + 2)").unwrap_err().contains("unbalanced"));
+        assert_eq!(evaluate("1 / (2 - 2)").unwrap_err(), "division by zero");
+        assert_eq!(evaluate("5 % 0").unwrap_err(), "division by zero");
+        assert!(evaluate("2 +").is_err());
+        assert!(evaluate("2 3").is_err());
+        assert!(evaluate("foo(1)").unwrap_err().contains("unknown name"));
+        assert!(evaluate("sqrt 4").is_err());
+        assert!(evaluate("sqrt(-1)").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Semantic codebase search tool — recursively scan files for definitions or keywords.
 //!
 //! Understands `.gitignore` automatically. Highly efficient thanks to `ignore` crate.
+//! Inside a workspace, `dir` must stay within it.
 
 use async_trait::async_trait;
 use ignore::WalkBuilder;
@@ -13,6 +14,8 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use zenclaw_core::error::Result;
 use zenclaw_core::tool::Tool;
 
+use super::filesystem::resolve_local;
+
 pub struct CodebaseSearchTool;
 
 impl CodebaseSearchTool {
@@ -71,7 +74,10 @@ impl Tool for CodebaseSearchTool {
             return Ok("Error: 'query' parameter is required.".into());
         }
 
-        let dir = args["dir"].as_str().unwrap_or(".").to_string();
+        let dir = match resolve_local(args["dir"].as_str().unwrap_or(".")) {
+            Ok(dir) => dir.to_string_lossy().to_string(),
+            Err(e) => return Ok(format!("Error: {}", e)),
+        };
         let case_sensitive = args["case_sensitive"].as_bool().unwrap_or(false);
         let ext_filter = args["file_extension"].as_str().map(|s| s.to_string());
 
@@ -212,3 +218,23 @@ impl Tool for CodebaseSearchTool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::tool::with_workspace;
+
+    #[tokio::test]
+    async fn test_search_stays_in_the_workspace() {
+        let outer = tempfile::tempdir().unwrap();
+        let ws = outer.path().join("ws");
+        std::fs::create_dir_all(&ws).unwrap();
+        std::fs::write(outer.path().join("secrets.env"), "TOKEN=abc\n").unwrap();
+
+        for dir in ["..".to_string(), outer.path().to_string_lossy().to_string()] {
+            let args = json!({ "query": "TOKEN", "dir": dir });
+            let out = with_workspace(Some(ws.clone()), CodebaseSearchTool::new().execute(args)).await.unwrap();
+            assert!(out.contains("outside workspace") && !out.contains("abc"), "{}: {}", dir, out);
+        }
+    }
+}
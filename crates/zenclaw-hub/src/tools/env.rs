@@ -1,19 +1,129 @@
 //! Environment tool — inspect and manage environment variables.
 //!
 //! Useful for debugging provider configs, checking API key presence, etc.
+//! Values of variables the [`EnvPolicy`] denies (API keys, tokens, secrets)
+//! never reach the model: they read as `<redacted>`. `set` doesn't touch
+//! ZenClaw's own environment — it records a per-session override in a
+//! [`SessionEnv`], which `exec` and `process` pass to the commands they
+//! start for that session.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
 
 use zenclaw_core::error::Result;
-use zenclaw_core::tool::Tool;
+use zenclaw_core::tool::{current_session, Tool};
+
+/// Shown instead of the value of a denied variable.
+pub const REDACTED: &str = "<redacted>";
+
+/// Name patterns denied by default (`*` matches anything, case-insensitive).
+pub const DEFAULT_DENY: &[&str] = &["*_KEY", "*_TOKEN", "*SECRET*", "*PRIVATE*", "*PASSWORD*", "*CREDENTIAL*"];
+
+/// Which variables the model may read.
+#[derive(Debug, Clone)]
+pub struct EnvPolicy {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl Default for EnvPolicy {
+    fn default() -> Self {
+        Self::new(Vec::new(), Vec::new())
+    }
+}
+
+impl EnvPolicy {
+    /// Deny [`DEFAULT_DENY`] plus `deny`; names matching `allow` are
+    /// readable even when denied.
+    pub fn new(allow: Vec<String>, deny: Vec<String>) -> Self {
+        Self {
+            allow,
+            deny: DEFAULT_DENY.iter().map(|p| p.to_string()).chain(deny).collect(),
+        }
+    }
+
+    pub fn is_readable(&self, name: &str) -> bool {
+        self.allow.iter().any(|p| matches_pattern(p, name)) || !self.deny.iter().any(|p| matches_pattern(p, name))
+    }
+
+    /// `value`, or [`REDACTED`] if `name` isn't readable.
+    fn show<'a>(&self, name: &str, value: &'a str) -> &'a str {
+        if self.is_readable(name) { value } else { REDACTED }
+    }
+}
+
+/// Case-insensitive match of `name` against `pattern`, where `*` matches
+/// any run of characters.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_ascii_uppercase();
+    let name = name.to_ascii_uppercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || !name[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Variables set by the `env` tool, per session.
+pub type SessionEnv = Arc<Mutex<HashMap<String, HashMap<String, String>>>>;
+
+pub fn new_session_env() -> SessionEnv {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Overrides set for the session of the running tool call.
+pub(crate) fn session_vars(env: &SessionEnv) -> HashMap<String, String> {
+    let session = current_session().unwrap_or_default();
+    env.lock()
+        .ok()
+        .and_then(|sessions| sessions.get(&session).cloned())
+        .unwrap_or_default()
+}
 
 /// Environment inspection tool.
-pub struct EnvTool;
+pub struct EnvTool {
+    policy: EnvPolicy,
+    session_env: SessionEnv,
+}
 
 impl EnvTool {
     pub fn new() -> Self {
-        Self
+        Self {
+            policy: EnvPolicy::default(),
+            session_env: new_session_env(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: EnvPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Share `set` overrides with the tools that start commands.
+    pub fn with_session_env(mut self, env: SessionEnv) -> Self {
+        self.session_env = env;
+        self
+    }
+
+    /// Apply `f` to this session's overrides.
+    fn update<T>(&self, f: impl FnOnce(&mut HashMap<String, String>) -> T) -> Option<T> {
+        let session = current_session().unwrap_or_default();
+        let mut sessions = self.session_env.lock().ok()?;
+        Some(f(sessions.entry(session).or_default()))
     }
 }
 
@@ -30,7 +140,8 @@ impl Tool for EnvTool {
     }
 
     fn description(&self) -> &str {
-        "Inspect environment variables. Can check if API keys are set, view PATH, etc. Sensitive values are masked."
+        "Inspect environment variables. Can check if API keys are set, view PATH, etc. Secret values (API keys, tokens) read as <redacted>. \
+'set' / 'unset' change variables for commands run later in this session only."
     }
 
     fn parameters(&self) -> Value {
@@ -39,12 +150,16 @@ impl Tool for EnvTool {
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["get", "list", "check"],
-                    "description": "Action: 'get' a variable, 'list' all vars, 'check' if key vars are set"
+                    "enum": ["get", "list", "check", "set", "unset"],
+                    "description": "Action: 'get' a variable, 'list' all vars, 'check' if key vars are set, 'set' / 'unset' a variable for later commands"
                 },
                 "name": {
                     "type": "string",
-                    "description": "Variable name (for 'get' action)"
+                    "description": "Variable name (for 'get', 'set' and 'unset')"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "Value (for 'set')"
                 }
             },
             "required": ["action"]
@@ -53,39 +168,35 @@ impl Tool for EnvTool {
 
     async fn execute(&self, args: Value) -> Result<String> {
         let action = args["action"].as_str().unwrap_or("check");
+        let name = args["name"].as_str().unwrap_or("");
+        let overrides = session_vars(&self.session_env);
 
         match action {
             "get" => {
-                let name = args["name"].as_str().unwrap_or("");
                 if name.is_empty() {
                     return Ok("Variable name is required.".to_string());
                 }
 
-                match std::env::var(name) {
-                    Ok(val) => {
-                        // Mask sensitive values
-                        let masked = if is_sensitive(name) {
-                            mask_value(&val)
-                        } else {
-                            val.clone()
-                        };
-                        Ok(format!("{}={}", name, masked))
-                    }
-                    Err(_) => Ok(format!("{} is not set", name)),
+                match overrides.get(name).cloned().or_else(|| std::env::var(name).ok()) {
+                    Some(val) => Ok(format!("{}={}", name, self.policy.show(name, &val))),
+                    None => Ok(format!("{} is not set", name)),
                 }
             }
             "list" => {
-                let mut vars: Vec<String> = std::env::vars()
+                let mut env: HashMap<String, String> = std::env::vars().collect();
+                env.extend(overrides);
+                let mut vars: Vec<String> = env
+                    .into_iter()
                     .filter(|(k, _)| !k.starts_with('_') && !k.starts_with("LS_"))
                     .map(|(k, v)| {
-                        let masked = if is_sensitive(&k) {
-                            mask_value(&v)
-                        } else if v.len() > 80 {
-                            format!("{}...", &v[..80])
+                        let shown = if !self.policy.is_readable(&k) {
+                            REDACTED.to_string()
+                        } else if v.chars().count() > 80 {
+                            format!("{}...", v.chars().take(80).collect::<String>())
                         } else {
                             v
                         };
-                        format!("  {}={}", k, masked)
+                        format!("  {}={}", k, shown)
                     })
                     .collect();
 
@@ -105,7 +216,7 @@ impl Tool for EnvTool {
 
                 let mut output = String::from("API Key Status:\n");
                 for (key, label) in &keys {
-                    let status = if std::env::var(key).is_ok() {
+                    let status = if overrides.contains_key(*key) || std::env::var(key).is_ok() {
                         "✅ Set"
                     } else {
                         "❌ Not set"
@@ -115,27 +226,87 @@ impl Tool for EnvTool {
 
                 Ok(output)
             }
-            _ => Ok(format!("Unknown action: {}. Use 'get', 'list', or 'check'.", action)),
+            "set" => {
+                let valid = !name.is_empty()
+                    && !name.starts_with(|c: char| c.is_ascii_digit())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid {
+                    return Ok(format!("Invalid variable name: '{}'. Use letters, digits and '_'.", name));
+                }
+                let value = args["value"].as_str().unwrap_or("").to_string();
+                self.update(|vars| vars.insert(name.to_string(), value));
+                Ok(format!("{} set for commands run later in this session.", name))
+            }
+            "unset" => {
+                if name.is_empty() {
+                    return Ok("Variable name is required.".to_string());
+                }
+                match self.update(|vars| vars.remove(name)).flatten() {
+                    Some(_) => Ok(format!("{} unset for this session.", name)),
+                    None => Ok(format!("{} was not set in this session.", name)),
+                }
+            }
+            _ => Ok(format!("Unknown action: {}. Use 'get', 'list', 'check', 'set' or 'unset'.", action)),
         }
     }
 }
 
-/// Check if a variable name likely contains sensitive data.
-fn is_sensitive(name: &str) -> bool {
-    let lower = name.to_lowercase();
-    lower.contains("key")
-        || lower.contains("secret")
-        || lower.contains("token")
-        || lower.contains("password")
-        || lower.contains("auth")
-        || lower.contains("credential")
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::tool::with_session;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = EnvPolicy::default();
+        for name in [
+            "OPENAI_API_KEY",
+            "telegram_bot_token",
+            "CLIENT_SECRET",
+            "AWS_SECRET_ACCESS_KEY",
+            "STRIPE_KEY",
+            "SSH_PRIVATE_KEY_PATH",
+            "DB_PASSWORD",
+        ] {
+            assert!(!policy.is_readable(name), "{}", name);
+        }
+        for name in ["PATH", "HOME", "RUST_LOG", "TOKENIZERS_PARALLELISM", "KEYMAP"] {
+            assert!(policy.is_readable(name), "{}", name);
+        }
+
+        let custom = EnvPolicy::new(vec!["GITHUB_TOKEN".into()], vec!["AWS_*".into()]);
+        assert!(custom.is_readable("GITHUB_TOKEN"));
+        assert!(!custom.is_readable("AWS_REGION"));
+        assert!(!custom.is_readable("OPENAI_API_KEY"));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_read_is_redacted() {
+        let tool = EnvTool::new();
+        let get = json!({ "action": "get", "name": "OPENAI_API_KEY" });
+        with_session("s1", async {
+            tool.execute(json!({ "action": "set", "name": "OPENAI_API_KEY", "value": "sk-SECRET" }))
+                .await
+                .unwrap();
+            assert_eq!(tool.execute(get.clone()).await.unwrap(), "OPENAI_API_KEY=<redacted>");
+            let listed = tool.execute(json!({ "action": "list" })).await.unwrap();
+            assert!(!listed.contains("sk-SECRET"), "{}", listed);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_set_is_session_scoped() {
+        let env = new_session_env();
+        let tool = EnvTool::new().with_session_env(env.clone());
+        with_session("s1", tool.execute(json!({ "action": "set", "name": "ZC_TEST_MODE", "value": "on" })))
+            .await
+            .unwrap();
 
-/// Mask a sensitive value, showing only first/last 3 chars.
-fn mask_value(val: &str) -> String {
-    if val.len() <= 8 {
-        "****".to_string()
-    } else {
-        format!("{}...{}", &val[..3], &val[val.len() - 3..])
+        assert!(std::env::var("ZC_TEST_MODE").is_err());
+        let get = json!({ "action": "get", "name": "ZC_TEST_MODE" });
+        assert_eq!(with_session("s1", tool.execute(get.clone())).await.unwrap(), "ZC_TEST_MODE=on");
+        assert_eq!(with_session("s2", tool.execute(get)).await.unwrap(), "ZC_TEST_MODE is not set");
+        assert_eq!(with_session("s1", async { session_vars(&env) }).await["ZC_TEST_MODE"], "on");
     }
 }
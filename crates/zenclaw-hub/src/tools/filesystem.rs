@@ -1,46 +1,207 @@
 //! Filesystem tools — read, write, edit, list directories.
+//!
+//! Paths may also be remote URIs (e.g. `sftp://host/etc/nginx.conf`); see
+//! [`super::remote_fs`] for the available backends.
+//!
+//! Before `write_file` or `edit_file` replace a file, its current content is
+//! saved next to it as `<file>.bak` (one backup per file, the latest).
+//! `undo_edit` swaps the two back.
 
 use async_trait::async_trait;
+use ignore::WalkBuilder;
 use serde_json::{json, Value};
-use std::path::{Path, PathBuf};
-use tokio::fs;
+use std::path::{Component, Path, PathBuf};
 
 use zenclaw_core::error::{Result, ZenClawError};
-use zenclaw_core::tool::Tool;
-
-/// Resolve path, optionally restricting to a workspace.
-fn resolve_path(path: &str, workspace: Option<&Path>) -> std::result::Result<PathBuf, String> {
-    let resolved = PathBuf::from(path)
-        .canonicalize()
-        .unwrap_or_else(|_| PathBuf::from(path));
-
-    if let Some(ws) = workspace {
-        let ws_resolved = ws.canonicalize().unwrap_or_else(|_| ws.to_path_buf());
-        if !resolved.starts_with(&ws_resolved) {
+use zenclaw_core::tool::{current_workspace, Tool};
+
+use super::remote_fs::{FsRouter, RemoteFs};
+
+/// Resolve a path to the string handed to the backend.
+///
+/// The agent's workspace for the running call (see
+/// [`current_workspace`]) takes precedence over the tool's own. Local paths
+/// are canonicalized and checked against the workspace; remote URIs pass
+/// through untouched but are refused when a workspace is set.
+fn resolve(path: &str, workspace: Option<&Path>) -> std::result::Result<String, String> {
+    let session = current_workspace();
+    let workspace = session.as_deref().or(workspace);
+    if FsRouter::is_remote(path) {
+        if let Some(ws) = workspace {
             return Err(format!(
-                "Path {} is outside workspace {}",
+                "Remote path {} is not allowed (restricted to workspace {})",
                 path,
                 ws.display()
             ));
         }
+        return Ok(path.to_string());
+    }
+    resolve_path(path, workspace).map(|p| p.to_string_lossy().to_string())
+}
+
+/// Resolve a local path for tools that search the disk (`grep`,
+/// `code_search`, `git`): inside the running call's workspace it is
+/// confined like the file tools' paths; otherwise it is used as given.
+pub(crate) fn resolve_local(path: &str) -> std::result::Result<PathBuf, String> {
+    match current_workspace() {
+        Some(ws) => resolve_path(path, Some(&ws)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// Resolve path, optionally restricting to a workspace.
+///
+/// Inside a workspace, relative paths start at its root, and the result
+/// must stay under the root once symlinks and `..` are resolved.
+fn resolve_path(path: &str, workspace: Option<&Path>) -> std::result::Result<PathBuf, String> {
+    let Some(ws) = workspace else {
+        return Ok(PathBuf::from(path)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(path)));
+    };
+
+    let root = ws.canonicalize().unwrap_or_else(|_| ws.to_path_buf());
+    let requested = Path::new(path);
+    let joined = if requested.is_absolute() { requested.to_path_buf() } else { root.join(requested) };
+    let outside = || format!("Path {} is outside workspace {}", path, ws.display());
+    let resolved = canonicalize_lenient(&joined).ok_or_else(outside)?;
+    if !resolved.starts_with(&root) {
+        return Err(outside());
     }
     Ok(resolved)
 }
 
+/// Canonicalize a path that may not exist yet: its longest existing
+/// ancestor is resolved (symlinks included) and the rest applied on top,
+/// `..` included. None if the missing part crosses a (dangling) symlink,
+/// whose target can't be checked.
+fn canonicalize_lenient(path: &Path) -> Option<PathBuf> {
+    let parts: Vec<Component> = path.components().collect();
+    let (mut resolved, rest) = (1..=parts.len()).rev().find_map(|split| {
+        let head: PathBuf = parts[..split].iter().collect();
+        head.canonicalize().ok().map(|p| (p, &parts[split..]))
+    })?;
+
+    for part in rest {
+        match part {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                if resolved.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(resolved)
+}
+
+/// Suffix of the backup kept next to a file replaced by `write_file` or `edit_file`.
+pub const BACKUP_SUFFIX: &str = ".bak";
+
+fn backup_path(resolved: &str) -> String {
+    format!("{}{}", resolved, BACKUP_SUFFIX)
+}
+
+/// Save `content` (the file's text before a change) as its backup.
+async fn backup(fs: &dyn RemoteFs, resolved: &str, content: &str) -> std::result::Result<(), String> {
+    fs.write(&backup_path(resolved), content)
+        .await
+        .map_err(|e| format!("Error: couldn't back up {} before changing it: {}", resolved, e))
+}
+
 // ─── ReadFile ──────────────────────────────────────────────
 
+/// Bytes `read_file` returns when the call sets no `max_bytes`.
+pub const DEFAULT_READ_BYTES: usize = 64 * 1024;
+
+/// Largest `max_bytes` a call may ask for.
+const MAX_READ_BYTES: usize = 1024 * 1024;
+
+/// Lines `start_line..=end_line` (1-based) of `content`, at most
+/// `max_bytes` of them, with a note giving the total line count. Without a
+/// range, content within `max_bytes` comes back as is.
+fn read_slice(
+    content: &str,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    max_bytes: usize,
+) -> std::result::Result<String, String> {
+    if start_line.is_none() && end_line.is_none() && content.len() <= max_bytes {
+        return Ok(content.to_string());
+    }
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let total = lines.len();
+    let start = start_line.unwrap_or(1).max(1);
+    let end = end_line.unwrap_or(total).min(total);
+    if start > total {
+        return Err(format!("start_line {} is past the end of the file ({} lines)", start, total));
+    }
+    if end < start {
+        return Err(format!("end_line {} is before start_line {}", end, start));
+    }
+
+    let mut out = String::new();
+    let mut last = start - 1;
+    for line in &lines[start - 1..end] {
+        if out.len() + line.len() > max_bytes {
+            break;
+        }
+        out.push_str(line);
+        last += 1;
+    }
+    if last < start {
+        // Not even the first line fits: return its head
+        let line = lines[start - 1];
+        let cut = (0..=max_bytes.min(line.len())).rev().find(|&i| line.is_char_boundary(i)).unwrap_or(0);
+        return Ok(format!(
+            "{}\n\n[Line {} of {} is longer than {} bytes and was cut.]",
+            &line[..cut],
+            start,
+            total,
+            max_bytes
+        ));
+    }
+
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    if last < end {
+        out.push_str(&format!(
+            "\n[Truncated at {} bytes: lines {}-{} of {} shown. Continue with start_line={}.]",
+            max_bytes,
+            start,
+            last,
+            total,
+            last + 1
+        ));
+    } else {
+        out.push_str(&format!("\n[Lines {}-{} of {}.]", start, end, total));
+    }
+    Ok(out)
+}
+
 pub struct ReadFileTool {
     pub workspace: Option<PathBuf>,
+    fs: FsRouter,
 }
 
 impl ReadFileTool {
     pub fn new() -> Self {
-        Self { workspace: None }
+        Self { workspace: None, fs: FsRouter::default() }
     }
     pub fn with_workspace(mut self, ws: &Path) -> Self {
         self.workspace = Some(ws.to_path_buf());
         self
     }
+    pub fn with_fs(mut self, fs: FsRouter) -> Self {
+        self.fs = fs;
+        self
+    }
 }
 
 impl Default for ReadFileTool {
@@ -51,13 +212,18 @@ impl Default for ReadFileTool {
 impl Tool for ReadFileTool {
     fn name(&self) -> &str { "read_file" }
     fn description(&self) -> &str {
-        "Read the contents of a file at the given path."
+        "Read the contents of a file at the given path (local, or a remote URI such as sftp://host/path). \
+         Large files are cut at max_bytes; read them in slices with start_line/end_line. \
+         Ranged or cut reads end with a note giving the file's total line count."
     }
     fn parameters(&self) -> Value {
         json!({
             "type": "object",
             "properties": {
-                "path": { "type": "string", "description": "The file path to read" }
+                "path": { "type": "string", "description": "The file path or remote URI to read" },
+                "start_line": { "type": "integer", "description": "First line to return (1-based, default: 1)" },
+                "end_line": { "type": "integer", "description": "Last line to return, inclusive (default: end of file)" },
+                "max_bytes": { "type": "integer", "description": "Most bytes to return (default: 65536, max: 1048576)" }
             },
             "required": ["path"]
         })
@@ -65,15 +231,26 @@ impl Tool for ReadFileTool {
 
     async fn execute(&self, args: Value) -> Result<String> {
         let path = args["path"].as_str().unwrap_or("");
-        let resolved = resolve_path(path, self.workspace.as_deref())
+        let resolved = resolve(path, self.workspace.as_deref())
             .map_err(|e| ZenClawError::ToolExecution { tool: "read_file".into(), message: e })?;
+        let fs = match self.fs.backend(&resolved) {
+            Ok(fs) => fs,
+            Err(e) => return Ok(format!("Error: {}", e)),
+        };
 
-        if !resolved.exists() {
-            return Ok(format!("Error: File not found: {}", path));
+        let content = match fs.read(&resolved).await {
+            Ok(content) => content,
+            Err(e) => return Ok(format!("Error reading file: {}", e)),
+        };
+        if content.contains('\0') {
+            return Ok(format!("Error: {} is a binary file, not text; read_file can't show it", path));
         }
-        match fs::read_to_string(&resolved).await {
-            Ok(content) => Ok(content),
-            Err(e) => Ok(format!("Error reading file: {}", e)),
+
+        let line = |key: &str| args[key].as_u64().map(|n| n as usize);
+        let max_bytes = line("max_bytes").unwrap_or(DEFAULT_READ_BYTES).clamp(1, MAX_READ_BYTES);
+        match read_slice(&content, line("start_line"), line("end_line"), max_bytes) {
+            Ok(slice) => Ok(slice),
+            Err(e) => Ok(format!("Error: {}", e)),
         }
     }
 }
@@ -82,14 +259,19 @@ impl Tool for ReadFileTool {
 
 pub struct WriteFileTool {
     pub workspace: Option<PathBuf>,
+    fs: FsRouter,
 }
 
 impl WriteFileTool {
-    pub fn new() -> Self { Self { workspace: None } }
+    pub fn new() -> Self { Self { workspace: None, fs: FsRouter::default() } }
     pub fn with_workspace(mut self, ws: &Path) -> Self {
         self.workspace = Some(ws.to_path_buf());
         self
     }
+    pub fn with_fs(mut self, fs: FsRouter) -> Self {
+        self.fs = fs;
+        self
+    }
 }
 
 impl Default for WriteFileTool {
@@ -106,23 +288,40 @@ impl Tool for WriteFileTool {
         json!({
             "type": "object",
             "properties": {
-                "path": { "type": "string", "description": "The file path to write to" },
+                "path": { "type": "string", "description": "The file path or remote URI to write to" },
                 "content": { "type": "string", "description": "The content to write" }
             },
             "required": ["path", "content"]
         })
     }
+    fn is_destructive(&self) -> bool { true }
 
     async fn execute(&self, args: Value) -> Result<String> {
         let path = args["path"].as_str().unwrap_or("");
         let content = args["content"].as_str().unwrap_or("");
-        let resolved = resolve_path(path, self.workspace.as_deref())
+        let resolved = resolve(path, self.workspace.as_deref())
             .map_err(|e| ZenClawError::ToolExecution { tool: "write_file".into(), message: e })?;
+        let fs = match self.fs.backend(&resolved) {
+            Ok(fs) => fs,
+            Err(e) => return Ok(format!("Error: {}", e)),
+        };
 
-        if let Some(parent) = resolved.parent() {
-            fs::create_dir_all(parent).await.ok();
-        }
-        match fs::write(&resolved, content).await {
+        let backed_up = match fs.read(&resolved).await {
+            Ok(previous) => match backup(fs, &resolved, &previous).await {
+                Ok(()) => true,
+                Err(message) => return Ok(message),
+            },
+            Err(_) => false,
+        };
+
+        match fs.write(&resolved, content).await {
+            Ok(()) if backed_up => Ok(format!(
+                "✅ Wrote {} bytes to {} (previous version saved to {}{})",
+                content.len(),
+                path,
+                path,
+                BACKUP_SUFFIX
+            )),
             Ok(()) => Ok(format!("✅ Wrote {} bytes to {}", content.len(), path)),
             Err(e) => Ok(format!("Error writing file: {}", e)),
         }
@@ -133,14 +332,19 @@ impl Tool for WriteFileTool {
 
 pub struct EditFileTool {
     pub workspace: Option<PathBuf>,
+    fs: FsRouter,
 }
 
 impl EditFileTool {
-    pub fn new() -> Self { Self { workspace: None } }
+    pub fn new() -> Self { Self { workspace: None, fs: FsRouter::default() } }
     pub fn with_workspace(mut self, ws: &Path) -> Self {
         self.workspace = Some(ws.to_path_buf());
         self
     }
+    pub fn with_fs(mut self, fs: FsRouter) -> Self {
+        self.fs = fs;
+        self
+    }
 }
 
 impl Default for EditFileTool {
@@ -164,55 +368,241 @@ impl Tool for EditFileTool {
             "required": ["path", "old_text", "new_text"]
         })
     }
+    fn is_destructive(&self) -> bool { true }
+
+    async fn preview(&self, args: &Value) -> Option<String> {
+        let edit = self.plan(args).await.ok()?.ok()?;
+        Some(unified_diff(&edit.path, &edit.old, &edit.new))
+    }
 
     async fn execute(&self, args: Value) -> Result<String> {
+        let edit = match self.plan(&args).await? {
+            Ok(edit) => edit,
+            Err(message) => return Ok(message),
+        };
+        if let Err(message) = backup(edit.fs, &edit.resolved, &edit.old).await {
+            return Ok(message);
+        }
+        match edit.fs.write(&edit.resolved, &edit.new).await {
+            Ok(()) => Ok(format!(
+                "✅ Edited {} (undo with undo_edit)\n\n{}",
+                edit.path,
+                unified_diff(&edit.path, &edit.old, &edit.new)
+            )),
+            Err(e) => Ok(format!("Error writing file: {}", e)),
+        }
+    }
+}
+
+/// An edit worked out in memory, not written yet.
+struct PlannedEdit<'a> {
+    fs: &'a dyn RemoteFs,
+    path: String,
+    resolved: String,
+    old: String,
+    new: String,
+}
+
+impl EditFileTool {
+    /// Read the file and apply the replacement in memory. The inner error
+    /// is the message returned to the model.
+    async fn plan(&self, args: &Value) -> Result<std::result::Result<PlannedEdit<'_>, String>> {
         let path = args["path"].as_str().unwrap_or("");
         let old_text = args["old_text"].as_str().unwrap_or("");
         let new_text = args["new_text"].as_str().unwrap_or("");
 
-        let resolved = resolve_path(path, self.workspace.as_deref())
+        let resolved = resolve(path, self.workspace.as_deref())
             .map_err(|e| ZenClawError::ToolExecution { tool: "edit_file".into(), message: e })?;
+        let fs = match self.fs.backend(&resolved) {
+            Ok(fs) => fs,
+            Err(e) => return Ok(Err(format!("Error: {}", e))),
+        };
 
-        let content = match fs::read_to_string(&resolved).await {
+        let content = match fs.read(&resolved).await {
             Ok(c) => c,
-            Err(e) => return Ok(format!("Error reading file: {}", e)),
+            Err(e) => return Ok(Err(format!("Error reading file: {}", e))),
         };
 
         if !content.contains(old_text) {
-            return Ok(format!(
+            return Ok(Err(format!(
                 "Error: old_text not found in {}. Use read_file to get exact content.",
                 path
-            ));
+            )));
         }
 
         let count = content.matches(old_text).count();
         if count > 1 {
-            return Ok(format!(
+            return Ok(Err(format!(
                 "Warning: old_text appears {} times. Provide more context to make unique.",
                 count
-            ));
+            )));
         }
 
-        let new_content = content.replacen(old_text, new_text, 1);
-        match fs::write(&resolved, new_content).await {
-            Ok(()) => Ok(format!("✅ Edited {}", path)),
-            Err(e) => Ok(format!("Error writing file: {}", e)),
+        let new = content.replacen(old_text, new_text, 1);
+        Ok(Ok(PlannedEdit { fs, path: path.to_string(), resolved, old: content, new }))
+    }
+}
+
+/// Context lines kept around each change in edit diffs.
+const DIFF_CONTEXT_LINES: usize = 3;
+/// Longest diff returned, in lines; the rest is cut.
+const MAX_DIFF_LINES: usize = 200;
+
+/// Unified diff (`@@` hunks) between two versions of `path`. Only the
+/// changed hunks are included, so large files stay short.
+fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let diff = similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .header(&format!("a/{}", path.trim_start_matches('/')), &format!("b/{}", path.trim_start_matches('/')))
+        .to_string();
+    let total = diff.lines().count();
+    if total <= MAX_DIFF_LINES {
+        return diff;
+    }
+    let mut cut: String = diff.lines().take(MAX_DIFF_LINES).map(|l| format!("{}\n", l)).collect();
+    cut.push_str(&format!("… diff cut at {} of {} lines\n", MAX_DIFF_LINES, total));
+    cut
+}
+
+// ─── UndoEdit ──────────────────────────────────────────────
+
+pub struct UndoEditTool {
+    pub workspace: Option<PathBuf>,
+    fs: FsRouter,
+}
+
+impl UndoEditTool {
+    pub fn new() -> Self { Self { workspace: None, fs: FsRouter::default() } }
+    pub fn with_workspace(mut self, ws: &Path) -> Self {
+        self.workspace = Some(ws.to_path_buf());
+        self
+    }
+    pub fn with_fs(mut self, fs: FsRouter) -> Self {
+        self.fs = fs;
+        self
+    }
+}
+
+impl Default for UndoEditTool {
+    fn default() -> Self { Self::new() }
+}
+
+#[async_trait]
+impl Tool for UndoEditTool {
+    fn name(&self) -> &str { "undo_edit" }
+    fn description(&self) -> &str {
+        "Undo the last write_file or edit_file on a file by restoring its backup. Running it again redoes the change."
+    }
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "The file to restore" }
+            },
+            "required": ["path"]
+        })
+    }
+    fn is_destructive(&self) -> bool { true }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let path = args["path"].as_str().unwrap_or("");
+        let resolved = resolve(path, self.workspace.as_deref())
+            .map_err(|e| ZenClawError::ToolExecution { tool: "undo_edit".into(), message: e })?;
+        let fs = match self.fs.backend(&resolved) {
+            Ok(fs) => fs,
+            Err(e) => return Ok(format!("Error: {}", e)),
+        };
+
+        let previous = match fs.read(&backup_path(&resolved)).await {
+            Ok(content) => content,
+            Err(_) => return Ok(format!("Error: no backup of {} to restore", path)),
+        };
+        let current = fs.read(&resolved).await.ok();
+
+        if let Err(e) = fs.write(&resolved, &previous).await {
+            return Ok(format!("Error writing file: {}", e));
+        }
+        // The undone version becomes the backup, so undoing again redoes it
+        if let Some(current) = current
+            && let Err(message) = backup(fs, &resolved, &current).await
+        {
+            return Ok(format!("✅ Restored {} from its backup, but {}", path, message.trim_start_matches("Error: ")));
         }
+        Ok(format!("✅ Restored {} from its backup", path))
     }
 }
 
 // ─── ListDir ───────────────────────────────────────────────
 
+/// Depth of a recursive `list_dir` when the call doesn't say.
+const DEFAULT_TREE_DEPTH: usize = 3;
+
+/// Deepest recursive listing a call may ask for.
+const MAX_TREE_DEPTH: u64 = 10;
+
+/// Entries shown by a recursive `list_dir`; the rest are counted.
+const MAX_TREE_ENTRIES: usize = 300;
+
+/// Directories a recursive listing never descends into.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+/// `root` as an indented tree, `max_depth` levels deep and at most
+/// `max_entries` entries long, siblings sorted by name.
+fn tree(root: &Path, label: &str, max_depth: usize, respect_gitignore: bool, max_entries: usize) -> String {
+    let mut walker = WalkBuilder::new(root);
+    walker
+        .max_depth(Some(max_depth))
+        .hidden(false)
+        .parents(respect_gitignore)
+        .ignore(respect_gitignore)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .require_git(false) // a .gitignore counts even outside a git checkout
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|e| {
+            let is_dir = e.file_type().is_some_and(|t| t.is_dir());
+            e.depth() == 0 || !is_dir || !SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+        });
+
+    let mut lines = vec![format!("📁 {}/", label.trim_end_matches('/'))];
+    let mut more = 0;
+    for entry in walker.build().flatten().filter(|e| e.depth() > 0) {
+        if lines.len() > max_entries {
+            more += 1;
+            continue;
+        }
+        let indent = "  ".repeat(entry.depth());
+        let name = entry.file_name().to_string_lossy();
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            lines.push(format!("{}📁 {}/", indent, name));
+        } else {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            lines.push(format!("{}📄 {} ({})", indent, name, human_size(size)));
+        }
+    }
+    if more > 0 {
+        lines.push(format!("... {} more", more));
+    }
+    lines.join("\n")
+}
+
 pub struct ListDirTool {
     pub workspace: Option<PathBuf>,
+    fs: FsRouter,
 }
 
 impl ListDirTool {
-    pub fn new() -> Self { Self { workspace: None } }
+    pub fn new() -> Self { Self { workspace: None, fs: FsRouter::default() } }
     pub fn with_workspace(mut self, ws: &Path) -> Self {
         self.workspace = Some(ws.to_path_buf());
         self
     }
+    pub fn with_fs(mut self, fs: FsRouter) -> Self {
+        self.fs = fs;
+        self
+    }
 }
 
 impl Default for ListDirTool {
@@ -223,13 +613,17 @@ impl Default for ListDirTool {
 impl Tool for ListDirTool {
     fn name(&self) -> &str { "list_dir" }
     fn description(&self) -> &str {
-        "List the contents of a directory."
+        "List the contents of a directory. With recursive=true, show a local directory as an indented tree \
+         (skipping .git, node_modules and target, and honoring .gitignore) to map out a project in one call."
     }
     fn parameters(&self) -> Value {
         json!({
             "type": "object",
             "properties": {
-                "path": { "type": "string", "description": "The directory path to list" }
+                "path": { "type": "string", "description": "The directory path or remote URI to list" },
+                "recursive": { "type": "boolean", "description": "List subdirectories too, as a tree (default: false)" },
+                "max_depth": { "type": "integer", "description": "Levels to descend when recursive (1-10, default: 3)" },
+                "respect_gitignore": { "type": "boolean", "description": "Leave out files matched by .gitignore when recursive (default: true)" }
             },
             "required": ["path"]
         })
@@ -237,31 +631,49 @@ impl Tool for ListDirTool {
 
     async fn execute(&self, args: Value) -> Result<String> {
         let path = args["path"].as_str().unwrap_or(".");
-        let resolved = resolve_path(path, self.workspace.as_deref())
+        let resolved = resolve(path, self.workspace.as_deref())
             .map_err(|e| ZenClawError::ToolExecution { tool: "list_dir".into(), message: e })?;
+        let fs = match self.fs.backend(&resolved) {
+            Ok(fs) => fs,
+            Err(e) => return Ok(format!("Error: {}", e)),
+        };
+
+        if args["recursive"].as_bool().unwrap_or(false) {
+            if FsRouter::is_remote(&resolved) {
+                return Ok("Error: recursive listing only works on local directories".to_string());
+            }
+            let root = PathBuf::from(&resolved);
+            if !root.is_dir() {
+                return Ok(format!("Error reading directory: {} is not a directory", path));
+            }
+            let max_depth = args["max_depth"]
+                .as_u64()
+                .map(|d| d.clamp(1, MAX_TREE_DEPTH) as usize)
+                .unwrap_or(DEFAULT_TREE_DEPTH);
+            let respect_gitignore = args["respect_gitignore"].as_bool().unwrap_or(true);
+            let label = path.to_string();
+            // `ignore` is synchronous, so walk on a blocking thread
+            return Ok(tokio::task::spawn_blocking(move || {
+                tree(&root, &label, max_depth, respect_gitignore, MAX_TREE_ENTRIES)
+            })
+            .await
+            .unwrap_or_else(|e| format!("Error reading directory: {}", e)));
+        }
 
-        let mut entries = match fs::read_dir(&resolved).await {
-            Ok(rd) => rd,
+        let entries = match fs.list(&resolved).await {
+            Ok(entries) => entries,
             Err(e) => return Ok(format!("Error reading directory: {}", e)),
         };
 
         let mut items = Vec::new();
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            let meta = entry.metadata().await.ok();
-            let is_dir = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
-            let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
-            let prefix = if is_dir { "📁" } else { "📄" };
-            let size_str = if is_dir {
+        for entry in entries {
+            let prefix = if entry.is_dir { "📁" } else { "📄" };
+            let size_str = if entry.is_dir {
                 String::new()
             } else {
-                format!(" ({})", human_size(size))
+                format!(" ({})", human_size(entry.size))
             };
-            items.push(format!(
-                "{} {}{}",
-                prefix,
-                entry.file_name().to_string_lossy(),
-                size_str
-            ));
+            items.push(format!("{} {}{}", prefix, entry.name, size_str));
         }
 
         items.sort();
@@ -284,3 +696,224 @@ fn human_size(bytes: u64) -> String {
     }
     format!("{:.1}TB", size)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_edit_returns_a_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        let lines: Vec<String> = (1..=20).map(|i| format!("line {}", i)).collect();
+        std::fs::write(&file, lines.join("\n") + "\n").unwrap();
+        let args = json!({ "path": file.to_string_lossy(), "old_text": "line 10\n", "new_text": "line ten\n" });
+
+        let tool = EditFileTool::new();
+        let preview = tool.preview(&args).await.unwrap();
+        assert!(std::fs::read_to_string(&file).unwrap().contains("line 10\n"), "preview wrote the file");
+
+        let result = tool.execute(args).await.unwrap();
+        assert!(result.starts_with("✅ Edited"));
+        assert!(result.ends_with(&preview));
+        assert!(preview.contains("@@ -7,7 +7,7 @@"));
+        assert!(preview.contains("\n-line 10\n+line ten\n"));
+        // Only the hunk and its context, not the whole file
+        assert!(!preview.contains("line 1\n") && !preview.contains("line 20"));
+    }
+
+    #[tokio::test]
+    async fn test_undo_restores_the_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        std::fs::write(&file, "port = 80\n").unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let edit = json!({ "path": path, "old_text": "80", "new_text": "8080" });
+        EditFileTool::new().execute(edit).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "port = 8080\n");
+        assert_eq!(std::fs::read_to_string(format!("{}.bak", path)).unwrap(), "port = 80\n");
+
+        let undo = UndoEditTool::new();
+        assert!(undo.execute(json!({ "path": path })).await.unwrap().starts_with("✅"));
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "port = 80\n");
+
+        // Undoing again redoes the edit
+        undo.execute(json!({ "path": path })).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "port = 8080\n");
+
+        WriteFileTool::new()
+            .execute(json!({ "path": path, "content": "port = 443\n" }))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(format!("{}.bak", path)).unwrap(), "port = 8080\n");
+
+        let missing = dir.path().join("new.txt").to_string_lossy().to_string();
+        assert!(undo.execute(json!({ "path": missing })).await.unwrap().starts_with("Error"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_goes_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("real.txt");
+        let link = dir.path().join("link.txt");
+        std::fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        super::super::remote_fs::LocalFs
+            .write(&link.to_string_lossy(), "new")
+            .await
+            .unwrap();
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+        // No temp files left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_workspace_rejects_traversal() {
+        let outer = tempfile::tempdir().unwrap();
+        let ws = outer.path().join("ws");
+        std::fs::create_dir_all(ws.join("src")).unwrap();
+        std::fs::write(outer.path().join("secret.txt"), "key").unwrap();
+        let root = ws.canonicalize().unwrap();
+
+        // Relative paths start at the root; `..` may move around inside it
+        assert_eq!(resolve_path("src/../notes.md", Some(&ws)).unwrap(), root.join("notes.md"));
+        assert_eq!(resolve_path(".", Some(&ws)).unwrap(), root);
+
+        for escape in [
+            "../secret.txt",
+            "src/../../secret.txt",
+            "new/../../secret.txt",
+            "/etc/passwd",
+        ] {
+            assert!(resolve_path(escape, Some(&ws)).is_err(), "{} escaped", escape);
+        }
+        let absolute = outer.path().join("ws/../secret.txt");
+        assert!(resolve_path(&absolute.to_string_lossy(), Some(&ws)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_workspace_rejects_symlink_escapes() {
+        let outer = tempfile::tempdir().unwrap();
+        let ws = outer.path().join("ws");
+        std::fs::create_dir_all(&ws).unwrap();
+        std::fs::write(outer.path().join("secret.txt"), "key").unwrap();
+        std::os::unix::fs::symlink(outer.path(), ws.join("up")).unwrap();
+        std::os::unix::fs::symlink(outer.path().join("missing.txt"), ws.join("dangling")).unwrap();
+        let writer = WriteFileTool::new();
+
+        let read = |path: &str| {
+            let args = json!({ "path": path });
+            zenclaw_core::tool::with_workspace(Some(ws.clone()), async move { ReadFileTool::new().execute(args).await })
+        };
+        assert!(read("up/secret.txt").await.is_err());
+        assert!(read("up/new/../secret.txt").await.is_err());
+
+        let write = zenclaw_core::tool::with_workspace(
+            Some(ws.clone()),
+            writer.execute(json!({ "path": "dangling", "content": "x" })),
+        );
+        assert!(write.await.is_err());
+        assert!(!outer.path().join("missing.txt").exists());
+
+        // Inside the workspace everything still works
+        let write = zenclaw_core::tool::with_workspace(
+            Some(ws.clone()),
+            writer.execute(json!({ "path": "notes/today.md", "content": "hi" })),
+        );
+        assert!(write.await.unwrap().starts_with("✅"));
+        assert_eq!(std::fs::read_to_string(ws.join("notes/today.md")).unwrap(), "hi");
+    }
+
+    #[test]
+    fn test_read_slice() {
+        let content: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+
+        assert_eq!(read_slice(&content, None, None, 1000).unwrap(), content);
+        assert_eq!(
+            read_slice(&content, Some(3), Some(4), 1000).unwrap(),
+            "line 3\nline 4\n\n[Lines 3-4 of 10.]"
+        );
+        assert_eq!(
+            read_slice(&content, Some(9), None, 1000).unwrap(),
+            "line 9\nline 10\n\n[Lines 9-10 of 10.]"
+        );
+        assert!(read_slice(&content, Some(11), None, 1000).is_err());
+        assert!(read_slice(&content, Some(5), Some(4), 1000).is_err());
+
+        // Too big for max_bytes: cut at a line boundary, with where to go on
+        let cut = read_slice(&content, None, None, 20).unwrap();
+        assert_eq!(
+            cut,
+            "line 1\nline 2\n\n[Truncated at 20 bytes: lines 1-2 of 10 shown. Continue with start_line=3.]"
+        );
+        assert!(read_slice("é".repeat(50).as_str(), None, None, 5).unwrap().starts_with("éé\n"));
+    }
+
+    #[tokio::test]
+    async fn test_read_file_refuses_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("logo.png");
+        std::fs::write(&bin, [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff]).unwrap();
+        let out = ReadFileTool::new()
+            .execute(json!({ "path": bin.to_string_lossy() }))
+            .await
+            .unwrap();
+        assert!(out.starts_with("Error") && out.contains("binary"), "{}", out);
+
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "a\nb\nc\n").unwrap();
+        let out = ReadFileTool::new()
+            .execute(json!({ "path": text.to_string_lossy(), "start_line": 2, "end_line": 2 }))
+            .await
+            .unwrap();
+        assert_eq!(out, "b\n\n[Lines 2-2 of 3.]");
+    }
+
+    #[test]
+    fn test_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/main.rs",
+            "src/tools/mod.rs",
+            "src/tools/deep/er/still.rs",
+            "target/debug/app",
+            "node_modules/left-pad/index.js",
+            "dist/bundle.js",
+            "README.md",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "dist/\n").unwrap();
+
+        let listing = tree(root, "proj", 3, true, 100);
+        assert_eq!(
+            listing,
+            "📁 proj/\n  📄 .gitignore (6.0B)\n  📄 README.md (1.0B)\n  📁 src/\n    📄 main.rs (1.0B)\n    📁 tools/\n      📁 deep/\n      📄 mod.rs (1.0B)"
+        );
+
+        let all = tree(root, "proj", 10, false, 100);
+        assert!(all.contains("📁 dist/") && all.contains("still.rs"));
+        assert!(!all.contains("target") && !all.contains("node_modules"));
+
+        let capped = tree(root, "proj", 10, false, 3);
+        assert_eq!(capped.lines().count(), 5);
+        assert!(capped.ends_with("... 8 more"), "{}", capped);
+    }
+
+    #[test]
+    fn test_long_diff_is_cut() {
+        let old: String = (0..500).map(|i| format!("{}\n", i)).collect();
+        let new: String = (0..500).map(|i| format!("{}!\n", i)).collect();
+        let diff = unified_diff("big.txt", &old, &new);
+        assert_eq!(diff.lines().count(), MAX_DIFF_LINES + 1);
+        assert!(diff.ends_with(&format!("diff cut at {} of 1003 lines\n", MAX_DIFF_LINES)));
+    }
+}
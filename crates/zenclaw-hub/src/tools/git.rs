@@ -0,0 +1,263 @@
+//! Git tool — inspect repository state (status, diff, log, blame, show).
+//!
+//! Shells out to the `git` binary with a fixed argument list per action, so
+//! only read-only commands can run: nothing here commits, checks out or
+//! pushes, and user arguments can't smuggle in extra options.
+//!
+//! Inside a workspace (see [`current_workspace`]) git runs there instead of
+//! in `working_dir`, and can't discover a repository above it.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::process::Stdio;
+use tokio::process::Command;
+
+use zenclaw_core::error::Result;
+use zenclaw_core::tool::{current_workspace, Tool};
+
+/// Read-only git commands.
+pub struct GitTool {
+    /// Repository directory (None = current directory).
+    pub working_dir: Option<String>,
+    /// Maximum output length in bytes.
+    pub max_output: usize,
+}
+
+impl GitTool {
+    pub fn new() -> Self {
+        Self {
+            working_dir: None,
+            max_output: 10_000,
+        }
+    }
+
+    pub fn with_working_dir(mut self, dir: &str) -> Self {
+        self.working_dir = Some(dir.to_string());
+        self
+    }
+
+    /// Run `git <args>` in the repository; Err holds the message for the model.
+    async fn git(&self, args: &[&str]) -> std::result::Result<String, String> {
+        let mut cmd = Command::new("git");
+        cmd.arg("--no-pager")
+            .args(args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        match current_workspace() {
+            Some(ws) => {
+                cmd.current_dir(&ws).env_remove("GIT_DIR").env_remove("GIT_WORK_TREE");
+                if let Some(parent) = ws.parent() {
+                    cmd.env("GIT_CEILING_DIRECTORIES", parent);
+                }
+            }
+            None => {
+                if let Some(dir) = &self.working_dir {
+                    cmd.current_dir(dir);
+                }
+            }
+        }
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| format!("Error: failed to run git: {}", e))?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(format!(
+                "Error: git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn truncate(&self, s: &str) -> String {
+        if s.len() <= self.max_output {
+            return s.to_string();
+        }
+        let mut cut = self.max_output;
+        while !s.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        format!("{}... [truncated, {} total bytes]", &s[..cut], s.len())
+    }
+}
+
+impl Default for GitTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reject arguments git would read as options (e.g. `--output=<file>`).
+fn check_arg(arg: &str) -> std::result::Result<(), String> {
+    if arg.starts_with('-') {
+        return Err(format!("Error: '{}' is not allowed — pass a path or ref, not an option.", arg));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        "Inspect the git repository in the working directory (read-only). Actions: \
+        'status' (branch and changed files), \
+        'diff' (unstaged changes; args: optional path, prefix with '--staged ' for staged changes), \
+        'log' (recent commits; args: count, default 20), \
+        'blame' (who changed each line; args: file), \
+        'show' (a commit's message and patch; args: ref, default HEAD). \
+        Cannot commit, push or change anything."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["status", "diff", "log", "blame", "show"],
+                    "description": "Git command to run"
+                },
+                "args": {
+                    "type": "string",
+                    "description": "Argument for the action: path (diff, blame), count (log) or ref (show)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let action = args["action"].as_str().unwrap_or("");
+        let arg = args["args"].as_str().map(str::trim).unwrap_or("");
+
+        match self.git(&["rev-parse", "--is-inside-work-tree"]).await {
+            Ok(out) if out.trim() == "true" => {}
+            _ => return Ok("Error: not a git repository (or inside .git).".into()),
+        }
+
+        let result = match action {
+            "status" => self.git(&["status", "--short", "--branch"]).await,
+            "diff" => {
+                let (staged, path) = match arg.strip_prefix("--staged") {
+                    Some(rest) => (true, rest.trim()),
+                    None => (false, arg),
+                };
+                if let Err(e) = check_arg(path) {
+                    return Ok(e);
+                }
+                let mut cmd = vec!["diff", "--no-ext-diff", "--no-textconv"];
+                if staged {
+                    cmd.push("--staged");
+                }
+                if !path.is_empty() {
+                    cmd.extend(["--", path]);
+                }
+                self.git(&cmd).await
+            }
+            "log" => {
+                let count = arg.parse::<usize>().unwrap_or(20).clamp(1, 200).to_string();
+                self.git(&["log", "--oneline", "--decorate", "-n", &count]).await
+            }
+            "blame" => {
+                if arg.is_empty() {
+                    return Ok("Error: 'args' must name the file to blame.".into());
+                }
+                if let Err(e) = check_arg(arg) {
+                    return Ok(e);
+                }
+                self.git(&["blame", "--date=short", "--", arg]).await
+            }
+            "show" => {
+                let rev = if arg.is_empty() { "HEAD" } else { arg };
+                if let Err(e) = check_arg(rev) {
+                    return Ok(e);
+                }
+                self.git(&["show", "--stat", "--patch", "--no-ext-diff", "--no-textconv", rev, "--"])
+                    .await
+            }
+            other => {
+                return Ok(format!(
+                    "Error: unknown action '{}'. Use status, diff, log, blame or show.",
+                    other
+                ));
+            }
+        };
+
+        Ok(match result {
+            Ok(out) if out.trim().is_empty() => format!("git {}: no output (nothing to show).", action),
+            Ok(out) => self.truncate(&out),
+            Err(e) => self.truncate(&e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use zenclaw_core::tool::with_workspace;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    /// A repository with one commit and an uncommitted change.
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        run(dir.path(), &["init", "-q", "-b", "main"]);
+        std::fs::write(dir.path().join("README.md"), "hello\n").unwrap();
+        run(dir.path(), &["add", "README.md"]);
+        run(dir.path(), &["commit", "-q", "-m", "Add readme"]);
+        std::fs::write(dir.path().join("README.md"), "hello world\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_check_arg_rejects_options() {
+        assert!(check_arg("src/main.rs").is_ok());
+        assert!(check_arg("HEAD~1").is_ok());
+        for arg in ["--output=/tmp/x", "-p", "--exec=sh"] {
+            assert!(check_arg(arg).is_err(), "{}", arg);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_and_log() {
+        let dir = repo();
+        let tool = GitTool::new().with_working_dir(&dir.path().to_string_lossy());
+
+        let status = tool.execute(json!({ "action": "status" })).await.unwrap();
+        assert!(status.starts_with("## main") && status.contains(" M README.md"), "{}", status);
+        let log = tool.execute(json!({ "action": "log", "args": "5" })).await.unwrap();
+        assert!(log.trim_end().ends_with("Add readme"), "{}", log);
+        let show = tool.execute(json!({ "action": "show", "args": "--output=/tmp/pwned" })).await.unwrap();
+        assert!(show.starts_with("Error: '--output=/tmp/pwned' is not allowed"), "{}", show);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_replaces_the_working_dir() {
+        let dir = repo();
+        let ws = dir.path().join("sessions/telegram_42");
+        std::fs::create_dir_all(&ws).unwrap();
+        let tool = GitTool::new().with_working_dir(&dir.path().to_string_lossy());
+
+        // The repository around the workspace is out of reach
+        let out = with_workspace(Some(ws), tool.execute(json!({ "action": "log" }))).await.unwrap();
+        assert!(out.starts_with("Error: not a git repository"), "{}", out);
+    }
+}
@@ -0,0 +1,209 @@
+//! Grep tool — exact regex search across files, like ripgrep.
+//!
+//! Complements `code_search`: this one reports every match as
+//! `file:line: text`, honours `.gitignore` and skips binary files. Inside a
+//! workspace, `path` must stay within it.
+
+use async_trait::async_trait;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use regex::RegexBuilder;
+use serde_json::{json, Value};
+
+use zenclaw_core::error::Result;
+use zenclaw_core::tool::Tool;
+
+use super::filesystem::resolve_local;
+
+/// Bytes inspected for a NUL byte to decide whether a file is binary.
+const BINARY_SNIFF_LEN: usize = 8192;
+/// Matched lines longer than this are cut to save context.
+const MAX_LINE_LEN: usize = 300;
+
+pub struct GrepTool;
+
+impl GrepTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for GrepTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for GrepTool {
+    fn name(&self) -> &str {
+        "grep"
+    }
+
+    fn description(&self) -> &str {
+        "Search files for a regular expression and list every matching line as 'file:line: text'. \
+        Respects .gitignore and skips binary files. \
+        Use this when you know the exact symbol or text; use code_search for a broader overview."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression (Rust regex syntax), e.g. 'fn\\s+build_agent' or 'TODO|FIXME'."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File or directory to search. Default: current directory."
+                },
+                "glob": {
+                    "type": "string",
+                    "description": "Only search files matching this glob, e.g. '*.rs' or 'src/**/*.ts'."
+                },
+                "max_matches": {
+                    "type": "integer",
+                    "description": "Stop after this many matching lines. Default: 100."
+                },
+                "case_insensitive": {
+                    "type": "boolean",
+                    "description": "Ignore case. Default: false."
+                }
+            },
+            "required": ["pattern"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let pattern = args["pattern"].as_str().unwrap_or("");
+        if pattern.is_empty() {
+            return Ok("Error: 'pattern' parameter is required.".into());
+        }
+
+        let path = match resolve_local(args["path"].as_str().unwrap_or(".")) {
+            Ok(path) => path,
+            Err(e) => return Ok(format!("Error: {}", e)),
+        };
+        let glob = args["glob"].as_str().filter(|g| !g.is_empty());
+        let max_matches = args["max_matches"].as_u64().unwrap_or(100).max(1) as usize;
+        let case_insensitive = args["case_insensitive"].as_bool().unwrap_or(false);
+
+        let re = match RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+        {
+            Ok(re) => re,
+            Err(e) => {
+                return Ok(format!(
+                    "Error: invalid regex '{}': {}\nEscape special characters (e.g. '\\(') to match them literally.",
+                    pattern, e
+                ));
+            }
+        };
+
+        if !path.exists() {
+            return Ok(format!("Error: path not found: {}", path.display()));
+        }
+
+        let mut walker = WalkBuilder::new(&path);
+        walker
+            .git_ignore(true)
+            .require_git(false) // a .gitignore counts even outside a git checkout
+            .sort_by_file_name(|a, b| a.cmp(b));
+        if let Some(glob) = glob {
+            let overrides = OverrideBuilder::new(&path)
+                .add(glob)
+                .and_then(|b| b.build());
+            match overrides {
+                Ok(o) => {
+                    walker.overrides(o);
+                }
+                Err(e) => return Ok(format!("Error: invalid glob '{}': {}", glob, e)),
+            }
+        }
+
+        // `ignore` is synchronous, so walk on a blocking thread
+        let result = tokio::task::spawn_blocking(move || {
+            let mut out = Vec::new();
+            let mut files = 0;
+
+            'walk: for entry in walker.build().flatten() {
+                if !entry.file_type().is_some_and(|t| t.is_file()) {
+                    continue;
+                }
+                let Ok(bytes) = std::fs::read(entry.path()) else { continue };
+                if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+                    continue;
+                }
+
+                let content = String::from_utf8_lossy(&bytes);
+                let mut hit = false;
+                for (idx, line) in content.lines().enumerate() {
+                    if !re.is_match(line) {
+                        continue;
+                    }
+                    hit = true;
+                    let text = match line.char_indices().nth(MAX_LINE_LEN) {
+                        Some((cut, _)) => format!("{}…", &line[..cut]),
+                        None => line.to_string(),
+                    };
+                    out.push(format!("{}:{}: {}", entry.path().display(), idx + 1, text));
+                    if out.len() >= max_matches {
+                        files += 1;
+                        break 'walk;
+                    }
+                }
+                files += usize::from(hit);
+            }
+            (out, files)
+        })
+        .await;
+
+        let Ok((matches, files)) = result else {
+            return Ok("Error: search thread failed".into());
+        };
+
+        if matches.is_empty() {
+            return Ok(format!("No matches for '{}'.", pattern));
+        }
+        let mut text = matches.join("\n");
+        if matches.len() >= max_matches {
+            text.push_str(&format!(
+                "\n\n⚠️ Stopped after {} matches — narrow the pattern, path or glob to see more.",
+                max_matches
+            ));
+        } else {
+            text.push_str(&format!("\n\n{} matches in {} files.", matches.len(), files));
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::tool::with_workspace;
+
+    #[tokio::test]
+    async fn test_grep_stays_in_the_workspace() {
+        let outer = tempfile::tempdir().unwrap();
+        let ws = outer.path().join("ws");
+        std::fs::create_dir_all(&ws).unwrap();
+        std::fs::write(ws.join("notes.md"), "api_key goes in the config\n").unwrap();
+        std::fs::write(outer.path().join("config.toml"), "api_key = \"sk-secret\"\n").unwrap();
+
+        let grep = |path: String| {
+            let args = json!({ "pattern": "api_key", "path": path });
+            with_workspace(Some(ws.clone()), async move { GrepTool::new().execute(args).await.unwrap() })
+        };
+        let inside = grep(".".into()).await;
+        assert!(inside.contains("notes.md:1: api_key goes in the config"), "{}", inside);
+        assert!(!inside.contains("sk-secret"));
+
+        for path in ["..".to_string(), outer.path().to_string_lossy().to_string()] {
+            let out = grep(path.clone()).await;
+            assert!(out.starts_with("Error: Path") && out.contains("outside workspace"), "{}: {}", path, out);
+        }
+    }
+}
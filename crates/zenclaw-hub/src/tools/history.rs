@@ -174,6 +174,7 @@ impl Tool for HistoryTool {
                             let emoji = match role.as_str() {
                                 "user" => "👤",
                                 "assistant" => "🤖",
+                                "summary" => "🧾",
                                 _ => "📝",
                             };
 
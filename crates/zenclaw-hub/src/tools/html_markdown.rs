@@ -0,0 +1,668 @@
+//! HTML → Markdown for fetched pages.
+//!
+//! A small, dependency-free converter. Like readability tools, it keeps only
+//! the main content block (the container with the most paragraph text) and
+//! drops navigation, scripts and forms. Headings, links, lists, emphasis,
+//! code and tables survive as Markdown.
+
+use reqwest::Url;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    Close(String),
+    Text(String),
+}
+
+/// Elements dropped with everything inside them.
+const SKIPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "head", "nav", "aside",
+    "footer", "form", "button", "select", "textarea", "title",
+];
+
+/// Elements that never have a closing tag.
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Blocks separated by a blank line.
+const BLOCKS: &[&str] = &[
+    "p", "div", "section", "article", "main", "header", "figure", "figcaption", "dl", "dt", "dd",
+    "address", "details", "summary", "table",
+];
+
+/// Class/id words that mark a content container, and ones that rule it out.
+const CONTENT_HINTS: &[&str] = &["content", "article", "post", "entry", "story", "main", "body"];
+const NOISE_HINTS: &[&str] = &["comment", "sidebar", "nav", "footer", "related", "share", "promo", "menu"];
+
+/// Below this much paragraph text, a candidate isn't trusted as the main content.
+const MIN_CONTENT_LEN: usize = 200;
+
+/// Convert an HTML page to Markdown. Relative links are resolved against `base_url`.
+pub(crate) fn html_to_markdown(html: &str, base_url: &str) -> String {
+    let tokens = tokenize(html);
+    let (start, end, whole_page) = main_content(&tokens);
+    let base = Url::parse(base_url).ok();
+
+    let mut md = Writer::new(base);
+    let mut i = start;
+    while i < end {
+        match &tokens[i] {
+            Token::Open {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                // Page headers are noise, but an article's own header holds its title
+                let skipped = SKIPPED.contains(&name.as_str()) || (whole_page && name == "header") || is_hidden(attrs);
+                if skipped && !self_closing && !VOID.contains(&name.as_str()) {
+                    i = matching_close(&tokens, i) + 1;
+                    continue;
+                }
+                if !skipped {
+                    md.open(name, attrs);
+                }
+            }
+            Token::Close(name) => md.close(name),
+            Token::Text(text) => md.text(text),
+        }
+        i += 1;
+    }
+
+    let body = md.finish();
+    match page_title(&tokens) {
+        Some(title) if !body.starts_with("# ") => format!("# {}\n\n{}", title, body),
+        _ => body,
+    }
+}
+
+// ─── Tokenizer ──────────────────────────────────────────
+
+fn tokenize(html: &str) -> Vec<Token> {
+    // ASCII lowercasing keeps byte offsets, so indices work on both strings
+    let lower = html.to_ascii_lowercase();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(lt) = html[pos..].find('<').map(|i| pos + i) else {
+            tokens.push(Token::Text(html[pos..].to_string()));
+            break;
+        };
+        if lt > pos {
+            tokens.push(Token::Text(html[pos..lt].to_string()));
+        }
+
+        let rest = &html[lt..];
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(html.len(), |i| lt + i + 3);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = rest.find('>').map_or(html.len(), |i| lt + i + 1);
+            continue;
+        }
+
+        let closing = rest.starts_with("</");
+        let name_start = lt + if closing { 2 } else { 1 };
+        let name_len = html[name_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(html.len() - name_start);
+        if name_len == 0 {
+            // A stray '<' in text
+            tokens.push(Token::Text("<".into()));
+            pos = lt + 1;
+            continue;
+        }
+        let name = lower[name_start..name_start + name_len].to_string();
+        let Some(gt) = tag_end(html, name_start + name_len) else { break };
+        pos = gt + 1;
+
+        if closing {
+            tokens.push(Token::Close(name));
+            continue;
+        }
+
+        let inner = html[name_start + name_len..gt].trim_end();
+        let self_closing = inner.ends_with('/');
+        let attrs = parse_attrs(inner.trim_end_matches('/'));
+
+        // Raw text: the content may contain '<' that isn't markup
+        if matches!(name.as_str(), "script" | "style" | "textarea" | "title") {
+            let stop = lower[pos..].find(&format!("</{}", name)).map_or(html.len(), |i| pos + i);
+            tokens.push(Token::Open {
+                name: name.clone(),
+                attrs,
+                self_closing: false,
+            });
+            tokens.push(Token::Text(html[pos..stop].to_string()));
+            tokens.push(Token::Close(name));
+            pos = lower[stop..].find('>').map_or(html.len(), |i| stop + i + 1);
+            continue;
+        }
+
+        tokens.push(Token::Open {
+            name,
+            attrs,
+            self_closing,
+        });
+    }
+    tokens
+}
+
+/// Index of the `>` closing the tag, skipping quoted attribute values.
+fn tag_end(html: &str, from: usize) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in html[from..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(from + i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut attrs = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        while i < chars.len() && (chars[i].is_whitespace() || chars[i] == '/') {
+            i += 1;
+        }
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '=' {
+            i += 1;
+        }
+        if start == i {
+            i += 1;
+            continue;
+        }
+        let name = chars[start..i].iter().collect::<String>().to_ascii_lowercase();
+
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if i < chars.len() && chars[i] == '=' {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let quote = chars[i];
+                i += 1;
+                let value_start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                value = chars[value_start..i].iter().collect();
+                i += 1;
+            } else {
+                let value_start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                value = chars[value_start..i].iter().collect();
+            }
+        }
+        attrs.push((name, decode_entities(&value)));
+    }
+    attrs
+}
+
+/// Decode named (common ones) and numeric character references.
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest[1..].find(';').filter(|&end| end <= 10).and_then(|end| {
+            let c = match &rest[1..1 + end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                "lsquo" => Some('‘'),
+                "rsquo" => Some('’'),
+                "ldquo" => Some('“'),
+                "rdquo" => Some('”'),
+                "copy" => Some('©'),
+                entity => entity.strip_prefix('#').and_then(|num| {
+                    match num.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => num.parse().ok(),
+                    }
+                    .and_then(char::from_u32)
+                }),
+            };
+            c.map(|c| (c, end + 2))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// ─── Content extraction ─────────────────────────────────
+
+fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+fn is_hidden(attrs: &[(String, String)]) -> bool {
+    attr(attrs, "hidden").is_some()
+        || attr(attrs, "aria-hidden") == Some("true")
+        || attr(attrs, "style").is_some_and(|s| s.replace(' ', "").contains("display:none"))
+}
+
+/// Index of the token closing the element opened at `open` (or the end).
+fn matching_close(tokens: &[Token], open: usize) -> usize {
+    let Token::Open { name, .. } = &tokens[open] else { return open };
+    let mut depth = 0;
+    for (j, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Open {
+                name: n,
+                self_closing: false,
+                ..
+            } if n == name => depth += 1,
+            Token::Close(n) if n == name => {
+                depth -= 1;
+                if depth == 0 {
+                    return j;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+fn is_candidate(name: &str, attrs: &[(String, String)]) -> bool {
+    if matches!(name, "article" | "main") || attr(attrs, "role") == Some("main") {
+        return true;
+    }
+    if !matches!(name, "div" | "section") {
+        return false;
+    }
+    let hints = format!(
+        "{} {}",
+        attr(attrs, "id").unwrap_or(""),
+        attr(attrs, "class").unwrap_or("")
+    )
+    .to_ascii_lowercase();
+    CONTENT_HINTS.iter().any(|h| hints.contains(h)) && !NOISE_HINTS.iter().any(|h| hints.contains(h))
+}
+
+/// Characters of text inside `<p>` elements.
+fn paragraph_text(tokens: &[Token]) -> usize {
+    let mut depth = 0usize;
+    let mut total = 0;
+    for token in tokens {
+        match token {
+            Token::Open { name, .. } if name == "p" => depth += 1,
+            Token::Close(name) if name == "p" => depth = depth.saturating_sub(1),
+            Token::Text(text) if depth > 0 => total += text.trim().len(),
+            _ => {}
+        }
+    }
+    total
+}
+
+/// Token range of the main content; the flag is set when no container
+/// stood out and the whole body is used.
+fn main_content(tokens: &[Token]) -> (usize, usize, bool) {
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let Token::Open {
+            name,
+            attrs,
+            self_closing: false,
+        } = token
+        else {
+            continue;
+        };
+        if !is_candidate(name, attrs) {
+            continue;
+        }
+        let close = matching_close(tokens, i);
+        let score = paragraph_text(&tokens[i + 1..close]);
+        if score >= MIN_CONTENT_LEN && best.is_none_or(|(s, _, _)| score > s) {
+            best = Some((score, i + 1, close));
+        }
+    }
+    if let Some((_, start, end)) = best {
+        return (start, end, false);
+    }
+
+    let body = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Open { name, .. } if name == "body"));
+    match body {
+        Some(i) => (i + 1, matching_close(tokens, i), true),
+        None => (0, tokens.len(), true),
+    }
+}
+
+fn page_title(tokens: &[Token]) -> Option<String> {
+    let i = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Open { name, .. } if name == "title"))?;
+    match tokens.get(i + 1) {
+        Some(Token::Text(text)) => {
+            let title = collapse_whitespace(&decode_entities(text));
+            (!title.is_empty()).then_some(title)
+        }
+        _ => None,
+    }
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ─── Markdown writer ────────────────────────────────────
+
+struct Writer {
+    base: Option<Url>,
+    /// Output buffers; each open `<a>` collects its text in a new one.
+    buffers: Vec<String>,
+    /// Resolved href of each open `<a>`.
+    links: Vec<Option<String>>,
+    /// Open lists: (ordered, items so far).
+    lists: Vec<(bool, usize)>,
+    pre: usize,
+    /// Header cells in the current table row, and whether the header separator was written.
+    row_headers: usize,
+    table_header_done: bool,
+}
+
+impl Writer {
+    fn new(base: Option<Url>) -> Self {
+        Self {
+            base,
+            buffers: vec![String::new()],
+            links: Vec::new(),
+            lists: Vec::new(),
+            pre: 0,
+            row_headers: 0,
+            table_header_done: false,
+        }
+    }
+
+    fn buf(&mut self) -> &mut String {
+        self.buffers.last_mut().expect("root buffer")
+    }
+
+    fn push(&mut self, s: &str) {
+        self.buf().push_str(s);
+    }
+
+    /// Start a new line unless at the start of one.
+    fn line(&mut self) {
+        let buf = self.buf();
+        if !buf.is_empty() && !buf.ends_with('\n') {
+            buf.push('\n');
+        }
+    }
+
+    /// Start a new paragraph (blank line before).
+    fn block(&mut self) {
+        let buf = self.buf();
+        if buf.is_empty() || buf.ends_with("\n\n") {
+            return;
+        }
+        buf.push_str(if buf.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+        match &self.base {
+            Some(base) => base.join(href).ok().map(|u| u.to_string()),
+            None => Some(href.to_string()),
+        }
+    }
+
+    fn open(&mut self, name: &str, attrs: &[(String, String)]) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block();
+                let level = name[1..].parse().unwrap_or(1);
+                self.push(&format!("{} ", "#".repeat(level)));
+            }
+            "br" if self.pre > 0 => self.push("\n"),
+            "br" => self.line(),
+            "hr" => {
+                self.block();
+                self.push("---");
+                self.block();
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.block();
+                } else {
+                    self.line();
+                }
+                self.lists.push((name == "ol", 0));
+            }
+            "li" => {
+                self.line();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some((true, n)) => {
+                        *n += 1;
+                        format!("{}. ", n)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.push(&format!("{}{}", indent, marker));
+            }
+            "a" => {
+                let href = attr(attrs, "href").and_then(|h| self.resolve(h));
+                self.links.push(href);
+                self.buffers.push(String::new());
+            }
+            "strong" | "b" => self.push("**"),
+            "em" | "i" => self.push("*"),
+            "code" if self.pre == 0 => self.push("`"),
+            "pre" => {
+                self.block();
+                self.push("```\n");
+                self.pre += 1;
+            }
+            "blockquote" => {
+                self.block();
+                self.push("> ");
+            }
+            "img" => {
+                let alt = attr(attrs, "alt").map(collapse_whitespace).unwrap_or_default();
+                let src = attr(attrs, "src").and_then(|s| self.resolve(s));
+                if let (false, Some(src)) = (alt.is_empty(), src) {
+                    self.push(&format!("![{}]({})", alt, src));
+                }
+            }
+            "tr" => {
+                self.line();
+                self.row_headers = 0;
+            }
+            "th" | "td" => {
+                if name == "th" {
+                    self.row_headers += 1;
+                }
+                self.push("| ");
+            }
+            "table" => {
+                self.block();
+                self.table_header_done = false;
+            }
+            _ if BLOCKS.contains(&name) => self.block(),
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => self.block(),
+            "ul" | "ol" => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block();
+                } else {
+                    self.line();
+                }
+            }
+            "a" if self.buffers.len() > 1 => {
+                let text = collapse_whitespace(&self.buffers.pop().unwrap_or_default());
+                let href = self.links.pop().flatten();
+                match href {
+                    Some(href) if !text.is_empty() => self.push(&format!("[{}]({})", text, href)),
+                    _ => self.push(&text),
+                }
+            }
+            "strong" | "b" => self.push("**"),
+            "em" | "i" => self.push("*"),
+            "code" if self.pre == 0 => self.push("`"),
+            "pre" if self.pre > 0 => {
+                self.pre -= 1;
+                self.line();
+                self.push("```");
+                self.block();
+            }
+            "th" | "td" => self.push(" "),
+            "tr" => {
+                self.push("|");
+                if self.row_headers > 0 && !self.table_header_done {
+                    let separator = "| --- ".repeat(self.row_headers);
+                    self.push(&format!("\n{}|", separator));
+                    self.table_header_done = true;
+                }
+            }
+            "blockquote" | "table" => self.block(),
+            _ if BLOCKS.contains(&name) => self.block(),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, raw: &str) {
+        let text = decode_entities(raw);
+        if self.pre > 0 {
+            self.push(&text);
+            return;
+        }
+        let collapsed = collapse_whitespace(&text);
+        if collapsed.is_empty() {
+            // Whitespace between inline elements still separates words
+            let buf = self.buf();
+            if !raw.is_empty() && !buf.is_empty() && !buf.ends_with([' ', '\n']) {
+                buf.push(' ');
+            }
+            return;
+        }
+        let buf = self.buf();
+        let leading = text.starts_with(char::is_whitespace) && !buf.is_empty() && !buf.ends_with([' ', '\n']);
+        if leading {
+            buf.push(' ');
+        }
+        buf.push_str(&collapsed);
+        if text.ends_with(char::is_whitespace) {
+            buf.push(' ');
+        }
+    }
+
+    fn finish(mut self) -> String {
+        // Unclosed links: keep their text
+        while self.buffers.len() > 1 {
+            let text = self.buffers.pop().unwrap_or_default();
+            self.push(&text);
+        }
+
+        let mut out = String::new();
+        let mut blank = 0;
+        for line in self.buffers[0].lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank += 1;
+                if blank > 1 {
+                    continue;
+                }
+            } else {
+                blank = 0;
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_main_article() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Ignored &amp; Co</title><script>var x = "<p>not text</p>";</script></head>
+<body>
+  <nav><a href="/">Menu</a> <a href="/about">About</a></nav>
+  <article>
+    <h1>Hello   World</h1>
+    <p>ZenClaw is a <strong>small</strong> agent. Read the <a href="/docs/intro">docs</a> first &mdash; it helps.
+       This paragraph is deliberately long enough to be picked as the main content of the page.</p>
+    <ul><li>one</li><li>two <em>items</em></li></ul>
+    <p>A second paragraph with more text, so the article easily beats everything else on this page.</p>
+  </article>
+  <footer>© 2025 Footer links</footer>
+</body></html>"#;
+
+        let md = html_to_markdown(html, "https://example.com/blog/post");
+        assert!(md.starts_with("# Hello World\n\n"), "{}", md);
+        assert!(md.contains("ZenClaw is a **small** agent."), "{}", md);
+        assert!(md.contains("[docs](https://example.com/docs/intro) first — it helps."), "{}", md);
+        assert!(md.contains("- one\n- two *items*"), "{}", md);
+        assert!(!md.contains("Menu"));
+        assert!(!md.contains("not text"));
+        assert!(!md.contains("Footer"));
+    }
+
+    #[test]
+    fn test_falls_back_to_body_with_title() {
+        let html = "<html><head><title>Status</title></head><body>\
+            <header>Site</header><p>All systems &lt;ok&gt;</p>\
+            <pre><code>line 1\n  line 2</code></pre></body></html>";
+
+        let md = html_to_markdown(html, "https://example.com/");
+        assert_eq!(md, "# Status\n\nAll systems <ok>\n\n```\nline 1\n  line 2\n```");
+    }
+}
@@ -158,7 +158,7 @@ impl Tool for IndexerTool {
 }
 
 /// Index a single file into RAG.
-fn index_single_file(rag: &RagStore, path: &std::path::Path) -> std::result::Result<usize, String> {
+pub fn index_single_file(rag: &RagStore, path: &std::path::Path) -> std::result::Result<usize, String> {
     let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
 
     if content.is_empty() {
@@ -180,7 +180,7 @@ fn index_single_file(rag: &RagStore, path: &std::path::Path) -> std::result::Res
 }
 
 /// Index a directory recursively.
-fn index_directory(
+pub fn index_directory(
     rag: &RagStore,
     dir: &std::path::Path,
     total_files: &mut usize,
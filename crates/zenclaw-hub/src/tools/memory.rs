@@ -0,0 +1,150 @@
+//! Memory tool — exact facts the agent keeps across turns.
+//!
+//! Facts are key-value pairs in the memory store's `kv` table, scoped to
+//! the session (or shared by all sessions with `scope: "global"`). The
+//! agent injects them into the system prompt every turn, so unlike RAG
+//! there is no search: what was saved is what the model sees.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use zenclaw_core::error::Result;
+use zenclaw_core::memory::{MemoryStore, KV_GLOBAL};
+use zenclaw_core::tool::{current_session, Tool};
+
+/// Longest key accepted, in characters.
+const MAX_KEY_CHARS: usize = 100;
+
+/// Longest value accepted, in characters.
+const MAX_VALUE_CHARS: usize = 1_000;
+
+/// Save, read and forget facts.
+pub struct MemoryTool {
+    memory: Arc<dyn MemoryStore>,
+}
+
+impl MemoryTool {
+    pub fn new(memory: Arc<dyn MemoryStore>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryTool {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn description(&self) -> &str {
+        "Remember exact facts across turns (the user's name, a deploy target, preferences). Saved facts are shown to you every turn. \
+Actions: 'set' (key + value), 'get' (key), 'delete' (key), 'list'. Facts belong to this conversation unless scope is 'global'."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["set", "get", "delete", "list"],
+                    "description": "Action type"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Short name of the fact, e.g. 'user_name' (for 'set', 'get' and 'delete')"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "The fact (for 'set')"
+                },
+                "scope": {
+                    "type": "string",
+                    "enum": ["session", "global"],
+                    "description": "'session' (default) or 'global' for facts every conversation should see"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let action = args["action"].as_str().unwrap_or("list");
+        let key = args["key"].as_str().unwrap_or("").trim();
+        let scope = match args["scope"].as_str() {
+            Some("global") => KV_GLOBAL.to_string(),
+            _ => current_session().unwrap_or_default(),
+        };
+
+        if action != "list" {
+            if key.is_empty() {
+                return Ok("Error: 'key' is required.".into());
+            }
+            if key.chars().count() > MAX_KEY_CHARS {
+                return Ok(format!("Error: keys are limited to {} characters.", MAX_KEY_CHARS));
+            }
+        }
+
+        match action {
+            "set" => {
+                let value = args["value"].as_str().unwrap_or("").trim();
+                if value.is_empty() {
+                    return Ok("Error: 'value' is required. Use action='delete' to forget a fact.".into());
+                }
+                if value.chars().count() > MAX_VALUE_CHARS {
+                    return Ok(format!("Error: values are limited to {} characters.", MAX_VALUE_CHARS));
+                }
+                self.memory.kv_set(&scope, key, value).await?;
+                Ok(format!("✅ Remembered {} = {}", key, value))
+            }
+            "get" => match self.memory.kv_get(&scope, key).await? {
+                Some(value) => Ok(format!("{} = {}", key, value)),
+                None => Ok(format!("No fact named '{}'.", key)),
+            },
+            "delete" => {
+                if self.memory.kv_delete(&scope, key).await? {
+                    Ok(format!("🗑️ Forgot {}", key))
+                } else {
+                    Ok(format!("No fact named '{}'.", key))
+                }
+            }
+            "list" => {
+                let facts = self.memory.kv_list(&scope).await?;
+                if facts.is_empty() {
+                    return Ok("No facts saved.".into());
+                }
+                let lines: Vec<String> = facts.iter().map(|(k, v)| format!("• {} = {}", k, v)).collect();
+                Ok(format!("Saved facts ({}):\n{}", facts.len(), lines.join("\n")))
+            }
+            _ => Ok(format!("Unknown action: {}. Use 'set', 'get', 'delete' or 'list'.", action)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use zenclaw_core::tool::with_session;
+
+    #[tokio::test]
+    async fn test_facts_are_per_session_unless_global() {
+        let tool = MemoryTool::new(Arc::new(SqliteMemory::in_memory().unwrap()));
+        let run = |session: &'static str, args: Value| with_session(session, tool.execute(args));
+
+        run("telegram:1", json!({ "action": "set", "key": "name", "value": "Ana" })).await.unwrap();
+        run("telegram:1", json!({ "action": "set", "key": "tz", "value": "UTC+7", "scope": "global" }))
+            .await
+            .unwrap();
+
+        let get = json!({ "action": "get", "key": "name" });
+        assert_eq!(run("telegram:1", get.clone()).await.unwrap(), "name = Ana");
+        assert_eq!(run("telegram:2", get).await.unwrap(), "No fact named 'name'.");
+        let global = json!({ "action": "get", "key": "tz", "scope": "global" });
+        assert_eq!(run("telegram:2", global).await.unwrap(), "tz = UTC+7");
+
+        assert!(run("telegram:1", json!({ "action": "delete", "key": "name" })).await.unwrap().contains("Forgot"));
+        assert_eq!(run("telegram:1", json!({ "action": "list" })).await.unwrap(), "No facts saved.");
+    }
+}
@@ -2,6 +2,7 @@ pub mod shell;
 pub mod process;
 pub mod subagent;
 pub mod filesystem;
+pub mod remote_fs;
 pub mod web_fetch;
 pub mod web_scrape;
 pub mod web_search;
@@ -12,11 +13,21 @@ pub mod history;
 pub mod indexer;
 pub mod webhook;
 pub mod env;
+pub mod memory;
+pub mod search_history;
+pub mod cache;
+pub mod calc;
 pub mod code_search;
+mod html_markdown;
+pub mod grep;
+pub mod git;
 pub use shell::ShellTool;
 pub use process::ProcessTool;
-pub use subagent::SubAgentTool;
-pub use filesystem::{ReadFileTool, WriteFileTool, EditFileTool, ListDirTool};
+pub use subagent::{AgentDelegateTool, SubAgentTool};
+pub use filesystem::{ReadFileTool, WriteFileTool, EditFileTool, UndoEditTool, ListDirTool};
+pub use remote_fs::{FsRouter, LocalFs, RemoteFs};
+#[cfg(feature = "sftp")]
+pub use remote_fs::SftpFs;
 pub use web_fetch::WebFetchTool;
 pub use web_scrape::WebScrapeTool;
 pub use web_search::WebSearchTool;
@@ -26,5 +37,11 @@ pub use health::HealthTool;
 pub use history::HistoryTool;
 pub use indexer::IndexerTool;
 pub use webhook::{WebhookTool, WebhookStore, new_webhook_store};
-pub use env::EnvTool;
+pub use env::{EnvPolicy, EnvTool, SessionEnv, new_session_env};
+pub use memory::MemoryTool;
+pub use search_history::SearchHistoryTool;
+pub use cache::ResponseCache;
+pub use calc::CalcTool;
 pub use code_search::CodebaseSearchTool;
+pub use grep::GrepTool;
+pub use git::GitTool;
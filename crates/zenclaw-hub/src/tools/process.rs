@@ -1,31 +1,97 @@
 //! Process tool — manage long-running background processes.
 //!
 //! Spawns, lists, checks status, and kills background processes.
-//! Captures stdout and stderr in real-time.
+//! Captures stdout and stderr in real-time: `read` returns only what a
+//! process printed since the previous `read`, so the agent can follow a
+//! build or a dev server while it runs. A handle is released once its
+//! process has exited and its remaining output has been read (or, unread,
+//! after [`FINISHED_TTL`]).
 
 use std::collections::HashMap;
 use std::process::Stdio;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use tokio::io::{AsyncReadExt, BufReader};
+use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use zenclaw_core::error::Result;
-use zenclaw_core::tool::Tool;
+use zenclaw_core::tool::{CancellationToken, Tool};
+
+use super::env::{session_vars, SessionEnv};
+use super::shell::kill_group;
+
+/// How long an exited process's handle is kept when nobody reads it.
+const FINISHED_TTL: Duration = Duration::from_secs(30 * 60);
 
 #[derive(Debug)]
 struct ManagedProcess {
     id: String,
     command: String,
     status: ProcessStatus,
-    output: Arc<Mutex<String>>,
+    output: Arc<Mutex<OutputBuffer>>,
     // We cannot easily hold Child if we want to kill it later from a different reference 
     // without complex Mutex tracking, so we store a kill sender.
     kill_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    /// When the process stopped for good (no restart coming).
+    finished: Option<Instant>,
+}
+
+impl ManagedProcess {
+    fn stop(&mut self, status: ProcessStatus) {
+        self.status = status;
+        self.finished = Some(Instant::now());
+    }
+}
+
+/// Combined stdout/stderr of a process; only the newest `cap` bytes are kept.
+#[derive(Debug, Default)]
+struct OutputBuffer {
+    text: String,
+    /// Bytes dropped from the front to stay under the cap.
+    dropped: usize,
+    /// Offset (counting dropped bytes) up to which `read_new` has returned output.
+    read: usize,
+}
+
+impl OutputBuffer {
+    fn push(&mut self, chunk: &str, cap: usize) {
+        self.text.push_str(chunk);
+        if self.text.len() > cap {
+            let mut cut = self.text.len() - cap;
+            while !self.text.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.text.drain(..cut);
+            self.dropped += cut;
+        }
+    }
+
+    /// Everything still buffered.
+    fn all(&self) -> String {
+        if self.dropped > 0 {
+            format!("...[truncated {} bytes]\n{}", self.dropped, self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    /// Output added since the last call, noting any that was dropped
+    /// before it could be read.
+    fn read_new(&mut self) -> String {
+        let mut out = String::new();
+        if self.read < self.dropped {
+            out = format!("...[{} bytes dropped before they were read]\n", self.dropped - self.read);
+        }
+        let start = self.read.max(self.dropped) - self.dropped;
+        out.push_str(&self.text[start..]);
+        self.read = self.dropped + self.text.len();
+        out
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -47,10 +113,24 @@ impl std::fmt::Display for ProcessStatus {
     }
 }
 
+/// Copy `pipe` into `output` as it arrives.
+fn capture(pipe: impl AsyncRead + Unpin + Send + 'static, output: Arc<Mutex<OutputBuffer>>, cap: usize) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = [0; 1024];
+        while let Ok(n) = reader.read(&mut buf).await {
+            if n == 0 { break; }
+            output.lock().await.push(&String::from_utf8_lossy(&buf[..n]), cap);
+        }
+    })
+}
+
 /// A tool to manage background processes.
 pub struct ProcessTool {
     processes: Arc<Mutex<HashMap<String, ManagedProcess>>>,
     max_output_size: usize,
+    /// Variables set with the `env` tool, passed to the session's processes.
+    session_env: Option<SessionEnv>,
 }
 
 impl ProcessTool {
@@ -58,8 +138,14 @@ impl ProcessTool {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
             max_output_size: 50_000,
+            session_env: None,
         }
     }
+
+    pub fn with_session_env(mut self, env: SessionEnv) -> Self {
+        self.session_env = Some(env);
+        self
+    }
 }
 
 impl Default for ProcessTool {
@@ -78,9 +164,11 @@ impl Tool for ProcessTool {
         "Long-Running Process Manager. Spawns and manages background tasks asynchonously.
 Actions:
 - 'spawn' (starts a command in the background, returns process ID)
+- 'read' (returns only the output printed since the last read, plus the status. requires 'process_id'. Poll it to follow builds, tests or servers)
 - 'status' (checks process status and returns the latest output logs. requires 'process_id')
 - 'kill' (terminates a running process. requires 'process_id')
-- 'list' (lists all managed background processes)"
+- 'list' (lists all managed background processes)
+An exited process's handle is released once its remaining output has been read."
     }
 
     fn parameters(&self) -> Value {
@@ -89,7 +177,7 @@ Actions:
             "properties": {
                 "action": {
                     "type": "string",
-                    "enum": ["spawn", "status", "kill", "list"],
+                    "enum": ["spawn", "read", "status", "kill", "list"],
                     "description": "Action type"
                 },
                 "command": {
@@ -98,7 +186,7 @@ Actions:
                 },
                 "process_id": {
                     "type": "string",
-                    "description": "The ID of the process (required for 'status' and 'kill')."
+                    "description": "The ID of the process (required for 'read', 'status' and 'kill'). 'id' is accepted too."
                 },
                 "auto_restart": {
                     "type": "boolean",
@@ -109,8 +197,24 @@ Actions:
         })
     }
 
+    /// Spawning runs arbitrary shell commands, just like `exec`.
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: Value) -> Result<String> {
+        self.execute_cancellable(args, &CancellationToken::new()).await
+    }
+
+    /// Processes spawned during a turn are killed if that turn is cancelled.
+    async fn execute_cancellable(&self, args: Value, cancel: &CancellationToken) -> Result<String> {
         let action = args["action"].as_str().unwrap_or("list");
+        let pid = args["process_id"].as_str().or_else(|| args["id"].as_str()).unwrap_or("");
+
+        self.processes
+            .lock()
+            .await
+            .retain(|_, p| p.finished.is_none_or(|at| at.elapsed() < FINISHED_TTL));
 
         match action {
             "spawn" => {
@@ -120,7 +224,7 @@ Actions:
                 }
 
                 let id = Uuid::new_v4().to_string().chars().take(8).collect::<String>();
-                let output = Arc::new(Mutex::new(String::new()));
+                let output = Arc::new(Mutex::new(OutputBuffer::default()));
                 let (kill_tx, kill_rx) = tokio::sync::oneshot::channel::<()>();
 
                 let process = ManagedProcess {
@@ -129,7 +233,11 @@ Actions:
                     status: ProcessStatus::Running,
                     output: output.clone(),
                     kill_tx: Some(kill_tx),
+                    finished: None,
                 };
+                // Registered before the task starts, so a process that exits
+                // immediately still finds its handle
+                self.processes.lock().await.insert(id.clone(), process);
 
                 let processes = self.processes.clone();
                 let output_clone = output.clone();
@@ -139,6 +247,8 @@ Actions:
 
                 let id_clone = id.clone();
                 let command_clone = command_str.clone();
+                let cancel = cancel.clone();
+                let envs = self.session_env.as_ref().map(session_vars).unwrap_or_default();
 
                 tokio::spawn(async move {
                     #[cfg(target_os = "windows")]
@@ -160,8 +270,7 @@ Actions:
                                     break;
                                 }
                                 p.status = ProcessStatus::Running;
-                                let mut out = p.output.lock().await;
-                                out.push_str("\n\n--- [Auto-Restarting Process] ---\n\n");
+                                p.output.lock().await.push("\n\n--- [Auto-Restarting Process] ---\n\n", max_size);
                             }
                         }
                         is_first_run = false;
@@ -170,51 +279,30 @@ Actions:
                         cmd.arg(arg)
                        .arg(&command_clone)
                        .stdout(Stdio::piped())
-                       .stderr(Stdio::piped());
+                       .stderr(Stdio::piped())
+                       .envs(&envs);
+                        #[cfg(unix)]
+                        cmd.process_group(0);
 
                         let mut child_process = match cmd.spawn() {
                             Ok(c) => c,
                             Err(e) => {
                                 let mut map = processes.lock().await;
                                 if let Some(p) = map.get_mut(&id_clone) {
-                                    p.status = ProcessStatus::Failed(e.to_string());
+                                    if auto_restart {
+                                        p.status = ProcessStatus::Failed(e.to_string());
+                                    } else {
+                                        p.stop(ProcessStatus::Failed(e.to_string()));
+                                    }
                                 }
                                 if auto_restart { continue; } else { return; }
                             }
                         };
 
-                        let stdout = child_process.stdout.take().unwrap();
-                        let stderr = child_process.stderr.take().unwrap();
-
-                        let out_ref1 = output_clone.clone();
-                        let out_ref2 = output_clone.clone();
-
-                    // Readers for stdout and stderr appending to the string
-                    let read_stdout = async move {
-                        let mut reader = BufReader::new(stdout);
-                        let mut buf = [0; 1024];
-                        while let Ok(n) = reader.read(&mut buf).await {
-                            if n == 0 { break; }
-                            let mut locked = out_ref1.lock().await;
-                            locked.push_str(&String::from_utf8_lossy(&buf[..n]));
-                            if locked.len() > max_size {
-                                *locked = format!("...[truncated] {}", &locked[locked.len() - max_size..]);
-                            }
-                        }
-                    };
-
-                    let read_stderr = async move {
-                        let mut reader = BufReader::new(stderr);
-                        let mut buf = [0; 1024];
-                        while let Ok(n) = reader.read(&mut buf).await {
-                            if n == 0 { break; }
-                            let mut locked = out_ref2.lock().await;
-                            locked.push_str(&String::from_utf8_lossy(&buf[..n]));
-                            if locked.len() > max_size {
-                                *locked = format!("...[truncated] {}", &locked[locked.len() - max_size..]);
-                            }
-                        }
-                    };
+                        // Readers run alongside the process, so output is
+                        // readable while it is still running
+                        let read_stdout = capture(child_process.stdout.take().unwrap(), output_clone.clone(), max_size);
+                        let read_stderr = capture(child_process.stderr.take().unwrap(), output_clone.clone(), max_size);
 
                         let current_rx = rx_wrapper.take();
 
@@ -222,21 +310,37 @@ Actions:
                             Some(mut rx) => {
                                 tokio::select! {
                                     _ = &mut rx => {
+                                        kill_group(child_process.id());
                                         let _ = child_process.kill().await;
                                         let mut map = processes.lock().await;
                                         if let Some(p) = map.get_mut(&id_clone) {
-                                            p.status = ProcessStatus::Killed;
+                                            p.stop(ProcessStatus::Killed);
                                         }
                                         break; // fully killed
                                     }
+                                    _ = cancel.cancelled() => {
+                                        kill_group(child_process.id());
+                                        let _ = child_process.kill().await;
+                                        let mut map = processes.lock().await;
+                                        if let Some(p) = map.get_mut(&id_clone) {
+                                            p.stop(ProcessStatus::Killed);
+                                            p.kill_tx = None;
+                                        }
+                                        break;
+                                    }
                                     status = child_process.wait() => {
-                                        tokio::join!(read_stdout, read_stderr);
+                                        let _ = tokio::join!(read_stdout, read_stderr);
                                         let mut map = processes.lock().await;
                                         if let Some(p) = map.get_mut(&id_clone) {
                                             let status_result: std::io::Result<std::process::ExitStatus> = status;
-                                            match status_result {
-                                                Ok(exit_status) => p.status = ProcessStatus::Finished(exit_status.code().unwrap_or(0)),
-                                                Err(e) => p.status = ProcessStatus::Failed(e.to_string()),
+                                            let status = match status_result {
+                                                Ok(exit_status) => ProcessStatus::Finished(exit_status.code().unwrap_or(0)),
+                                                Err(e) => ProcessStatus::Failed(e.to_string()),
+                                            };
+                                            if auto_restart {
+                                                p.status = status;
+                                            } else {
+                                                p.stop(status);
                                             }
                                         }
                                         rx_wrapper = Some(rx); // put it back for next loop
@@ -252,12 +356,29 @@ Actions:
                     } // end loop
                 });
 
-                self.processes.lock().await.insert(id.clone(), process);
+                Ok(format!("✅ Background Process Spawned.\n  ID: {}\n  Target: {}\nTip: use action='read' to follow its output.", id, command_str))
+            }
+            "read" => {
+                if pid.is_empty() {
+                    return Ok("Error: 'process_id' is required.".into());
+                }
 
-                Ok(format!("✅ Background Process Spawned.\n  ID: {}\n  Target: {}\nTip: use action='status' to check output.", id, command_str))
+                let mut map = self.processes.lock().await;
+                let Some(process) = map.get(pid) else {
+                    return Ok(format!("Error: Process {} not found.", pid));
+                };
+                let new_output = process.output.lock().await.read_new();
+                let mut result = format!("Process: {}\nStatus: {}\n--- New Output ---\n{}", pid, process.status, new_output);
+                if new_output.is_empty() {
+                    result.push_str("(no new output)");
+                }
+                if process.finished.is_some() {
+                    map.remove(pid);
+                    result.push_str("\n--- Process exited; handle released ---");
+                }
+                Ok(result)
             }
             "status" => {
-                let pid = args["process_id"].as_str().unwrap_or("");
                 if pid.is_empty() {
                     return Ok("Error: 'process_id' is required.".into());
                 }
@@ -266,13 +387,12 @@ Actions:
                 if let Some(process) = map.get(pid) {
                     let out = process.output.lock().await;
                     Ok(format!("Process: {}\nCommand: {}\nStatus: {}\n--- Output Log ---\n{}", 
-                               pid, process.command, process.status, *out))
+                               pid, process.command, process.status, out.all()))
                 } else {
                     Ok(format!("Error: Process {} not found.", pid))
                 }
             }
             "kill" => {
-                let pid = args["process_id"].as_str().unwrap_or("");
                 if pid.is_empty() {
                     return Ok("Error: 'process_id' is required.".into());
                 }
@@ -281,7 +401,7 @@ Actions:
                 if let Some(process) = map.get_mut(pid) {
                     if let Some(tx) = process.kill_tx.take() {
                         let _ = tx.send(()); // Triggers the await select loop
-                        process.status = ProcessStatus::Killed;
+                        process.stop(ProcessStatus::Killed);
                         Ok(format!("✅ Sent kill signal to Process {}.", pid))
                     } else if process.status == ProcessStatus::Running {
                         Ok("Error: Kill signal already sent.".into())
@@ -309,3 +429,112 @@ Actions:
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_new_returns_only_unread_output() {
+        let mut buf = OutputBuffer::default();
+        buf.push("hello ", 10);
+        assert_eq!(buf.read_new(), "hello ");
+        assert_eq!(buf.read_new(), "");
+
+        buf.push("world", 10);
+        assert_eq!(buf.read_new(), "world");
+
+        // 14 more bytes: the cap keeps the last 10, 4 unread ones are lost
+        buf.push("0123456789abcd", 10);
+        assert_eq!(buf.read_new(), "...[4 bytes dropped before they were read]\n456789abcd");
+        assert_eq!(buf.all(), "...[truncated 15 bytes]\n456789abcd");
+    }
+
+    #[test]
+    fn test_push_keeps_char_boundaries() {
+        let mut buf = OutputBuffer::default();
+        buf.push("aé", 2);
+        assert_eq!(buf.text, "é");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_read_follows_a_process_until_it_exits() {
+        let tool = ProcessTool::new();
+        let spawned = tool.execute(json!({ "action": "spawn", "command": "echo first" })).await.unwrap();
+        let id = spawned.lines().find_map(|l| l.trim().strip_prefix("ID: ")).unwrap().to_string();
+
+        let mut seen = String::new();
+        for _ in 0..100 {
+            let read = tool.execute(json!({ "action": "read", "id": id })).await.unwrap();
+            seen.push_str(&read);
+            if read.contains("handle released") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(seen.matches("first").count(), 1, "{}", seen);
+        assert!(seen.contains("handle released"), "{}", seen);
+
+        let gone = tool.execute(json!({ "action": "read", "id": id })).await.unwrap();
+        assert!(gone.contains("not found"));
+    }
+
+    /// Asks to spawn `touch <marker>` once, then answers with the result.
+    struct SpawnProvider(String);
+
+    #[async_trait]
+    impl zenclaw_core::provider::LlmProvider for SpawnProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        fn default_model(&self) -> &str {
+            "fake"
+        }
+
+        async fn chat(&self, request: zenclaw_core::provider::ChatRequest) -> Result<zenclaw_core::message::LlmResponse> {
+            use zenclaw_core::message::{FunctionCall, Role, TokenUsage, ToolCall};
+
+            let result = request.messages.iter().find(|m| m.role == Role::Tool);
+            let tool_calls = match result {
+                Some(_) => Vec::new(),
+                None => vec![ToolCall {
+                    id: "call_1".into(),
+                    r#type: "function".into(),
+                    function: FunctionCall {
+                        name: "process".into(),
+                        arguments: json!({ "action": "spawn", "command": format!("touch {}", self.0) }).to_string(),
+                    },
+                }],
+            };
+            Ok(zenclaw_core::message::LlmResponse {
+                content: result.and_then(|m| m.content.clone()),
+                tool_calls,
+                model: "fake".into(),
+                usage: TokenUsage::default(),
+                finish_reason: "stop".into(),
+            })
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_spawn_needs_confirmation() {
+        use zenclaw_core::agent::{Agent, AgentConfig};
+
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("spawned");
+        let mut agent = Agent::with_config(AgentConfig {
+            confirm_destructive: true,
+            ..Default::default()
+        });
+        agent.tools.register(ProcessTool::new());
+        let memory = zenclaw_core::memory::InMemoryStore::new();
+
+        let provider = SpawnProvider(marker.to_string_lossy().to_string());
+        agent.process(&provider, &memory, "start it", "cli:p", None).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!marker.exists(), "spawned without confirmation");
+    }
+}
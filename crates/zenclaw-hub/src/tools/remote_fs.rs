@@ -0,0 +1,382 @@
+//! Remote filesystem backends for the file tools.
+//!
+//! Paths are routed by URI scheme: plain paths go to [`LocalFs`], while
+//! `sftp://user@host[:port]/path` goes to [`SftpFs`] (behind the `sftp`
+//! feature). New backends (e.g. S3) only need to implement [`RemoteFs`] and
+//! be added to an [`FsRouter`].
+
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::fs;
+
+use zenclaw_core::error::{Result, ZenClawError};
+
+/// A directory entry returned by [`RemoteFs::list`].
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+/// Filesystem backend used by `read_file`, `write_file`, `edit_file` and `list_dir`.
+#[async_trait]
+pub trait RemoteFs: Send + Sync {
+    /// URI scheme handled by this backend (e.g. `"sftp"`), or `""` for local paths.
+    fn scheme(&self) -> &str;
+
+    /// Read a file as UTF-8 text.
+    async fn read(&self, path: &str) -> Result<String>;
+
+    /// Write a file, creating parent directories if needed. Backends
+    /// replace the file atomically where they can, and write through
+    /// symlinks instead of replacing them.
+    async fn write(&self, path: &str, content: &str) -> Result<()>;
+
+    /// List the entries of a directory.
+    async fn list(&self, path: &str) -> Result<Vec<DirEntry>>;
+}
+
+// ─── Local ─────────────────────────────────────────────────
+
+/// The local filesystem (default backend).
+///
+/// Writes go to a temporary file in the target's directory which is then
+/// renamed over it, so a crash mid-write never leaves a half-written file.
+pub struct LocalFs;
+
+/// Where a write to `path` should land: the file a symlink points to
+/// (followed to the end of the chain), else `path` itself.
+async fn write_target(path: &Path) -> std::path::PathBuf {
+    let mut target = path.to_path_buf();
+    // Bounded, in case of a symlink loop
+    for _ in 0..40 {
+        match fs::symlink_metadata(&target).await {
+            Ok(meta) if meta.file_type().is_symlink() => match fs::read_link(&target).await {
+                Ok(link) => {
+                    target = match target.parent() {
+                        Some(parent) => parent.join(link),
+                        None => link,
+                    }
+                }
+                Err(_) => break,
+            },
+            _ => break,
+        }
+    }
+    target
+}
+
+#[async_trait]
+impl RemoteFs for LocalFs {
+    fn scheme(&self) -> &str {
+        ""
+    }
+
+    async fn read(&self, path: &str) -> Result<String> {
+        if !Path::new(path).exists() {
+            return Err(ZenClawError::Other(format!("File not found: {}", path)));
+        }
+        String::from_utf8(fs::read(path).await?)
+            .map_err(|_| ZenClawError::Other(format!("{} is a binary file, not UTF-8 text", path)))
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<()> {
+        let target = write_target(Path::new(path)).await;
+        let dir = match target.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => std::path::PathBuf::from("."),
+        };
+        fs::create_dir_all(&dir).await.ok();
+
+        let name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let temp = dir.join(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4().simple()));
+        let written = async {
+            let mut file = fs::File::create(&temp).await?;
+            tokio::io::AsyncWriteExt::write_all(&mut file, content.as_bytes()).await?;
+            file.sync_all().await?;
+            // Keep the replaced file's permissions (e.g. an executable script)
+            if let Ok(meta) = fs::metadata(&target).await {
+                fs::set_permissions(&temp, meta.permissions()).await?;
+            }
+            fs::rename(&temp, &target).await
+        }
+        .await;
+        if written.is_err() {
+            fs::remove_file(&temp).await.ok();
+        }
+        Ok(written?)
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<DirEntry>> {
+        let mut entries = fs::read_dir(path).await?;
+        let mut items = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let meta = entry.metadata().await.ok();
+            items.push(DirEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir: meta.as_ref().map(|m| m.is_dir()).unwrap_or(false),
+                size: meta.as_ref().map(|m| m.len()).unwrap_or(0),
+            });
+        }
+        Ok(items)
+    }
+}
+
+// ─── SFTP (via the system `ssh` client) ────────────────────
+
+/// Remote files over SSH: `sftp://user@host[:port]/absolute/path`.
+///
+/// Uses the system `ssh` binary in batch mode, so authentication comes from
+/// the user's SSH agent, keys and `~/.ssh/config` — no passwords are handled
+/// here. Listing requires GNU `find` on the remote host.
+#[cfg(feature = "sftp")]
+pub struct SftpFs {
+    identity: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "sftp")]
+impl SftpFs {
+    pub fn new() -> Self {
+        Self { identity: None }
+    }
+
+    /// Use a specific private key instead of the SSH agent/config default.
+    pub fn with_identity(mut self, path: &Path) -> Self {
+        self.identity = Some(path.to_path_buf());
+        self
+    }
+
+    /// Split `sftp://user@host:port/path` into (destination, port, path).
+    fn parse(uri: &str) -> Result<(String, Option<u16>, String)> {
+        let rest = uri
+            .strip_prefix("sftp://")
+            .ok_or_else(|| ZenClawError::Other(format!("Not an sftp:// URI: {}", uri)))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            return Err(ZenClawError::Other(format!("Missing host in {}", uri)));
+        }
+
+        let (dest, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port
+                    .parse()
+                    .map_err(|_| ZenClawError::Other(format!("Invalid port in {}", uri)))?;
+                (host.to_string(), Some(port))
+            }
+            None => (authority.to_string(), None),
+        };
+        if !is_ssh_destination(&dest) {
+            return Err(ZenClawError::Other(format!("Invalid host in {}: expected [user@]host", uri)));
+        }
+        Ok((dest, port, path.to_string()))
+    }
+
+    async fn run(&self, uri: &str, script: impl FnOnce(&str) -> String, stdin: Option<&str>) -> Result<String> {
+        use std::process::Stdio;
+        use tokio::io::AsyncWriteExt;
+
+        let (dest, port, path) = Self::parse(uri)?;
+
+        let mut cmd = tokio::process::Command::new("ssh");
+        cmd.arg("-o").arg("BatchMode=yes");
+        if let Some(port) = port {
+            cmd.arg("-p").arg(port.to_string());
+        }
+        if let Some(ref identity) = self.identity {
+            cmd.arg("-i").arg(identity);
+        }
+        cmd.arg("--")
+            .arg(&dest)
+            .arg(script(&shell_quote(&path)))
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| ZenClawError::Other(format!("Failed to run ssh: {}", e)))?;
+        if let Some(input) = stdin
+            && let Some(mut pipe) = child.stdin.take()
+        {
+            pipe.write_all(input.as_bytes()).await?;
+            drop(pipe);
+        }
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(ZenClawError::Other(format!(
+                "ssh {} failed: {}",
+                dest,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[cfg(feature = "sftp")]
+impl Default for SftpFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "sftp")]
+#[async_trait]
+impl RemoteFs for SftpFs {
+    fn scheme(&self) -> &str {
+        "sftp"
+    }
+
+    async fn read(&self, path: &str) -> Result<String> {
+        self.run(path, |p| format!("cat -- {}", p), None).await
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<()> {
+        // Resolve symlinks, then write a temp file and rename it over the target
+        self.run(
+            path,
+            |p| {
+                format!(
+                    "t=$(readlink -f -- {p} || echo {p}) && mkdir -p -- \"$(dirname -- \"$t\")\" \
+                     && cat > \"$t.tmp.$$\" && mv -f -- \"$t.tmp.$$\" \"$t\""
+                )
+            },
+            Some(content),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn list(&self, path: &str) -> Result<Vec<DirEntry>> {
+        let out = self
+            .run(path, |p| format!("find {} -mindepth 1 -maxdepth 1 -printf '%y\\t%s\\t%f\\n'", p), None)
+            .await?;
+        Ok(out
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                let kind = parts.next()?;
+                let size = parts.next()?.parse().unwrap_or(0);
+                let name = parts.next()?.to_string();
+                Some(DirEntry { name, is_dir: kind == "d", size })
+            })
+            .collect())
+    }
+}
+
+/// `[user@]host` with nothing ssh could read as an option: user and host
+/// are letters, digits, `.`, `_` and `-`, and neither starts with `-`.
+#[cfg(feature = "sftp")]
+fn is_ssh_destination(dest: &str) -> bool {
+    let (user, host) = match dest.split_once('@') {
+        Some((user, host)) => (Some(user), host),
+        None => (None, dest),
+    };
+    let valid = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('-')
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    };
+    user.is_none_or(valid) && valid(host)
+}
+
+/// Quote a string for a POSIX shell.
+#[cfg(feature = "sftp")]
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+// ─── Router ────────────────────────────────────────────────
+
+/// Picks a backend for a path based on its URI scheme.
+#[derive(Clone)]
+pub struct FsRouter {
+    local: Arc<dyn RemoteFs>,
+    remotes: Vec<Arc<dyn RemoteFs>>,
+}
+
+impl FsRouter {
+    /// Local filesystem only.
+    pub fn local() -> Self {
+        Self {
+            local: Arc::new(LocalFs),
+            remotes: Vec::new(),
+        }
+    }
+
+    /// Add a remote backend (replaces any backend with the same scheme).
+    pub fn with_backend(mut self, backend: Arc<dyn RemoteFs>) -> Self {
+        self.remotes.retain(|b| b.scheme() != backend.scheme());
+        self.remotes.push(backend);
+        self
+    }
+
+    /// True if `path` is a `scheme://` URI rather than a local path.
+    pub fn is_remote(path: &str) -> bool {
+        path.split_once("://")
+            .is_some_and(|(scheme, _)| !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric()))
+    }
+
+    /// Backend for `path`, or an error naming the unsupported scheme.
+    pub fn backend(&self, path: &str) -> std::result::Result<&dyn RemoteFs, String> {
+        if !Self::is_remote(path) {
+            return Ok(self.local.as_ref());
+        }
+        let scheme = path.split_once("://").map(|(s, _)| s).unwrap_or_default();
+        self.remotes
+            .iter()
+            .find(|b| b.scheme() == scheme)
+            .map(|b| b.as_ref())
+            .ok_or_else(|| {
+                let mut known: Vec<&str> = self.remotes.iter().map(|b| b.scheme()).collect();
+                known.sort_unstable();
+                if known.is_empty() {
+                    format!("Remote paths ({}://) are not enabled in this build", scheme)
+                } else {
+                    format!("Unsupported scheme '{}://' (available: {})", scheme, known.join(", "))
+                }
+            })
+    }
+}
+
+impl Default for FsRouter {
+    /// Local filesystem plus every remote backend compiled in.
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut router = Self::local();
+        #[cfg(feature = "sftp")]
+        {
+            router = router.with_backend(Arc::new(SftpFs::new()));
+        }
+        router
+    }
+}
+
+#[cfg(all(test, feature = "sftp"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_option_like_hosts() {
+        let (dest, port, path) = SftpFs::parse("sftp://deploy@web-1.example.com:2222/srv/app").unwrap();
+        assert_eq!((dest.as_str(), port, path.as_str()), ("deploy@web-1.example.com", Some(2222), "/srv/app"));
+
+        for uri in [
+            "sftp://-oProxyCommand=touch%20pwned/tmp",
+            "sftp://-oProxyCommand=sh@host/tmp",
+            "sftp://user@-oProxyCommand=sh/tmp",
+            "sftp://user@host name/tmp",
+            "sftp://@host/tmp",
+        ] {
+            assert!(SftpFs::parse(uri).is_err(), "{} accepted", uri);
+        }
+    }
+}
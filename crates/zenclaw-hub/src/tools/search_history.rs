@@ -0,0 +1,141 @@
+//! History search tool — find what was said in past conversations.
+//!
+//! Full-text search over the saved user and assistant messages (SQLite
+//! FTS5 in `memory.db`), so "what did we decide about the database last
+//! week?" can be answered from the record instead of from memory.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use zenclaw_core::error::Result;
+use zenclaw_core::memory::{HistoryMatch, MemoryStore};
+use zenclaw_core::tool::{current_session, Tool};
+
+/// Results returned when the model doesn't ask for a number.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Most results returned by one call.
+const MAX_LIMIT: usize = 50;
+
+/// Characters of each message shown.
+const SNIPPET_CHARS: usize = 300;
+
+/// Search saved conversations.
+pub struct SearchHistoryTool {
+    memory: Arc<dyn MemoryStore>,
+    /// Search every session, not only the current one.
+    all_sessions: bool,
+}
+
+impl SearchHistoryTool {
+    pub fn new(memory: Arc<dyn MemoryStore>) -> Self {
+        Self {
+            memory,
+            all_sessions: true,
+        }
+    }
+
+    /// Confine searches to the session of the call — for bots and the API,
+    /// where sessions belong to different people.
+    pub fn current_session_only(mut self) -> Self {
+        self.all_sessions = false;
+        self
+    }
+}
+
+/// One result line: when, where, who, and the start of the message.
+pub fn format_match(found: &HistoryMatch) -> String {
+    let time = found
+        .entry
+        .timestamp
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "unknown time".into());
+    let role = format!("{:?}", found.entry.message.role).to_lowercase();
+    let text = found.entry.message.content.as_deref().unwrap_or_default().replace('\n', " ");
+    let text = if text.chars().count() > SNIPPET_CHARS {
+        format!("{}…", text.chars().take(SNIPPET_CHARS).collect::<String>())
+    } else {
+        text
+    };
+    format!("[{}] {} ({}): {}", time, found.session_key, role, text)
+}
+
+#[async_trait]
+impl Tool for SearchHistoryTool {
+    fn name(&self) -> &str {
+        "search_history"
+    }
+
+    fn description(&self) -> &str {
+        "Search past conversations for messages containing all the given words (prefixes match: 'deploy' finds 'deployment'). \
+Returns matching messages with their session and time, best matches first."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to look for, e.g. 'database migration'"
+                },
+                "current_session": {
+                    "type": "boolean",
+                    "description": "Only search this conversation (default: false)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max results (default: 10, max: 50)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let query = args["query"].as_str().unwrap_or("").trim();
+        if query.is_empty() {
+            return Ok("Error: 'query' is required.".into());
+        }
+        let limit = args["limit"].as_u64().map_or(DEFAULT_LIMIT, |n| (n as usize).clamp(1, MAX_LIMIT));
+        let session = current_session();
+        let scope = if self.all_sessions && !args["current_session"].as_bool().unwrap_or(false) {
+            None
+        } else {
+            Some(session.unwrap_or_default())
+        };
+
+        let found = self.memory.search_history(query, scope.as_deref(), limit).await?;
+        if found.is_empty() {
+            return Ok(format!("No messages found for '{}'.", query));
+        }
+        let lines: Vec<String> = found.iter().map(format_match).collect();
+        Ok(format!("Found {} message(s) for '{}':\n{}", found.len(), query, lines.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use zenclaw_core::tool::with_session;
+
+    #[tokio::test]
+    async fn test_public_search_stays_in_the_session() {
+        let memory = Arc::new(SqliteMemory::in_memory().unwrap());
+        memory.save_turn("telegram:1", "Our deploy target is fly.io", "Noted.").await.unwrap();
+        memory.save_turn("telegram:2", "How do I deploy to Render?", "Push to main.").await.unwrap();
+
+        let args = json!({ "query": "deploy" });
+        let all = SearchHistoryTool::new(memory.clone());
+        let found = with_session("telegram:1", all.execute(args.clone())).await.unwrap();
+        assert!(found.starts_with("Found 2 message(s)"), "{}", found);
+
+        let own = SearchHistoryTool::new(memory).current_session_only();
+        let found = with_session("telegram:1", own.execute(args)).await.unwrap();
+        assert!(found.starts_with("Found 1 message(s)"), "{}", found);
+        assert!(found.contains("telegram:1 (user): Our deploy target is fly.io"), "{}", found);
+    }
+}
@@ -5,8 +5,10 @@ use serde_json::{json, Value};
 use std::process::Stdio;
 use tokio::process::Command;
 
-use zenclaw_core::error::Result;
-use zenclaw_core::tool::Tool;
+use zenclaw_core::error::{Result, ZenClawError};
+use zenclaw_core::tool::{CancellationToken, Tool};
+
+use super::env::{session_vars, SessionEnv};
 
 /// Execute shell commands. Core tool for system interaction.
 pub struct ShellTool {
@@ -14,6 +16,8 @@ pub struct ShellTool {
     pub working_dir: Option<String>,
     /// Maximum output length in bytes.
     pub max_output: usize,
+    /// Variables set with the `env` tool, passed to the session's commands.
+    session_env: Option<SessionEnv>,
 }
 
 impl ShellTool {
@@ -21,6 +25,7 @@ impl ShellTool {
         Self {
             working_dir: None,
             max_output: 10_000,
+            session_env: None,
         }
     }
 
@@ -28,6 +33,11 @@ impl ShellTool {
         self.working_dir = Some(dir.to_string());
         self
     }
+
+    pub fn with_session_env(mut self, env: SessionEnv) -> Self {
+        self.session_env = Some(env);
+        self
+    }
 }
 
 impl Default for ShellTool {
@@ -36,6 +46,33 @@ impl Default for ShellTool {
     }
 }
 
+/// Kill the process group led by `pid` — the shell and everything it started.
+///
+/// The child must have been spawned with `process_group(0)`.
+#[cfg(unix)]
+pub(crate) fn kill_group(pid: Option<u32>) {
+    if let Some(pid) = pid {
+        // SAFETY: killpg has no memory-safety preconditions
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+}
+
+/// Without process groups only the direct child is killed (via `kill_on_drop`).
+#[cfg(not(unix))]
+pub(crate) fn kill_group(_pid: Option<u32>) {}
+
+/// Kills the command's process group if the tool future is cancelled or
+/// dropped (e.g. its task was aborted) before the command finished.
+struct GroupGuard(Option<u32>);
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        kill_group(self.0.take());
+    }
+}
+
 #[async_trait]
 impl Tool for ShellTool {
     fn name(&self) -> &str {
@@ -67,7 +104,15 @@ impl Tool for ShellTool {
         })
     }
 
+    fn is_destructive(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: Value) -> Result<String> {
+        self.execute_cancellable(args, &CancellationToken::new()).await
+    }
+
+    async fn execute_cancellable(&self, args: Value, cancel: &CancellationToken) -> Result<String> {
         let command = args["command"]
             .as_str()
             .unwrap_or("")
@@ -103,18 +148,35 @@ impl Tool for ShellTool {
         cmd.arg(&arg)
             .arg(&command)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Own process group, so cancelling also kills background jobs and pipelines
+        #[cfg(unix)]
+        cmd.process_group(0);
 
         if let Some(ref dir) = cwd {
             cmd.current_dir(dir);
         }
+        if let Some(env) = &self.session_env {
+            cmd.envs(session_vars(env));
+        }
 
-        let output = cmd.output().await.map_err(|e| {
-            zenclaw_core::error::ZenClawError::ToolExecution {
-                tool: "exec".to_string(),
-                message: format!("Failed to execute: {}", e),
+        let failed = |e: std::io::Error| ZenClawError::ToolExecution {
+            tool: "exec".to_string(),
+            message: format!("Failed to execute: {}", e),
+        };
+        let child = cmd.spawn().map_err(failed)?;
+        let mut guard = GroupGuard(child.id());
+
+        let output = tokio::select! {
+            output = child.wait_with_output() => output.map_err(failed)?,
+            _ = cancel.cancelled() => {
+                tracing::info!("Cancelled: {}", command);
+                return Err(ZenClawError::Cancelled);
             }
-        })?;
+        };
+        // Finished normally — leave anything it put in the background alone
+        guard.0 = None;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -143,3 +205,40 @@ impl Tool for ShellTool {
         Ok(result)
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_cancel_kills_background_child() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let args = json!({
+            "command": format!("sleep 60 & echo $! > {}; wait", pid_file.display())
+        });
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let err = ShellTool::new().execute_cancellable(args, &cancel).await.unwrap_err();
+        assert!(matches!(err, ZenClawError::Cancelled));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // The `sleep 60` must be gone (or a zombie waiting to be reaped)
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        assert!(
+            stat.is_empty() || stat.split_whitespace().nth(2) == Some("Z"),
+            "sleep still running: {}",
+            stat
+        );
+    }
+}
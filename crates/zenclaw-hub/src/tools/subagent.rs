@@ -1,21 +1,150 @@
-//! Sub Agent tool — spawn independent background AI workers.
+//! Sub Agent tools — delegate work to other agents.
 //!
-//! Spawns `zenclaw ask` in the background for autonomous tasks.
+//! - [`SubAgentTool`] runs `zenclaw ask` child processes: one in the background
+//!   for a long autonomous task, or several in parallel whose answers come
+//!   back in the same turn.
+//! - [`AgentDelegateTool`] runs a named specialist from an [`AgentRouter`]
+//!   and returns its answer within the same turn.
 
 use std::env;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use serde_json::{json, Value};
+use tokio::process::Command;
 
 use zenclaw_core::error::Result;
+use zenclaw_core::memory::MemoryStore;
+use zenclaw_core::provider::LlmProvider;
 use zenclaw_core::tool::Tool;
 
+use crate::router::AgentRouter;
+
+/// How many sub-agent levels above this process — set on every child so
+/// sub-agents can't spawn sub-agents forever.
+pub const DEPTH_ENV: &str = "ZENCLAW_SUBAGENT_DEPTH";
+
+/// Most tasks accepted in one parallel call.
+const MAX_TASKS: usize = 10;
+/// Byte budget for the results handed to the synthesis pass (argv limits).
+const MAX_SYNTHESIS_INPUT: usize = 60_000;
+
+/// Sub-agent depth of the current process (0 = top-level agent).
+fn current_depth() -> usize {
+    env::var(DEPTH_ENV).ok().and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
 /// Sub-Agent Spawner.
-pub struct SubAgentTool;
+pub struct SubAgentTool {
+    /// Deepest allowed nesting (a sub-agent at this depth can't spawn more).
+    pub max_depth: usize,
+    /// Sub-agents running at once in parallel mode.
+    pub max_concurrency: usize,
+    /// Time limit per sub-agent in parallel mode.
+    pub timeout: Duration,
+}
 
 impl SubAgentTool {
     pub fn new() -> Self {
-        Self
+        Self {
+            max_depth: 2,
+            max_concurrency: 3,
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    pub fn with_max_concurrency(mut self, n: usize) -> Self {
+        self.max_concurrency = n.max(1);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run `zenclaw ask <task>` one level deeper and return its answer.
+    async fn run_task(&self, task: &str, depth: usize) -> std::result::Result<String, String> {
+        let exe = env::current_exe().unwrap_or_else(|_| "zenclaw".into());
+        let mut cmd = Command::new(exe);
+        cmd.args(["--no-update-check", "ask", task])
+            .env(DEPTH_ENV, (depth + 1).to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let output = match tokio::time::timeout(self.timeout, cmd.output()).await {
+            Err(_) => return Err(format!("timed out after {}s", self.timeout.as_secs())),
+            Ok(Err(e)) => return Err(format!("failed to start: {}", e)),
+            Ok(Ok(output)) => output,
+        };
+
+        let answer = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !answer.is_empty() {
+            return Ok(answer);
+        }
+        // `ask` reports agent errors on stderr, after the token usage line
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty() && !l.starts_with("tokens:"))
+            .unwrap_or("no answer");
+        Err(reason.trim().to_string())
+    }
+
+    /// Run every task concurrently (at most `max_concurrency` at once),
+    /// then optionally merge the answers with one more sub-agent.
+    async fn run_parallel(&self, tasks: Vec<String>, aggregate: bool, depth: usize) -> String {
+        let results: Vec<std::result::Result<String, String>> = stream::iter(tasks.clone())
+            .map(|task| async move { self.run_task(&task, depth).await })
+            .buffered(self.max_concurrency.max(1))
+            .collect()
+            .await;
+
+        let failed = results.iter().filter(|r| r.is_err()).count();
+        let sections: Vec<String> = tasks
+            .iter()
+            .zip(&results)
+            .enumerate()
+            .map(|(i, (task, result))| match result {
+                Ok(answer) => format!("### Task {}: {}\n{}", i + 1, task, answer),
+                Err(e) => format!("### Task {}: {}\nError: {}", i + 1, task, e),
+            })
+            .collect();
+        let combined = sections.join("\n\n");
+
+        if !aggregate || failed == tasks.len() {
+            return combined;
+        }
+
+        let mut input = combined.as_str();
+        if input.len() > MAX_SYNTHESIS_INPUT {
+            let mut cut = MAX_SYNTHESIS_INPUT;
+            while !input.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            input = &input[..cut];
+        }
+        let prompt = format!(
+            "Several sub-agents worked on parts of one job. Merge their results into a single, \
+             coherent answer: remove repetition, resolve or flag contradictions, and mention any \
+             task that failed.\n\n{}",
+            input
+        );
+        match self.run_task(&prompt, depth).await {
+            Ok(summary) => summary,
+            Err(e) => format!("(Synthesis failed: {} — raw results follow.)\n\n{}", e, combined),
+        }
     }
 }
 
@@ -32,8 +161,11 @@ impl Tool for SubAgentTool {
     }
 
     fn description(&self) -> &str {
-        "Spawn a Sub-Agent (a clone of yourself) to work on complex or long-running tasks in the background autonomously. 
-Returns a PID that you MUST use with the 'process' tool (action='status') to check its progress!"
+        "Delegate work to Sub-Agents (clones of yourself that cannot see this conversation). \
+        With 'tasks': run several independent subtasks in parallel and get all their answers back now; \
+        set 'aggregate' to have them merged into one answer. \
+        With a single 'task': spawn one long-running sub-agent in the background — this returns a command \
+        you MUST run with the 'process' tool (action='spawn'), then check it with action='status'."
     }
 
     fn parameters(&self) -> Value {
@@ -42,30 +174,228 @@ Returns a PID that you MUST use with the 'process' tool (action='status') to che
             "properties": {
                 "task": {
                     "type": "string",
-                    "description": "Clear and detailed instructions for the sub-agent."
+                    "description": "Clear and detailed instructions for one background sub-agent."
+                },
+                "tasks": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Independent, self-contained subtasks to run in parallel (max 10)."
+                },
+                "aggregate": {
+                    "type": "boolean",
+                    "description": "With 'tasks': merge the answers into one synthesis. Default: false."
                 }
-            },
-            "required": ["task"]
+            }
         })
     }
 
     async fn execute(&self, args: Value) -> Result<String> {
+        let depth = current_depth();
+        if depth >= self.max_depth {
+            return Ok(format!(
+                "Error: sub-agent depth limit reached ({} of {}). Do this task yourself.",
+                depth, self.max_depth
+            ));
+        }
+
+        let tasks: Vec<String> = args["tasks"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|t| t.as_str())
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !tasks.is_empty() {
+            if tasks.len() > MAX_TASKS {
+                return Ok(format!(
+                    "Error: {} tasks given, at most {} run in one call.",
+                    tasks.len(),
+                    MAX_TASKS
+                ));
+            }
+            let aggregate = args["aggregate"].as_bool().unwrap_or(false);
+            return Ok(self.run_parallel(tasks, aggregate, depth).await);
+        }
+
         let task = args["task"].as_str().unwrap_or("");
         if task.is_empty() {
-            return Ok("Error: 'task' is required.".into());
+            return Ok("Error: 'task' or 'tasks' is required.".into());
         }
 
         let exe_path = env::current_exe().unwrap_or_else(|_| "zenclaw".into());
         let exe_str = exe_path.to_string_lossy();
 
-        // We wrap the task so that the AI knows to use the process tool to actually start it.
-        // Wait! We can actually tell it to delegate to the 'process' tool directly.
-        
-        let shell_cmd = format!("{} ask \"{}\"", exe_str, task.replace('"', "\\\""));
-        
+        // The process tool runs this through the shell, so the depth goes in as an env assignment
+        #[cfg(target_os = "windows")]
+        let depth_prefix = format!("set {}={}&& ", DEPTH_ENV, depth + 1);
+        #[cfg(not(target_os = "windows"))]
+        let depth_prefix = format!("{}={} ", DEPTH_ENV, depth + 1);
+
+        let shell_cmd = format!(
+            "{}{} ask \"{}\"",
+            depth_prefix,
+            exe_str,
+            task.replace('"', "\\\"")
+        );
+
         Ok(format!(
             "Sub-Agent request acknowledged. To actually spawn the sub-agent in the background, you MUST now use the 'process' tool with action='spawn' and this exact command:\n\n{}",
             shell_cmd
         ))
     }
 }
+
+/// Delegate a task to a named agent registered in an [`AgentRouter`].
+///
+/// Each delegation runs in a throwaway session so specialists start clean
+/// and don't leave history behind.
+pub struct AgentDelegateTool {
+    router: Arc<AgentRouter>,
+    provider: Arc<dyn LlmProvider>,
+    memory: Arc<dyn MemoryStore>,
+    description: String,
+}
+
+impl AgentDelegateTool {
+    pub fn new(
+        router: Arc<AgentRouter>,
+        provider: Arc<dyn LlmProvider>,
+        memory: Arc<dyn MemoryStore>,
+    ) -> Self {
+        let roster: Vec<String> = router
+            .list()
+            .into_iter()
+            .map(|(name, desc)| format!("- {}: {}", name, desc))
+            .collect();
+        let description = format!(
+            "Ask a specialist agent to handle a task and return its answer. Give it a complete, self-contained task — it cannot see this conversation.\nAvailable agents:\n{}",
+            roster.join("\n")
+        );
+
+        Self {
+            router,
+            provider,
+            memory,
+            description,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for AgentDelegateTool {
+    fn name(&self) -> &str {
+        "ask_agent"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> Value {
+        let names: Vec<&str> = self.router.list().into_iter().map(|(name, _)| name).collect();
+        json!({
+            "type": "object",
+            "properties": {
+                "agent": {
+                    "type": "string",
+                    "enum": names,
+                    "description": "Name of the agent to delegate to"
+                },
+                "task": {
+                    "type": "string",
+                    "description": "Clear and detailed instructions for the agent."
+                }
+            },
+            "required": ["agent", "task"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<String> {
+        let agent = args["agent"].as_str().unwrap_or("");
+        let task = args["task"].as_str().unwrap_or("");
+        if agent.is_empty() || task.is_empty() {
+            return Ok("Error: 'agent' and 'task' are required.".into());
+        }
+
+        let session_key = format!("subagent:{}:{}", agent, uuid::Uuid::new_v4());
+        let result = self
+            .router
+            .ask(agent, self.provider.as_ref(), self.memory.as_ref(), task, &session_key)
+            .await;
+        let _ = self.memory.clear_history(&session_key).await;
+
+        match result {
+            Ok(answer) => Ok(format!("[{}] {}", agent, answer)),
+            Err(e) => Ok(format!("Error: agent '{}' failed: {}", agent, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::agent::Agent;
+    use zenclaw_core::error::ZenClawError;
+    use zenclaw_core::memory::InMemoryStore;
+    use zenclaw_core::message::{LlmResponse, TokenUsage};
+    use zenclaw_core::provider::ChatRequest;
+    use zenclaw_core::tool::ToolRegistry;
+
+    /// Answers with the last user message it was sent.
+    struct EchoProvider;
+
+    #[async_trait]
+    impl LlmProvider for EchoProvider {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn default_model(&self) -> &str {
+            "echo-model"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<LlmResponse> {
+            let task = request
+                .messages
+                .iter()
+                .rev()
+                .find_map(|m| m.content.clone())
+                .ok_or_else(|| ZenClawError::Provider("no messages".into()))?;
+            Ok(LlmResponse {
+                content: Some(format!("done: {}", task)),
+                tool_calls: Vec::new(),
+                model: "echo-model".into(),
+                usage: TokenUsage::default(),
+                finish_reason: "stop".into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ask_agent_through_the_registry() {
+        let mut router = AgentRouter::new();
+        router.register("researcher", "Finds facts", vec![], Agent::new());
+        let memory: Arc<dyn MemoryStore> = Arc::new(InMemoryStore::new());
+
+        let mut tools = ToolRegistry::new();
+        tools.register(AgentDelegateTool::new(Arc::new(router), Arc::new(EchoProvider), memory.clone()));
+        assert_eq!(tools.get("ask_agent").unwrap().parameters()["properties"]["agent"]["enum"], json!(["researcher"]));
+
+        let answer = tools
+            .execute("ask_agent", json!({ "agent": "researcher", "task": "population of Bandung" }))
+            .await
+            .unwrap();
+        assert_eq!(answer, "[researcher] done: population of Bandung");
+
+        let unknown = tools
+            .execute("ask_agent", json!({ "agent": "poet", "task": "a haiku" }))
+            .await
+            .unwrap();
+        assert!(unknown.starts_with("Error: agent 'poet' failed: Unknown agent"), "{}", unknown);
+    }
+}
@@ -1,4 +1,7 @@
 //! Web fetch tool — HTTP requests for retrieving web content.
+//!
+//! HTML pages are reduced to their main content and converted to Markdown
+//! (see `html_markdown`); other content types pass through unchanged.
 
 use async_trait::async_trait;
 use reqwest::Client;
@@ -7,10 +10,15 @@ use serde_json::{json, Value};
 use zenclaw_core::error::Result;
 use zenclaw_core::tool::Tool;
 
+use super::cache::ResponseCache;
+use super::html_markdown::html_to_markdown;
+
 /// Fetch content from a URL.
 pub struct WebFetchTool {
     client: Client,
     max_body_size: usize,
+    /// Recent successful GET responses keyed by URL.
+    cache: ResponseCache,
 }
 
 impl WebFetchTool {
@@ -21,8 +29,15 @@ impl WebFetchTool {
                 .build()
                 .unwrap_or_default(),
             max_body_size: 50_000,
+            cache: ResponseCache::default(),
         }
     }
+
+    /// Keep GET responses for `ttl` (5 minutes by default); zero disables caching.
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache = ResponseCache::new(ttl, super::cache::DEFAULT_CAPACITY);
+        self
+    }
 }
 
 impl Default for WebFetchTool {
@@ -38,7 +53,8 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch content from a URL via HTTP GET. Returns the response body as text."
+        "Fetch content from a URL via HTTP GET. HTML pages are returned as clean Markdown \
+        of the main content (headings, links, lists); other responses as text."
     }
 
     fn parameters(&self) -> Value {
@@ -61,6 +77,10 @@ impl Tool for WebFetchTool {
                 "headers": {
                     "type": "object",
                     "description": "Custom headers as key-value pairs"
+                },
+                "raw": {
+                    "type": "boolean",
+                    "description": "Return HTML as-is instead of converting it to Markdown. Default: false"
                 }
             },
             "required": ["url"]
@@ -70,6 +90,15 @@ impl Tool for WebFetchTool {
     async fn execute(&self, args: Value) -> Result<String> {
         let url = args["url"].as_str().unwrap_or("");
         let method = args["method"].as_str().unwrap_or("GET").to_uppercase();
+        let raw = args["raw"].as_bool().unwrap_or(false);
+
+        // Only plain GETs are cached: other methods or custom headers may change the answer
+        let cacheable = method == "GET" && args["headers"].as_object().is_none_or(|h| h.is_empty());
+        let cache_key = format!("{}|raw={}", url, raw);
+        if cacheable && let Some(cached) = self.cache.get(&cache_key) {
+            tracing::info!("web_fetch: cache_hit for {}", url);
+            return Ok(cached);
+        }
 
         let mut request = match method.as_str() {
             "POST" => self.client.post(url),
@@ -103,21 +132,35 @@ impl Tool for WebFetchTool {
                     .collect::<Vec<_>>()
                     .join("\n");
 
-                let body = resp.text().await.unwrap_or_default();
+                let is_html = resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|ct| ct.contains("text/html") || ct.contains("application/xhtml"));
+                let final_url = resp.url().to_string();
+
+                let mut body = resp.text().await.unwrap_or_default();
+                if is_html && !raw {
+                    body = html_to_markdown(&body, &final_url);
+                }
                 let truncated = if body.len() > self.max_body_size {
-                    format!(
-                        "{}...\n[truncated, {} total bytes]",
-                        &body[..self.max_body_size],
-                        body.len()
-                    )
+                    let mut cut = self.max_body_size;
+                    while !body.is_char_boundary(cut) {
+                        cut -= 1;
+                    }
+                    format!("{}...(truncated, {} total bytes)", &body[..cut], body.len())
                 } else {
                     body
                 };
 
-                Ok(format!(
+                let output = format!(
                     "Status: {}\n\nHeaders:\n{}\n\nBody:\n{}",
                     status, headers_str, truncated
-                ))
+                );
+                if cacheable && status.is_success() {
+                    self.cache.insert(&cache_key, output.clone());
+                }
+                Ok(output)
             }
             Err(e) => Ok(format!("Error fetching {}: {}", url, e)),
         }
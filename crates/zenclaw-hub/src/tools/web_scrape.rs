@@ -1,26 +1,246 @@
 //! Web scrape tool — Extract clean Markdown from web pages.
+//!
+//! Besides single pages it can crawl a site (same host only). Crawl progress
+//! (frontier + visited set) is checkpointed after every page under
+//! `<data_dir>/zenclaw/crawls/<crawl_id>.json`, and page content is appended
+//! to `<crawl_id>.md`, so a crawl cut short by the tool timeout or a cancel
+//! can be resumed with `resume: <crawl_id>` instead of starting over. A
+//! crawl can only be resumed from the session that started it.
+
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use zenclaw_core::error::Result;
-use zenclaw_core::tool::Tool;
+use zenclaw_core::tool::{current_session, Tool};
+
+/// Stop crawling (and checkpoint) before the agent's 60s tool timeout hits.
+const CRAWL_BUDGET: Duration = Duration::from_secs(50);
+/// Crawl ids are this many lowercase hex digits.
+const CRAWL_ID_LEN: usize = 8;
 
 pub struct WebScrapeTool {
     client: Client,
+    crawl_dir: PathBuf,
 }
 
+/// Checkpointed state of a multi-page crawl.
+#[derive(Debug, Serialize, Deserialize)]
+struct CrawlState {
+    id: String,
+    /// Session that started the crawl; only it may resume it.
+    #[serde(default)]
+    session: Option<String>,
+    root: String,
+    max_pages: usize,
+    frontier: VecDeque<String>,
+    visited: HashSet<String>,
+    /// Pages fetched so far, in order.
+    pages: Vec<String>,
+}
 
 impl WebScrapeTool {
     pub fn new() -> Self {
+        let crawl_dir = dirs::data_dir()
+            .unwrap_or_else(|| std::path::PathBuf::from("."))
+            .join("zenclaw")
+            .join("crawls");
+
         Self {
             client: Client::builder()
                 .timeout(std::time::Duration::from_secs(45))
                 .build()
                 .unwrap_or_default(),
+            crawl_dir,
+        }
+    }
+
+    fn state_path(&self, id: &str) -> PathBuf {
+        self.crawl_dir.join(format!("{}.json", id))
+    }
+
+    fn content_path(&self, id: &str) -> PathBuf {
+        self.crawl_dir.join(format!("{}.md", id))
+    }
+
+    /// Saved crawl `id` of the current session. Anything but a generated id
+    /// is refused, so the model can't point `resume` outside the crawl dir.
+    fn load_state(&self, id: &str) -> Option<CrawlState> {
+        if !is_crawl_id(id) {
+            return None;
+        }
+        let raw = std::fs::read_to_string(self.state_path(id)).ok()?;
+        let state: CrawlState = serde_json::from_str(&raw).ok()?;
+        (state.session == current_session()).then_some(state)
+    }
+
+    fn save_state(&self, state: &CrawlState) {
+        std::fs::create_dir_all(&self.crawl_dir).ok();
+        match serde_json::to_string(state) {
+            Ok(raw) => {
+                if let Err(e) = std::fs::write(self.state_path(&state.id), raw) {
+                    tracing::warn!("Failed to checkpoint crawl {}: {}", state.id, e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize crawl {}: {}", state.id, e),
+        }
+    }
+
+    /// Fetch a page as Markdown: Jina Reader first, local headless browser as
+    /// fallback. Returns `(method, body)`.
+    async fn fetch(&self, url: &str) -> std::result::Result<(&'static str, String), String> {
+        // 1. Try Jina Reader API to get clean Markdown
+        let target_url = format!("https://r.jina.ai/{}", url);
+
+        let request = self.client.get(&target_url)
+            .header("X-Return-Format", "markdown");
+
+        match request.send().await {
+            Ok(resp) => {
+                let status = resp.status();
+                if !status.is_success() {
+                    tracing::warn!("Jina Reader API failed with status {}. Falling back to local Headless Browser...", status);
+                } else {
+                    return Ok(("MARKDOWN", resp.text().await.unwrap_or_default()));
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Jina extract failed for {}: {}. Falling back to Headless Browser...", url, e);
+            }
+        }
+
+        // Fallback: Local Headless SPA Browser (Puppeteer)
+        tracing::info!("🕸️ Initiating Local Headless Scrape for {}...", url);
+        match tokio::process::Command::new("node")
+            .arg("bridge/scrape.js")
+            .arg(url)
+            .output()
+            .await
+        {
+            Ok(output) => {
+                let body = String::from_utf8_lossy(&output.stdout).to_string();
+                if output.status.success() && !body.is_empty() {
+                    Ok(("TEXT VIA HEADLESS BROWSER", body))
+                } else {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    Err(format!("Error: Headless browser failed. Status: {}. Error: {}", output.status, stderr))
+                }
+            }
+            Err(e) => Err(format!("Error: Both Jina API and Local Headless Browser failed to extract {}: {}", url, e)),
+        }
+    }
+
+    /// Crawl (or resume crawling) until the page limit, an empty frontier, or
+    /// the time budget. State is checkpointed after every page.
+    async fn crawl(&self, mut state: CrawlState, max_chars: usize) -> String {
+        let started = Instant::now();
+        let resumed_at = state.pages.len();
+        let content_path = self.content_path(&state.id);
+        let root_host = Url::parse(&state.root).ok().and_then(|u| u.host_str().map(str::to_string));
+
+        while state.pages.len() < state.max_pages && started.elapsed() < CRAWL_BUDGET {
+            let Some(url) = state.frontier.pop_front() else { break };
+            if !state.visited.insert(url.clone()) {
+                continue;
+            }
+
+            match self.fetch(&url).await {
+                Ok((_, body)) => {
+                    for link in extract_links(&url, &body) {
+                        let same_host = Url::parse(&link).ok().and_then(|u| u.host_str().map(str::to_string)) == root_host;
+                        if same_host && !state.visited.contains(&link) && !state.frontier.contains(&link) {
+                            state.frontier.push_back(link);
+                        }
+                    }
+
+                    let page = format!("\n\n--- PAGE: {} ---\n\n{}", url, body);
+                    let appended = std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&content_path)
+                        .and_then(|mut f| std::io::Write::write_all(&mut f, page.as_bytes()));
+                    if let Err(e) = appended {
+                        tracing::warn!("Failed to save crawled page {}: {}", url, e);
+                    }
+                    state.pages.push(url);
+                }
+                Err(e) => tracing::warn!("Crawl {} skipped {}: {}", state.id, url, e),
+            }
+
+            self.save_state(&state);
+        }
+
+        let done = state.pages.len() >= state.max_pages || state.frontier.is_empty();
+        let mut out = format!(
+            "--- CRAWL {} OF {} ---\n\nPages: {} fetched ({} this run), {} queued\nContent file: {}\n",
+            state.id,
+            state.root,
+            state.pages.len(),
+            state.pages.len() - resumed_at,
+            state.frontier.len(),
+            content_path.display()
+        );
+        if done {
+            out.push_str("Status: complete\n");
+        } else {
+            out.push_str(&format!(
+                "Status: paused (time budget) — call web_scrape again with resume=\"{}\" to continue\n",
+                state.id
+            ));
+        }
+
+        let new_pages = &state.pages[resumed_at..];
+        if !new_pages.is_empty() {
+            out.push_str("\nFetched this run:\n");
+            for page in new_pages {
+                out.push_str(&format!("  • {}\n", page));
+            }
+        }
+
+        // Include the tail of the content (the pages from this run) up to max_chars
+        if let Ok(content) = std::fs::read_to_string(&content_path) {
+            let start = content.len().saturating_sub(max_chars);
+            let start = (start..content.len()).find(|&i| content.is_char_boundary(i)).unwrap_or(content.len());
+            out.push_str(&format!("\n{}", &content[start..]));
+        }
+
+        out
+    }
+}
+
+fn new_crawl_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..CRAWL_ID_LEN].to_string()
+}
+
+fn is_crawl_id(id: &str) -> bool {
+    id.len() == CRAWL_ID_LEN && id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Absolute http(s) links found in Markdown `[..](url)` or HTML `href="url"`,
+/// with fragments removed.
+fn extract_links(base: &str, body: &str) -> Vec<String> {
+    let Ok(base) = Url::parse(base) else { return Vec::new() };
+    let re = regex::Regex::new(r#"\]\(([^)\s]+)\)|href="([^"]+)""#).unwrap();
+
+    let mut links = Vec::new();
+    for caps in re.captures_iter(body) {
+        let Some(raw) = caps.get(1).or_else(|| caps.get(2)) else { continue };
+        let Ok(mut url) = base.join(raw.as_str()) else { continue };
+        if url.scheme() != "http" && url.scheme() != "https" {
+            continue;
+        }
+        url.set_fragment(None);
+        let url = url.to_string();
+        if !links.contains(&url) {
+            links.push(url);
         }
     }
+    links
 }
 
 impl Default for WebScrapeTool {
@@ -36,7 +256,7 @@ impl Tool for WebScrapeTool {
     }
 
     fn description(&self) -> &str {
-        "Extract clean Markdown from any website URL. Best for reading articles, blogs, or documentation. Removes ads, navbars, and HTML bloat."
+        "Extract clean Markdown from any website URL. Best for reading articles, blogs, or documentation. Removes ads, navbars, and HTML bloat. Set max_pages > 1 to crawl a site; an unfinished crawl can be continued with resume=<crawl_id>."
     }
 
     fn parameters(&self) -> Value {
@@ -50,19 +270,21 @@ impl Tool for WebScrapeTool {
                 "max_chars": {
                     "type": "integer",
                     "description": "Maximum characters to return (default: 8000 ≈ 2K tokens). Use higher values only when you need full document (max: 50000)."
+                },
+                "max_pages": {
+                    "type": "integer",
+                    "description": "Crawl up to this many pages on the same site, following links from url (default: 1 = single page, max: 500)"
+                },
+                "resume": {
+                    "type": "string",
+                    "description": "Crawl id of an unfinished crawl to continue (url is not needed)"
                 }
-            },
-            "required": ["url"]
+            }
         })
     }
 
 
     async fn execute(&self, args: Value) -> Result<String> {
-        let url = args["url"].as_str().unwrap_or("");
-        if url.is_empty() {
-            return Ok("Error: No URL provided".into());
-        }
-
         // Respect LLM-requested limit; default 8K chars ≈ 2K tokens — enough for
         // most factual queries. LLM can request more if explicitly needed.
         let max_chars = args["max_chars"]
@@ -70,72 +292,111 @@ impl Tool for WebScrapeTool {
             .map(|n| n.clamp(500, 50_000) as usize)
             .unwrap_or(8_000);
 
-        // 1. Try Jina Reader API to get clean Markdown
-        let target_url = format!("https://r.jina.ai/{}", url);
-        
-        let request = self.client.get(&target_url)
-            .header("X-Return-Format", "markdown");
+        if let Some(id) = args["resume"].as_str().filter(|s| !s.is_empty()) {
+            return match self.load_state(id) {
+                Some(state) => Ok(self.crawl(state, max_chars).await),
+                None => Ok(format!("Error: No saved crawl with id '{}'", id)),
+            };
+        }
 
-        match request.send().await {
-            Ok(resp) => {
-                let status = resp.status();
-                if !status.is_success() {
-                    tracing::warn!("Jina Reader API failed with status {}. Falling back to local Headless Browser...", status);
-                } else {
-                    let body = resp.text().await.unwrap_or_default();
-                    let truncated = if body.len() > max_chars {
-                        format!(
-                            "{}...\n\n[Truncated at {} chars. Full: {} chars. Use max_chars={} for more.]",
-                            &body[..max_chars], max_chars, body.len(),
-                            body.len().min(50_000)
-                        )
-                    } else {
-                        body
-                    };
-
-                    return Ok(format!(
-                        "--- EXTRACTED MARKDOWN FROM {} ---\n\n{}",
-                        url, truncated
-                    ));
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Jina extract failed for {}: {}. Falling back to Headless Browser...", url, e);
-            }
+        let url = args["url"].as_str().unwrap_or("");
+        if url.is_empty() {
+            return Ok("Error: No URL provided".into());
         }
 
-        // Fallback: Local Headless SPA Browser (Puppeteer)
-        tracing::info!("🕸️ Initiating Local Headless Scrape for {}...", url);
-        match tokio::process::Command::new("node")
-            .arg("bridge/scrape.js")
-            .arg(url)
-            .output()
-            .await
-        {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let body = stdout.to_string();
-                if output.status.success() && !body.is_empty() {
-                    let truncated = if body.len() > max_chars {
-                        format!(
-                            "{}...\n\n[Truncated at {} chars. Full: {} chars.]",
-                            &body[..max_chars], max_chars, body.len()
-                        )
-                    } else {
-                        body
-                    };
-
-
-                    Ok(format!(
-                        "--- EXTRACTED TEXT VIA HEADLESS BROWSER FROM {} ---\n\n{}",
-                        url, truncated
-                    ))
+        let max_pages = args["max_pages"].as_u64().map(|n| n.clamp(1, 500) as usize).unwrap_or(1);
+        if max_pages > 1 {
+            let state = CrawlState {
+                id: new_crawl_id(),
+                session: current_session(),
+                root: url.to_string(),
+                max_pages,
+                frontier: VecDeque::from([url.to_string()]),
+                visited: HashSet::new(),
+                pages: Vec::new(),
+            };
+            self.save_state(&state);
+            return Ok(self.crawl(state, max_chars).await);
+        }
+
+        match self.fetch(url).await {
+            Ok((method, body)) => {
+                let truncated = if body.len() > max_chars {
+                    format!(
+                        "{}...\n\n[Truncated at {} chars. Full: {} chars. Use max_chars={} for more.]",
+                        &body[..max_chars], max_chars, body.len(),
+                        body.len().min(50_000)
+                    )
                 } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    Ok(format!("Error: Headless browser failed. Status: {}. Error: {}", output.status, stderr))
-                }
+                    body
+                };
+
+                Ok(format!(
+                    "--- EXTRACTED {} FROM {} ---\n\n{}",
+                    method, url, truncated
+                ))
             }
-            Err(e) => Ok(format!("Error: Both Jina API and Local Headless Browser failed to extract {}: {}", url, e)),
+            Err(e) => Ok(e),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenclaw_core::tool::with_session;
+
+    fn tool(dir: &std::path::Path) -> WebScrapeTool {
+        WebScrapeTool { client: Client::new(), crawl_dir: dir.to_path_buf() }
+    }
+
+    fn state(id: &str, session: Option<&str>) -> CrawlState {
+        CrawlState {
+            id: id.into(),
+            session: session.map(str::to_string),
+            root: "https://example.com".into(),
+            max_pages: 5,
+            frontier: VecDeque::new(),
+            visited: HashSet::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_crawl_ids() {
+        assert!(is_crawl_id(&new_crawl_id()));
+        assert!(is_crawl_id("0a1b2c3d"));
+        for bad in ["", "0a1b2c3", "0a1b2c3d4", "0A1B2C3D", "../../x", "../state", "0a1b/c3d"] {
+            assert!(!is_crawl_id(bad), "{}", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_rejects_other_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let crawls = dir.path().join("crawls");
+        std::fs::create_dir_all(&crawls).unwrap();
+        // A valid crawl file one level up, reachable with a traversing id
+        std::fs::write(
+            dir.path().join("secret.json"),
+            serde_json::to_string(&state("secret", None)).unwrap(),
+        )
+        .unwrap();
+
+        let out = tool(&crawls).execute(json!({"resume": "../secret"})).await.unwrap();
+        assert!(out.starts_with("Error: No saved crawl"), "{}", out);
+    }
+
+    #[tokio::test]
+    async fn test_resume_is_per_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = tool(dir.path());
+        tool.save_state(&state("0a1b2c3d", Some("telegram:1")));
+
+        let other = with_session("telegram:2", async { tool.load_state("0a1b2c3d") }).await;
+        assert!(other.is_none());
+        assert!(tool.load_state("0a1b2c3d").is_none());
+        let own = with_session("telegram:1", async { tool.load_state("0a1b2c3d") }).await;
+        assert_eq!(own.unwrap().root, "https://example.com");
+    }
+}
@@ -7,17 +7,36 @@
 //! 3. DDG Lite endpoint        — Simpler HTML, good fallback on restricted nets
 //! 4. Wikipedia Search API     — Best for encyclopedic/factual queries
 //!
-//! Language detection is delegated to the LLM agent loop via the `lang`
-//! parameter — the AI detects the user's language and passes 'id'/'en'/etc.
-//! This keeps the tool generic with no hardcoded language heuristics.
+//! The search language comes from the `lang` parameter ('id'/'en'), which
+//! the LLM is asked to pass. With `lang='auto'` (or none) the query's
+//! language is detected with `whatlang` and picks the Wikipedia subdomain
+//! and Jina `X-Locale`; unclear queries search English and Indonesian
+//! Wikipedia.
+//!
+//! `offset` pages through results: every engine skips its first `offset`
+//! hits (Wikipedia via `sroffset`, DDG Lite via its `s` form parameter).
+//! URLs a session was already shown for the same query are left out of
+//! later pages.
+//!
+//! `format='json'` returns `{instant_answer, results: [{title, url, snippet,
+//! source_engine}]}` for programmatic consumers instead of markdown.
 
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::{json, Value};
 use std::collections::HashSet;
+use whatlang::Lang;
 
 use zenclaw_core::error::Result;
-use zenclaw_core::tool::Tool;
+use zenclaw_core::tool::{current_session, Tool};
+
+use super::cache::ResponseCache;
+
+/// Highest `offset` accepted.
+const MAX_OFFSET: u64 = 100;
+
+/// How long the URLs shown to a session are remembered for paging.
+const SEEN_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 60);
 
 // Rotate between realistic browser User-Agents to reduce block chance
 const USER_AGENTS: &[&str] = &[
@@ -27,6 +46,86 @@ const USER_AGENTS: &[&str] = &[
     "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.3 Safari/605.1.15",
 ];
 
+/// Languages `auto` can resolve to: (whatlang language, Wikipedia subdomain,
+/// Jina locale).
+const LANGS: &[(Lang, &str, &str)] = &[
+    (Lang::Eng, "en", "en-US"),
+    (Lang::Ind, "id", "id-ID"),
+    (Lang::Jav, "jv", "id-ID"),
+    (Lang::Spa, "es", "es-ES"),
+    (Lang::Por, "pt", "pt-BR"),
+    (Lang::Fra, "fr", "fr-FR"),
+    (Lang::Deu, "de", "de-DE"),
+    (Lang::Ita, "it", "it-IT"),
+    (Lang::Nld, "nl", "nl-NL"),
+    (Lang::Rus, "ru", "ru-RU"),
+    (Lang::Tur, "tr", "tr-TR"),
+    (Lang::Vie, "vi", "vi-VN"),
+    (Lang::Tha, "th", "th-TH"),
+    (Lang::Tgl, "tl", "fil-PH"),
+    (Lang::Jpn, "ja", "ja-JP"),
+    (Lang::Kor, "ko", "ko-KR"),
+    (Lang::Cmn, "zh", "zh-CN"),
+    (Lang::Ara, "ar", "ar-SA"),
+    (Lang::Hin, "hi", "hi-IN"),
+];
+
+/// The language to search in: an explicit `lang` as given; for `auto`, the
+/// one detected in `query`, or `auto` when whatlang doesn't find the
+/// detection reliable.
+fn resolve_lang<'a>(query: &str, lang: &'a str) -> &'a str {
+    if lang != "auto" {
+        return lang;
+    }
+    whatlang::detect(query)
+        .filter(|info| info.is_reliable())
+        .and_then(|info| LANGS.iter().find(|(l, _, _)| *l == info.lang()))
+        .map_or("auto", |(_, code, _)| code)
+}
+
+/// Jina `X-Locale` for a language code ("" = none).
+fn jina_locale(lang: &str) -> &'static str {
+    LANGS
+        .iter()
+        .find(|(_, code, _)| *code == lang)
+        .map_or("", |(_, _, locale)| locale)
+}
+
+/// Wikipedia subdomains to search: the language's own, or English and
+/// Indonesian when it is `auto` (or one Wikipedia doesn't have here).
+fn wiki_langs(lang: &str) -> &'static [&'static str] {
+    match LANGS.iter().find(|(_, code, _)| *code == lang) {
+        Some((_, code, _)) => std::slice::from_ref(code),
+        None => &["en", "id"],
+    }
+}
+
+/// DDG Lite results page for `query`, starting after `offset` hits.
+fn ddg_lite_url(query: &str, lang: &str, offset: usize) -> String {
+    let kl = match lang {
+        "id" => "id-id",
+        "en" => "us-en",
+        _ => "wt-wt",
+    };
+    let mut url = format!("https://lite.duckduckgo.com/lite/?q={}&kl={}", percent_encode(query), kl);
+    if offset > 0 {
+        // The same fields DDG Lite's own "Next Page" form sends
+        url.push_str(&format!("&s={}&dc={}", offset, offset + 1));
+    }
+    url
+}
+
+/// Wikipedia search API call for `query` on the `wiki` subdomain.
+fn wikipedia_url(wiki: &str, query: &str, max: usize, offset: usize) -> String {
+    format!(
+        "https://{}.wikipedia.org/w/api.php?action=query&list=search&srsearch={}&srlimit={}&sroffset={}&format=json&utf8=1&srprop=snippet|titlesnippet",
+        wiki,
+        percent_encode(query),
+        max,
+        offset
+    )
+}
+
 fn pick_user_agent() -> &'static str {
     let idx = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -54,6 +153,10 @@ fn percent_encode(s: &str) -> String {
 pub struct WebSearchTool {
     client: Client,
     max_results: usize,
+    /// Recent results keyed by normalized (query, lang, max_results, offset).
+    cache: ResponseCache,
+    /// URLs already shown, newline-separated, keyed by (session, query).
+    seen: ResponseCache,
 }
 
 impl WebSearchTool {
@@ -66,7 +169,27 @@ impl WebSearchTool {
             .build()
             .unwrap_or_default();
 
-        Self { client, max_results: 8 }
+        Self {
+            client,
+            max_results: 8,
+            cache: ResponseCache::default(),
+            seen: ResponseCache::new(SEEN_TTL, super::cache::DEFAULT_CAPACITY),
+        }
+    }
+
+    /// Whether later pages skip URLs the session was already shown (on by
+    /// default).
+    pub fn with_page_dedup(mut self, enabled: bool) -> Self {
+        if !enabled {
+            self.seen = ResponseCache::disabled();
+        }
+        self
+    }
+
+    /// Keep results for `ttl` (5 minutes by default); zero disables caching.
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache = ResponseCache::new(ttl, super::cache::DEFAULT_CAPACITY);
+        self
     }
 }
 
@@ -89,7 +212,7 @@ impl Tool for WebSearchTool {
         IMPORTANT: Detect the language of the user's query and pass it as 'lang' parameter:\n\
         - If user writes in Indonesian/Bahasa Indonesia → pass lang='id'\n\
         - If user writes in English → pass lang='en'\n\
-        - If unsure → pass lang='auto'\n\
+        - If unsure → pass lang='auto' and the tool detects it from the query\n\
         Always search in the same language as the user's question for best results."
     }
 
@@ -105,6 +228,15 @@ impl Tool for WebSearchTool {
                     "type": "integer",
                     "description": "Maximum results to return (1-15, default: 8)"
                 },
+                "format": {
+                    "type": "string",
+                    "enum": ["markdown", "json"],
+                    "description": "'markdown' (default) to read, 'json' for {instant_answer, results: [{title, url, snippet, source_engine}]} to parse"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Results to skip, for the next page when the first one is thin (e.g. offset=8 after 8 results; default: 0)"
+                },
                 "lang": {
                     "type": "string",
                     "enum": ["id", "en", "auto"],
@@ -126,38 +258,67 @@ impl Tool for WebSearchTool {
         if query.is_empty() {
             return Ok("Error: query is required".to_string());
         }
+        let lang = resolve_lang(query, lang);
+        let offset = args["offset"].as_u64().map(|n| n.min(MAX_OFFSET) as usize).unwrap_or(0);
+        let json_format = args["format"].as_str() == Some("json");
+
+        let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let seen_key = current_session()
+            .filter(|_| self.seen.is_enabled())
+            .map(|session| format!("{}|{}", session, normalized));
+        // Later pages depend on what the session has seen
+        let cache_key = match &seen_key {
+            Some(key) if offset > 0 => format!("{}|{}|{}|{}|{}", key, lang, max_results, offset, json_format),
+            _ => format!("{}|{}|{}|{}|{}", normalized, lang, max_results, offset, json_format),
+        };
+        if let Some(cached) = self.cache.get(&cache_key) {
+            tracing::info!("web_search: cache_hit for \"{}\" (lang={}, offset={})", query, lang, offset);
+            return Ok(cached);
+        }
 
-        tracing::info!("web_search: \"{}\" (max={}, lang={})", query, max_results, lang);
+        tracing::info!("web_search: \"{}\" (max={}, lang={}, offset={})", query, max_results, lang, offset);
 
         let mut output = String::new();
-        let mut all_results: Vec<SearchResult> = Vec::new();
-        let mut seen_urls: HashSet<String> = HashSet::new();
+        // Each result with the engine that found it
+        let mut all_results: Vec<(&'static str, SearchResult)> = Vec::new();
+        let shown = seen_key.as_deref().map(|key| self.shown_urls(key)).unwrap_or_default();
+        // Results from earlier pages count as duplicates
+        let mut seen_urls: HashSet<String> = if offset > 0 { shown.clone() } else { HashSet::new() };
 
         // ── Run all engines in parallel ───────────────────────────────────
+        let first_page = offset == 0;
         let (instant_opt, jina_results, lite_results, wiki_results) = tokio::join!(
-            self.ddg_instant(query),
-            self.jina_search(query, max_results, lang),
-            self.ddg_lite(query, max_results, lang),
-            self.wikipedia_search(query, 4, lang),
+            async {
+                if first_page {
+                    self.ddg_instant(query).await
+                } else {
+                    None
+                }
+            },
+            self.jina_search(query, offset + max_results, lang),
+            self.ddg_lite(query, max_results, lang, offset),
+            self.wikipedia_search(query, 4, lang, offset),
         );
+        // Jina has no paging: skip the first `offset` of a longer list
+        let jina_results = jina_results.into_iter().skip(offset);
 
         // ── Strategy 1: DDG Instant Answer (direct factual answer, highest priority) ──
-        if let Some(instant) = instant_opt {
-            output.push_str(&instant);
+        if let Some(instant) = &instant_opt {
+            output.push_str(instant);
             output.push_str("\n\n");
         }
 
         // ── Strategy 2: Jina Search (primary web results, clean markdown) ──
         for r in jina_results {
             if seen_urls.insert(r.url.clone()) {
-                all_results.push(r);
+                all_results.push(("jina", r));
             }
         }
 
         // ── Strategy 3: DDG Lite (fallback, always merge — dedup handles overlap) ──
         for r in lite_results {
             if seen_urls.insert(r.url.clone()) {
-                all_results.push(r);
+                all_results.push(("ddg", r));
             }
         }
 
@@ -168,14 +329,24 @@ impl Tool for WebSearchTool {
                 wiki_top_url = Some(r.url.clone());
             }
             if seen_urls.insert(r.url.clone()) {
-                all_results.push(r);
+                all_results.push(("wikipedia", r));
+            }
+        }
+
+        if json_format {
+            let take = all_results.len().min(max_results);
+            let output = json_output(instant_opt.as_deref(), &all_results[..take]);
+            if let Some(key) = &seen_key {
+                self.remember_shown(key, shown, all_results[..take].iter().map(|(_, r)| r.url.clone()));
             }
+            self.cache.insert(&cache_key, output.clone());
+            return Ok(output);
         }
 
         // ── Strategy 5: Jina Reader — full article when no instant answer ──
         // Fetches the top Wikipedia article for rich factual context.
         // Useful on networks where DuckDuckGo endpoints are blocked.
-        if output.trim().is_empty() && let Some(wiki_url) = wiki_top_url {
+        if first_page && output.trim().is_empty() && let Some(wiki_url) = wiki_top_url {
             tracing::info!("web_search: Jina Reader fallback for: {}", wiki_url);
             let jina_url = format!("https://r.jina.ai/{}", wiki_url);
             if let Ok(resp) = self.client.get(&jina_url)
@@ -200,10 +371,10 @@ impl Tool for WebSearchTool {
         let take = all_results.len().min(max_results);
         if take > 0 {
             output.push_str(&format!("## Web Results for: \"{}\"\n\n", query));
-            for (i, r) in all_results[..take].iter().enumerate() {
+            for (i, (_, r)) in all_results[..take].iter().enumerate() {
                 output.push_str(&format!(
                     "{}. **{}**\n   🔗 {}\n",
-                    i + 1,
+                    offset + i + 1,
                     r.title,
                     r.url
                 ));
@@ -212,6 +383,9 @@ impl Tool for WebSearchTool {
                 }
                 output.push('\n');
             }
+            if take == max_results {
+                output.push_str(&format!("_More results: call again with offset={}_\n", offset + max_results));
+            }
         }
 
         if output.trim().is_empty() {
@@ -228,6 +402,10 @@ impl Tool for WebSearchTool {
 
         let bytes = output.len();
         tracing::debug!("web_search: {} total results ({} bytes)", take, bytes);
+        if let Some(key) = &seen_key {
+            self.remember_shown(key, shown, all_results[..take].iter().map(|(_, r)| r.url.clone()));
+        }
+        self.cache.insert(&cache_key, output.clone());
         Ok(output)
     }
 }
@@ -237,6 +415,21 @@ impl Tool for WebSearchTool {
 // ─────────────────────────────────────────────────────────────────────────────
 
 impl WebSearchTool {
+    /// URLs already shown for `key` (session and query).
+    fn shown_urls(&self, key: &str) -> HashSet<String> {
+        self.seen
+            .get(key)
+            .map(|urls| urls.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn remember_shown(&self, key: &str, mut shown: HashSet<String>, urls: impl Iterator<Item = String>) {
+        shown.extend(urls);
+        let mut urls: Vec<String> = shown.into_iter().collect();
+        urls.sort();
+        self.seen.insert(key, urls.join("\n"));
+    }
+
     /// DDG Instant Answer API — returns direct answers, abstracts, related topics.
     /// JSON format, no HTML parsing required, very low block risk.
     async fn ddg_instant(&self, query: &str) -> Option<String> {
@@ -306,11 +499,7 @@ impl WebSearchTool {
     /// Handles both JSON response and plain-text/markdown fallback.
     async fn jina_search(&self, query: &str, max: usize, lang: &str) -> Vec<SearchResult> {
         // Jina Search supports locale via X-Locale header
-        let locale = match lang {
-            "id" => "id-ID",
-            "en" => "en-US",
-            _ => "",
-        };
+        let locale = jina_locale(lang);
 
         let url = format!("https://s.jina.ai/{}", percent_encode(query));
 
@@ -386,18 +575,8 @@ impl WebSearchTool {
 
 
     /// DDG Lite — simpler HTML format, low block rate on congested/edge IPs.
-    async fn ddg_lite(&self, query: &str, max: usize, lang: &str) -> Vec<SearchResult> {
-        let kl = match lang {
-            "id" => "id-id",
-            "en" => "us-en",
-            _ => "wt-wt",
-        };
-
-        let url = format!(
-            "https://lite.duckduckgo.com/lite/?q={}&kl={}",
-            percent_encode(query),
-            kl
-        );
+    async fn ddg_lite(&self, query: &str, max: usize, lang: &str, offset: usize) -> Vec<SearchResult> {
+        let url = ddg_lite_url(query, lang, offset);
 
         let resp = self
             .client
@@ -424,36 +603,15 @@ impl WebSearchTool {
     }
 
     /// Wikipedia Search API — free, JSON, no auth, great for factual queries.
-    /// Lang is passed directly from the LLM tool call — no hardcoded detection.
-    async fn wikipedia_search(&self, query: &str, max: usize, lang: &str) -> Vec<SearchResult> {
-        // Map lang to Wikipedia language code; default to English
-        // The LLM is instructed in the tool description to pass 'id' for Indonesian
-        let wiki_lang = match lang {
-            "id" => "id",
-            "en" => "en",
-            // For 'auto', try both in parallel and merge
-            _ => "en",
-        };
-
-        // When auto, also search Indonesian Wikipedia if query might be Indonesian
-        // We rely on simple heuristic: any non-ASCII Latin characters → likely Indonesian
-        // (but this is just a bonus, not the primary detection mechanism)
-        let langs_to_try: &[&str] = if lang == "auto" {
-            &["en", "id"]
-        } else {
-            std::slice::from_ref(&wiki_lang)
-        };
+    /// Searches the wiki of `lang` (see [`wiki_langs`]).
+    async fn wikipedia_search(&self, query: &str, max: usize, lang: &str, offset: usize) -> Vec<SearchResult> {
+        let langs_to_try = wiki_langs(lang);
 
         let mut results = Vec::new();
         let mut seen: HashSet<u64> = HashSet::new();
 
         for &wl in langs_to_try {
-            let url = format!(
-                "https://{}.wikipedia.org/w/api.php?action=query&list=search&srsearch={}&srlimit={}&format=json&utf8=1&srprop=snippet|titlesnippet",
-                wl,
-                percent_encode(query),
-                max
-            );
+            let url = wikipedia_url(wl, query, max, offset);
 
             let resp = self
                 .client
@@ -513,6 +671,27 @@ struct SearchResult {
     snippet: String,
 }
 
+/// The `format='json'` output: the instant answer and results tagged with
+/// their engine.
+fn json_output(instant_answer: Option<&str>, results: &[(&str, SearchResult)]) -> String {
+    let results: Vec<Value> = results
+        .iter()
+        .map(|(engine, r)| {
+            json!({
+                "title": r.title,
+                "url": r.url,
+                "snippet": r.snippet,
+                "source_engine": engine,
+            })
+        })
+        .collect();
+    json!({
+        "instant_answer": instant_answer.map(str::trim),
+        "results": results,
+    })
+    .to_string()
+}
+
 /// Parse DuckDuckGo HTML (main endpoint) search results.
 fn parse_ddg_html(html: &str, max: usize) -> Vec<SearchResult> {
     let mut results = Vec::new();
@@ -757,3 +936,88 @@ fn parse_jina_text(text: &str, max: usize) -> Vec<SearchResult> {
 fn _ensure_ddg_html_parser_available() {
     let _ = parse_ddg_html("", 0);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_detects_indonesian() {
+        let lang = resolve_lang("bagaimana cara membuat rendang daging sapi yang empuk dan enak", "auto");
+        assert_eq!(lang, "id");
+        assert_eq!(wiki_langs(lang), &["id"]);
+        assert_eq!(jina_locale(lang), "id-ID");
+    }
+
+    #[test]
+    fn test_auto_detects_english() {
+        let lang = resolve_lang("what is the capital of france", "auto");
+        assert_eq!(lang, "en");
+        assert_eq!(wiki_langs(lang), &["en"]);
+        assert_eq!(jina_locale(lang), "en-US");
+    }
+
+    #[test]
+    fn test_explicit_lang_wins() {
+        let query = "bagaimana cara membuat rendang daging sapi yang empuk dan enak";
+        assert_eq!(resolve_lang(query, "en"), "en");
+        assert_eq!(resolve_lang("how do I cook a tender beef rendang at home", "id"), "id");
+    }
+
+    #[test]
+    fn test_page_urls_carry_the_offset() {
+        assert_eq!(ddg_lite_url("rust async", "en", 0), "https://lite.duckduckgo.com/lite/?q=rust+async&kl=us-en");
+        assert_eq!(
+            ddg_lite_url("rust async", "en", 8),
+            "https://lite.duckduckgo.com/lite/?q=rust+async&kl=us-en&s=8&dc=9"
+        );
+        assert!(wikipedia_url("id", "rendang", 4, 8).starts_with("https://id.wikipedia.org/w/api.php?"));
+        assert!(wikipedia_url("id", "rendang", 4, 8).contains("&srsearch=rendang&srlimit=4&sroffset=8&"));
+    }
+
+    #[test]
+    fn test_shown_urls_accumulate_per_session() {
+        let tool = WebSearchTool::new();
+        let first: Vec<String> = vec!["https://a.example".into(), "https://b.example".into()];
+        tool.remember_shown("cli:x|rust", tool.shown_urls("cli:x|rust"), first.into_iter());
+        tool.remember_shown("cli:x|rust", tool.shown_urls("cli:x|rust"), std::iter::once("https://c.example".into()));
+
+        let shown = tool.shown_urls("cli:x|rust");
+        assert_eq!(shown.len(), 3);
+        assert!(shown.contains("https://a.example") && shown.contains("https://c.example"));
+        assert!(tool.shown_urls("telegram:1|rust").is_empty());
+
+        let off = WebSearchTool::new().with_page_dedup(false);
+        off.remember_shown("cli:x|rust", HashSet::new(), std::iter::once("https://a.example".into()));
+        assert!(off.shown_urls("cli:x|rust").is_empty());
+    }
+
+    #[test]
+    fn test_json_output() {
+        let result = |title: &str, url: &str| SearchResult {
+            title: title.into(),
+            url: url.into(),
+            snippet: String::new(),
+        };
+        let results = [
+            ("jina", result("Rust", "https://www.rust-lang.org")),
+            ("wikipedia", result("Rust (programming language)", "https://en.wikipedia.org/?curid=29414838")),
+        ];
+        let out: Value = serde_json::from_str(&json_output(Some("Rust is a language.\n"), &results)).unwrap();
+        assert_eq!(out["instant_answer"], "Rust is a language.");
+        assert_eq!(out["results"].as_array().unwrap().len(), 2);
+        assert_eq!(out["results"][0]["url"], "https://www.rust-lang.org");
+        assert_eq!(out["results"][1]["source_engine"], "wikipedia");
+
+        let empty: Value = serde_json::from_str(&json_output(None, &[])).unwrap();
+        assert_eq!(empty, json!({ "instant_answer": null, "results": [] }));
+    }
+
+    #[test]
+    fn test_unclear_query_searches_both_wikis() {
+        let lang = resolve_lang("42", "auto");
+        assert_eq!(lang, "auto");
+        assert_eq!(wiki_langs(lang), &["en", "id"]);
+        assert_eq!(jina_locale(lang), "");
+    }
+}
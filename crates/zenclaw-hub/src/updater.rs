@@ -1,18 +1,38 @@
 //! Auto-update checker — check for new ZenClaw releases on GitHub.
+//!
+//! [`apply_update`] also installs a release in place: the asset download is
+//! resumable (HTTP `Range` on a `.part` file) and retried, and the archive's
+//! size and SHA-256 are verified before the running binary is swapped.
 
-use serde::Deserialize;
-use tracing::info;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
 
 use zenclaw_core::error::{Result, ZenClawError};
 
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const GITHUB_API: &str = "https://api.github.com/repos/volumeee/zenclaw/releases/latest";
+/// Download attempts before giving up (each one resumes the last).
+const DOWNLOAD_ATTEMPTS: u32 = 5;
 
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
     html_url: String,
     body: Option<String>,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a GitHub release.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    /// Size in bytes as reported by GitHub.
+    pub size: u64,
 }
 
 /// Check for updates on GitHub.
@@ -47,6 +67,7 @@ pub async fn check_for_updates() -> Result<Option<UpdateInfo>> {
             latest: latest.to_string(),
             url: release.html_url,
             changelog: release.body.unwrap_or_default(),
+            assets: release.assets,
         }))
     } else {
         Ok(None)
@@ -54,11 +75,307 @@ pub async fn check_for_updates() -> Result<Option<UpdateInfo>> {
 }
 
 /// Update info.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
     pub current: String,
     pub latest: String,
     pub url: String,
     pub changelog: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+impl UpdateInfo {
+    /// The release archive for this OS/architecture
+    /// (`zenclaw-<os>-<arch>.tar.gz`, or `.zip` on Windows).
+    pub fn platform_asset(&self) -> Option<&ReleaseAsset> {
+        let ext = if cfg!(windows) { "zip" } else { "tar.gz" };
+        let name = format!("zenclaw-{}-{}.{}", std::env::consts::OS, std::env::consts::ARCH, ext);
+        self.assets.iter().find(|a| a.name == name)
+    }
+
+    fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.name == name)
+    }
+}
+
+/// Cached result of the last update check.
+#[derive(Debug, Serialize, Deserialize)]
+struct UpdateCache {
+    /// Unix timestamp (seconds) of the last successful check.
+    checked_at: i64,
+    /// Release found at that time (None = already up to date).
+    update: Option<UpdateInfo>,
+}
+
+/// Check for updates, reusing the cached result at `cache_path` if it is
+/// younger than `interval_hours`. Fresh results are written back to the cache.
+pub async fn check_for_updates_cached(
+    cache_path: &Path,
+    interval_hours: u64,
+) -> Result<Option<UpdateInfo>> {
+    let now = chrono::Utc::now().timestamp();
+
+    if let Some(cache) = read_cache(cache_path) {
+        let age = now.saturating_sub(cache.checked_at);
+        if age >= 0 && (age as u64) < interval_hours * 3600 {
+            debug!("Using cached update check ({}s old)", age);
+            // The binary may have been upgraded since the check was cached.
+            return Ok(cache
+                .update
+                .filter(|u| version_greater(&u.latest, CURRENT_VERSION)));
+        }
+    }
+
+    let update = check_for_updates().await?;
+    write_cache(cache_path, &update);
+    Ok(update)
+}
+
+/// Store a check result in the cache file (best effort).
+pub fn write_cache(cache_path: &Path, update: &Option<UpdateInfo>) {
+    let cache = UpdateCache {
+        checked_at: chrono::Utc::now().timestamp(),
+        update: update.clone(),
+    };
+    if let Ok(json) = serde_json::to_string_pretty(&cache) {
+        if let Some(parent) = cache_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let _ = std::fs::write(cache_path, json);
+    }
+}
+
+fn read_cache(cache_path: &Path) -> Option<UpdateCache> {
+    let content = std::fs::read_to_string(cache_path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// ─── Self-install ─────────────────────────────────────────
+
+/// Download `asset` to `dest`, retrying failed attempts and resuming from the
+/// bytes already on disk (`<dest>.part`). `progress(done, total)` is called
+/// as data arrives.
+pub async fn download_asset(
+    asset: &ReleaseAsset,
+    dest: &Path,
+    progress: &(dyn Fn(u64, u64) + Send + Sync),
+) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .user_agent("zenclaw-updater")
+        .connect_timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| ZenClawError::Other(format!("HTTP client error: {}", e)))?;
+    let part = PathBuf::from(format!("{}.part", dest.display()));
+
+    let mut attempt = 1;
+    loop {
+        match download_range(&client, asset, &part, progress).await {
+            Ok(()) => break,
+            Err(e) if attempt < DOWNLOAD_ATTEMPTS => {
+                let delay = Duration::from_secs(2u64.pow(attempt));
+                warn!("Download attempt {}/{} failed: {} — retrying in {}s", attempt, DOWNLOAD_ATTEMPTS, e, delay.as_secs());
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let size = tokio::fs::metadata(&part).await?.len();
+    if asset.size > 0 && size != asset.size {
+        tokio::fs::remove_file(&part).await.ok();
+        return Err(ZenClawError::Other(format!(
+            "Downloaded {} bytes but {} expects {}",
+            size, asset.name, asset.size
+        )));
+    }
+    tokio::fs::rename(&part, dest).await?;
+    Ok(())
+}
+
+/// One download attempt, continuing from the end of `part` if it exists.
+async fn download_range(
+    client: &reqwest::Client,
+    asset: &ReleaseAsset,
+    part: &Path,
+    progress: &(dyn Fn(u64, u64) + Send + Sync),
+) -> Result<()> {
+    let mut offset = tokio::fs::metadata(part).await.map(|m| m.len()).unwrap_or(0);
+    if asset.size > 0 && offset >= asset.size {
+        if offset == asset.size {
+            return Ok(());
+        }
+        // Larger than the asset: a stale file from another release
+        tokio::fs::remove_file(part).await.ok();
+        offset = 0;
+    }
+
+    let mut request = client.get(&asset.browser_download_url);
+    if offset > 0 {
+        debug!("Resuming {} at byte {}", asset.name, offset);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut resp = request
+        .send()
+        .await
+        .map_err(|e| ZenClawError::Other(format!("Download failed: {}", e)))?;
+    if !resp.status().is_success() {
+        return Err(ZenClawError::Other(format!("Download failed: HTTP {}", resp.status())));
+    }
+
+    // 200 instead of 206 means the server ignored the range: start over
+    let resumed = resp.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut done = if resumed { offset } else { 0 };
+    let total = if asset.size > 0 {
+        asset.size
+    } else {
+        done + resp.content_length().unwrap_or(0)
+    };
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .await?;
+    progress(done, total);
+
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| ZenClawError::Other(format!("Download interrupted at {} bytes: {}", done, e)))?
+    {
+        file.write_all(&chunk).await?;
+        done += chunk.len() as u64;
+        progress(done, total);
+    }
+    file.flush().await?;
+
+    if asset.size > 0 && done < asset.size {
+        return Err(ZenClawError::Other(format!(
+            "Connection closed at {}/{} bytes",
+            done, asset.size
+        )));
+    }
+    Ok(())
+}
+
+/// SHA-256 of a file as lowercase hex.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Expected SHA-256 of `asset`, from the `<asset>.sha256` file published
+/// alongside it (None if the release has none).
+async fn expected_sha256(info: &UpdateInfo, asset: &ReleaseAsset) -> Result<Option<String>> {
+    let Some(sum_asset) = info.asset(&format!("{}.sha256", asset.name)) else {
+        return Ok(None);
+    };
+
+    let text = reqwest::Client::builder()
+        .user_agent("zenclaw-updater")
+        .build()
+        .map_err(|e| ZenClawError::Other(format!("HTTP client error: {}", e)))?
+        .get(&sum_asset.browser_download_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| ZenClawError::Other(format!("Checksum download failed: {}", e)))?
+        .text()
+        .await
+        .map_err(|e| ZenClawError::Other(format!("Checksum download failed: {}", e)))?;
+
+    let sum = text.split_whitespace().next().unwrap_or_default().to_lowercase();
+    if sum.len() != 64 || !sum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ZenClawError::Other(format!("Malformed checksum file {}", sum_asset.name)));
+    }
+    Ok(Some(sum))
+}
+
+/// Download, verify and install the release in `info` over the running
+/// binary. Partial downloads in `work_dir` are resumed on the next call.
+/// The previous binary is kept next to the new one with an `.old` extension.
+///
+/// Returns the path of the replaced executable.
+pub async fn apply_update(
+    info: &UpdateInfo,
+    work_dir: &Path,
+    progress: &(dyn Fn(u64, u64) + Send + Sync),
+) -> Result<PathBuf> {
+    let asset = info.platform_asset().ok_or_else(|| {
+        ZenClawError::Other(format!(
+            "v{} has no build for {}-{}",
+            info.latest,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+
+    std::fs::create_dir_all(work_dir)?;
+    let archive = work_dir.join(&asset.name);
+    download_asset(asset, &archive, progress).await?;
+
+    match expected_sha256(info, asset).await? {
+        Some(expected) => {
+            let actual = sha256_file(&archive)?;
+            if actual != expected {
+                std::fs::remove_file(&archive).ok();
+                return Err(ZenClawError::Other(format!(
+                    "Checksum mismatch for {} (expected {}, got {})",
+                    asset.name, expected, actual
+                )));
+            }
+        }
+        None => warn!("{} has no published checksum — verified size only", asset.name),
+    }
+
+    // Unpack with the system `tar` (bsdtar on Windows also reads .zip)
+    let unpack_dir = work_dir.join(format!("v{}", info.latest));
+    std::fs::create_dir_all(&unpack_dir)?;
+    let status = tokio::process::Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&unpack_dir)
+        .status()
+        .await
+        .map_err(|e| ZenClawError::Other(format!("Failed to run tar: {}", e)))?;
+    if !status.success() {
+        return Err(ZenClawError::Other(format!("Failed to unpack {}", asset.name)));
+    }
+
+    let stem = asset.name.trim_end_matches(".tar.gz").trim_end_matches(".zip");
+    let new_binary = unpack_dir.join(format!("{}{}", stem, std::env::consts::EXE_SUFFIX));
+    if !new_binary.is_file() {
+        return Err(ZenClawError::Other(format!("{} does not contain {}", asset.name, new_binary.display())));
+    }
+
+    // Swap: move the running binary aside (allowed even on Windows), copy the new one in
+    let exe = std::env::current_exe()?;
+    let backup = exe.with_extension("old");
+    std::fs::remove_file(&backup).ok();
+    std::fs::rename(&exe, &backup)?;
+    if let Err(e) = std::fs::copy(&new_binary, &exe) {
+        std::fs::rename(&backup, &exe).ok();
+        return Err(e.into());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    std::fs::remove_file(&archive).ok();
+    std::fs::remove_dir_all(&unpack_dir).ok();
+    info!("Installed v{} at {}", info.latest, exe.display());
+    Ok(exe)
 }
 
 /// Simple semantic version comparison.
@@ -98,4 +415,33 @@ mod tests {
         assert!(!version_greater("0.1.0", "0.1.0"));
         assert!(!version_greater("0.0.9", "0.1.0"));
     }
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fresh_cache_skips_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("update_check.json");
+
+        let info = UpdateInfo {
+            current: CURRENT_VERSION.to_string(),
+            latest: "999.0.0".to_string(),
+            url: "https://example.com".to_string(),
+            changelog: String::new(),
+            assets: Vec::new(),
+        };
+        write_cache(&path, &Some(info));
+
+        let cached = check_for_updates_cached(&path, 24).await.unwrap();
+        assert_eq!(cached.map(|u| u.latest), Some("999.0.0".to_string()));
+    }
 }